@@ -0,0 +1,590 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! Compare the APIs two builds of a crate export, and classify each
+//! difference by whether TypeScript code written against the old bindings
+//! still compiles against the new.
+use std::{collections::BTreeMap, fmt::Display};
+
+use anyhow::Result;
+use camino::Utf8Path;
+use heck::ToUpperCamelCase;
+use serde::Serialize;
+use uniffi_bindgen::{
+    cargo_metadata::CrateConfigSupplier,
+    interface::{AsType, Callable, Field, ObjectImpl, Type},
+    BindgenLoader, BindgenPaths, ComponentInterface,
+};
+
+use ubrn_common::CrateMetadata;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Function,
+    Object,
+    Constructor,
+    Method,
+    Record,
+    Field,
+    Enum,
+    Error,
+    Variant,
+    CallbackInterface,
+}
+
+impl Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Function => "function",
+            Self::Object => "object",
+            Self::Constructor => "constructor",
+            Self::Method => "method",
+            Self::Record => "record",
+            Self::Field => "field",
+            Self::Enum => "enum",
+            Self::Error => "error",
+            Self::Variant => "variant",
+            Self::CallbackInterface => "callback interface",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ApiChange {
+    /// `namespace::Item`, `namespace::Type.member` for members.
+    pub item: String,
+    pub kind: ItemKind,
+    pub change: ChangeKind,
+    pub breaking: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ApiDiff {
+    pub changes: Vec<ApiChange>,
+}
+
+impl ApiDiff {
+    /// Load the component interfaces of two libraries and compare them.
+    ///
+    /// `manifest_path` is only needed for crates that use UDL, to find it.
+    pub fn between_libraries(
+        old: &Utf8Path,
+        new: &Utf8Path,
+        manifest_path: Option<&Utf8Path>,
+    ) -> Result<Self> {
+        let loader = {
+            let mut paths = BindgenPaths::default();
+            if let Some(manifest_path) = manifest_path {
+                let metadata = CrateMetadata::cargo_metadata(manifest_path)?;
+                paths.add_layer(CrateConfigSupplier::from(metadata));
+            }
+            BindgenLoader::new(paths)
+        };
+        let load = |path: &Utf8Path| -> Result<Vec<ComponentInterface>> {
+            let metadata = crate::cli::load_metadata(&loader, path)?;
+            loader.load_cis(metadata)
+        };
+        Ok(Self::between(&load(old)?, &load(new)?))
+    }
+
+    pub fn between(old: &[ComponentInterface], new: &[ComponentInterface]) -> Self {
+        let mut diff = Differ::default();
+        let old = by_name(old, |ci| ci.namespace());
+        let new = by_name(new, |ci| ci.namespace());
+        for (ns, old_ci, new_ci) in pair(&old, &new) {
+            match (old_ci, new_ci) {
+                (Some(o), Some(n)) => diff.component(ns, o, n),
+                // A whole namespace is every item in it.
+                (Some(o), None) => diff.component(ns, o, &ComponentInterface::new(ns)),
+                (None, Some(n)) => diff.component(ns, &ComponentInterface::new(ns), n),
+                (None, None) => unreachable!(),
+            }
+        }
+        let mut changes = diff.changes;
+        changes.sort_by(|a, b| (!a.breaking, &a.item, a.kind).cmp(&(!b.breaking, &b.item, b.kind)));
+        Self { changes }
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|c| c.breaking)
+    }
+
+    pub fn non_breaking(&self) -> impl Iterator<Item = &ApiChange> {
+        self.changes.iter().filter(|c| !c.breaking)
+    }
+
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+}
+
+/// Which side of the FFI calls a callable, and which implements it.
+#[derive(Clone, Copy)]
+enum Caller {
+    /// Functions, constructors and methods of objects.
+    TypeScript,
+    /// Methods of callback interfaces.
+    Rust,
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<ApiChange>,
+}
+
+impl Differ {
+    fn push(
+        &mut self,
+        item: String,
+        kind: ItemKind,
+        change: ChangeKind,
+        breaking: bool,
+        detail: Option<String>,
+    ) {
+        self.changes.push(ApiChange {
+            item,
+            kind,
+            change,
+            breaking,
+            detail,
+        });
+    }
+
+    /// Adding is safe and removing is not, for most kinds of item.
+    fn presence<T>(&mut self, item: &str, kind: ItemKind, old: Option<T>, new: Option<T>) -> bool {
+        match (old, new) {
+            (Some(_), None) => self.push(item.into(), kind, ChangeKind::Removed, true, None),
+            (None, Some(_)) => self.push(item.into(), kind, ChangeKind::Added, false, None),
+            _ => return true,
+        }
+        false
+    }
+
+    fn component(&mut self, ns: &str, old: &ComponentInterface, new: &ComponentInterface) {
+        let old_fns = by_name(old.function_definitions(), |f| f.name());
+        let new_fns = by_name(new.function_definitions(), |f| f.name());
+        for (name, o, n) in pair(&old_fns, &new_fns) {
+            let item = format!("{ns}::{name}");
+            if self.presence(&item, ItemKind::Function, o, n) {
+                self.callable(
+                    &item,
+                    ItemKind::Function,
+                    *o.unwrap(),
+                    *n.unwrap(),
+                    Caller::TypeScript,
+                );
+            }
+        }
+
+        let old_objects = by_name(old.object_definitions(), |o| o.name());
+        let new_objects = by_name(new.object_definitions(), |o| o.name());
+        for (name, o, n) in pair(&old_objects, &new_objects) {
+            let item = format!("{ns}::{name}");
+            let kind = if old.is_name_used_as_error(name) || new.is_name_used_as_error(name) {
+                ItemKind::Error
+            } else {
+                ItemKind::Object
+            };
+            if !self.presence(&item, kind, o, n) {
+                continue;
+            }
+            let (o, n) = (o.unwrap(), n.unwrap());
+            let (old_ctors, new_ctors) = (o.constructors(), n.constructors());
+            let old_ctors = by_name(&old_ctors, |c| c.name());
+            let new_ctors = by_name(&new_ctors, |c| c.name());
+            for (ctor, oc, nc) in pair(&old_ctors, &new_ctors) {
+                let item = format!("{item}.{ctor}");
+                if self.presence(&item, ItemKind::Constructor, oc, nc) {
+                    self.callable(
+                        &item,
+                        ItemKind::Constructor,
+                        **oc.unwrap(),
+                        **nc.unwrap(),
+                        Caller::TypeScript,
+                    );
+                }
+            }
+            let (old_methods, new_methods) = (o.methods(), n.methods());
+            let old_methods = by_name(&old_methods, |m| m.name());
+            let new_methods = by_name(&new_methods, |m| m.name());
+            for (method, om, nm) in pair(&old_methods, &new_methods) {
+                let item = format!("{item}.{method}");
+                if self.presence(&item, ItemKind::Method, om, nm) {
+                    self.callable(
+                        &item,
+                        ItemKind::Method,
+                        **om.unwrap(),
+                        **nm.unwrap(),
+                        Caller::TypeScript,
+                    );
+                }
+            }
+        }
+
+        let old_records = by_name(old.record_definitions(), |r| r.name());
+        let new_records = by_name(new.record_definitions(), |r| r.name());
+        for (name, o, n) in pair(&old_records, &new_records) {
+            let item = format!("{ns}::{name}");
+            if self.presence(&item, ItemKind::Record, o, n) {
+                self.fields(&item, o.unwrap().fields(), n.unwrap().fields());
+            }
+        }
+
+        let old_enums = by_name(old.enum_definitions(), |e| e.name());
+        let new_enums = by_name(new.enum_definitions(), |e| e.name());
+        for (name, o, n) in pair(&old_enums, &new_enums) {
+            let item = format!("{ns}::{name}");
+            let is_error = old.is_name_used_as_error(name) || new.is_name_used_as_error(name);
+            let kind = if is_error {
+                ItemKind::Error
+            } else {
+                ItemKind::Enum
+            };
+            if !self.presence(&item, kind, o, n) {
+                continue;
+            }
+            let (o, n) = (o.unwrap(), n.unwrap());
+            let old_variants = by_name(o.variants(), |v| v.name());
+            let new_variants = by_name(n.variants(), |v| v.name());
+            for (variant, ov, nv) in pair(&old_variants, &new_variants) {
+                let item = format!("{item}.{variant}");
+                match (ov, nv) {
+                    (Some(_), None) => {
+                        self.push(item, ItemKind::Variant, ChangeKind::Removed, true, None)
+                    }
+                    // Errors are caught, not matched exhaustively, and a
+                    // non-exhaustive enum already asks for a default case;
+                    // for any other enum, an exhaustive `switch` stops
+                    // compiling.
+                    (None, Some(_)) => {
+                        let breaking = !is_error && !n.is_non_exhaustive();
+                        let detail = breaking
+                            .then(|| "exhaustive switches over the enum must handle it".into());
+                        self.push(item, ItemKind::Variant, ChangeKind::Added, breaking, detail);
+                    }
+                    (Some(ov), Some(nv)) => self.fields(&item, ov.fields(), nv.fields()),
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+
+        let old_cbs = by_name(old.callback_interface_definitions(), |c| c.name());
+        let new_cbs = by_name(new.callback_interface_definitions(), |c| c.name());
+        for (name, o, n) in pair(&old_cbs, &new_cbs) {
+            let item = format!("{ns}::{name}");
+            if !self.presence(&item, ItemKind::CallbackInterface, o, n) {
+                continue;
+            }
+            // TypeScript implements these, so adding a method breaks it.
+            let (old_methods, new_methods) = (o.unwrap().methods(), n.unwrap().methods());
+            let old_methods = by_name(&old_methods, |m| m.name());
+            let new_methods = by_name(&new_methods, |m| m.name());
+            for (method, om, nm) in pair(&old_methods, &new_methods) {
+                let item = format!("{item}.{method}");
+                match (om, nm) {
+                    (Some(_), None) => {
+                        self.push(item, ItemKind::Method, ChangeKind::Removed, true, None)
+                    }
+                    (None, Some(_)) => self.push(
+                        item,
+                        ItemKind::Method,
+                        ChangeKind::Added,
+                        true,
+                        Some("implementations must add it".into()),
+                    ),
+                    (Some(om), Some(nm)) => {
+                        self.callable(&item, ItemKind::Method, **om, **nm, Caller::Rust)
+                    }
+                    (None, None) => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Compare two signatures. What TypeScript passes to a callable it calls
+    /// may widen, and what it gets back may narrow; for one it implements,
+    /// the other way around.
+    fn callable(
+        &mut self,
+        item: &str,
+        kind: ItemKind,
+        old: &dyn Callable,
+        new: &dyn Callable,
+        caller: Caller,
+    ) {
+        let mut breaking = Vec::new();
+        let mut other = Vec::new();
+        // Arguments TypeScript may leave out. For an implementation, any
+        // change in how it is called breaks it.
+        let mut optional = Vec::new();
+        let (old_args, new_args) = (old.arguments(), new.arguments());
+        let mut arg_types = Vec::new();
+        // Arguments are positional in the generated TypeScript.
+        for i in 0..old_args.len().max(new_args.len()) {
+            match (old_args.get(i), new_args.get(i)) {
+                (Some(o), None) => breaking.push(format!("argument `{}` removed", o.name())),
+                (None, Some(n)) if n.default_value().is_some() => {
+                    optional.push(format!("optional argument `{}` added", n.name()))
+                }
+                (None, Some(n)) => breaking.push(format!("argument `{}` added", n.name())),
+                (Some(o), Some(n)) => {
+                    arg_types.push((n.name(), o.as_type(), n.as_type()));
+                    if o.name() != n.name() {
+                        other.push(format!("argument `{}` renamed to `{}`", o.name(), n.name()));
+                    }
+                    match (o.default_value().is_some(), n.default_value().is_some()) {
+                        (true, false) => {
+                            breaking.push(format!("argument `{}` is no longer optional", n.name()))
+                        }
+                        (false, true) => {
+                            optional.push(format!("argument `{}` is now optional", n.name()))
+                        }
+                        _ => {}
+                    }
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+        match caller {
+            Caller::TypeScript => other.extend(optional),
+            Caller::Rust => breaking.extend(optional),
+        }
+        for (name, o, n) in arg_types {
+            let (ot, nt) = (type_label(&o), type_label(&n));
+            if ot == nt {
+                continue;
+            }
+            let note = format!("argument `{name}` changed from `{ot}` to `{nt}`");
+            let fits = match caller {
+                Caller::TypeScript => assignable(&o, &n),
+                Caller::Rust => assignable(&n, &o),
+            };
+            if fits {
+                other.push(note);
+            } else {
+                breaking.push(note);
+            }
+        }
+        let (or, nr) = (old.return_type(), new.return_type());
+        let (ort, nrt) = (opt_type_label(or), opt_type_label(nr));
+        if ort != nrt {
+            let note = format!("return type changed from `{ort}` to `{nrt}`");
+            let fits = match (or, nr, caller) {
+                (Some(o), Some(n), Caller::TypeScript) => assignable(n, o),
+                (Some(o), Some(n), Caller::Rust) => assignable(o, n),
+                _ => false,
+            };
+            if fits {
+                other.push(note);
+            } else {
+                breaking.push(note);
+            }
+        }
+        if old.is_async() != new.is_async() {
+            let now = if new.is_async() { "async" } else { "sync" };
+            breaking.push(format!("is now {now}"));
+        }
+        // TypeScript does not check what a function throws.
+        let (ot, nt) = (
+            opt_type_label(old.throws_type()),
+            opt_type_label(new.throws_type()),
+        );
+        if ot != nt {
+            other.push(format!("throws `{nt}` instead of `{ot}`"));
+        }
+        self.changed(item, kind, breaking, other);
+    }
+
+    /// Compare the fields of a record, or of an enum variant.
+    fn fields(&mut self, item: &str, old: &[Field], new: &[Field]) {
+        let old = by_name(old, |f| f.name());
+        let new = by_name(new, |f| f.name());
+        for (name, o, n) in pair(&old, &new) {
+            let item = format!("{item}.{name}");
+            match (o, n) {
+                (Some(_), None) => {
+                    self.push(item, ItemKind::Field, ChangeKind::Removed, true, None)
+                }
+                // Object literals of the type must now include it.
+                (None, Some(n)) => {
+                    let optional = n.default_value().is_some();
+                    let detail = (!optional).then(|| "required, with no default".to_string());
+                    self.push(item, ItemKind::Field, ChangeKind::Added, !optional, detail);
+                }
+                (Some(o), Some(n)) => {
+                    let mut breaking = Vec::new();
+                    let mut other = Vec::new();
+                    let (o_type, n_type) = (o.as_type(), n.as_type());
+                    let (ot, nt) = (type_label(&o_type), type_label(&n_type));
+                    // Records are both made and read in TypeScript, so the
+                    // type must stay the same there.
+                    if ts_type_label(&o_type) != ts_type_label(&n_type) {
+                        breaking.push(format!("changed from `{ot}` to `{nt}`"));
+                    } else if ot != nt {
+                        other.push(format!("changed from `{ot}` to `{nt}`"));
+                    }
+                    match (o.default_value().is_some(), n.default_value().is_some()) {
+                        (true, false) => breaking.push("no longer has a default".to_string()),
+                        (false, true) => other.push("now has a default".to_string()),
+                        _ => {}
+                    }
+                    self.changed(&item, ItemKind::Field, breaking, other);
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+
+    fn changed(&mut self, item: &str, kind: ItemKind, breaking: Vec<String>, other: Vec<String>) {
+        if !breaking.is_empty() {
+            let detail = breaking
+                .into_iter()
+                .chain(other)
+                .collect::<Vec<_>>()
+                .join("; ");
+            self.push(item.into(), kind, ChangeKind::Changed, true, Some(detail));
+        } else if !other.is_empty() {
+            let detail = other.join("; ");
+            self.push(item.into(), kind, ChangeKind::Changed, false, Some(detail));
+        }
+    }
+}
+
+fn by_name<'a, T>(items: &'a [T], name: impl Fn(&'a T) -> &'a str) -> BTreeMap<&'a str, &'a T> {
+    items.iter().map(|i| (name(i), i)).collect()
+}
+
+/// Every key of either map, with what each map has for it.
+fn pair<'m, 'a, T>(
+    old: &'m BTreeMap<&'a str, T>,
+    new: &'m BTreeMap<&'a str, T>,
+) -> Vec<(&'a str, Option<&'m T>, Option<&'m T>)> {
+    let mut keys: Vec<&'a str> = old.keys().chain(new.keys()).copied().collect();
+    keys.sort_unstable();
+    keys.dedup();
+    keys.into_iter()
+        .map(|k| (k, old.get(k), new.get(k)))
+        .collect()
+}
+
+fn opt_type_label(t: Option<&Type>) -> String {
+    t.map_or_else(|| "()".to_string(), type_label)
+}
+
+/// A type, as written in Rust, without module paths: which crate a type
+/// lives in is not part of its TypeScript API.
+fn type_label(t: &Type) -> String {
+    match t {
+        Type::UInt8 => "u8".into(),
+        Type::Int8 => "i8".into(),
+        Type::UInt16 => "u16".into(),
+        Type::Int16 => "i16".into(),
+        Type::UInt32 => "u32".into(),
+        Type::Int32 => "i32".into(),
+        Type::UInt64 => "u64".into(),
+        Type::Int64 => "i64".into(),
+        Type::Float32 => "f32".into(),
+        Type::Float64 => "f64".into(),
+        Type::Boolean => "bool".into(),
+        Type::String => "String".into(),
+        Type::Bytes => "Vec<u8>".into(),
+        Type::Timestamp => "SystemTime".into(),
+        Type::Duration => "Duration".into(),
+        Type::Object { name, .. }
+        | Type::Record { name, .. }
+        | Type::Enum { name, .. }
+        | Type::CallbackInterface { name, .. }
+        | Type::Custom { name, .. } => name.clone(),
+        Type::Optional { inner_type } => format!("Option<{}>", type_label(inner_type)),
+        Type::Sequence { inner_type } => format!("Vec<{}>", type_label(inner_type)),
+        Type::Map {
+            key_type,
+            value_type,
+        } => format!(
+            "HashMap<{}, {}>",
+            type_label(key_type),
+            type_label(value_type)
+        ),
+    }
+}
+
+/// Whether TypeScript accepts a value of type `from` where `to` is expected.
+/// Types that render the same are interchangeable, and `T | undefined` also
+/// takes a `T`.
+fn assignable(from: &Type, to: &Type) -> bool {
+    match (from, to) {
+        (Type::Optional { inner_type: f }, Type::Optional { inner_type: t }) => assignable(f, t),
+        (_, Type::Optional { inner_type }) => assignable(from, inner_type),
+        _ => ts_type_label(from) == ts_type_label(to),
+    }
+}
+
+/// A type, as the generated TypeScript spells it; see `type_label_for` in
+/// `gen_typescript`. Byte arrays are `ArrayBuffer` or `Uint8Array` depending
+/// on the config, which does not change between the two builds.
+fn ts_type_label(t: &Type) -> String {
+    match t {
+        Type::UInt8
+        | Type::Int8
+        | Type::UInt16
+        | Type::Int16
+        | Type::UInt32
+        | Type::Int32
+        | Type::Float32
+        | Type::Float64
+        | Type::Duration => "number".into(),
+        Type::UInt64 | Type::Int64 => "bigint".into(),
+        Type::Boolean => "boolean".into(),
+        Type::String => "string".into(),
+        Type::Bytes => "ArrayBuffer".into(),
+        Type::Timestamp => "Date".into(),
+        Type::Object { name, imp, .. } => {
+            let name = name.to_upper_camel_case();
+            if matches!(imp, ObjectImpl::Struct) {
+                format!("{name}Like")
+            } else {
+                name
+            }
+        }
+        Type::Record { name, .. }
+        | Type::Enum { name, .. }
+        | Type::CallbackInterface { name, .. }
+        | Type::Custom { name, .. } => match name.to_upper_camel_case().as_str() {
+            "Error" => "Exception".into(),
+            name => name.into(),
+        },
+        Type::Optional { inner_type } => format!("{} | undefined", ts_type_label(inner_type)),
+        Type::Sequence { inner_type } => format!("Array<{}>", ts_type_label(inner_type)),
+        Type::Map {
+            key_type,
+            value_type,
+        } => format!(
+            "Map<{}, {}>",
+            ts_type_label(key_type),
+            ts_type_label(value_type)
+        ),
+    }
+}
@@ -20,6 +20,7 @@ pub fn generate_entrypoint(
     match &switches.flavor {
         AbiFlavor::Jsi => gen_cpp::generate_entrypoint(crate_, modules),
         AbiFlavor::Napi => Ok(String::new()),
+        AbiFlavor::Deno => Ok(String::new()),
         #[cfg(feature = "wasm")]
         AbiFlavor::Wasm => gen_rust::generate_entrypoint(crate_, modules),
         #[cfg(feature = "wasm")]
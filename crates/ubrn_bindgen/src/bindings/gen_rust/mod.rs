@@ -121,6 +121,7 @@ impl From<&AbiFlavor> for FlavorParams<'_> {
         match value {
             AbiFlavor::Jsi => unreachable!("Jsi should be only generating C++ not Rust"),
             AbiFlavor::Napi => unreachable!("Napi should be only generating TypeScript not Rust"),
+            AbiFlavor::Deno => unreachable!("Deno should be only generating TypeScript not Rust"),
             AbiFlavor::Wasm => wasm_flavor(),
             AbiFlavor::Wasm2 => unreachable!("Wasm2 has no per-crate Rust shim"),
         }
@@ -234,7 +234,7 @@ fn render_variant_discr(config: &Config, discr: &general::LiteralNode) -> String
     }
 }
 
-pub(super) fn build_field(config: &Config, field: &general::Field) -> TsField {
+pub(super) fn build_field(config: &Config, field: &general::Field, namespace: &str) -> TsField {
     let name = field.name.to_lower_camel_case();
     let is_optional = matches!(&field.ty.ty, general::Type::Optional { .. });
     let ts_type = if is_optional {
@@ -252,6 +252,8 @@ pub(super) fn build_field(config: &Config, field: &general::Field) -> TsField {
         .as_ref()
         .map(|default| render_default_value(config, default));
     let docstring = field.docstring.as_deref().map(format_docstring_indented);
+    let validator = validator_for(config, &field.ty.ty, namespace);
+    let json_codec = json_codec_for(config, &field.ty.ty, namespace);
     TsField {
         name,
         ts_type,
@@ -259,17 +261,23 @@ pub(super) fn build_field(config: &Config, field: &general::Field) -> TsField {
         ffi_converter,
         default_value,
         docstring,
+        validator,
+        json_codec,
     }
 }
 
-pub(super) fn build_variant(config: &Config, variant: &general::Variant) -> TsVariant {
+pub(super) fn build_variant(
+    config: &Config,
+    variant: &general::Variant,
+    namespace: &str,
+) -> TsVariant {
     let name = variant.name.to_upper_camel_case();
     let docstring = variant.docstring.as_deref().map(format_docstring_indented);
     let discriminant = render_variant_discr(config, &variant.discr);
     let fields: Vec<TsField> = variant
         .fields
         .iter()
-        .map(|field| build_field(config, field))
+        .map(|field| build_field(config, field, namespace))
         .collect();
     let has_nameless_fields = matches!(variant.fields_kind, general::FieldsKind::Unnamed);
     let has_field_defaults = fields.iter().any(|f| f.default_value.is_some());
@@ -300,10 +308,11 @@ pub(super) fn build_enum(config: &Config, en: &general::Enum, flavor: &AbiFlavor
 
     let discr_type = discr_type_for(config, en);
 
+    let namespace = namespace_of(&en.self_type.ty);
     let variants: Vec<TsVariant> = en
         .variants
         .iter()
-        .map(|variant| build_variant(config, variant))
+        .map(|variant| build_variant(config, variant, namespace))
         .collect();
 
     let force_async = config.force_async.is_forced(&en.name);
@@ -337,6 +346,8 @@ pub(super) fn build_enum(config: &Config, en: &general::Enum, flavor: &AbiFlavor
         uniffi_traits,
         constructors,
         methods,
+        generate_validators: config.generate_validators,
+        generate_json: config.generate_json,
     }
 }
 
@@ -345,10 +356,11 @@ pub(super) fn build_record(config: &Config, rec: &general::Record, flavor: &AbiF
     let ffi_converter_name = ffi_converter_name_for(config, &rec.self_type);
     let docstring = rec.docstring.as_deref().map(format_docstring);
 
+    let namespace = namespace_of(&rec.self_type.ty);
     let fields: Vec<TsField> = rec
         .fields
         .iter()
-        .map(|field| build_field(config, field))
+        .map(|field| build_field(config, field, namespace))
         .collect();
 
     // Suppress default TS factory helpers when Rust defines a constructor with the same name.
@@ -385,6 +397,8 @@ pub(super) fn build_record(config: &Config, rec: &general::Record, flavor: &AbiF
         uniffi_traits,
         constructors,
         methods,
+        generate_validators: config.generate_validators,
+        generate_json: config.generate_json,
     }
 }
 
@@ -437,6 +451,8 @@ pub(super) fn build_callable(
     force_async: bool,
 ) -> TsCallable {
     let name = fn_name(&callable.name);
+    let renders_async = callable.async_data.is_some() || force_async;
+    let trace_name = trace_name_for(config, callable, &name, renders_async);
     let arguments: Vec<TsArg> = callable
         .arguments
         .iter()
@@ -451,6 +467,7 @@ pub(super) fn build_callable(
             ffi_converter: ffi_converter_name_for(config, tn),
             ffi_type,
             is_rust_buffer,
+            is_external_bytes: config.returns_external_bytes(flavor, callable),
         }
     });
     let throws = callable
@@ -475,7 +492,40 @@ pub(super) fn build_callable(
         ffi_name: callable_ffi_name,
         ffi_async,
         receiver,
+        trace_name,
         force_async,
+        batched: false,
+    }
+}
+
+fn trace_name_for(
+    config: &Config,
+    callable: &general::Callable,
+    name: &str,
+    renders_async: bool,
+) -> String {
+    match &callable.kind {
+        general::CallableKind::Function => name.to_string(),
+        // A sync primary constructor is the class's `constructor`.
+        general::CallableKind::Constructor {
+            self_type,
+            primary: true,
+        } if !renders_async => {
+            format!("{}.constructor", trace_owner(config, self_type))
+        }
+        general::CallableKind::Method { self_type }
+        | general::CallableKind::Constructor { self_type, .. }
+        | general::CallableKind::VTableMethod { self_type } => {
+            format!("{}.{name}", trace_owner(config, self_type))
+        }
+    }
+}
+
+fn trace_owner(config: &Config, self_type: &general::TypeNode) -> String {
+    match &self_type.ty {
+        // The class, rather than its `Like` interface.
+        general::Type::Interface { name, .. } => name.to_upper_camel_case(),
+        ty => type_label_for(config, ty),
     }
 }
 
@@ -653,7 +703,11 @@ pub(super) fn build_object(
     let methods: Vec<TsMethod> = interface
         .methods
         .iter()
-        .map(|m| build_method_callable(config, m, &ffi_clone, flavor, force_async))
+        .map(|m| {
+            let mut built = build_method_callable(config, m, &ffi_clone, flavor, force_async);
+            built.batched = is_batched(config, flavor, Some(&interface.name), &m.callable, &built);
+            built
+        })
         .collect();
 
     let uniffi_traits = build_uniffi_traits(
@@ -854,11 +908,27 @@ pub(super) fn build_functions(
         .iter()
         .map(|f| {
             let force_async = config.force_async.is_forced(&f.callable.name);
-            build_callable(config, &f.callable, &f.docstring, None, flavor, force_async)
+            let mut built =
+                build_callable(config, &f.callable, &f.docstring, None, flavor, force_async);
+            built.batched = is_batched(config, flavor, None, &f.callable, &built);
+            built
         })
         .collect()
 }
 
+/// Whether the `batch` config gives `callable` a `{name}Batch` companion.
+/// It calls through the low-level module's `_batch` helper, which only the
+/// player flavors generate, and returns its results synchronously.
+fn is_batched(
+    config: &Config,
+    flavor: &AbiFlavor,
+    owner: Option<&str>,
+    callable: &general::Callable,
+    built: &TsCallable,
+) -> bool {
+    flavor.supports_player() && !built.renders_async() && config.is_batched(owner, &callable.name)
+}
+
 pub(super) fn build_initialization(
     namespace: &general::Namespace,
     flavor: &AbiFlavor,
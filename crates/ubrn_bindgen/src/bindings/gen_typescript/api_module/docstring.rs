@@ -24,6 +24,25 @@ pub(super) fn format_docstring_indented(docstring: &str) -> String {
     format_docstring_at(docstring, 4)
 }
 
+/// Recover the docstring from a block made by [`format_docstring_at`].
+pub(super) fn unformat_docstring(formatted: &str) -> String {
+    let lines: Vec<&str> = formatted.lines().map(str::trim_start).collect();
+    let inner = match lines.as_slice() {
+        [first, middle @ .., last] if *first == "/**" && *last == "*/" => middle,
+        _ => return formatted.to_string(),
+    };
+    inner
+        .iter()
+        .map(|line| {
+            line.strip_prefix("* ")
+                .or_else(|| line.strip_prefix('*'))
+                .unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace("*\\/", "*/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,4 +78,14 @@ mod tests {
             "Docs with */ in them."
         )));
     }
+
+    #[test]
+    fn unformat_round_trips() {
+        let docstring = "First line.\n\n  Indented, with */ in it.";
+        assert_eq!(unformat_docstring(&format_docstring(docstring)), docstring);
+        assert_eq!(
+            unformat_docstring(&format_docstring_indented(docstring)),
+            docstring
+        );
+    }
 }
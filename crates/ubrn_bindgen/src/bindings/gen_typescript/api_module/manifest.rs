@@ -0,0 +1,413 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+//! A JSON description of the TypeScript API a `TsApiModule` renders.
+//!
+//! Written alongside the `.ts` file when `apiManifest` is set, for tools
+//! that want the binding surface without parsing TypeScript. Names and types
+//! are as they appear in the generated TypeScript, not in the Rust.
+
+use serde::Serialize;
+
+use super::docstring::unformat_docstring;
+use super::nodes::*;
+use super::TsApiModule;
+
+/// Bumped whenever a field is removed or changes meaning.
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ApiManifest {
+    manifest_version: u32,
+    module: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docstring: Option<String>,
+    functions: Vec<ApiCallable>,
+    types: Vec<ApiType>,
+}
+
+#[derive(Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum ApiType {
+    Record {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        docstring: Option<String>,
+        fields: Vec<ApiField>,
+        constructors: Vec<ApiCallable>,
+        methods: Vec<ApiCallable>,
+        traits: Vec<&'static str>,
+    },
+    Enum {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        docstring: Option<String>,
+        /// Flat enums are a TypeScript `enum`; the others are tagged unions.
+        is_flat: bool,
+        variants: Vec<ApiVariant>,
+        constructors: Vec<ApiCallable>,
+        methods: Vec<ApiCallable>,
+        traits: Vec<&'static str>,
+    },
+    Error {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        docstring: Option<String>,
+        is_flat: bool,
+        variants: Vec<ApiVariant>,
+    },
+    Object {
+        name: String,
+        interface_name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        docstring: Option<String>,
+        is_error: bool,
+        constructors: Vec<ApiCallable>,
+        methods: Vec<ApiCallable>,
+        traits: Vec<&'static str>,
+    },
+    CallbackInterface {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        docstring: Option<String>,
+        methods: Vec<ApiCallable>,
+    },
+    Custom {
+        name: String,
+        /// `None` when a custom type config names a type it imports.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        ts_type: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiCallable {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docstring: Option<String>,
+    is_async: bool,
+    arguments: Vec<ApiArgument>,
+    /// As declared, so `Promise<T>` for async callables.
+    return_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    throws: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiArgument {
+    name: String,
+    ts_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_value: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiField {
+    name: String,
+    ts_type: String,
+    is_optional: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docstring: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiVariant {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    docstring: Option<String>,
+    fields: Vec<ApiField>,
+}
+
+impl ApiManifest {
+    pub(crate) fn new(module: &TsApiModule) -> Self {
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            module: module.module_name.clone(),
+            docstring: docstring(&module.namespace_docstring),
+            functions: module.functions.iter().map(ApiCallable::new).collect(),
+            types: module
+                .type_definitions
+                .iter()
+                .filter_map(ApiType::new)
+                .collect(),
+        }
+    }
+
+    pub(crate) fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+impl ApiType {
+    fn new(td: &TsTypeDefinition) -> Option<Self> {
+        Some(match td {
+            TsTypeDefinition::Record(r) => Self::Record {
+                name: r.ts_name.clone(),
+                docstring: docstring(&r.docstring),
+                fields: r.fields.iter().map(ApiField::new).collect(),
+                constructors: callables(&r.constructors),
+                methods: callables(&r.methods),
+                traits: traits(&r.uniffi_traits),
+            },
+            TsTypeDefinition::FlatEnum(e) | TsTypeDefinition::TaggedEnum(e) if !e.is_error => {
+                Self::Enum {
+                    name: e.ts_name.clone(),
+                    docstring: docstring(&e.docstring),
+                    is_flat: e.is_flat,
+                    variants: e.variants.iter().map(ApiVariant::new).collect(),
+                    constructors: callables(&e.constructors),
+                    methods: callables(&e.methods),
+                    traits: traits(&e.uniffi_traits),
+                }
+            }
+            TsTypeDefinition::FlatEnum(e)
+            | TsTypeDefinition::FlatError(e)
+            | TsTypeDefinition::TaggedEnum(e) => Self::Error {
+                name: e.ts_name.clone(),
+                docstring: docstring(&e.docstring),
+                is_flat: e.is_flat,
+                variants: e.variants.iter().map(ApiVariant::new).collect(),
+            },
+            TsTypeDefinition::Object(o) => {
+                // A synchronous primary constructor is the class's own
+                // `constructor`; an async one is a static factory.
+                let primary = o.primary_constructor.iter().map(|c| {
+                    let mut callable = ApiCallable::new(c);
+                    if !c.renders_async() {
+                        callable.name = "constructor".into();
+                    }
+                    callable
+                });
+                Self::Object {
+                    name: o.impl_class_name.clone(),
+                    interface_name: o.protocol_name.clone(),
+                    docstring: docstring(&o.docstring),
+                    is_error: o.is_error,
+                    constructors: primary
+                        .chain(o.alternate_constructors.iter().map(ApiCallable::new))
+                        .collect(),
+                    methods: callables(&o.methods),
+                    traits: traits(&o.uniffi_traits),
+                }
+            }
+            TsTypeDefinition::CallbackInterface(c) => Self::CallbackInterface {
+                name: c.ts_name.clone(),
+                docstring: docstring(&c.docstring),
+                methods: callables(&c.methods),
+            },
+            TsTypeDefinition::Custom(c) => Self::Custom {
+                name: c.type_name.clone(),
+                ts_type: match &c.custom_config {
+                    None => Some(c.builtin_type_name.clone()),
+                    Some(config) => config.concrete_type_name.clone(),
+                },
+            },
+            // Converters and imports, not part of this module's API.
+            TsTypeDefinition::SimpleWrapper(_)
+            | TsTypeDefinition::StringHelper(_)
+            | TsTypeDefinition::External(_) => return None,
+        })
+    }
+}
+
+impl ApiCallable {
+    fn new(c: &TsCallable) -> Self {
+        let return_type = c
+            .return_type
+            .as_ref()
+            .map_or("void", |rt| rt.ts_type.as_str());
+        Self {
+            name: c.name.clone(),
+            docstring: docstring(&c.docstring),
+            is_async: c.renders_async(),
+            arguments: c
+                .arguments
+                .iter()
+                .map(|a| ApiArgument {
+                    name: a.name.clone(),
+                    ts_type: a.ts_type.clone(),
+                    default_value: a.default_value.clone(),
+                })
+                .collect(),
+            return_type: if c.renders_async() {
+                format!("Promise<{return_type}>")
+            } else {
+                return_type.to_string()
+            },
+            throws: c.throws.as_ref().map(|t| t.decl_type_name.clone()),
+        }
+    }
+}
+
+impl ApiField {
+    fn new(f: &TsField) -> Self {
+        Self {
+            name: f.name.clone(),
+            ts_type: f.ts_type.clone(),
+            is_optional: f.is_optional,
+            default_value: f.default_value.clone(),
+            docstring: docstring(&f.docstring),
+        }
+    }
+}
+
+impl ApiVariant {
+    fn new(v: &TsVariant) -> Self {
+        Self {
+            name: v.name.clone(),
+            docstring: docstring(&v.docstring),
+            fields: v.fields.iter().map(ApiField::new).collect(),
+        }
+    }
+}
+
+fn callables(cs: &[TsCallable]) -> Vec<ApiCallable> {
+    cs.iter().map(ApiCallable::new).collect()
+}
+
+fn traits(ts: &[TsUniffiTrait]) -> Vec<&'static str> {
+    ts.iter()
+        .map(|t| match t {
+            TsUniffiTrait::Display { .. } => "Display",
+            TsUniffiTrait::Debug { .. } => "Debug",
+            TsUniffiTrait::Eq { .. } => "Eq",
+            TsUniffiTrait::Hash { .. } => "Hash",
+            TsUniffiTrait::Ord { .. } => "Ord",
+        })
+        .collect()
+}
+
+/// The nodes hold docstrings as rendered JSDoc blocks; tools want the text.
+fn docstring(ds: &Option<String>) -> Option<String> {
+    ds.as_deref().map(unformat_docstring)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    fn callable(name: &str, ffi_async: bool) -> TsCallable {
+        TsCallable {
+            name: name.into(),
+            docstring: Some("/**\n * Adds them up.\n */".into()),
+            arguments: vec![TsArg {
+                name: "left".into(),
+                ts_type: "number".into(),
+                ffi_converter: "FfiConverterInt32".into(),
+                default_value: Some("1".into()),
+            }],
+            return_type: Some(TsReturnType {
+                ts_type: "number".into(),
+                ffi_converter: "FfiConverterInt32".into(),
+                ffi_type: "number".into(),
+                is_rust_buffer: false,
+                is_external_bytes: false,
+            }),
+            throws: Some(TsErrorType {
+                lift_error_fn: String::new(),
+                lower_error_fn: String::new(),
+                decl_type_name: "ArithmeticError".into(),
+            }),
+            ffi_name: format!("ffi_{name}"),
+            ffi_async: ffi_async.then(|| TsAsyncFfi {
+                poll: "poll".into(),
+                complete: "complete".into(),
+                free: "free".into(),
+                cancel: "cancel".into(),
+            }),
+            receiver: None,
+            trace_name: name.into(),
+            force_async: false,
+            batched: false,
+        }
+    }
+
+    fn to_value(td: &TsTypeDefinition) -> Value {
+        serde_json::to_value(ApiType::new(td)).unwrap()
+    }
+
+    #[test]
+    fn callables_use_typescript_signatures() {
+        let value = serde_json::to_value(ApiCallable::new(&callable("add", true))).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "add",
+                "docstring": "Adds them up.",
+                "isAsync": true,
+                "arguments": [{ "name": "left", "tsType": "number", "defaultValue": "1" }],
+                "returnType": "Promise<number>",
+                "throws": "ArithmeticError",
+            })
+        );
+    }
+
+    #[test]
+    fn records_list_their_fields() {
+        let record = TsTypeDefinition::Record(TsRecord {
+            ts_name: "Point".into(),
+            ffi_converter_name: "FfiConverterTypePoint".into(),
+            docstring: None,
+            fields: vec![TsField {
+                name: "x".into(),
+                ts_type: "number".into(),
+                is_optional: false,
+                ffi_converter: "FfiConverterFloat64".into(),
+                default_value: None,
+                docstring: Some("    /**\n     * Across.\n     */".into()),
+                validator: "uniffiValidators.float64".into(),
+                json_codec: "uniffiJson.float64".into(),
+            }],
+            has_create_constructor: false,
+            has_new_constructor: false,
+            uniffi_traits: vec![],
+            constructors: vec![],
+            methods: vec![],
+            generate_validators: false,
+            generate_json: false,
+        });
+        assert_eq!(
+            to_value(&record),
+            json!({
+                "kind": "record",
+                "name": "Point",
+                "fields": [{
+                    "name": "x",
+                    "tsType": "number",
+                    "isOptional": false,
+                    "docstring": "Across.",
+                }],
+                "constructors": [],
+                "methods": [],
+                "traits": [],
+            })
+        );
+    }
+
+    #[test]
+    fn converters_are_not_part_of_the_api() {
+        let wrapper = TsTypeDefinition::SimpleWrapper(TsSimpleWrapper {
+            infra_class: "FfiConverterOptional".into(),
+            ffi_converter_name: "FfiConverterOptionalString".into(),
+            type_label: "string | undefined".into(),
+            inner_converters: vec![],
+        });
+        assert!(ApiType::new(&wrapper).is_none());
+    }
+}
@@ -12,6 +12,7 @@
 
 mod builders;
 mod docstring;
+mod manifest;
 mod nodes;
 mod type_helpers;
 
@@ -29,6 +30,8 @@ use self::builders::*;
 use self::docstring::format_docstring;
 use self::nodes::*;
 
+pub(crate) use self::manifest::ApiManifest;
+
 pub(crate) use self::nodes::{
     InitializationIR, TsCallable, TsCallbackInterface, TsCustomType, TsEnum, TsExternalType,
     TsFunction, TsObject, TsRecord, TsSimpleWrapper, TsTypeDefinition, TsUniffiTrait,
@@ -43,6 +46,7 @@ pub(crate) struct TsApiModule {
     pub is_verbose: bool,
     pub supports_rust_backtrace: bool,
     pub console_import: Option<String>,
+    pub tracer_import: Option<String>,
     pub file_imports: Vec<TsFileImport>,
     pub converter_imports: Vec<TsConverterImport>,
     pub exported_converters: BTreeSet<String>,
@@ -220,11 +224,27 @@ impl ImportAccumulator {
         {
             self.add_infra_value("uniffiCreateRecord");
         }
+        if e.generate_validators {
+            self.collect_validators();
+        }
+        if e.generate_json {
+            self.collect_json();
+        }
         self.collect_uniffi_traits(&e.uniffi_traits);
         self.collect_callables(&e.constructors);
         self.collect_callables(&e.methods);
     }
 
+    fn collect_validators(&mut self) {
+        self.add_infra_value("uniffiValidators");
+        self.add_infra_type("UniffiValidator");
+    }
+
+    fn collect_json(&mut self) {
+        self.add_infra_value("uniffiJson");
+        self.add_infra_type("UniffiJsonCodec");
+    }
+
     fn collect_record(&mut self, r: &TsRecord) {
         self.add_infra_value("uniffiCreateRecord");
         self.add_infra_value("AbstractFfiConverterByteArray");
@@ -234,6 +254,12 @@ impl ImportAccumulator {
         if r.has_callables() {
             self.add_infra_value("uniffiTypeNameSymbol");
         }
+        if r.generate_validators {
+            self.collect_validators();
+        }
+        if r.generate_json {
+            self.collect_json();
+        }
         self.collect_uniffi_traits(&r.uniffi_traits);
         self.collect_callables(&r.constructors);
         self.collect_callables(&r.methods);
@@ -443,6 +469,21 @@ impl TsApiModule {
         defs
     }
 
+    /// The low-level module's `_batch` helpers that the `{name}Batch`
+    /// companions call.
+    fn batch_helpers(&self) -> Vec<String> {
+        let methods = self.type_definitions.iter().flat_map(|td| match td {
+            TsTypeDefinition::Object(o) => o.methods.as_slice(),
+            _ => &[],
+        });
+        self.functions
+            .iter()
+            .chain(methods)
+            .filter(|c| c.batched)
+            .map(|c| format!("{}_batch", c.ffi_name))
+            .collect()
+    }
+
     fn collect_all_imports(&self) -> ImportAccumulator {
         let mut acc = ImportAccumulator::new();
         acc.collect_base_imports();
@@ -455,6 +496,11 @@ impl TsApiModule {
             acc.collect_callable(func);
         }
 
+        if self.tracer_import.is_some() {
+            acc.add_infra_value("uniffiTraceCall");
+            acc.add_infra_value("uniffiTraceCallAsync");
+        }
+
         if self.is_verbose {
             let has_async = self.functions.iter().any(|f| f.is_ffi_async())
                 || self.type_definitions.iter().any(|td| match td {
@@ -516,6 +562,7 @@ impl TsApiModule {
             is_verbose: config.is_verbose(),
             supports_rust_backtrace,
             console_import: config.console_import.clone(),
+            tracer_import: config.tracer_import.clone(),
             file_imports: Vec::new(),
             converter_imports: Vec::new(),
             exported_converters: BTreeSet::new(),
@@ -530,14 +577,15 @@ impl TsApiModule {
         // Build file imports: FFI types first, then cross-module imports
         let mut file_imports = Vec::new();
 
-        if !ffi_exported_definitions.is_empty() {
+        let batch_helpers = module.batch_helpers();
+        if !ffi_exported_definitions.is_empty() || !batch_helpers.is_empty() {
             file_imports.push(TsFileImport {
                 path: format!("./{}-ffi", module.module_name),
                 types: ffi_exported_definitions
                     .iter()
                     .map(|def| def.name().to_string())
                     .collect(),
-                values: Vec::new(),
+                values: batch_helpers,
             });
         }
 
@@ -656,7 +704,9 @@ mod force_async_validation_tests {
                 cancel: "cancel".into(),
             }),
             receiver: None,
+            trace_name: name.into(),
             force_async: false,
+            batched: false,
         }
     }
 
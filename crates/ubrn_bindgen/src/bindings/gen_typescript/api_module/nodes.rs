@@ -64,6 +64,10 @@ pub(crate) struct TsField {
     pub ffi_converter: String,
     pub default_value: Option<String>,
     pub docstring: Option<String>,
+    /// A `uniffiValidators` expression; only rendered with `generateValidators`.
+    pub validator: String,
+    /// A `uniffiJson` expression; only rendered with `generateJson`.
+    pub json_codec: String,
 }
 
 pub(crate) struct TsVariant {
@@ -86,6 +90,8 @@ pub(crate) struct TsEnum {
     pub uniffi_traits: Vec<TsUniffiTrait>,
     pub constructors: Vec<TsConstructor>,
     pub methods: Vec<TsMethod>,
+    pub generate_validators: bool,
+    pub generate_json: bool,
 }
 
 impl TsEnum {
@@ -109,6 +115,8 @@ pub(crate) struct TsRecord {
     pub uniffi_traits: Vec<TsUniffiTrait>,
     pub constructors: Vec<TsConstructor>,
     pub methods: Vec<TsMethod>,
+    pub generate_validators: bool,
+    pub generate_json: bool,
 }
 
 impl TsRecord {
@@ -137,9 +145,14 @@ pub(crate) struct TsCallable {
     pub ffi_name: String,
     pub ffi_async: Option<TsAsyncFfi>,
     pub receiver: Option<TsReceiver>,
+    /// The name reported to the tracer: `function`, or `Type.method`.
+    pub trace_name: String,
     /// `forceAsync` names this callable's owning type or function: give it an
     /// async signature over a synchronous FFI body.
     pub force_async: bool,
+    /// `batch` names this synchronous callable: it gets a `{name}Batch`
+    /// companion, which makes a whole list of calls in one crossing.
+    pub batched: bool,
 }
 
 impl TsCallable {
@@ -193,6 +206,10 @@ pub(crate) struct TsReturnType {
     /// booleans, pointers) flow through their converters' `lift` directly
     /// without a buffer to free.
     pub is_rust_buffer: bool,
+    /// True when the runtime returns the `RustBuffer` as a view it frees
+    /// itself once the view is collected (see `TsConfig::returns_external_bytes`):
+    /// the call body lifts the bytes in place and never frees them.
+    pub is_external_bytes: bool,
 }
 
 #[derive(Clone)]
@@ -104,6 +104,195 @@ pub(super) fn type_label_for(config: &Config, ty: &general::Type) -> String {
     }
 }
 
+/// The namespace a record, enum or other user type is declared in; empty
+/// for builtin types.
+pub(super) fn namespace_of(ty: &general::Type) -> &str {
+    match ty {
+        general::Type::Interface { namespace, .. }
+        | general::Type::Record { namespace, .. }
+        | general::Type::Enum { namespace, .. }
+        | general::Type::CallbackInterface { namespace, .. }
+        | general::Type::Custom { namespace, .. } => namespace,
+        _ => "",
+    }
+}
+
+/// An expression for the `uniffiValidators` validator of a type, for
+/// `generateValidators`.
+///
+/// Records and enums of this `namespace` refer to their own generated
+/// validators, lazily, since they may be declared further down. Types from
+/// other modules, and custom types with a config, cannot be checked here.
+pub(super) fn validator_for(config: &Config, ty: &general::Type, namespace: &str) -> String {
+    let v = |name: &str| format!("uniffiValidators.{name}");
+    match ty {
+        general::Type::UInt8 => v("uint8"),
+        general::Type::Int8 => v("int8"),
+        general::Type::UInt16 => v("uint16"),
+        general::Type::Int16 => v("int16"),
+        general::Type::UInt32 => v("uint32"),
+        general::Type::Int32 => v("int32"),
+        general::Type::UInt64 => v("uint64"),
+        general::Type::Int64 => v("int64"),
+        general::Type::Float32 => v("float32"),
+        general::Type::Float64 => v("float64"),
+        general::Type::Boolean => v("bool"),
+        general::Type::String => v("string"),
+        general::Type::Bytes => {
+            if config.strict_byte_arrays {
+                v("uint8Array")
+            } else {
+                v("arrayBuffer")
+            }
+        }
+        general::Type::Timestamp => v("timestamp"),
+        general::Type::Duration => v("duration"),
+        general::Type::Interface { .. } | general::Type::CallbackInterface { .. } => {
+            format!("{}(\"{}\")", v("object"), type_label_for(config, ty))
+        }
+        general::Type::Record {
+            namespace: ns,
+            name,
+        }
+        | general::Type::Enum {
+            namespace: ns,
+            name,
+        } => {
+            if ns != namespace {
+                return v("any");
+            }
+            let name = rewrite_js_builtins(&name.to_upper_camel_case());
+            format!("{}(\"{name}\", () => {name}Validator)", v("lazy"))
+        }
+        general::Type::Custom { name, builtin, .. } => {
+            if config.custom_types.contains_key(name) {
+                v("any")
+            } else {
+                validator_for(config, builtin, namespace)
+            }
+        }
+        general::Type::Optional { inner_type } => {
+            format!(
+                "{}({})",
+                v("optional"),
+                validator_for(config, inner_type, namespace)
+            )
+        }
+        general::Type::Sequence { inner_type } => {
+            format!(
+                "{}({})",
+                v("sequence"),
+                validator_for(config, inner_type, namespace)
+            )
+        }
+        general::Type::Map {
+            key_type,
+            value_type,
+        } => format!(
+            "{}({}, {})",
+            v("map"),
+            validator_for(config, key_type, namespace),
+            validator_for(config, value_type, namespace)
+        ),
+    }
+}
+
+/// An expression for the `uniffiJson` codec of a type, for `generateJson`.
+///
+/// Records and enums of this `namespace` refer to their own generated codecs,
+/// lazily. Custom types are encoded with their `toJson`/`fromJson` hooks if
+/// configured, otherwise as their builtin type. Objects, callback interfaces
+/// and types from other modules have no encoding.
+pub(super) fn json_codec_for(config: &Config, ty: &general::Type, namespace: &str) -> String {
+    let j = |name: &str| format!("uniffiJson.{name}");
+    let unsupported = || format!("{}(\"{}\")", j("unsupported"), type_label_for(config, ty));
+    match ty {
+        general::Type::UInt8 => j("uint8"),
+        general::Type::Int8 => j("int8"),
+        general::Type::UInt16 => j("uint16"),
+        general::Type::Int16 => j("int16"),
+        general::Type::UInt32 => j("uint32"),
+        general::Type::Int32 => j("int32"),
+        general::Type::UInt64 => j("uint64"),
+        general::Type::Int64 => j("int64"),
+        general::Type::Float32 => j("float32"),
+        general::Type::Float64 => j("float64"),
+        general::Type::Boolean => j("bool"),
+        general::Type::String => j("string"),
+        general::Type::Bytes => {
+            if config.strict_byte_arrays {
+                j("uint8Array")
+            } else {
+                j("arrayBuffer")
+            }
+        }
+        general::Type::Timestamp => j("timestamp"),
+        general::Type::Duration => j("duration"),
+        general::Type::Interface { .. } | general::Type::CallbackInterface { .. } => unsupported(),
+        general::Type::Record {
+            namespace: ns,
+            name,
+        }
+        | general::Type::Enum {
+            namespace: ns,
+            name,
+        } => {
+            if ns != namespace {
+                return unsupported();
+            }
+            let name = rewrite_js_builtins(&name.to_upper_camel_case());
+            format!("{}(() => {name}Json)", j("lazy"))
+        }
+        general::Type::Custom { name, builtin, .. } => {
+            let builtin = json_codec_for(config, builtin, namespace);
+            let Some(cfg) = config.custom_types.get(name) else {
+                return builtin;
+            };
+            match cfg.json_hooks("value", "json") {
+                Some((to_json, from_json)) => format!(
+                    "{}((value) => {to_json}, (json) => {from_json})",
+                    j("custom")
+                ),
+                None => format!(
+                    "{}({builtin}, (value) => {}, (intermediate) => {})",
+                    j("convert"),
+                    cfg.lower("value"),
+                    cfg.lift("intermediate")
+                ),
+            }
+        }
+        general::Type::Optional { inner_type } => {
+            format!(
+                "{}({})",
+                j("optional"),
+                json_codec_for(config, inner_type, namespace)
+            )
+        }
+        general::Type::Sequence { inner_type } => {
+            format!(
+                "{}({})",
+                j("sequence"),
+                json_codec_for(config, inner_type, namespace)
+            )
+        }
+        general::Type::Map {
+            key_type,
+            value_type,
+        } => {
+            let value = json_codec_for(config, value_type, namespace);
+            if matches!(**key_type, general::Type::String) {
+                format!("{}({value})", j("stringMap"))
+            } else {
+                format!(
+                    "{}({}, {value})",
+                    j("map"),
+                    json_codec_for(config, key_type, namespace)
+                )
+            }
+        }
+    }
+}
+
 static TS_KEYWORDS: LazyLock<HashSet<&'static str>> = LazyLock::new(|| {
     HashSet::from([
         "break",
@@ -5,8 +5,11 @@
  */
 use std::collections::HashMap;
 
-use heck::ToUpperCamelCase;
+use heck::{ToLowerCamelCase, ToUpperCamelCase};
 use serde::{Deserialize, Serialize};
+use uniffi_bindgen::pipeline::general;
+
+use crate::switches::AbiFlavor;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
@@ -15,6 +18,11 @@ pub(crate) struct TsConfig {
     pub(crate) log_level: LogLevel,
     #[serde(default)]
     pub(crate) console_import: Option<String>,
+    /// A module whose default export is a `UniffiTracer`. When set, every
+    /// call into Rust is reported to it; when not, no tracing code is
+    /// generated.
+    #[serde(default)]
+    pub(crate) tracer_import: Option<String>,
     #[serde(default)]
     pub(crate) custom_types: HashMap<String, CustomTypeConfig>,
     #[serde(default)]
@@ -27,12 +35,36 @@ pub(crate) struct TsConfig {
     /// When `true`, emit byte arrays (`Vec<u8>`) as `Uint8Array` instead of `ArrayBuffer`.
     #[serde(default)]
     pub(crate) strict_byte_arrays: bool,
+    /// When `true`, with `strictByteArrays`, the napi flavor returns the
+    /// bytes of synchronous calls as views over Rust's buffer, freed once
+    /// the view is garbage collected, rather than as copies.
+    #[serde(default)]
+    pub(crate) external_byte_arrays: bool,
     /// Give the named types and functions — or everything, when `true` — an
     /// `async`/`Promise<T>` surface. The FFI calls underneath stay
     /// synchronous: this is a migration aid toward moving them off the main
     /// thread.
     #[serde(default)]
     pub(crate) force_async: ForceAsync,
+    /// When `true`, write a JSON description of each module's API beside
+    /// its `.ts` file, for docs and lint tooling.
+    #[serde(default)]
+    pub(crate) api_manifest: bool,
+    /// When `true`, emit a runtime validator for each record and enum, for
+    /// checking values that did not come from Rust.
+    #[serde(default)]
+    pub(crate) generate_validators: bool,
+    /// When `true`, emit a JSON codec for each record and enum, with
+    /// `toJSON` and `fromJSON` functions.
+    #[serde(default)]
+    pub(crate) generate_json: bool,
+    /// Functions and methods, as `function` or `Type.method`, given a
+    /// `{ffi_name}_batch` helper in the napi player's low-level module. The
+    /// helper makes a whole list of calls in one crossing into Rust. Sync
+    /// functions and object methods also get a `{name}Batch` companion in
+    /// the generated API, which lowers and lifts around the helper.
+    #[serde(default)]
+    pub(crate) batch: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -60,6 +92,46 @@ impl TsConfig {
     pub(crate) fn is_debug(&self) -> bool {
         self.log_level.is_debug()
     }
+
+    /// Whether the `batch` list names the top-level function `name`, or the
+    /// method `name` of the type `owner`.
+    ///
+    /// Names are normalized as for `forceAsync`: types to UpperCamelCase,
+    /// functions and methods to lowerCamelCase.
+    pub(crate) fn is_batched(&self, owner: Option<&str>, name: &str) -> bool {
+        let name = name.to_lower_camel_case();
+        let owner = owner.map(ToUpperCamelCase::to_upper_camel_case);
+        self.batch.iter().any(|entry| match entry.split_once('.') {
+            Some((ty, method)) => {
+                owner.as_deref() == Some(ty.to_upper_camel_case().as_str())
+                    && method.to_lower_camel_case() == name
+            }
+            None => owner.is_none() && entry.to_lower_camel_case() == name,
+        })
+    }
+
+    /// Whether `externalByteArrays` applies: it needs the `Uint8Array`
+    /// surface of `strictByteArrays`, and only the napi flavor has it.
+    pub(crate) fn uses_external_byte_arrays(&self, flavor: &AbiFlavor) -> bool {
+        matches!(flavor, AbiFlavor::Napi) && self.strict_byte_arrays && self.external_byte_arrays
+    }
+
+    /// Whether a call to `callable` hands its `Vec<u8>` result to JS as a view
+    /// over Rust's memory instead of a copy. Only synchronous calls do this,
+    /// under `externalByteArrays`: the runtime frees the buffer once the view
+    /// is garbage collected, so the generated code must not free it.
+    pub(crate) fn returns_external_bytes(
+        &self,
+        flavor: &AbiFlavor,
+        callable: &general::Callable,
+    ) -> bool {
+        self.uses_external_byte_arrays(flavor)
+            && callable.async_data.is_none()
+            && matches!(
+                callable.return_type.ty.as_ref().map(|tn| &tn.ty),
+                Some(general::Type::Bytes)
+            )
+    }
 }
 
 /// `forceAsync` config value: a bool (all / nothing) or an explicit name list.
@@ -105,6 +177,13 @@ pub(crate) struct CustomTypeConfig {
     pub(crate) into_custom: String,
     #[serde(alias = "lower")]
     pub(crate) from_custom: String,
+    /// Expressions to convert between the custom type and JSON, for
+    /// `generateJson`. Without them, the custom type is encoded as its
+    /// builtin type.
+    #[serde(default)]
+    pub(crate) to_json: Option<String>,
+    #[serde(default)]
+    pub(crate) from_json: Option<String>,
 }
 
 impl CustomTypeConfig {
@@ -114,6 +193,12 @@ impl CustomTypeConfig {
     pub(crate) fn lower(&self, variable: &str) -> String {
         self.from_custom.replace("{}", variable)
     }
+    /// The `toJson` and `fromJson` expressions, if both are given.
+    pub(crate) fn json_hooks(&self, value: &str, json: &str) -> Option<(String, String)> {
+        let to_json = self.to_json.as_ref()?;
+        let from_json = self.from_json.as_ref()?;
+        Some((to_json.replace("{}", value), from_json.replace("{}", json)))
+    }
 }
 
 #[cfg(test)]
@@ -161,3 +246,83 @@ mod force_async_tests {
         assert!(!default.force_async.is_forced("Whatever"));
     }
 }
+
+#[cfg(test)]
+mod custom_type_tests {
+    use super::*;
+
+    fn custom_type(toml: &str) -> CustomTypeConfig {
+        let config: TsConfig = toml::from_str(toml).unwrap();
+        config.custom_types["Millis"].clone()
+    }
+
+    #[test]
+    fn json_hooks_substitute_the_variable() {
+        let cfg = custom_type(
+            r#"
+            [customTypes.Millis]
+            lift = "new Date(Number({}))"
+            lower = "BigInt({}.getTime())"
+            toJson = "{}.toISOString()"
+            fromJson = "new Date({})"
+            "#,
+        );
+        assert_eq!(
+            cfg.json_hooks("value", "json"),
+            Some(("value.toISOString()".into(), "new Date(json)".into()))
+        );
+    }
+
+    #[test]
+    fn json_hooks_need_both_directions() {
+        let cfg = custom_type(
+            r#"
+            [customTypes.Millis]
+            lift = "new Date(Number({}))"
+            lower = "BigInt({}.getTime())"
+            toJson = "{}.toISOString()"
+            "#,
+        );
+        assert_eq!(cfg.json_hooks("value", "json"), None);
+    }
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+
+    #[test]
+    fn matches_functions_and_methods_across_case_conventions() {
+        let cfg: TsConfig = toml::from_str(r#"batch = ["push_record", "store.addItem"]"#).unwrap();
+        assert!(cfg.is_batched(None, "push_record"));
+        assert!(cfg.is_batched(None, "pushRecord"));
+        assert!(cfg.is_batched(Some("Store"), "add_item"));
+        assert!(!cfg.is_batched(Some("Store"), "push_record"));
+        assert!(!cfg.is_batched(None, "add_item"));
+        assert!(!cfg.is_batched(Some("Other"), "add_item"));
+    }
+
+    #[test]
+    fn default_batches_nothing() {
+        assert!(!TsConfig::default().is_batched(None, "anything"));
+    }
+}
+
+#[cfg(test)]
+mod external_byte_arrays_tests {
+    use super::*;
+
+    #[test]
+    fn needs_its_own_key_and_strict_byte_arrays() {
+        let strict_only: TsConfig = toml::from_str("strictByteArrays = true").unwrap();
+        assert!(!strict_only.uses_external_byte_arrays(&AbiFlavor::Napi));
+
+        let external_only: TsConfig = toml::from_str("externalByteArrays = true").unwrap();
+        assert!(!external_only.uses_external_byte_arrays(&AbiFlavor::Napi));
+
+        let both: TsConfig =
+            toml::from_str("strictByteArrays = true\nexternalByteArrays = true").unwrap();
+        assert!(both.uses_external_byte_arrays(&AbiFlavor::Napi));
+        assert!(!both.uses_external_byte_arrays(&AbiFlavor::Jsi));
+    }
+}
@@ -4,10 +4,13 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 
+use std::collections::HashSet;
+
 use heck::ToLowerCamelCase;
 use uniffi_bindgen::pipeline::general;
 
 use super::nodes::*;
+use super::spec_bytes::SpecEncoder;
 use super::type_mapping::ffi_type_to_player;
 use crate::bindings::gen_typescript::config::TsConfig;
 use crate::bindings::gen_typescript::ffi_module::type_mapping::ffi_type_to_ts;
@@ -25,14 +28,28 @@ impl PlayerFfiModule {
     ) -> Self {
         let has_async = namespace_has_async(namespace);
 
+        // The same definitions are also written in the runtime's binary
+        // encoding, which is what the napi flavor hands to `register()`.
+        let mut encoder = SpecEncoder::new();
         let symbols = Self::build_symbols(namespace);
-        let functions = Self::build_functions(namespace, has_async);
-        let callbacks = Self::build_callbacks(namespace);
-        let structs = Self::build_structs(namespace);
+        let external_returns = Self::callable_ffi_names(namespace, |_, callable| {
+            config.returns_external_bytes(flavor, callable)
+        });
+        let functions =
+            Self::build_functions(namespace, has_async, &external_returns, &mut encoder);
+        let callbacks = Self::build_callbacks(namespace, &mut encoder);
+        let structs = Self::build_structs(namespace, &mut encoder);
+        let api_checks = Self::build_api_checks(namespace, &mut encoder);
+        let spec_bytes = encoder.finish(
+            &symbols.rustbuffer_alloc,
+            &symbols.rustbuffer_free,
+            &symbols.rustbuffer_from_bytes,
+        );
 
         // Reuse TsFfiModule for the typed interface, but we'll use raw
         // symbol names (no ubrn_ prefix) in the NativeModuleInterface.
         let ts_module = Self::build_typed_module(namespace, has_async);
+        let batched_functions = Self::build_batched_functions(namespace, config, has_async);
 
         Self {
             strict_type_checking: config.strict_type_checking,
@@ -43,8 +60,11 @@ impl PlayerFfiModule {
             functions,
             callbacks,
             structs,
+            api_checks,
+            spec_bytes,
             typed_functions: ts_module.functions,
             typed_definitions: ts_module.definitions,
+            batched_functions,
         }
     }
 
@@ -56,23 +76,66 @@ impl PlayerFfiModule {
         }
     }
 
-    fn build_functions(namespace: &general::Namespace, has_async: bool) -> Vec<PlayerFunctionDef> {
+    /// The contract version and API checksums this module was generated with,
+    /// which the runtime compares with the library's before registering.
+    fn build_api_checks(
+        namespace: &general::Namespace,
+        encoder: &mut SpecEncoder,
+    ) -> PlayerApiChecks {
+        let contract_version = namespace
+            .correct_contract_version
+            .parse()
+            .expect("uniffi contract version is a u32");
+        let checks = PlayerApiChecks {
+            contract_version_symbol: namespace.ffi_uniffi_contract_version.0.clone(),
+            contract_version,
+            checksums: namespace
+                .checksums
+                .iter()
+                .map(|c| (c.fn_name.0.clone(), c.checksum))
+                .collect(),
+        };
+        let checksums: Vec<(&str, u16)> = checks
+            .checksums
+            .iter()
+            .map(|(symbol, checksum)| (symbol.as_str(), *checksum))
+            .collect();
+        encoder.api_checks(
+            &checks.contract_version_symbol,
+            checks.contract_version,
+            &checksums,
+        );
+        checks
+    }
+
+    fn build_functions(
+        namespace: &general::Namespace,
+        has_async: bool,
+        external_returns: &HashSet<String>,
+        encoder: &mut SpecEncoder,
+    ) -> Vec<PlayerFunctionDef> {
         let mut result = Vec::new();
 
         for def in &namespace.ffi_definitions {
             if let general::FfiDefinition::RustFunction(func) = def {
                 if TsFfiModule::should_include_function(func, has_async) {
-                    let args: Vec<String> = func
-                        .arguments
-                        .iter()
-                        .map(|arg| ffi_type_to_player(&arg.ty.ty))
-                        .collect();
-
-                    let ret = func
-                        .return_type
-                        .ty
-                        .as_ref()
-                        .map(|rt| ffi_type_to_player(&rt.ty))
+                    let arg_types: Vec<&general::FfiType> =
+                        func.arguments.iter().map(|arg| &arg.ty.ty).collect();
+                    let ret_type = func.return_type.ty.as_ref().map(|rt| &rt.ty);
+                    let external_return = external_returns.contains(&func.name.0);
+                    encoder.function(
+                        &func.name.0,
+                        &arg_types,
+                        ret_type,
+                        func.has_rust_call_status_arg,
+                        external_return,
+                    );
+
+                    let args: Vec<String> =
+                        arg_types.iter().map(|ty| ffi_type_to_player(ty)).collect();
+
+                    let ret = ret_type
+                        .map(ffi_type_to_player)
                         .unwrap_or_else(|| "FfiType.Void".into());
 
                     result.push(PlayerFunctionDef {
@@ -80,6 +143,7 @@ impl PlayerFfiModule {
                         args,
                         ret,
                         has_rust_call_status: func.has_rust_call_status_arg,
+                        external_return,
                     });
                 }
             }
@@ -88,7 +152,10 @@ impl PlayerFfiModule {
         result
     }
 
-    fn build_callbacks(namespace: &general::Namespace) -> Vec<PlayerCallbackDef> {
+    fn build_callbacks(
+        namespace: &general::Namespace,
+        encoder: &mut SpecEncoder,
+    ) -> Vec<PlayerCallbackDef> {
         let mut result = Vec::new();
 
         for def in &namespace.ffi_definitions {
@@ -97,44 +164,53 @@ impl PlayerFfiModule {
                     a.name == "uniffi_out_return" || a.name == "uniffi_out_dropped_callback"
                 });
 
-                let args: Vec<String> = ft
+                let arg_types: Vec<&general::FfiType> = ft
                     .arguments
                     .iter()
                     .filter(|a| {
                         a.name != "uniffi_out_return" && a.name != "uniffi_out_dropped_callback"
                     })
-                    .map(|arg| ffi_type_to_player(&arg.ty.ty))
+                    .map(|arg| &arg.ty.ty)
                     .collect();
 
-                let ret = if has_out_return {
+                let ret_type = if has_out_return {
                     ft.arguments
                         .iter()
                         .find(|a| {
                             a.name == "uniffi_out_return" || a.name == "uniffi_out_dropped_callback"
                         })
-                        .map(|a| {
-                            let inner = match &a.ty.ty {
-                                general::FfiType::Reference(t)
-                                | general::FfiType::MutReference(t) => t.as_ref(),
-                                t => t,
-                            };
-                            ffi_type_to_player(inner)
+                        .map(|a| match &a.ty.ty {
+                            general::FfiType::Reference(t) | general::FfiType::MutReference(t) => {
+                                t.as_ref()
+                            }
+                            t => t,
                         })
-                        .unwrap_or_else(|| "FfiType.Void".into())
                 } else {
-                    ft.return_type
-                        .ty
-                        .as_ref()
-                        .map(|rt| ffi_type_to_player(&rt.ty))
-                        .unwrap_or_else(|| "FfiType.Void".into())
+                    ft.return_type.ty.as_ref().map(|rt| &rt.ty)
                 };
 
+                let once = is_called_once(&ft.name);
+                encoder.callback(
+                    &ft.name.0,
+                    &arg_types,
+                    ret_type,
+                    ft.has_rust_call_status_arg,
+                    has_out_return,
+                    once,
+                );
+
+                let args: Vec<String> = arg_types.iter().map(|ty| ffi_type_to_player(ty)).collect();
+                let ret = ret_type
+                    .map(ffi_type_to_player)
+                    .unwrap_or_else(|| "FfiType.Void".into());
+
                 result.push(PlayerCallbackDef {
                     name: ft.name.0.clone(),
                     args,
                     ret,
                     has_rust_call_status: ft.has_rust_call_status_arg,
                     out_return: has_out_return,
+                    once,
                 });
             }
         }
@@ -142,11 +218,21 @@ impl PlayerFfiModule {
         result
     }
 
-    fn build_structs(namespace: &general::Namespace) -> Vec<PlayerStructDef> {
+    fn build_structs(
+        namespace: &general::Namespace,
+        encoder: &mut SpecEncoder,
+    ) -> Vec<PlayerStructDef> {
         let mut result = Vec::new();
 
         for def in &namespace.ffi_definitions {
             if let general::FfiDefinition::Struct(s) = def {
+                let field_types: Vec<(&str, &general::FfiType)> = s
+                    .fields
+                    .iter()
+                    .map(|f| (f.name.as_str(), &f.ty.ty))
+                    .collect();
+                encoder.structure(&s.name.0, &field_types);
+
                 let fields: Vec<PlayerFieldDef> = s
                     .fields
                     .iter()
@@ -166,6 +252,67 @@ impl PlayerFfiModule {
         result
     }
 
+    /// The FFI functions behind the callables named in the `batch` config.
+    fn build_batched_functions(
+        namespace: &general::Namespace,
+        config: &TsConfig,
+        has_async: bool,
+    ) -> Vec<FfiFunctionDecl> {
+        if config.batch.is_empty() {
+            return Vec::new();
+        }
+
+        let ffi_names = Self::callable_ffi_names(namespace, |owner, callable| {
+            config.is_batched(owner, &callable.name)
+        });
+
+        namespace
+            .ffi_definitions
+            .iter()
+            .filter_map(|def| match def {
+                general::FfiDefinition::RustFunction(func)
+                    if ffi_names.contains(&func.name.0)
+                        && TsFfiModule::should_include_function(func, has_async) =>
+                {
+                    Some(Self::map_ffi_function_for_player(func))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The FFI function names of the top-level functions, constructors and
+    /// methods accepted by `filter`, which is given the owning type's name.
+    fn callable_ffi_names(
+        namespace: &general::Namespace,
+        filter: impl Fn(Option<&str>, &general::Callable) -> bool,
+    ) -> HashSet<String> {
+        let mut ffi_names = HashSet::new();
+        let mut collect = |owner: Option<&str>, callable: &general::Callable| {
+            if filter(owner, callable) {
+                ffi_names.insert(callable.ffi_func.0.clone());
+            }
+        };
+        for func in &namespace.functions {
+            collect(None, &func.callable);
+        }
+        for def in &namespace.type_definitions {
+            let (name, constructors, methods) = match def {
+                general::TypeDefinition::Interface(i) => (&i.name, &i.constructors, &i.methods),
+                general::TypeDefinition::Record(r) => (&r.name, &r.constructors, &r.methods),
+                general::TypeDefinition::Enum(e) => (&e.name, &e.constructors, &e.methods),
+                _ => continue,
+            };
+            for cons in constructors {
+                collect(Some(name), &cons.callable);
+            }
+            for meth in methods {
+                collect(Some(name), &meth.callable);
+            }
+        }
+        ffi_names
+    }
+
     /// Build a `TsFfiModule` for the player, using raw symbol names
     /// (no `ubrn_` prefix) so the interface matches what `register()` returns.
     fn build_typed_module(namespace: &general::Namespace, has_async: bool) -> TsFfiModule {
@@ -228,3 +375,14 @@ impl PlayerFfiModule {
         }
     }
 }
+
+/// Whether UniFFI calls each pointer it is handed for this callback exactly once: the
+/// continuation a Rust future is polled with, and the callback Rust calls when it
+/// drops a foreign future. The runtime frees their trampolines once the JS function
+/// is collected, as it cannot for any other callback.
+fn is_called_once(name: &general::FfiFunctionTypeName) -> bool {
+    matches!(
+        name.0.as_str(),
+        "RustFutureContinuationCallback" | "ForeignFutureDroppedCallback"
+    )
+}
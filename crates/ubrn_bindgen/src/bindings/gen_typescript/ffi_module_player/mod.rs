@@ -6,15 +6,17 @@
 
 mod builder;
 mod nodes;
+mod spec_bytes;
 mod type_mapping;
 
 pub(crate) use nodes::PlayerFfiModule;
 pub use nodes::{LibResolution, TripleStyle};
+pub use spec_bytes::SpecEncoder;
 
 /// Render a minimal player template for snapshot testing. Hidden from API docs.
 #[doc(hidden)]
 pub fn render_minimal_for_test(lib_resolution: LibResolution, crate_name: &str) -> String {
-    use nodes::{PlayerFfiModule, PlayerSymbols};
+    use nodes::{PlayerApiChecks, PlayerFfiModule, PlayerSymbols};
     let module = PlayerFfiModule {
         strict_type_checking: false,
         flavor: crate::AbiFlavor::Napi,
@@ -28,8 +30,40 @@ pub fn render_minimal_for_test(lib_resolution: LibResolution, crate_name: &str)
         functions: Vec::new(),
         callbacks: Vec::new(),
         structs: Vec::new(),
+        api_checks: PlayerApiChecks::empty_for_test(),
+        spec_bytes: SpecEncoder::new().finish(
+            "ubrn_test_alloc",
+            "ubrn_test_free",
+            "ubrn_test_from_bytes",
+        ),
         typed_functions: Vec::new(),
         typed_definitions: Vec::new(),
+        batched_functions: Vec::new(),
     };
     super::generate_player_lowlevel_code(module).expect("render")
 }
+
+/// Render the player template with one function, `fn_name`, in the `batch`
+/// config, for snapshot testing its `_batch` helper. Hidden from API docs.
+#[doc(hidden)]
+pub fn render_batched_for_test(fn_name: &str) -> String {
+    use super::ffi_module::{FfiArgDecl, FfiFunctionDecl};
+    let decl = || FfiFunctionDecl {
+        name: fn_name.to_string(),
+        arguments: vec![
+            FfiArgDecl {
+                name: "value".into(),
+                type_name: "number".into(),
+            },
+            FfiArgDecl {
+                name: "uniffi_out_err".into(),
+                type_name: "UniffiRustCallStatus".into(),
+            },
+        ],
+        return_type: Some("number".into()),
+    };
+    let mut module = nodes::PlayerFfiModule::empty_for_test(crate::AbiFlavor::Napi);
+    module.typed_functions.push(decl());
+    module.batched_functions.push(decl());
+    super::generate_player_lowlevel_code(module).expect("render")
+}
@@ -28,7 +28,8 @@ pub enum LibResolution {
 }
 
 /// Which platform-triple naming convention the consuming npm packages use.
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TripleStyle {
     /// `aarch64-apple-darwin`, `x86_64-unknown-linux-gnu`, … (cargo `--target`).
     #[default]
@@ -71,11 +72,21 @@ pub(crate) struct PlayerFfiModule {
     pub callbacks: Vec<PlayerCallbackDef>,
     /// Struct registrations for `register({ structs: { ... } })`.
     pub structs: Vec<PlayerStructDef>,
+    /// What `register({ apiChecks: { ... } })` verifies against the library
+    /// before exposing any function.
+    pub api_checks: PlayerApiChecks,
+    /// `symbols`, `functions`, `callbacks`, `structs` and `api_checks` in the
+    /// runtime's binary spec encoding. The napi flavor passes these bytes to `register()` in place
+    /// of the `DEFINITIONS` object.
+    pub spec_bytes: Vec<u8>,
     /// Functions for the `NativeModuleInterface` TypeScript type.
     /// Uses the same IR as the JSI ffi module (for rendering the interface).
     pub typed_functions: Vec<super::super::ffi_module::FfiFunctionDecl>,
     /// Definitions (callbacks/structs) for TypeScript type exports.
     pub typed_definitions: Vec<super::super::ffi_module::FfiDefinitionDecl>,
+    /// The `typed_functions` named in the `batch` config, each of which gets a
+    /// `{name}_batch` helper.
+    pub batched_functions: Vec<super::super::ffi_module::FfiFunctionDecl>,
 }
 
 pub(crate) struct PlayerSymbols {
@@ -84,6 +95,27 @@ pub(crate) struct PlayerSymbols {
     pub rustbuffer_from_bytes: String,
 }
 
+pub(crate) struct PlayerApiChecks {
+    /// The raw symbol of the function returning the library's uniffi contract
+    /// version.
+    pub contract_version_symbol: String,
+    /// The contract version the bindings were generated for.
+    pub contract_version: u32,
+    /// Each checksum function's raw symbol, and the value it must return.
+    pub checksums: Vec<(String, u16)>,
+}
+
+impl PlayerApiChecks {
+    /// Checks with no checksums, for the codegen snapshot tests.
+    pub(super) fn empty_for_test() -> Self {
+        Self {
+            contract_version_symbol: "ffi_ubrn_test_uniffi_contract_version".into(),
+            contract_version: 29,
+            checksums: Vec::new(),
+        }
+    }
+}
+
 pub(crate) struct PlayerFunctionDef {
     /// The raw FFI symbol name (e.g. "uniffi_arithmetical_fn_func_add").
     pub name: String,
@@ -93,6 +125,9 @@ pub(crate) struct PlayerFunctionDef {
     pub ret: String,
     /// Whether this function has a trailing RustCallStatus argument.
     pub has_rust_call_status: bool,
+    /// Whether the runtime returns this function's `RustBuffer` as a view it
+    /// frees once the view is garbage collected.
+    pub external_return: bool,
 }
 
 pub(crate) struct PlayerCallbackDef {
@@ -106,6 +141,8 @@ pub(crate) struct PlayerCallbackDef {
     pub has_rust_call_status: bool,
     /// Whether this callback uses the out-return convention.
     pub out_return: bool,
+    /// Whether the library calls each pointer it is handed for this callback exactly once.
+    pub once: bool,
 }
 
 pub(crate) struct PlayerStructDef {
@@ -140,8 +177,24 @@ impl PlayerFfiModule {
             functions: Vec::new(),
             callbacks: Vec::new(),
             structs: Vec::new(),
+            api_checks: PlayerApiChecks::empty_for_test(),
+            spec_bytes: Vec::new(),
             typed_functions: Vec::new(),
             typed_definitions: Vec::new(),
+            batched_functions: Vec::new(),
         }
     }
+
+    /// `spec_bytes` as the body of a `Uint8Array` literal, in rows short enough
+    /// to keep the generated file reviewable.
+    pub fn spec_bytes_literal(&self) -> String {
+        self.spec_bytes
+            .chunks(24)
+            .map(|row| {
+                let row: Vec<String> = row.iter().map(u8::to_string).collect();
+                format!("  {},", row.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
@@ -0,0 +1,231 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+//! Writes the player's FFI definitions in the binary encoding that
+//! `uniffi_runtime_core::spec::binary::decode` reads.
+//!
+//! The layout is documented, and owned, by that module; this is a second
+//! writer for it that works from `general::FfiType` rather than the runtime's
+//! `FfiTypeDesc`, so codegen does not have to depend on the runtime. The
+//! `player_spec_bytes` integration test decodes our output with the runtime to
+//! keep the two in step.
+
+use uniffi_bindgen::pipeline::general;
+
+const MAGIC: &[u8; 4] = b"UBSP";
+const FORMAT_VERSION: u16 = 3;
+
+const FLAG_RUST_CALL_STATUS: u8 = 0b01;
+const FLAG_OUT_RETURN: u8 = 0b10;
+const FLAG_ONCE: u8 = 0b100;
+const FLAG_EXTERNAL_RETURN: u8 = 0b1000;
+
+const TAG_UINT8: u8 = 0;
+const TAG_INT8: u8 = 1;
+const TAG_UINT16: u8 = 2;
+const TAG_INT16: u8 = 3;
+const TAG_UINT32: u8 = 4;
+const TAG_INT32: u8 = 5;
+const TAG_UINT64: u8 = 6;
+const TAG_INT64: u8 = 7;
+const TAG_FLOAT32: u8 = 8;
+const TAG_FLOAT64: u8 = 9;
+const TAG_HANDLE: u8 = 10;
+const TAG_RUST_BUFFER: u8 = 11;
+const TAG_FOREIGN_BYTES: u8 = 12;
+const TAG_RUST_CALL_STATUS: u8 = 13;
+const TAG_VOID_POINTER: u8 = 14;
+const TAG_VOID: u8 = 15;
+const TAG_CALLBACK: u8 = 16;
+const TAG_STRUCT: u8 = 17;
+const TAG_REFERENCE: u8 = 18;
+const TAG_MUT_REFERENCE: u8 = 19;
+
+/// Accumulates functions, callbacks and structs in any order, and lays them
+/// out in the encoding's section order on [`finish`](Self::finish).
+///
+/// A `None` return type stands for `Void`.
+#[derive(Default)]
+pub struct SpecEncoder {
+    functions: Section,
+    callbacks: Section,
+    structs: Section,
+    api_checks: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct Section {
+    count: u32,
+    bytes: Vec<u8>,
+}
+
+impl SpecEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn function(
+        &mut self,
+        name: &str,
+        args: &[&general::FfiType],
+        ret: Option<&general::FfiType>,
+        has_rust_call_status: bool,
+        external_return: bool,
+    ) {
+        let out = self.functions.entry();
+        write_str(out, name);
+        write_signature(out, args, ret);
+        let mut flags = 0;
+        if has_rust_call_status {
+            flags |= FLAG_RUST_CALL_STATUS;
+        }
+        if external_return {
+            flags |= FLAG_EXTERNAL_RETURN;
+        }
+        out.push(flags);
+    }
+
+    pub fn callback(
+        &mut self,
+        name: &str,
+        args: &[&general::FfiType],
+        ret: Option<&general::FfiType>,
+        has_rust_call_status: bool,
+        out_return: bool,
+        once: bool,
+    ) {
+        let out = self.callbacks.entry();
+        write_str(out, name);
+        write_signature(out, args, ret);
+        let mut flags = 0;
+        if has_rust_call_status {
+            flags |= FLAG_RUST_CALL_STATUS;
+        }
+        if out_return {
+            flags |= FLAG_OUT_RETURN;
+        }
+        if once {
+            flags |= FLAG_ONCE;
+        }
+        out.push(flags);
+    }
+
+    pub fn structure(&mut self, name: &str, fields: &[(&str, &general::FfiType)]) {
+        let out = self.structs.entry();
+        write_str(out, name);
+        write_count(out, fields.len());
+        for (field_name, ty) in fields {
+            write_str(out, field_name);
+            write_type(out, ty);
+        }
+    }
+
+    /// The contract version and API checksums the runtime verifies against the
+    /// library before registering anything. Each checksum is keyed by the
+    /// symbol that returns it.
+    pub fn api_checks(
+        &mut self,
+        contract_version_symbol: &str,
+        contract_version: u32,
+        checksums: &[(&str, u16)],
+    ) {
+        let mut out = Vec::new();
+        write_str(&mut out, contract_version_symbol);
+        out.extend_from_slice(&contract_version.to_le_bytes());
+        write_count(&mut out, checksums.len());
+        for (symbol, checksum) in checksums {
+            write_str(&mut out, symbol);
+            out.extend_from_slice(&checksum.to_le_bytes());
+        }
+        self.api_checks = Some(out);
+    }
+
+    /// Produce the complete encoding, headed by the RustBuffer symbol names.
+    pub fn finish(self, alloc: &str, free: &str, from_bytes: &str) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        write_str(&mut out, alloc);
+        write_str(&mut out, free);
+        write_str(&mut out, from_bytes);
+        for section in [self.functions, self.callbacks, self.structs] {
+            out.extend_from_slice(&section.count.to_le_bytes());
+            out.extend_from_slice(&section.bytes);
+        }
+        match self.api_checks {
+            None => out.push(0),
+            Some(bytes) => {
+                out.push(1);
+                out.extend_from_slice(&bytes);
+            }
+        }
+        out
+    }
+}
+
+impl Section {
+    fn entry(&mut self) -> &mut Vec<u8> {
+        self.count += 1;
+        &mut self.bytes
+    }
+}
+
+fn write_count(out: &mut Vec<u8>, n: usize) {
+    let n = u32::try_from(n).expect("spec entry count exceeds u32");
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_count(out, s.len());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_signature(out: &mut Vec<u8>, args: &[&general::FfiType], ret: Option<&general::FfiType>) {
+    write_count(out, args.len());
+    for arg in args {
+        write_type(out, arg);
+    }
+    match ret {
+        Some(ty) => write_type(out, ty),
+        None => out.push(TAG_VOID),
+    }
+}
+
+fn write_type(out: &mut Vec<u8>, ffi_type: &general::FfiType) {
+    match ffi_type {
+        general::FfiType::UInt8 => out.push(TAG_UINT8),
+        general::FfiType::Int8 => out.push(TAG_INT8),
+        general::FfiType::UInt16 => out.push(TAG_UINT16),
+        general::FfiType::Int16 => out.push(TAG_INT16),
+        general::FfiType::UInt32 => out.push(TAG_UINT32),
+        general::FfiType::Int32 => out.push(TAG_INT32),
+        general::FfiType::UInt64 => out.push(TAG_UINT64),
+        general::FfiType::Int64 => out.push(TAG_INT64),
+        general::FfiType::Float32 => out.push(TAG_FLOAT32),
+        general::FfiType::Float64 => out.push(TAG_FLOAT64),
+        general::FfiType::Handle(_) => out.push(TAG_HANDLE),
+        general::FfiType::RustBuffer(_) => out.push(TAG_RUST_BUFFER),
+        general::FfiType::ForeignBytes => out.push(TAG_FOREIGN_BYTES),
+        general::FfiType::RustCallStatus => out.push(TAG_RUST_CALL_STATUS),
+        general::FfiType::VoidPointer => out.push(TAG_VOID_POINTER),
+        general::FfiType::Function(name) => {
+            out.push(TAG_CALLBACK);
+            write_str(out, &name.0);
+        }
+        general::FfiType::Struct(name) => {
+            out.push(TAG_STRUCT);
+            write_str(out, &name.0);
+        }
+        general::FfiType::Reference(inner) => {
+            out.push(TAG_REFERENCE);
+            write_type(out, inner);
+        }
+        general::FfiType::MutReference(inner) => {
+            out.push(TAG_MUT_REFERENCE);
+            write_type(out, inner);
+        }
+    }
+}
@@ -158,11 +158,36 @@ console.debug(`-- {{ ffi_name }}`);
    instead to avoid allocating a fresh closure object at every call
    site invocation (V8-friendlier). -#}
 
+{#- With `tracerImport`, the call bodies below are wrapped in a closure
+   passed to `uniffiTraceCall`, or `uniffiTraceCallAsync` for anything the
+   caller awaits: async Rust, and `forceAsync` over a synchronous body.
+   Without it, nothing is emitted. -#}
+{%- macro trace_head(callable) -%}
+uniffiTraceCall{% if callable.renders_async() %}Async{% endif %}(uniffiTracer, "{{ module.module_name }}", "{{ callable.trace_name }}", [
+    {%- for arg in callable.arguments %}{{ arg.name }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    ], {% if callable.renders_async() %}async {% endif %}() =>
+{%- endmacro -%}
+
+{%- macro trace_start(callable) %}
+{%- if module.tracer_import.is_some() %}
+    return {% call trace_head(callable) %} {
+{%- endif %}
+{%- endmacro %}
+
+{%- macro trace_end() %}
+{%- if module.tracer_import.is_some() %}
+    });
+{%- endif %}
+{%- endmacro %}
+
 {#- Call body for value-receiver method: sync only (trait methods are never async). -#}
 {%- macro call_body_value(callable) %}
+{%- call trace_start(callable) %}
 {%- match callable.return_type -%}
 {%-     when Some with (return_type) %}
-{%- if return_type.is_rust_buffer %}
+{%- if return_type.is_external_bytes %}
+    return {{ return_type.ffi_converter }}.liftView({% call to_ffi_value_call(callable) %});
+{%- elif return_type.is_rust_buffer %}
     const __rb: Uint8Array = {% call to_ffi_value_call(callable) %};
     try {
         return {{ return_type.ffi_converter }}.lift(__rb);
@@ -175,16 +200,20 @@ console.debug(`-- {{ ffi_name }}`);
 {%-     when None %}
 {%-         call to_ffi_value_call(callable) %};
 {%- endmatch %}
+{%- call trace_end() %}
 {%- endmacro %}
 
 {#- Call body for method (pointer receiver): sync or async. -#}
 {%- macro call_body_method(callable, obj_factory) %}
+{%- call trace_start(callable) %}
 {%- if callable.is_ffi_async() %}
 {%-   call call_body_async(callable, obj_factory) %}
 {%- else %}
 {%-     match callable.return_type -%}
 {%-         when Some with (return_type) %}
-{%- if return_type.is_rust_buffer %}
+{%- if return_type.is_external_bytes %}
+    return {{ return_type.ffi_converter }}.liftView({% call to_ffi_pointer_call(callable, obj_factory) %});
+{%- elif return_type.is_rust_buffer %}
     const __rb: Uint8Array = {% call to_ffi_pointer_call(callable, obj_factory) %};
     try {
         return {{ return_type.ffi_converter }}.lift(__rb);
@@ -198,16 +227,20 @@ console.debug(`-- {{ ffi_name }}`);
 {%-             call to_ffi_pointer_call(callable, obj_factory) %};
 {%-     endmatch %}
 {%- endif %}
+{%- call trace_end() %}
 {%- endmacro %}
 
 {#- Call body for function (no receiver): sync or async. -#}
 {%- macro call_body_function(callable) %}
+{%- call trace_start(callable) %}
 {%- if callable.is_ffi_async() %}
 {%-   call call_body_async(callable, "unreachable") %}
 {%- else %}
 {%-     match callable.return_type -%}
 {%-         when Some with (return_type) %}
-{%- if return_type.is_rust_buffer %}
+{%- if return_type.is_external_bytes %}
+    return {{ return_type.ffi_converter }}.liftView({% call to_ffi_call(callable) %});
+{%- elif return_type.is_rust_buffer %}
     const __rb: Uint8Array = {% call to_ffi_call(callable) %};
     try {
         return {{ return_type.ffi_converter }}.lift(__rb);
@@ -221,6 +254,76 @@ console.debug(`-- {{ ffi_name }}`);
 {%-             call to_ffi_call(callable) %};
 {%-     endmatch %}
 {%- endif %}
+{%- call trace_end() %}
+{%- endmacro %}
+
+{#- The `{name}Batch` companion of a callable named in the `batch` config.
+   Every call's arguments are lowered up front, and the calls made through
+   the low-level module's `_batch` helper. Each result is then checked and
+   lifted in order; the first error is thrown only once every result is
+   lifted, so no returned buffer is left unfreed. -#}
+{%- macro batch_decl(callable, prefix, obj_factory) %}
+    /**
+     * Calls `{{ callable.name }}` once for each entry of `calls`, in order, in
+     * one crossing into Rust. Every call is made, even after one fails.
+     */
+    {{ prefix }}{{ callable.name }}Batch(calls: Array<[
+    {%- for arg in callable.arguments -%}
+        {{ arg.name }}: {{ arg.ts_type }}
+        {%- if !loop.last %}, {% endif -%}
+    {%- endfor -%}
+    ]>): Array<{% call raw_return_type(callable) %}>{% call throws_kw(callable) %} {
+        const __statuses = calls.map(() => uniffiCaller.createCallStatus());
+        const __returned = {{ callable.ffi_name }}_batch(
+            calls.map<Parameters<typeof {{ callable.ffi_name }}_batch>[0][number]>(([
+            {%- for arg in callable.arguments -%}
+                {{ arg.name }}{% if !loop.last %}, {% endif -%}
+            {%- endfor -%}
+            ], __i) => [
+                {%- if callable.receiver.is_some() %}
+                {{ obj_factory }}.clonePointer(this),
+                {%- endif %}
+                {%- call arg_list_lowered(callable) %}
+                __statuses[__i],
+            ]),
+        );
+        let __failed: { error: unknown } | undefined;
+        const __results = __returned.map((__value, __i) => {
+            try {
+                uniffiCaller.checkCallStatus(
+                    __statuses[__i],
+                    /*liftString:*/ FfiConverterString.lift.bind(FfiConverterString),
+                    {%- if let Some(e) = callable.throws %}
+                    /*liftError:*/ {{ e.lift_error_fn }},
+                    {%- endif %}
+                );
+                {%- match callable.return_type %}
+                {%- when Some with (return_type) %}
+                {%- if return_type.is_external_bytes %}
+                return {{ return_type.ffi_converter }}.liftView(__value);
+                {%- elif return_type.is_rust_buffer %}
+                try {
+                    return {{ return_type.ffi_converter }}.lift(__value);
+                } finally {
+                    nativeModule().rustbuffer_free(__value);
+                }
+                {%- else %}
+                return {{ return_type.ffi_converter }}.lift(__value);
+                {%- endif %}
+                {%- when None %}
+                {%- endmatch %}
+            } catch (__error) {
+                if (__failed === undefined) {
+                    __failed = { error: __error };
+                }
+                return undefined as never;
+            }
+        });
+        if (__failed !== undefined) {
+            throw __failed.error;
+        }
+        return __results;
+    }
 {%- endmacro %}
 
 {#- Async call body: wraps uniffiRustCallAsync with optional stack trace capture. -#}
@@ -0,0 +1,53 @@
+{#- JSON codecs, emitted with `generateJson`. #}
+
+{%- macro record_json(rec) %}
+{%- if rec.generate_json %}
+
+/**
+ * Converts a {@link {{ rec.ts_name }}} to and from JSON, for `JSON.stringify` and `JSON.parse`.
+ */
+export const {{ rec.ts_name }}Json: UniffiJsonCodec<{{ rec.ts_name }}> = uniffiJson.record("{{ rec.ts_name }}", {
+    {%- for field in rec.fields %}
+    {{ field.name }}: {{ field.json_codec }},
+    {%- endfor %}
+});
+{%- endif %}
+{%- endmacro %}
+
+{%- macro enum_json(e) %}
+{%- if e.generate_json %}
+
+/**
+ * Converts a {@link {{ e.ts_name }}} to and from JSON, for `JSON.stringify` and `JSON.parse`.
+ */
+export const {{ e.ts_name }}Json: UniffiJsonCodec<{{ e.ts_name }}> =
+{%- if e.is_flat && e.is_error %} uniffiJson.flatError("{{ e.ts_name }}", {
+    {%- for variant in e.variants %}
+    {{ variant.name }}: (message) => new {{ e.ts_name }}.{{ variant.name }}(message),
+    {%- endfor %}
+});
+{%- else if e.is_flat %} uniffiJson.flatEnum("{{ e.ts_name }}", {
+    {%- for variant in e.variants %}
+    {{ variant.name }}: {{ e.ts_name }}.{{ variant.name }},
+    {%- endfor %}
+});
+{%- else %} uniffiJson.taggedEnum("{{ e.ts_name }}", {
+    {%- for variant in e.variants %}
+    {%- if variant.has_nameless_fields %}
+    {{ variant.name }}: uniffiJson.tupleVariant([
+    {%- for field in variant.fields %}{{ field.json_codec }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    ], (inner) => new {{ e.ts_name }}.{{ variant.name }}(
+    {%- for field in variant.fields %}inner[{{ loop.index0 }}]{% if !loop.last %}, {% endif %}{% endfor -%}
+    )),
+    {%- else if variant.fields.is_empty() %}
+    {{ variant.name }}: uniffiJson.variant({}, () => new {{ e.ts_name }}.{{ variant.name }}()),
+    {%- else %}
+    {{ variant.name }}: uniffiJson.variant({
+    {%- for field in variant.fields %}{{ field.name }}: {{ field.json_codec }}{% if !loop.last %}, {% endif %}{% endfor -%}
+    }, (inner) => new {{ e.ts_name }}.{{ variant.name }}(inner)),
+    {%- endif %}
+    {%- endfor %}
+});
+{%- endif %}
+{%- endif %}
+{%- endmacro %}
@@ -45,6 +45,9 @@ export class {{ obj.impl_class_name }} extends UniffiAbstractObject
 
     {% for meth in obj.methods -%}
     {%- call _object_method_decl(obj, "", meth) %}
+    {%- if meth.batched %}
+    {% call cb::batch_decl(meth, "", obj.obj_factory) %}
+    {%- endif %}
     {% endfor %}
 
     {%- for tm in obj.uniffi_traits %}
@@ -80,8 +83,15 @@ export class {{ obj.impl_class_name }} extends UniffiAbstractObject
     uniffiDestroy(): void {
         const ptr = (this as any)[destructorGuardSymbol];
         if (ptr !== undefined) {
+            {%- if module.flavor.supports_reload() %}
+            // One made before a reload went with the old library.
+            if (ptr.generation === uniffiGeneration()) {
+                {{ obj.obj_factory }}.freePointer({{ obj.obj_factory }}.pointer(this));
+            }
+            {%- else %}
             const pointer = {{ obj.obj_factory }}.pointer(this);
             {{ obj.obj_factory }}.freePointer(pointer);
+            {%- endif %}
             {{ obj.obj_factory }}.unbless(ptr);
             delete (this as any)[destructorGuardSymbol];
         }
@@ -105,9 +115,19 @@ export class {{ obj.impl_class_name }} extends UniffiAbstractObject
 const {{ obj.obj_factory }}: UniffiObjectFactory<{{ obj.protocol_name }}> = (() => {
     {% if obj.supports_finalization_registry %}
     /// <reference lib="es2021" />
+    {%- if module.flavor.supports_reload() %}
+    // Each handle is held with its generation: one made before a reload went
+    // with the old library, and has nothing left to free.
+    const registry = typeof FinalizationRegistry !== 'undefined' ? new FinalizationRegistry<[UniffiHandle, number]>(([heldValue, generation]: [UniffiHandle, number]) => {
+        if (generation === uniffiGeneration()) {
+            {{ obj.obj_factory }}.freePointer(heldValue);
+        }
+    }) : null;
+    {%- else %}
     const registry = typeof FinalizationRegistry !== 'undefined' ? new FinalizationRegistry<UniffiHandle>((heldValue: UniffiHandle) => {
         {{ obj.obj_factory }}.freePointer(heldValue);
     }) : null;
+    {%- endif %}
     {% endif %}
     return {
     create(pointer: UniffiHandle): {{ obj.protocol_name }} {
@@ -123,9 +143,12 @@ const {{ obj.obj_factory }}: UniffiObjectFactory<{{ obj.protocol_name }}> = (()
         const ptr = {
             p, // make sure this object doesn't get optimized away.
             markDestroyed: () => undefined,
+            {%- if module.flavor.supports_reload() %}
+            generation: uniffiGeneration(),
+            {%- endif %}
         };
         if (registry) {
-            registry.register(ptr, p, ptr);
+            registry.register(ptr, {% if module.flavor.supports_reload() %}[p, ptr.generation]{% else %}p{% endif %}, ptr);
         }
         return ptr;
     },
@@ -150,9 +173,15 @@ const {{ obj.obj_factory }}: UniffiObjectFactory<{{ obj.protocol_name }}> = (()
     {%- endif %}
 
     pointer(obj_: {{ obj.protocol_name }}): UniffiHandle {
-        if ((obj_ as any)[destructorGuardSymbol] === undefined) {
+        const guard = (obj_ as any)[destructorGuardSymbol];
+        if (guard === undefined) {
             throw new UniffiInternalError.UnexpectedNullPointer();
         }
+        {%- if module.flavor.supports_reload() %}
+        if (guard.generation !== uniffiGeneration()) {
+            throw new UniffiInternalError.ReloadedObject();
+        }
+        {%- endif %}
         return (obj_ as any)[pointerLiteralSymbol];
     },
 
@@ -197,7 +226,8 @@ const {{ obj.ffi_error_converter_name }} = new FfiConverterObjectAsError("{{ obj
     {%- call cb::arg_list_decl(cons) -%}) {%- call cb::throws_kw(cons) %} {
         super();
         const pointer =
-            {% call cb::to_ffi_call(cons) %};
+            {% if module.tracer_import.is_some() %}{% call cb::trace_head(cons) %} {% endif -%}
+            {% call cb::to_ffi_call(cons) %}{% if module.tracer_import.is_some() %}){% endif %};
         this[pointerLiteralSymbol] = pointer;
         this[destructorGuardSymbol] = {{ obj.obj_factory }}.bless(pointer);
     }
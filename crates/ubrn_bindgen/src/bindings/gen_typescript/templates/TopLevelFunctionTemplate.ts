@@ -7,4 +7,7 @@ export {% if func.renders_async() %}async {% endif %}function {{ func.name }}(
     {%- call cb::throws_kw(func) %} {
     {%- call cb::call_body_function(func) %}
     }
+{%- if func.batched %}
+{% call cb::batch_decl(func, "export function ", "unreachable") %}
+{%- endif %}
 {%- endmacro %}
@@ -0,0 +1,40 @@
+{#- Runtime validators, emitted with `generateValidators`. #}
+
+{%- macro record_validator(rec) %}
+{%- if rec.generate_validators %}
+
+/**
+ * Checks that a value is a {@link {{ rec.ts_name }}}, e.g. one parsed from JSON, before it is passed to Rust.
+ */
+export const {{ rec.ts_name }}Validator: UniffiValidator<{{ rec.ts_name }}> = uniffiValidators.record("{{ rec.ts_name }}", {
+    {%- for field in rec.fields %}
+    {{ field.name }}: {{ field.validator }},
+    {%- endfor %}
+});
+{%- endif %}
+{%- endmacro %}
+
+{%- macro enum_validator(e) %}
+{%- if e.generate_validators %}
+
+/**
+ * Checks that a value is a {@link {{ e.ts_name }}}, e.g. one parsed from JSON, before it is passed to Rust.
+ */
+export const {{ e.ts_name }}Validator: UniffiValidator<{{ e.ts_name }}> =
+{%- if e.is_error %} uniffiValidators.instance("{{ e.ts_name }}", {{ e.ts_name }}.instanceOf);
+{%- else if e.is_flat %} uniffiValidators.oneOf("{{ e.ts_name }}", [
+    {%- for variant in e.variants %}{{ e.ts_name }}.{{ variant.name }}{% if !loop.last %}, {% endif %}{% endfor -%}
+]);
+{%- else %} uniffiValidators.taggedEnum("{{ e.ts_name }}", {
+    {%- for variant in e.variants %}
+    {{ variant.name }}: {
+    {%- for field in variant.fields %}
+    {%- if variant.has_nameless_fields %}{{ loop.index0 }}{% else %}{{ field.name }}{% endif %}: {{ field.validator }}
+    {%- if !loop.last %}, {% endif %}
+    {%- endfor -%}
+    },
+    {%- endfor %}
+});
+{%- endif %}
+{%- endif %}
+{%- endmacro %}
@@ -17,6 +17,21 @@ if (!initialized) {
 }
 
 export async function uniffiInitAsync() { /* sync flavor: no-op */ }
+{%- if flavor.supports_reload() %}
+
+/**
+ * Swap in a rebuilt copy of the native library, so a dev server can pick up
+ * a new build without restarting Node. `libPath` must name a new file, such
+ * as a copy of the build: the loader hands back a library it already has.
+ *
+ * Objects made before the reload throw when used.
+ */
+export function uniffiReload(libPath: string) {
+  {%- for m in modules %}
+  {{ m.ts() }}.default.reload(libPath);
+  {%- endfor %}
+}
+{%- endif %}
 {% else %}
 // The module is opened here rather than in an environment-specific entrypoint:
 // `@ubjs/wasm` resolves to its browser or node build through the
@@ -12,6 +12,9 @@
 // generated `index.ts`'s job, so this module
 // bundles for node, browsers and React Native alike.
 import { FfiType } from "@ubjs/wasm/core";
+{%- else if module.flavor.is_deno() %}
+// Loads the library through the host's own FFI, `Deno.dlopen` or `bun:ffi`.
+import { UniffiNativeModule, FfiType, resolveLibPath } from "@ubjs/deno";
 {%- else %}
 import lib from "@ubjs/node";
 const { UniffiNativeModule, FfiType, resolveLibPath } = lib;
@@ -26,6 +29,7 @@ import {
   type UniffiResult,
 } from '@ubjs/core';
 
+{%- if !module.flavor.supports_binary_player_spec() %}
 const DEFINITIONS = {
   symbols: {
     rustbuffer_alloc: "{{ module.symbols.rustbuffer_alloc }}",
@@ -38,6 +42,9 @@ const DEFINITIONS = {
       args: [{{ func.args.join(", ") }}],
       ret: {{ func.ret }},
       hasRustCallStatus: {{ func.has_rust_call_status }},
+      {%- if func.external_return %}
+      externalReturn: true,
+      {%- endif %}
     },
     {%- endfor %}
   },
@@ -50,6 +57,9 @@ const DEFINITIONS = {
       {%- if cb.out_return %}
       outReturn: true,
       {%- endif %}
+      {%- if cb.once %}
+      once: true,
+      {%- endif %}
     },
     {%- endfor %}
   },
@@ -62,7 +72,23 @@ const DEFINITIONS = {
     ],
     {%- endfor %}
   },
+  apiChecks: {
+    contractVersionSymbol: "{{ module.api_checks.contract_version_symbol }}",
+    contractVersion: {{ module.api_checks.contract_version }},
+    checksums: {
+      {%- for (symbol, checksum) in module.api_checks.checksums %}
+      "{{ symbol }}": {{ checksum }},
+      {%- endfor %}
+    },
+  },
 } as const;
+{%- else %}
+// The FFI definitions in the runtime's binary spec encoding, which
+// `register()` decodes without walking a JS object.
+const DEFINITIONS = new Uint8Array([
+{{ module.spec_bytes_literal() }}
+]);
+{%- endif %}
 
 interface NativeModuleInterface {
     {%- for func in module.typed_functions %}
@@ -78,6 +104,11 @@ interface NativeModuleInterface {
     // module exposes them as method properties.
     rustbuffer_alloc(n: number): Uint8Array;
     rustbuffer_free(view: Uint8Array): void;
+    {%- if !module.batched_functions.is_empty() %}
+    // Makes each `[name, args]` call in one crossing into Rust. Only the napi
+    // runtime has it; the `_batch` helpers below fall back to a loop.
+    batch?(calls: Array<[string, unknown[]]>): unknown[];
+    {%- endif %}
 }
 
 {%- if module.flavor.is_wasm2() %}
@@ -100,6 +131,9 @@ export function setNativeModule(m: Record<string, (...args: any[]) => any>) {
 }
 {%- else %}
 let _nativeModule: NativeModuleInterface | undefined;
+{%- if module.flavor.supports_reload() %}
+let _uniffiModule: ReturnType<typeof UniffiNativeModule.open> | undefined;
+{%- endif %}
 const getter: () => NativeModuleInterface = () => {
   if (!_nativeModule) {
     const libPath = resolveLibPath({
@@ -120,11 +154,60 @@ const getter: () => NativeModuleInterface = () => {
     });
     const mod_ = UniffiNativeModule.open(libPath);
     _nativeModule = mod_.register(DEFINITIONS) as unknown as NativeModuleInterface;
+    {%- if module.flavor.supports_reload() %}
+    _uniffiModule = mod_;
+    {%- endif %}
   }
   return _nativeModule;
 };
+{%- if module.flavor.supports_reload() %}
+
+let _generation = 0;
+
+// Counts the reloads so far. An object records it when it is made, and is
+// refused once it has moved on: its handle points into a replaced library.
+export function uniffiGeneration(): number {
+  return _generation;
+}
+
+// Swaps in a rebuilt copy of the library at `libPath`, checked against these
+// definitions, and rebinds every call to it. See `UniffiNativeModule.reload`.
+export function uniffiReload(libPath: string): void {
+  getter();
+  _nativeModule = _uniffiModule!.reload(libPath, DEFINITIONS) as unknown as NativeModuleInterface;
+  _generation += 1;
+}
+
+// The runtime's counters for the library: its live callback trampolines and
+// external buffers. See `UniffiNativeModule.metrics`.
+export function uniffiMetrics(): ReturnType<NonNullable<typeof _uniffiModule>["metrics"]> {
+  getter();
+  return _uniffiModule!.metrics();
+}
+{%- endif %}
 {%- endif %}
 export default getter;
+{%- for func in module.batched_functions %}
+{%- let return_type = func.return_type.clone().unwrap_or("void".into()) %}
+
+// Calls `{{ func.name }}` once for each entry of `calls`, in order, in one
+// crossing into Rust. Each call reports errors through its own status.
+export function {{ func.name }}_batch(
+  calls: Array<[
+  {%- for arg in func.arguments %}
+    {{- arg.name }}: {{ arg.type_name }}
+    {%- if !loop.last %}, {% endif %}
+  {%- endfor %}]>,
+): Array<{{ return_type }}> {
+  const nm = getter();
+  if (nm.batch === undefined) {
+    return calls.map((args) => nm.{{ func.name }}(...args));
+  }
+  return nm.batch(
+    calls.map((args) => ["{{ func.name }}", args]),
+  ) as Array<{{ return_type }}>;
+}
+{%- endfor %}
 
 // Structs and function types for calling back into Typescript from Rust.
 {%- for def in module.typed_definitions %}
@@ -11,6 +11,8 @@
 {%- import "RecordTemplate.ts" as rec %}
 {%- import "ObjectTemplate.ts" as ot %}
 {%- import "CallbackInterfaceTemplate.ts" as cbit %}
+{%- import "ValidatorTemplate.ts" as val %}
+{%- import "JsonTemplate.ts" as json %}
 
 {%- if !module.strict_type_checking %}
 /* tslint:disable */
@@ -19,7 +21,8 @@
 {%- endif %}
 
 {%- if module.flavor.supports_globalthis_native_module() || module.flavor.supports_player() %}
-import nativeModule from "./{{ module.module_name }}-ffi";
+import nativeModule
+{%- if module.flavor.supports_reload() %}, { uniffiGeneration, uniffiReload }{% endif %} from "./{{ module.module_name }}-ffi";
 {%- else %}
 import * as wasmBundle from "./wasm-bindgen/index.js";
 {%- endif %}
@@ -30,6 +33,10 @@ import {
 } from "{{ imp.path }}";
 {%- endfor %}
 
+{%- if let Some(tracer) = module.tracer_import %}
+import uniffiTracer from "{{ tracer }}";
+{%- endif %}
+
 {%- for conv in module.converter_imports %}
 import {{ conv.default_name }} from "{{ conv.path }}";
 const { {{ conv.converters|join(", ") }} } = {{ conv.default_name }}.converters;
@@ -78,12 +85,20 @@ const uniffiIsDebug =
 {%- when TsTypeDefinition::External(_) %}
 {%- when TsTypeDefinition::FlatEnum(e) %}
 {% call en::flat_enum(e) %}
+{%- call val::enum_validator(e) %}
+{%- call json::enum_json(e) %}
 {%- when TsTypeDefinition::FlatError(e) %}
 {% call err::flat_error(e) %}
+{%- call val::enum_validator(e) %}
+{%- call json::enum_json(e) %}
 {%- when TsTypeDefinition::TaggedEnum(e) %}
 {% call te::tagged_enum(e) %}
+{%- call val::enum_validator(e) %}
+{%- call json::enum_json(e) %}
 {%- when TsTypeDefinition::Record(rec) %}
 {% call rec::record(rec) %}
+{%- call val::record_validator(rec) %}
+{%- call json::record_json(rec) %}
 {%- when TsTypeDefinition::Object(obj) %}
 {% call ot::object(obj) %}
 {%- when TsTypeDefinition::CallbackInterface(cbi) %}
@@ -95,6 +110,14 @@ const uniffiIsDebug =
 
 export default Object.freeze({
   initialize: uniffiEnsureInitialized,
+  {%- if module.flavor.supports_reload() %}
+  // Swaps in a rebuilt copy of the native library at `libPath`, and
+  // initializes it. Objects made before then throw when used.
+  reload(libPath: string) {
+    uniffiReload(libPath);
+    uniffiEnsureInitialized();
+  },
+  {%- endif %}
   {%- if !module.exported_converters.is_empty() %}
   converters: {
   {%- for converter in module.exported_converters %}
@@ -48,6 +48,10 @@ impl ModuleMetadata {
         format!("{}.ts", self.ts())
     }
 
+    pub fn api_manifest_filename(&self) -> String {
+        format!("{}.api.json", self.ts())
+    }
+
     pub fn ts_ffi(&self) -> String {
         format!("{}-ffi", self.namespace)
     }
@@ -22,7 +22,7 @@ use super::{
     switches::{AbiFlavor, SwitchArgs},
 };
 
-#[derive(Args, Debug)]
+#[derive(Args, Clone, Debug)]
 pub struct BindingsArgs {
     #[command(flatten)]
     pub(crate) source: SourceArgs,
@@ -56,6 +56,17 @@ impl BindingsArgs {
         self
     }
 
+    /// The same bindings, written to different directories.
+    pub fn with_output_dirs(mut self, ts_dir: &Utf8Path, cpp_dir: &Utf8Path) -> Self {
+        self.output = OutputArgs::new(ts_dir, cpp_dir, self.output.no_format);
+        self
+    }
+
+    /// The UDL or library file the bindings are generated from.
+    pub fn source_path(&self) -> &Utf8Path {
+        &self.source.source
+    }
+
     pub fn ts_dir(&self) -> &Utf8Path {
         &self.output.ts_dir
     }
@@ -132,6 +143,11 @@ impl SourceArgs {
         }
     }
 
+    /// Whether the source is a library, given with `--library`.
+    pub fn is_library(&self) -> bool {
+        self.library_mode
+    }
+
     /// Returns the source path (a UDL file or library file).
     pub fn source(&self) -> &Utf8PathBuf {
         &self.source
@@ -173,6 +189,7 @@ impl BindingsArgs {
                 generate_cpp(&components, &abi_dir, !out.no_format)?;
             }
             AbiFlavor::Napi => { /* No C++ generation for Napi */ }
+            AbiFlavor::Deno => { /* The host's own FFI loads the cdylib */ }
             #[cfg(feature = "wasm")]
             AbiFlavor::Wasm => {
                 let metadata = load_metadata(&loader, &source_path)?;
@@ -212,6 +229,18 @@ impl BindingsArgs {
         Ok(modules)
     }
 
+    /// The component interfaces the source exports, to compare one build's
+    /// API with another's, as `ApiDiff::between` does.
+    pub fn component_interfaces(
+        &self,
+        manifest_path: Option<&Utf8PathBuf>,
+    ) -> Result<Vec<ComponentInterface>> {
+        let source_path = path_or_shim(&self.source.source)?;
+        let loader = self.create_pipeline_loader(manifest_path)?;
+        let metadata = load_metadata(&loader, &source_path)?;
+        loader.load_cis(metadata)
+    }
+
     fn create_loader(&self, manifest_path: Option<&Utf8PathBuf>) -> Result<BindgenLoader> {
         let mut bindgen_paths = BindgenPaths::default();
         if let Some(config_path) = &self.source.config {
@@ -263,6 +292,11 @@ fn generate_api_from_pipeline(
             switches.flavor.clone(),
             ffi_exports,
         )?;
+        if config.api_manifest {
+            let manifest = gen_typescript::api_module::ApiManifest::new(&api_module);
+            let path = ts_dir.join(module.api_manifest_filename());
+            ubrn_common::write_file(path, manifest.to_json()?)?;
+        }
         let code = gen_typescript::generate_api_code_from_ir(api_module)?;
         let path = ts_dir.join(module.ts_filename());
         ubrn_common::write_file(path, code)?;
@@ -326,10 +360,11 @@ fn generate_ffi_from_pipeline(
 
         let config = extract_ts_config(namespace)?;
         let code = match &switches.flavor {
-            AbiFlavor::Napi => {
+            AbiFlavor::Napi | AbiFlavor::Deno => {
                 let lib_resolution = lib_resolution.clone().ok_or_else(|| {
                     anyhow::anyhow!(
-                        "napi codegen requires a LibResolution; pass --lib-colocated, --lib-absolute, or --lib-package-base"
+                        "{:?} codegen requires a LibResolution; pass --lib-colocated, --lib-absolute, or --lib-package-base",
+                        switches.flavor
                     )
                 })?;
                 let crate_name = namespace.crate_name.clone();
@@ -378,7 +413,7 @@ fn generate_ffi_from_pipeline(
 /// from its globals + data segments instead of falling through to the native
 /// dylib symbol-table reader. Non-wasm sources (UDL, native libraries) take
 /// the unchanged default path.
-fn load_metadata(
+pub(crate) fn load_metadata(
     loader: &uniffi_bindgen::BindgenLoader,
     source_path: &Utf8Path,
 ) -> Result<uniffi_meta::MetadataGroupMap> {
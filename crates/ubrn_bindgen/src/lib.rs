@@ -3,6 +3,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
+pub mod api_diff;
 mod bindings;
 mod cli;
 mod react_native;
@@ -25,7 +26,7 @@ pub mod ffi_module_player_lib_resolution {
 #[doc(hidden)]
 pub mod __player_template_test {
     pub use crate::bindings::gen_typescript::ffi_module_player::{
-        render_minimal_for_test, LibResolution, TripleStyle,
+        render_batched_for_test, render_minimal_for_test, LibResolution, SpecEncoder, TripleStyle,
     };
 }
 
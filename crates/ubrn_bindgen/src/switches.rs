@@ -31,6 +31,10 @@ impl SwitchArgs {
 pub enum AbiFlavor {
     Jsi,
     Napi,
+    /// Deno and Bun, which load the library through their own FFI
+    /// (`Deno.dlopen`, `bun:ffi`) rather than through a native addon.
+    #[clap(alias = "bun")]
+    Deno,
     #[cfg(feature = "wasm")]
     Wasm,
     #[cfg(feature = "wasm")]
@@ -42,6 +46,7 @@ impl AbiFlavor {
         match self {
             Self::Jsi => "Entrypoint.cpp",
             Self::Napi => "", // No native entrypoint needed
+            Self::Deno => "",
             #[cfg(feature = "wasm")]
             Self::Wasm => "src/lib.rs",
             #[cfg(feature = "wasm")]
@@ -63,36 +68,29 @@ impl AbiFlavor {
     pub fn supports_player(&self) -> bool {
         #[cfg(feature = "wasm")]
         {
-            matches!(self, Self::Napi | Self::Wasm2)
+            matches!(self, Self::Napi | Self::Deno | Self::Wasm2)
         }
         #[cfg(not(feature = "wasm"))]
         {
-            matches!(self, Self::Napi)
+            matches!(self, Self::Napi | Self::Deno)
         }
     }
 
     /// Whether FFI function names on the native module use the `ubrn_` prefix.
-    /// JSI and WASM both use this prefix; the Napi player uses raw symbol names.
+    /// JSI and WASM both use this prefix; the players use raw symbol names.
     pub fn supports_ubrn_prefix(&self) -> bool {
-        #[cfg(feature = "wasm")]
-        {
-            !matches!(self, Self::Napi | Self::Wasm2)
-        }
-        #[cfg(not(feature = "wasm"))]
-        {
-            !matches!(self, Self::Napi)
-        }
+        !self.supports_player()
     }
 
     /// Whether the runtime uses a plain `{ code: 0 }` object for RustCallStatus.
     pub fn supports_plain_call_status(&self) -> bool {
         #[cfg(feature = "wasm")]
         {
-            matches!(self, Self::Jsi | Self::Napi | Self::Wasm2)
+            matches!(self, Self::Jsi | Self::Napi | Self::Deno | Self::Wasm2)
         }
         #[cfg(not(feature = "wasm"))]
         {
-            matches!(self, Self::Jsi | Self::Napi)
+            matches!(self, Self::Jsi | Self::Napi | Self::Deno)
         }
     }
 
@@ -117,11 +115,11 @@ impl AbiFlavor {
 
     /// Whether this flavor initializes synchronously at module load.
     ///
-    /// Sync flavors (JSI, Napi) call `initialize()` from the index.ts top
+    /// Sync flavors (JSI, Napi, Deno) call `initialize()` from the index.ts top
     /// level and treat `uniffiInitAsync` as a no-op for parity. Async
     /// flavors (Wasm) defer all initialization into `uniffiInitAsync`.
     pub fn supports_sync_initialization(&self) -> bool {
-        matches!(self, Self::Jsi | Self::Napi)
+        matches!(self, Self::Jsi | Self::Napi | Self::Deno)
     }
 
     /// Whether the bindgen emits an `index.ts` beside the per-module wrappers.
@@ -131,14 +129,27 @@ impl AbiFlavor {
     /// and `index.web.ts` respectively — so a second index here would be
     /// redundant for them.
     pub fn supports_index_ts_at_generation(&self) -> bool {
-        #[cfg(feature = "wasm")]
-        {
-            matches!(self, Self::Napi | Self::Wasm2)
-        }
-        #[cfg(not(feature = "wasm"))]
-        {
-            matches!(self, Self::Napi)
-        }
+        self.supports_player()
+    }
+
+    /// Whether the player is handed its definitions in the runtime's binary
+    /// spec encoding. Only the napi addon decodes it; the TypeScript players
+    /// (Deno, Wasm2) read the object form.
+    pub fn supports_binary_player_spec(&self) -> bool {
+        matches!(self, Self::Napi)
+    }
+
+    /// Whether the runtime can swap in a rebuilt library without a restart,
+    /// so the generated code rebinds its calls and tracks which build each
+    /// object came from. Only the napi addon has `reload()`.
+    pub fn supports_reload(&self) -> bool {
+        matches!(self, Self::Napi)
+    }
+
+    /// Deno specifically — the player runtime is `@ubjs/deno`, which runs
+    /// under Deno and Bun.
+    pub fn is_deno(&self) -> bool {
+        matches!(self, Self::Deno)
     }
 
     /// Wasm2 specifically — the per-module wrapper stays environment-neutral,
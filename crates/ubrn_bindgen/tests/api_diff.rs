@@ -0,0 +1,292 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+//! Checks how `ApiDiff` classifies changes between two versions of an
+//! interface, each described in UDL.
+
+use ubrn_bindgen::api_diff::{ApiDiff, ChangeKind, ItemKind};
+use uniffi_bindgen::ComponentInterface;
+
+fn diff(old: &str, new: &str) -> ApiDiff {
+    let ci = |udl: &str| ComponentInterface::from_webidl(udl, "crate_name").unwrap();
+    ApiDiff::between(&[ci(old)], &[ci(new)])
+}
+
+fn summary(diff: &ApiDiff) -> Vec<(String, ItemKind, ChangeKind, bool)> {
+    diff.changes
+        .iter()
+        .map(|c| (c.item.clone(), c.kind, c.change, c.breaking))
+        .collect()
+}
+
+fn one(
+    item: &str,
+    kind: ItemKind,
+    change: ChangeKind,
+    breaking: bool,
+) -> Vec<(String, ItemKind, ChangeKind, bool)> {
+    vec![(item.to_string(), kind, change, breaking)]
+}
+
+#[test]
+fn identical_interfaces_have_no_changes() {
+    let udl = "namespace ns { u32 add(u32 a, u32 b); };";
+    assert!(diff(udl, udl).changes.is_empty());
+}
+
+#[test]
+fn functions_added_and_removed() {
+    let d = diff("namespace ns { void a(); };", "namespace ns { void b(); };");
+    assert_eq!(
+        summary(&d),
+        vec![
+            (
+                "ns::a".to_string(),
+                ItemKind::Function,
+                ChangeKind::Removed,
+                true
+            ),
+            (
+                "ns::b".to_string(),
+                ItemKind::Function,
+                ChangeKind::Added,
+                false
+            ),
+        ]
+    );
+    assert!(d.has_breaking_changes());
+}
+
+#[test]
+fn arguments_with_defaults_are_not_breaking() {
+    let d = diff(
+        "namespace ns { void f(u32 a); };",
+        "namespace ns { void f(u32 a, optional u32 b = 2); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, false)
+    );
+
+    let d = diff(
+        "namespace ns { void f(u32 a); };",
+        "namespace ns { void f(u32 a, u32 b); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, true)
+    );
+}
+
+#[test]
+fn changed_types_are_breaking() {
+    let d = diff(
+        "namespace ns { u32 f(u32 a); };",
+        "namespace ns { u64 f(u32 a); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, true)
+    );
+    assert_eq!(
+        d.changes[0].detail.as_deref(),
+        Some("return type changed from `u32` to `u64`")
+    );
+}
+
+#[test]
+fn throws_is_not_breaking() {
+    let d = diff(
+        "namespace ns { void f(); }; [Error] enum E { \"A\" };",
+        "namespace ns { [Throws=E] void f(); }; [Error] enum E { \"A\" };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, false)
+    );
+}
+
+#[test]
+fn record_fields() {
+    let d = diff(
+        "namespace ns {}; dictionary R { u32 a; };",
+        "namespace ns {}; dictionary R { u32 a; u32 b = 0; string c; };",
+    );
+    assert_eq!(
+        summary(&d),
+        vec![
+            (
+                "ns::R.c".to_string(),
+                ItemKind::Field,
+                ChangeKind::Added,
+                true
+            ),
+            (
+                "ns::R.b".to_string(),
+                ItemKind::Field,
+                ChangeKind::Added,
+                false
+            ),
+        ]
+    );
+}
+
+#[test]
+fn enum_variants_break_exhaustive_switches() {
+    let d = diff(
+        "namespace ns {}; enum E { \"A\" };",
+        "namespace ns {}; enum E { \"A\", \"B\" };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::E.B", ItemKind::Variant, ChangeKind::Added, true)
+    );
+}
+
+#[test]
+fn error_variants_added_are_not_breaking() {
+    let d = diff(
+        "namespace ns { [Throws=E] void f(); }; [Error] enum E { \"A\" };",
+        "namespace ns { [Throws=E] void f(); }; [Error] enum E { \"A\", \"B\" };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::E.B", ItemKind::Variant, ChangeKind::Added, false)
+    );
+
+    let d = diff(
+        "namespace ns { [Throws=E] void f(); }; [Error] enum E { \"A\", \"B\" };",
+        "namespace ns { [Throws=E] void f(); }; [Error] enum E { \"A\" };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::E.B", ItemKind::Variant, ChangeKind::Removed, true)
+    );
+}
+
+#[test]
+fn objects_and_callback_interfaces() {
+    let d = diff(
+        "namespace ns {}; interface O { constructor(); void m(); };",
+        "namespace ns {}; interface O { constructor(); void m(); void n(); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::O.n", ItemKind::Method, ChangeKind::Added, false)
+    );
+
+    let d = diff(
+        "namespace ns {}; callback interface C { void m(); };",
+        "namespace ns {}; callback interface C { void m(); void n(); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::C.n", ItemKind::Method, ChangeKind::Added, true)
+    );
+}
+
+#[test]
+fn types_are_compared_as_typescript_spells_them() {
+    for (old, new) in [
+        ("void f(u8 a);", "void f(u32 a);"),
+        ("void f(i32 a);", "void f(f64 a);"),
+        ("u64 f();", "i64 f();"),
+    ] {
+        let d = diff(
+            &format!("namespace ns {{ {old} }};"),
+            &format!("namespace ns {{ {new} }};"),
+        );
+        assert_eq!(
+            summary(&d),
+            one("ns::f", ItemKind::Function, ChangeKind::Changed, false),
+            "{old} to {new}"
+        );
+    }
+
+    let d = diff(
+        "namespace ns {}; dictionary R { u8 a; };",
+        "namespace ns {}; dictionary R { u16 a; };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::R.a", ItemKind::Field, ChangeKind::Changed, false)
+    );
+    assert_eq!(
+        d.changes[0].detail.as_deref(),
+        Some("changed from `u8` to `u16`")
+    );
+}
+
+#[test]
+fn optional_arguments_and_required_returns_are_not_breaking() {
+    let d = diff(
+        "namespace ns { void f(u32 a); };",
+        "namespace ns { void f(u32? a); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, false)
+    );
+    let d = diff("namespace ns { u32? f(); };", "namespace ns { u32 f(); };");
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, false)
+    );
+
+    // The other way around, callers must handle `undefined`.
+    let d = diff(
+        "namespace ns { void f(u32? a); };",
+        "namespace ns { void f(u32 a); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, true)
+    );
+    let d = diff("namespace ns { u32 f(); };", "namespace ns { u32? f(); };");
+    assert_eq!(
+        summary(&d),
+        one("ns::f", ItemKind::Function, ChangeKind::Changed, true)
+    );
+
+    // Records are written as well as read.
+    let d = diff(
+        "namespace ns {}; dictionary R { u32 a; };",
+        "namespace ns {}; dictionary R { u32? a; };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::R.a", ItemKind::Field, ChangeKind::Changed, true)
+    );
+}
+
+#[test]
+fn callback_interfaces_are_implemented_in_typescript() {
+    // An implementation is passed the arguments, and returns the result.
+    let d = diff(
+        "namespace ns {}; callback interface C { void m(u32 a); };",
+        "namespace ns {}; callback interface C { void m(u32? a); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::C.m", ItemKind::Method, ChangeKind::Changed, true)
+    );
+    let d = diff(
+        "namespace ns {}; callback interface C { u32 m(); };",
+        "namespace ns {}; callback interface C { u32? m(); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::C.m", ItemKind::Method, ChangeKind::Changed, false)
+    );
+    let d = diff(
+        "namespace ns {}; callback interface C { void m(u8 a); };",
+        "namespace ns {}; callback interface C { void m(u16 a); };",
+    );
+    assert_eq!(
+        summary(&d),
+        one("ns::C.m", ItemKind::Method, ChangeKind::Changed, false)
+    );
+}
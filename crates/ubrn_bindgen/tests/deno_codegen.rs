@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+//! Checks that `wrapper-ffi-player.ts` renders its Deno branches: the runtime
+//! import comes from `@ubjs/deno`, the definitions are the object form rather
+//! than napi's binary spec, and the getter opens the library the same way
+//! napi's does.
+
+use ubrn_bindgen::{render_player_lowlevel_for_test, AbiFlavor};
+
+#[test]
+fn deno_player_ffi_renders_expected_markers() {
+    let rendered = render_player_lowlevel_for_test(&AbiFlavor::Deno)
+        .expect("rendering deno player wrapper-ffi.ts should succeed");
+
+    assert!(
+        rendered.contains(
+            r#"import { UniffiNativeModule, FfiType, resolveLibPath } from "@ubjs/deno";"#
+        ),
+        "expected deno runtime import in rendered output:\n{rendered}"
+    );
+    assert!(
+        !rendered.contains("@ubjs/node"),
+        "deno rendering must not import the napi runtime:\n{rendered}"
+    );
+
+    // Deno and Bun have no decoder for the binary spec; they get the object form.
+    assert!(
+        rendered.contains("symbols: {"),
+        "expected object-form definitions in deno rendering:\n{rendered}"
+    );
+    assert!(
+        !rendered.contains("new Uint8Array(["),
+        "deno rendering must not include the binary spec:\n{rendered}"
+    );
+
+    assert!(
+        rendered.contains("UniffiNativeModule.open(libPath)"),
+        "expected the shared open/register getter in deno rendering:\n{rendered}"
+    );
+    assert!(
+        !rendered.contains("setNativeModule"),
+        "deno rendering must not include the wasm2 setNativeModule export:\n{rendered}"
+    );
+}
+
+#[test]
+fn napi_player_ffi_keeps_binary_spec() {
+    let rendered = render_player_lowlevel_for_test(&AbiFlavor::Napi)
+        .expect("rendering napi player wrapper-ffi.ts should succeed");
+    assert!(
+        rendered.contains(r#"import lib from "@ubjs/node";"#),
+        "expected napi runtime import in rendered output:\n{rendered}"
+    );
+    assert!(
+        !rendered.contains("@ubjs/deno"),
+        "napi rendering must not import the deno runtime:\n{rendered}"
+    );
+}
@@ -0,0 +1,156 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+//! Codegen writes the player's binary spec without depending on the runtime,
+//! so this decodes what it writes with the runtime's own decoder, and checks
+//! every type tag and flag lands where the runtime expects it.
+
+use ubrn_bindgen::__player_template_test::SpecEncoder;
+use uniffi_bindgen::pipeline::general::{FfiFunctionTypeName, FfiStructName, FfiType, HandleKind};
+use uniffi_runtime_core::spec::binary::decode;
+use uniffi_runtime_core::FfiTypeDesc;
+
+#[test]
+fn runtime_decodes_codegen_spec() {
+    let scalars = [
+        (FfiType::UInt8, FfiTypeDesc::UInt8),
+        (FfiType::Int8, FfiTypeDesc::Int8),
+        (FfiType::UInt16, FfiTypeDesc::UInt16),
+        (FfiType::Int16, FfiTypeDesc::Int16),
+        (FfiType::UInt32, FfiTypeDesc::UInt32),
+        (FfiType::Int32, FfiTypeDesc::Int32),
+        (FfiType::UInt64, FfiTypeDesc::UInt64),
+        (FfiType::Int64, FfiTypeDesc::Int64),
+        (FfiType::Float32, FfiTypeDesc::Float32),
+        (FfiType::Float64, FfiTypeDesc::Float64),
+        (FfiType::Handle(HandleKind::RustFuture), FfiTypeDesc::Handle),
+        (FfiType::RustBuffer(None), FfiTypeDesc::RustBuffer),
+        (FfiType::ForeignBytes, FfiTypeDesc::ForeignBytes),
+        (FfiType::RustCallStatus, FfiTypeDesc::RustCallStatus),
+        (FfiType::VoidPointer, FfiTypeDesc::VoidPointer),
+    ];
+    let callback = FfiType::Function(FfiFunctionTypeName("Complete".into()));
+    let reference = FfiType::Reference(Box::new(FfiType::Struct(FfiStructName("Result".into()))));
+    let mut_reference = FfiType::MutReference(Box::new(FfiType::UInt64));
+
+    let mut encoder = SpecEncoder::new();
+    let scalar_args: Vec<&FfiType> = scalars.iter().map(|(ty, _)| ty).collect();
+    encoder.function(
+        "fn_scalars",
+        &scalar_args,
+        Some(&FfiType::Int32),
+        true,
+        false,
+    );
+    encoder.function("fn_void", &[], None, false, false);
+    encoder.function(
+        "fn_bytes",
+        &[],
+        Some(&FfiType::RustBuffer(None)),
+        true,
+        true,
+    );
+    encoder.callback(
+        "CallbackMethod",
+        &[&FfiType::UInt64, &callback],
+        Some(&reference),
+        true,
+        true,
+        false,
+    );
+    encoder.callback(
+        "Continuation",
+        &[&FfiType::UInt64],
+        None,
+        false,
+        false,
+        true,
+    );
+    encoder.structure("VTable", &[("method", &callback), ("free", &mut_reference)]);
+    encoder.api_checks(
+        "ffi_uniffi_contract_version",
+        29,
+        &[
+            ("uniffi_checksum_fn_scalars", 4021),
+            ("uniffi_checksum_fn_void", 7),
+        ],
+    );
+    let bytes = encoder.finish("rb_alloc", "rb_free", "rb_from_bytes");
+
+    let spec = decode(&bytes).expect("the runtime decodes codegen output");
+
+    assert_eq!(spec.rustbuffer_symbols.alloc, "rb_alloc");
+    assert_eq!(spec.rustbuffer_symbols.free, "rb_free");
+    assert_eq!(spec.rustbuffer_symbols.from_bytes, "rb_from_bytes");
+
+    let f = &spec.functions["fn_scalars"];
+    let expected: Vec<FfiTypeDesc> = scalars.into_iter().map(|(_, desc)| desc).collect();
+    assert_eq!(f.args, expected);
+    assert_eq!(f.ret, FfiTypeDesc::Int32);
+    assert!(f.has_rust_call_status);
+    assert!(!f.external_return);
+
+    let f = &spec.functions["fn_void"];
+    assert!(f.args.is_empty());
+    assert_eq!(f.ret, FfiTypeDesc::Void);
+    assert!(!f.has_rust_call_status);
+    assert!(!f.external_return);
+
+    let f = &spec.functions["fn_bytes"];
+    assert_eq!(f.ret, FfiTypeDesc::RustBuffer);
+    assert!(f.has_rust_call_status);
+    assert!(f.external_return);
+
+    let cb = &spec.callbacks["CallbackMethod"];
+    assert_eq!(
+        cb.args,
+        vec![
+            FfiTypeDesc::UInt64,
+            FfiTypeDesc::Callback("Complete".into())
+        ]
+    );
+    assert_eq!(
+        cb.ret,
+        FfiTypeDesc::Reference(Box::new(FfiTypeDesc::Struct("Result".into())))
+    );
+    assert!(cb.has_rust_call_status);
+    assert!(cb.out_return);
+    assert!(!cb.once);
+
+    let cb = &spec.callbacks["Continuation"];
+    assert_eq!(cb.ret, FfiTypeDesc::Void);
+    assert!(!cb.has_rust_call_status);
+    assert!(!cb.out_return);
+    assert!(cb.once);
+
+    let fields = &spec.structs["VTable"].fields;
+    assert_eq!(fields[0].name, "method");
+    assert_eq!(
+        fields[0].field_type,
+        FfiTypeDesc::Callback("Complete".into())
+    );
+    assert_eq!(fields[1].name, "free");
+    assert_eq!(
+        fields[1].field_type,
+        FfiTypeDesc::MutReference(Box::new(FfiTypeDesc::UInt64))
+    );
+
+    let checks = spec.api_checks.expect("the api checks are encoded");
+    assert_eq!(
+        checks.contract_version_symbol,
+        "ffi_uniffi_contract_version"
+    );
+    assert_eq!(checks.contract_version, 29);
+    assert_eq!(checks.checksums["uniffi_checksum_fn_scalars"], 4021);
+    assert_eq!(checks.checksums["uniffi_checksum_fn_void"], 7);
+}
+
+#[test]
+fn runtime_decodes_codegen_spec_without_checks() {
+    let bytes = SpecEncoder::new().finish("rb_alloc", "rb_free", "rb_from_bytes");
+    let spec = decode(&bytes).expect("the runtime decodes codegen output");
+    assert!(spec.api_checks.is_none());
+}
@@ -4,7 +4,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 use expect_test::expect;
-use ubrn_bindgen::__player_template_test::{render_minimal_for_test, LibResolution, TripleStyle};
+use ubrn_bindgen::__player_template_test::{
+    render_batched_for_test, render_minimal_for_test, LibResolution, TripleStyle,
+};
 
 fn extract_getter_block(rendered: &str) -> String {
     let start = rendered
@@ -21,6 +23,7 @@ fn template_colocated() {
     let rendered = render_minimal_for_test(LibResolution::Colocated, "my_crate");
     expect![[r#"
         let _nativeModule: NativeModuleInterface | undefined;
+        let _uniffiModule: ReturnType<typeof UniffiNativeModule.open> | undefined;
         const getter: () => NativeModuleInterface = () => {
           if (!_nativeModule) {
             const libPath = resolveLibPath({
@@ -29,9 +32,33 @@ fn template_colocated() {
             });
             const mod_ = UniffiNativeModule.open(libPath);
             _nativeModule = mod_.register(DEFINITIONS) as unknown as NativeModuleInterface;
+            _uniffiModule = mod_;
           }
           return _nativeModule;
         };
+
+        let _generation = 0;
+
+        // Counts the reloads so far. An object records it when it is made, and is
+        // refused once it has moved on: its handle points into a replaced library.
+        export function uniffiGeneration(): number {
+          return _generation;
+        }
+
+        // Swaps in a rebuilt copy of the library at `libPath`, checked against these
+        // definitions, and rebinds every call to it. See `UniffiNativeModule.reload`.
+        export function uniffiReload(libPath: string): void {
+          getter();
+          _nativeModule = _uniffiModule!.reload(libPath, DEFINITIONS) as unknown as NativeModuleInterface;
+          _generation += 1;
+        }
+
+        // The runtime's counters for the library: its live callback trampolines and
+        // external buffers. See `UniffiNativeModule.metrics`.
+        export function uniffiMetrics(): ReturnType<NonNullable<typeof _uniffiModule>["metrics"]> {
+          getter();
+          return _uniffiModule!.metrics();
+        }
         export default getter;"#]]
     .assert_eq(&extract_getter_block(&rendered));
 }
@@ -44,6 +71,7 @@ fn template_absolute() {
     );
     expect![[r#"
         let _nativeModule: NativeModuleInterface | undefined;
+        let _uniffiModule: ReturnType<typeof UniffiNativeModule.open> | undefined;
         const getter: () => NativeModuleInterface = () => {
           if (!_nativeModule) {
             const libPath = resolveLibPath({
@@ -53,9 +81,33 @@ fn template_absolute() {
             });
             const mod_ = UniffiNativeModule.open(libPath);
             _nativeModule = mod_.register(DEFINITIONS) as unknown as NativeModuleInterface;
+            _uniffiModule = mod_;
           }
           return _nativeModule;
         };
+
+        let _generation = 0;
+
+        // Counts the reloads so far. An object records it when it is made, and is
+        // refused once it has moved on: its handle points into a replaced library.
+        export function uniffiGeneration(): number {
+          return _generation;
+        }
+
+        // Swaps in a rebuilt copy of the library at `libPath`, checked against these
+        // definitions, and rebinds every call to it. See `UniffiNativeModule.reload`.
+        export function uniffiReload(libPath: string): void {
+          getter();
+          _nativeModule = _uniffiModule!.reload(libPath, DEFINITIONS) as unknown as NativeModuleInterface;
+          _generation += 1;
+        }
+
+        // The runtime's counters for the library: its live callback trampolines and
+        // external buffers. See `UniffiNativeModule.metrics`.
+        export function uniffiMetrics(): ReturnType<NonNullable<typeof _uniffiModule>["metrics"]> {
+          getter();
+          return _uniffiModule!.metrics();
+        }
         export default getter;"#]]
     .assert_eq(&extract_getter_block(&rendered));
 }
@@ -71,6 +123,7 @@ fn template_require_cargo() {
     );
     expect![[r#"
         let _nativeModule: NativeModuleInterface | undefined;
+        let _uniffiModule: ReturnType<typeof UniffiNativeModule.open> | undefined;
         const getter: () => NativeModuleInterface = () => {
           if (!_nativeModule) {
             const libPath = resolveLibPath({
@@ -81,9 +134,33 @@ fn template_require_cargo() {
             });
             const mod_ = UniffiNativeModule.open(libPath);
             _nativeModule = mod_.register(DEFINITIONS) as unknown as NativeModuleInterface;
+            _uniffiModule = mod_;
           }
           return _nativeModule;
         };
+
+        let _generation = 0;
+
+        // Counts the reloads so far. An object records it when it is made, and is
+        // refused once it has moved on: its handle points into a replaced library.
+        export function uniffiGeneration(): number {
+          return _generation;
+        }
+
+        // Swaps in a rebuilt copy of the library at `libPath`, checked against these
+        // definitions, and rebinds every call to it. See `UniffiNativeModule.reload`.
+        export function uniffiReload(libPath: string): void {
+          getter();
+          _nativeModule = _uniffiModule!.reload(libPath, DEFINITIONS) as unknown as NativeModuleInterface;
+          _generation += 1;
+        }
+
+        // The runtime's counters for the library: its live callback trampolines and
+        // external buffers. See `UniffiNativeModule.metrics`.
+        export function uniffiMetrics(): ReturnType<NonNullable<typeof _uniffiModule>["metrics"]> {
+          getter();
+          return _uniffiModule!.metrics();
+        }
         export default getter;"#]]
     .assert_eq(&extract_getter_block(&rendered));
 }
@@ -99,6 +176,7 @@ fn template_require_node() {
     );
     expect![[r#"
         let _nativeModule: NativeModuleInterface | undefined;
+        let _uniffiModule: ReturnType<typeof UniffiNativeModule.open> | undefined;
         const getter: () => NativeModuleInterface = () => {
           if (!_nativeModule) {
             const libPath = resolveLibPath({
@@ -109,9 +187,33 @@ fn template_require_node() {
             });
             const mod_ = UniffiNativeModule.open(libPath);
             _nativeModule = mod_.register(DEFINITIONS) as unknown as NativeModuleInterface;
+            _uniffiModule = mod_;
           }
           return _nativeModule;
         };
+
+        let _generation = 0;
+
+        // Counts the reloads so far. An object records it when it is made, and is
+        // refused once it has moved on: its handle points into a replaced library.
+        export function uniffiGeneration(): number {
+          return _generation;
+        }
+
+        // Swaps in a rebuilt copy of the library at `libPath`, checked against these
+        // definitions, and rebinds every call to it. See `UniffiNativeModule.reload`.
+        export function uniffiReload(libPath: string): void {
+          getter();
+          _nativeModule = _uniffiModule!.reload(libPath, DEFINITIONS) as unknown as NativeModuleInterface;
+          _generation += 1;
+        }
+
+        // The runtime's counters for the library: its live callback trampolines and
+        // external buffers. See `UniffiNativeModule.metrics`.
+        export function uniffiMetrics(): ReturnType<NonNullable<typeof _uniffiModule>["metrics"]> {
+          getter();
+          return _uniffiModule!.metrics();
+        }
         export default getter;"#]]
     .assert_eq(&extract_getter_block(&rendered));
 }
@@ -150,3 +252,60 @@ fn template_absolute_with_windows_path_uses_forward_slashes() {
     // No backslash should appear in the rendered string literal.
     assert!(!block.contains('\\'), "backslash leaked: {block}");
 }
+
+#[test]
+fn template_napi_definitions_are_binary() {
+    let rendered = render_minimal_for_test(LibResolution::Colocated, "my_crate");
+    let start = rendered
+        .find("const DEFINITIONS")
+        .expect("could not find DEFINITIONS in rendered template");
+    let end = start
+        + rendered[start..]
+            .find("]);")
+            .expect("unterminated DEFINITIONS")
+        + 3;
+    expect![[r#"
+        const DEFINITIONS = new Uint8Array([
+          85, 66, 83, 80, 3, 0, 15, 0, 0, 0, 117, 98, 114, 110, 95, 116, 101, 115, 116, 95, 97, 108, 108, 111,
+          99, 14, 0, 0, 0, 117, 98, 114, 110, 95, 116, 101, 115, 116, 95, 102, 114, 101, 101, 20, 0, 0, 0, 117,
+          98, 114, 110, 95, 116, 101, 115, 116, 95, 102, 114, 111, 109, 95, 98, 121, 116, 101, 115, 0, 0, 0, 0, 0,
+          0, 0, 0, 0, 0, 0, 0, 0,
+        ]);"#]]
+    .assert_eq(&rendered[start..end]);
+}
+
+#[test]
+fn template_batched_function_has_helper() {
+    let rendered = render_batched_for_test("uniffi_my_crate_fn_func_push");
+    assert!(
+        rendered.contains("batch?(calls: Array<[string, unknown[]]>): unknown[];"),
+        "expected an optional `batch` on NativeModuleInterface"
+    );
+    let start = rendered
+        .find("export function uniffi_my_crate_fn_func_push_batch")
+        .expect("could not find the batch helper in rendered template");
+    let end = start
+        + rendered[start..]
+            .find("\n}\n")
+            .expect("unterminated helper")
+        + 2;
+    expect![[r#"
+        export function uniffi_my_crate_fn_func_push_batch(
+          calls: Array<[value: number, uniffi_out_err: UniffiRustCallStatus]>,
+        ): Array<number> {
+          const nm = getter();
+          if (nm.batch === undefined) {
+            return calls.map((args) => nm.uniffi_my_crate_fn_func_push(...args));
+          }
+          return nm.batch(
+            calls.map((args) => ["uniffi_my_crate_fn_func_push", args]),
+          ) as Array<number>;
+        }"#]]
+    .assert_eq(&rendered[start..end]);
+}
+
+#[test]
+fn template_without_batch_config_has_no_batch_method() {
+    let rendered = render_minimal_for_test(LibResolution::Colocated, "my_crate");
+    assert!(!rendered.contains("batch"));
+}
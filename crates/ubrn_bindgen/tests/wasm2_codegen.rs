@@ -30,6 +30,13 @@ fn wasm2_player_ffi_renders_expected_markers() {
         "expected PLAYER_DEFINITIONS export in rendered output:\n{rendered}"
     );
 
+    // The runtime checks the `.wasm` against these before registering.
+    assert!(
+        rendered.contains(r#"contractVersionSymbol: "ffi_ubrn_test_uniffi_contract_version","#)
+            && rendered.contains("contractVersion: 29,"),
+        "expected apiChecks in the wasm2 definitions:\n{rendered}"
+    );
+
     // Wasm2 lets the runtime push the native module in via setNativeModule.
     assert!(
         rendered.contains("export function setNativeModule"),
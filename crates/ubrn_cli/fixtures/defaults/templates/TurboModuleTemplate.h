@@ -0,0 +1,13 @@
+{%- set ns = config.project.cpp_namespace() -%}
+// Overridden by the project
+#pragma once
+#include <cstdint>
+#include <jsi/jsi.h>
+#include <ReactCommon/CallInvoker.h>
+
+namespace {{ ns }} {
+  using namespace facebook;
+
+  uint8_t installRustCrate(jsi::Runtime &runtime, std::shared_ptr<react::CallInvoker> callInvoker);
+  uint8_t cleanupRustCrate(jsi::Runtime &runtime);
+}
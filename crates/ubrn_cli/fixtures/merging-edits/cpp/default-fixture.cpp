@@ -0,0 +1,16 @@
+// Generated by uniffi-bindgen-react-native
+#include "default-fixture.h"
+#include "generated/arithmetic.hpp"
+
+namespace defaultfixture {
+	using namespace facebook;
+
+	uint8_t installRustCrate(jsi::Runtime &runtime, std::shared_ptr<react::CallInvoker> callInvoker) {
+		NativeArithmetic::registerModule(runtime, callInvoker);
+		return true;
+	}
+
+	uint8_t cleanupRustCrate(jsi::Runtime &runtime) {
+		return cleanupExtras(runtime);
+	}
+}
@@ -0,0 +1,15 @@
+#ifndef DEFAULTFIXTURE_H
+#define DEFAULTFIXTURE_H
+// Generated by uniffi-bindgen-react-native
+#include <jsi/jsi.h>
+#include <ReactCommon/CallInvoker.h>
+
+namespace defaultfixture {
+  using namespace facebook;
+
+  uint8_t installRustCrate(jsi::Runtime &runtime, std::shared_ptr<react::CallInvoker> callInvoker);
+  uint8_t cleanupRustCrate(jsi::Runtime &runtime);
+  uint8_t installExtras(jsi::Runtime &runtime);
+}
+
+#endif /* DEFAULTFIXTURE_H */
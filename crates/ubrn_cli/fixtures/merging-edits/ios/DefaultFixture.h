@@ -0,0 +1,2 @@
+// Written before .ubrn/generated was kept.
+#import <Foundation/Foundation.h>
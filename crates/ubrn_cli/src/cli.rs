@@ -7,7 +7,10 @@ use anyhow::Result;
 use clap::{Parser, Subcommand};
 
 use crate::{
-    commands::{checkout::GitRepoArgs, BuildArgs, CheckoutArgs, GenerateArgs},
+    commands::{
+        checkout::GitRepos, ApiDiffArgs, BuildArgs, CheckArgs, CheckoutArgs, GenerateArgs,
+        PackageArgs,
+    },
     workspace, AsConfig,
 };
 
@@ -33,16 +36,35 @@ pub(crate) enum CliCmd {
     ///
     /// These steps are already performed when building with `--and-generate`.
     Generate(GenerateArgs),
+    /// Build the crate and lay it out as npm packages, one per platform.
+    ///
+    /// Also adds those packages to the `optionalDependencies` of the
+    /// project's `package.json`, so npm installs only the matching one.
+    Package(PackageArgs),
+    /// Validate the project config and toolchain without building.
+    ///
+    /// Prints the names derived from the config and `package.json`, and
+    /// checks for the Rust targets, tools and crate type each platform needs.
+    Check(CheckArgs),
+    /// Compare the APIs of two builds of a library.
+    ///
+    /// Lists what was added, removed or changed, and whether each change
+    /// breaks TypeScript code written against the old bindings. Exits with
+    /// an error if any do.
+    ApiDiff(ApiDiffArgs),
 }
 
 impl CliCmd {
     pub(crate) fn run(&self) -> Result<()> {
         match self {
             Self::Checkout(c) => {
-                AsConfig::<GitRepoArgs>::as_config(c)?.checkout(&workspace::project_root()?)
+                AsConfig::<GitRepos>::as_config(c)?.checkout(&workspace::project_root()?)
             }
             Self::Build(b) => b.build(),
             Self::Generate(g) => g.run(),
+            Self::Package(p) => p.run(),
+            Self::Check(c) => c.run(),
+            Self::ApiDiff(a) => a.run(),
         }
     }
 }
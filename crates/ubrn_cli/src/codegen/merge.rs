@@ -0,0 +1,175 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! The last generated version of each file is kept in `.ubrn/generated`, so
+//! that changes made to a file since can be carried over to the next one,
+//! with a three-way merge.
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use ubrn_common::mk_dir;
+
+const STATE_DIR: &str = ".ubrn/generated";
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Merge {
+    /// Nothing has been changed by hand: the file is as generated.
+    Generated(String),
+    /// The changes made by hand are kept, alongside the generated ones.
+    Merged(String),
+    /// The changes made by hand and the generated ones overlap, and are
+    /// written between conflict markers.
+    Conflicted(String),
+}
+
+impl Merge {
+    fn contents(&self) -> &str {
+        match self {
+            Self::Generated(s) | Self::Merged(s) | Self::Conflicted(s) => s,
+        }
+    }
+}
+
+pub(crate) struct GeneratedFiles {
+    project_root: Utf8PathBuf,
+}
+
+impl GeneratedFiles {
+    pub(crate) fn new(project_root: &Utf8Path) -> Self {
+        Self {
+            project_root: project_root.to_owned(),
+        }
+    }
+
+    fn last_generated_path(&self, rel: &Utf8Path) -> Utf8PathBuf {
+        self.project_root.join(STATE_DIR).join(rel)
+    }
+
+    /// Write the generated contents to the file, keeping whatever has been
+    /// changed in it since it was last generated.
+    pub(crate) fn write(&self, rel: &Utf8Path, generated: &str) -> Result<Merge> {
+        let path = self.project_root.join(rel);
+        let last_generated = read_if_exists(&self.last_generated_path(rel))?;
+        let on_disk = read_if_exists(&path)?;
+        let merge = three_way_merge(last_generated.as_deref(), on_disk.as_deref(), generated);
+
+        mk_dir(path.parent().expect("Parent for path"))?;
+        ubrn_common::write_file(&path, merge.contents())?;
+
+        let last_generated = self.last_generated_path(rel);
+        mk_dir(last_generated.parent().expect("Parent for path"))?;
+        ubrn_common::write_file(&last_generated, generated)?;
+        Ok(merge)
+    }
+}
+
+/// Whether the path, relative to the project root, is the last generated
+/// copy of another file.
+pub(crate) fn is_last_generated(path: &Utf8Path) -> bool {
+    path.starts_with(STATE_DIR)
+}
+
+fn read_if_exists(path: &Utf8Path) -> Result<Option<String>> {
+    Ok(if path.exists() {
+        Some(ubrn_common::read_to_string(path)?)
+    } else {
+        None
+    })
+}
+
+fn three_way_merge(last_generated: Option<&str>, on_disk: Option<&str>, generated: &str) -> Merge {
+    match (last_generated, on_disk) {
+        (_, None) => Merge::Generated(generated.to_string()),
+        (_, Some(disk)) if disk == generated => Merge::Generated(generated.to_string()),
+        (Some(last), Some(disk)) if last == disk => Merge::Generated(generated.to_string()),
+        // Without a record of what was last generated, there is no telling
+        // the changes made by hand from those made to the templates, so
+        // the whole file is left for the user to resolve.
+        (None, Some(disk)) => match diffy::merge("", disk, generated) {
+            Ok(merged) => Merge::Merged(merged),
+            Err(conflicted) => Merge::Conflicted(conflicted),
+        },
+        (Some(last), Some(disk)) if last == generated => Merge::Merged(disk.to_string()),
+        (Some(last), Some(disk)) => match diffy::merge(last, disk, generated) {
+            Ok(merged) => Merge::Merged(merged),
+            Err(conflicted) => Merge::Conflicted(conflicted),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAST: &str = "one\ntwo\nthree\nfour\nfive\n";
+
+    #[test]
+    fn untouched_files_are_regenerated() {
+        let generated = "one\ntwo\n3\nfour\nfive\n";
+        assert_eq!(
+            three_way_merge(Some(LAST), Some(LAST), generated),
+            Merge::Generated(generated.to_string())
+        );
+        assert_eq!(
+            three_way_merge(Some(LAST), None, generated),
+            Merge::Generated(generated.to_string())
+        );
+        assert_eq!(
+            three_way_merge(None, Some(generated), generated),
+            Merge::Generated(generated.to_string())
+        );
+    }
+
+    #[test]
+    fn edits_without_a_last_generated_copy_are_marked_as_conflicts() {
+        let generated = "one\ntwo\n3\nfour\nfive\n";
+        let Merge::Conflicted(text) = three_way_merge(None, Some("edited\n"), generated) else {
+            panic!("Expected a conflict");
+        };
+        assert_eq!(
+            text,
+            "<<<<<<< ours\nedited\n\
+             ||||||| original\n\
+             =======\none\ntwo\n3\nfour\nfive\n\
+             >>>>>>> theirs\n"
+        );
+    }
+
+    #[test]
+    fn edits_are_kept_when_nothing_new_is_generated() {
+        let edited = "one\ntwo\nthree\nfour\nfive\nsix\n";
+        assert_eq!(
+            three_way_merge(Some(LAST), Some(edited), LAST),
+            Merge::Merged(edited.to_string())
+        );
+    }
+
+    #[test]
+    fn edits_are_merged_with_what_is_generated() {
+        let edited = "one\ntwo\nthree\nfour\nfive\nsix\n";
+        let generated = "1\ntwo\nthree\nfour\nfive\n";
+        assert_eq!(
+            three_way_merge(Some(LAST), Some(edited), generated),
+            Merge::Merged("1\ntwo\nthree\nfour\nfive\nsix\n".to_string())
+        );
+    }
+
+    #[test]
+    fn overlapping_edits_are_marked_as_conflicts() {
+        let edited = "one\ntwo\nTHREE\nfour\nfive\n";
+        let generated = "one\ntwo\n3\nfour\nfive\n";
+        let Merge::Conflicted(text) = three_way_merge(Some(LAST), Some(edited), generated) else {
+            panic!("Expected a conflict");
+        };
+        assert_eq!(
+            text,
+            "one\ntwo\n\
+             <<<<<<< ours\nTHREE\n\
+             ||||||| original\nthree\n\
+             =======\n3\n\
+             >>>>>>> theirs\n\
+             four\nfive\n"
+        );
+    }
+}
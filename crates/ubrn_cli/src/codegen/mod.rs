@@ -5,27 +5,35 @@
  */
 use std::{cell::OnceCell, collections::BTreeMap, rc::Rc};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use askama::DynTemplate;
 use camino::{Utf8Path, Utf8PathBuf};
 use path_slash::PathExt;
 
 use ubrn_bindgen::ModuleMetadata;
-use ubrn_common::{mk_dir, CrateMetadata};
+use ubrn_common::CrateMetadata;
 
 use crate::config::ProjectConfig;
 
-pub(crate) trait RenderedFile: DynTemplate {
+mod merge;
+mod overrides;
+
+pub(crate) use merge::is_last_generated;
+use merge::{GeneratedFiles, Merge};
+pub(crate) use overrides::eject;
+use overrides::TemplateOverrides;
+
+/// Implemented by `templated_file!`.
+pub(crate) trait NamedTemplate {
+    /// The file name of the template, which an override in the `templates`
+    /// directory shares.
+    fn template_name(&self) -> &'static str;
+}
+
+pub(crate) trait RenderedFile: DynTemplate + NamedTemplate {
     fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf;
     fn relative_to(&self, project_root: &Utf8Path, to: &Utf8Path) -> Utf8PathBuf {
-        let file = self.path(project_root);
-        let from = file
-            .parent()
-            .expect("Expected this file to have a directory");
-        let rel =
-            pathdiff::diff_utf8_paths(to, from).expect("Should be able to find a relative path");
-        // Normalize to forward slashes so templates produce valid paths on Windows.
-        Utf8PathBuf::from(rel.as_std_path().to_slash_lossy().as_ref())
+        relative_to_file(&self.path(project_root), to)
     }
     fn filter_by(&self) -> bool {
         true
@@ -36,6 +44,15 @@ pub(crate) trait RenderedFile: DynTemplate {
     }
 }
 
+fn relative_to_file(file: &Utf8Path, to: &Utf8Path) -> Utf8PathBuf {
+    let from = file
+        .parent()
+        .expect("Expected this file to have a directory");
+    let rel = pathdiff::diff_utf8_paths(to, from).expect("Should be able to find a relative path");
+    // Normalize to forward slashes so templates produce valid paths on Windows.
+    Utf8PathBuf::from(rel.as_std_path().to_slash_lossy().as_ref())
+}
+
 pub(crate) struct TemplateConfig {
     pub(crate) project: ProjectConfig,
     pub(crate) rust_crate: CrateMetadata,
@@ -83,8 +100,11 @@ pub(crate) fn render_files(
 ) -> Result<()> {
     let files = files.filter(|f| f.filter_by());
     let project_root = config.project.project_root();
-    let map = render_templates(project_root, files)?;
+    let overrides = TemplateOverrides::new(&config)?;
+    let map = render_templates(project_root, &overrides, files)?;
     let exclude_files = config.project.exclude_files();
+    let generated_files = GeneratedFiles::new(project_root);
+    let mut conflicts = Vec::new();
     for (path, contents) in map {
         // We don't want to write files that the config file has excluded.
         // In order to test if it is excluded, we need to get the file path
@@ -94,9 +114,23 @@ pub(crate) fn render_files(
         if exclude_files.is_match(&rel) {
             continue;
         }
-        let parent = path.parent().expect("Parent for path");
-        mk_dir(parent)?;
-        ubrn_common::write_file(path, contents)?;
+        match generated_files.write(&rel, &contents)? {
+            Merge::Generated(_) => {}
+            Merge::Merged(_) => println!("Kept the changes made to {rel}"),
+            Merge::Conflicted(_) => conflicts.push(rel),
+        }
+    }
+
+    if !conflicts.is_empty() {
+        let files = conflicts
+            .iter()
+            .map(|rel| format!("  {rel}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        bail!(
+            "The changes made to these files conflict with the newly generated code:\n{files}\n\
+             Resolve the conflict markers in each, then regenerate to check."
+        );
     }
 
     Ok(())
@@ -104,11 +138,15 @@ pub(crate) fn render_files(
 
 fn render_templates(
     project_root: &Utf8Path,
+    overrides: &TemplateOverrides,
     files: impl Iterator<Item = Rc<dyn RenderedFile>>,
 ) -> Result<BTreeMap<Utf8PathBuf, String>> {
     let mut map = BTreeMap::default();
     for f in files {
-        let text = f.dyn_render()?;
+        let text = match overrides.render(project_root, f.as_ref())? {
+            Some(text) => text,
+            None => f.dyn_render()?,
+        };
         let path = f.path(project_root);
         map.insert(path, f.transform_str(project_root, text)?);
     }
@@ -138,6 +176,12 @@ macro_rules! templated_file {
                     self.config.project.project_root().into()
                 }
             }
+
+            impl $crate::codegen::NamedTemplate for $T {
+                fn template_name(&self) -> &'static str {
+                    $filename
+                }
+            }
         }
     };
 }
@@ -174,6 +218,8 @@ pub(crate) mod files {
             Platform::Wasm2 => {
                 files.extend(wasm2::get_files(config.clone()));
             }
+            // Node loads the library directly: there is no glue to generate.
+            Platform::Napi => {}
         }
         files
     }
@@ -220,6 +266,7 @@ mod tests {
                 targets: Default::default(),
                 cargo_extras: ExtraArgs::default(),
                 codegen_output_dir: "ios/generated".to_string(),
+                deployment_target: "13.4".to_string(),
             };
             let bindings = BindingsConfig {
                 cpp: "cpp/bindings".to_string(),
@@ -232,6 +279,7 @@ mod tests {
                 ts: "src".to_string(),
                 spec_name: "MyRustCrate".to_string(),
                 entrypoint: "index.react-native.tsx".to_string(),
+                mode: Default::default(),
             };
             let repository = format!("https://github.com/user/{name}");
 
@@ -251,14 +299,19 @@ mod tests {
                 wasm,
                 #[cfg(feature = "wasm")]
                 wasm2,
+                napi: Default::default(),
                 bindings,
                 tm,
                 exclude_files: Default::default(),
+                templates: None,
             }
         }
     }
 
-    fn create_template_config(name: &str, modules: &[&str]) -> Result<Rc<TemplateConfig>> {
+    pub(super) fn create_template_config(
+        name: &str,
+        modules: &[&str],
+    ) -> Result<Rc<TemplateConfig>> {
         let manifest_dir: Utf8PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
         let crate_metadata = CrateMetadata::try_from(manifest_dir.clone())?;
         let crate_config: CrateConfig = crate_metadata.clone().try_into()?;
@@ -0,0 +1,569 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! Templates from the `templates` directory of the config file, rendered at
+//! runtime in place of the built-in template of the same name.
+//!
+//! The built-in turbo-module and platform templates keep to the syntax that
+//! both askama and minijinja understand, so an ejected template renders here
+//! unchanged. The context mirrors the askama one: `config`, with the same
+//! fields and methods, and the `project_root()` and `relative_to(root, dir)`
+//! helpers.
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use minijinja::{
+    context,
+    value::{from_args, Object, ObjectRepr, Rest},
+    AutoEscape, Environment, Error, ErrorKind, State, UndefinedBehavior, Value,
+};
+use ubrn_bindgen::ModuleMetadata;
+use ubrn_common::{mk_dir, CrateMetadata};
+
+use super::{relative_to_file, RenderedFile, TemplateConfig};
+use crate::{
+    config::{BindingsConfig, ProjectConfig},
+    jsi::{android::AndroidConfig, crossplatform::TurboModulesConfig, ios::IOsConfig},
+};
+
+macro_rules! builtin {
+    ($dir:literal, $name:literal) => {
+        (
+            $name,
+            include_str!(concat!("../../templates/jsi/", $dir, "/", $name)),
+        )
+    };
+}
+
+/// The templates which can be overridden, by name.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    builtin!("android", "AndroidManifest.xml"),
+    builtin!("android", "CMakeLists.txt"),
+    builtin!("android", "ExpoModuleTemplate.kt"),
+    builtin!("android", "ModuleTemplate.java"),
+    builtin!("android", "ModuleTemplate.kt"),
+    builtin!("android", "PackageTemplate.java"),
+    builtin!("android", "PackageTemplate.kt"),
+    builtin!("android", "build.gradle"),
+    builtin!("android", "build.kt.gradle"),
+    builtin!("android", "cpp-adapter.cpp"),
+    builtin!("android", "proguard-rules.pro"),
+    builtin!("crossplatform", "NativeCodegenTemplate.ts"),
+    builtin!("crossplatform", "NativeExpoTemplate.ts"),
+    builtin!("crossplatform", "TurboModuleTemplate.cpp"),
+    builtin!("crossplatform", "TurboModuleTemplate.h"),
+    builtin!("crossplatform", "app.plugin.js"),
+    builtin!("crossplatform", "expo-module.config.json"),
+    builtin!("crossplatform", "index.tsx"),
+    builtin!("ios", "ExpoInstallerTemplate.h"),
+    builtin!("ios", "ExpoInstallerTemplate.mm"),
+    builtin!("ios", "ExpoModuleTemplate.swift"),
+    builtin!("ios", "ModuleTemplate.h"),
+    builtin!("ios", "ModuleTemplate.mm"),
+    builtin!("ios", "module-template.podspec"),
+];
+
+fn builtin_template(name: &str) -> Option<&'static str> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, source)| *source)
+}
+
+fn builtin_names() -> String {
+    BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, _)| format!("  {name}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write the built-in template out to the templates directory, as a starting
+/// point for an override.
+pub(crate) fn eject(project: &ProjectConfig, name: &str) -> Result<Utf8PathBuf> {
+    let Some(source) = builtin_template(name) else {
+        bail!("{name} is not one of the templates:\n{}", builtin_names());
+    };
+    let Some(dir) = project.templates_dir() else {
+        bail!("Set `templates` in the config file to the directory to eject {name} into");
+    };
+    let path = dir.join(name);
+    if path.exists() {
+        bail!("{path} already exists");
+    }
+    mk_dir(&dir)?;
+    ubrn_common::write_file(&path, source)?;
+    Ok(path)
+}
+
+#[derive(Default)]
+pub(crate) struct TemplateOverrides {
+    sources: BTreeMap<String, String>,
+    config: Value,
+}
+
+impl TemplateOverrides {
+    pub(crate) fn new(config: &TemplateConfig) -> Result<Self> {
+        let Some(dir) = config.project.templates_dir() else {
+            return Ok(Default::default());
+        };
+        if !dir.is_dir() {
+            bail!("The templates directory {dir} does not exist");
+        }
+        let mut sources = BTreeMap::new();
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            let name = entry.file_name();
+            if name.starts_with('.') {
+                continue;
+            }
+            if builtin_template(name).is_none() {
+                bail!(
+                    "{} does not override any of the templates:\n{}",
+                    entry.path(),
+                    builtin_names()
+                );
+            }
+            let source = ubrn_common::read_to_string(entry.path())?;
+            sources.insert(name.to_string(), source);
+        }
+        Ok(Self {
+            sources,
+            config: config_value(config),
+        })
+    }
+
+    /// Render the override for this file, if there is one.
+    pub(crate) fn render(
+        &self,
+        project_root: &Utf8Path,
+        file: &dyn RenderedFile,
+    ) -> Result<Option<String>> {
+        let name = file.template_name();
+        let Some(source) = self.sources.get(name) else {
+            return Ok(None);
+        };
+        let file_path = file.path(project_root);
+        let root = Utf8PathBuf::from(project_root);
+        let env = environment();
+        let text = env
+            .template_from_named_str(name, source)
+            .and_then(|template| {
+                template.render(context! {
+                    config => self.config.clone(),
+                    project_root => Value::from_function(move || path_value(root.clone())),
+                    relative_to => Value::from_function(move |_root: Value, to: Value| {
+                        let to = Utf8PathBuf::from(to.to_string());
+                        path_value(relative_to_file(&file_path, &to))
+                    }),
+                })
+            })
+            .with_context(|| format!("Failed to render the {name} template override"))?;
+        Ok(Some(text))
+    }
+}
+
+fn environment() -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_auto_escape_callback(|_| AutoEscape::None);
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.add_filter("fmt", |value: Value, format: String| {
+        substitute(&format, &[value])
+    });
+    env.add_filter("format", |format: String, args: Rest<Value>| {
+        substitute(&format, &args)
+    });
+    env.set_unknown_method_callback(string_methods);
+    env
+}
+
+/// The `{}` placeholders of askama's `fmt` and `format` filters.
+fn substitute(format: &str, args: &[Value]) -> Result<String, Error> {
+    let mut parts = format.split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    let mut args = args.iter();
+    for part in parts {
+        let Some(arg) = args.next() else {
+            return Err(Error::from(ErrorKind::MissingArgument));
+        };
+        text.push_str(&arg.to_string());
+        text.push_str(part);
+    }
+    if args.next().is_some() {
+        return Err(Error::from(ErrorKind::TooManyArguments));
+    }
+    Ok(text)
+}
+
+/// The methods of Rust's `String` the built-in templates use.
+fn string_methods(_: &State, value: &Value, method: &str, args: &[Value]) -> Result<Value, Error> {
+    match (value.as_str(), method) {
+        (Some(s), "replace") => {
+            let (from, to): (&str, &str) = from_args(args)?;
+            Ok(Value::from(s.replace(from, to)))
+        }
+        _ => Err(Error::from(ErrorKind::UnknownMethod)),
+    }
+}
+
+fn config_value(config: &TemplateConfig) -> Value {
+    let modules: Vec<_> = config
+        .modules
+        .iter()
+        .map(|m| Value::from_object(ModuleValue(m.clone())))
+        .collect();
+    context! {
+        project => Value::from_object(ProjectValue(config.project.clone())),
+        rust_crate => Value::from_object(CrateValue(config.rust_crate.clone())),
+        modules => modules,
+        native_bindings => config.native_bindings,
+    }
+}
+
+fn path_value(path: Utf8PathBuf) -> Value {
+    Value::from_object(PathValue(path))
+}
+
+fn path_arg(args: &[Value]) -> Result<Utf8PathBuf, Error> {
+    let (path,): (Value,) = from_args(args)?;
+    Ok(Utf8PathBuf::from(path.to_string()))
+}
+
+fn no_args<T: Into<Value>>(args: &[Value], value: T) -> Result<Value, Error> {
+    let () = from_args(args)?;
+    Ok(value.into())
+}
+
+#[derive(Debug)]
+struct PathValue(Utf8PathBuf);
+
+impl Object for PathValue {
+    fn repr(self: &Arc<Self>) -> ObjectRepr {
+        ObjectRepr::Plain
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        match method {
+            "join" => Ok(path_value(self.0.join(path_arg(args)?))),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+
+    fn render(self: &Arc<Self>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug)]
+struct ProjectValue(ProjectConfig);
+
+impl Object for ProjectValue {
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        let p = &self.0;
+        Some(match key.as_str()? {
+            "android" => Value::from_object(AndroidValue(p.android.clone())),
+            "ios" => Value::from_object(IosValue(p.ios.clone())),
+            "tm" => Value::from_object(TurboModuleValue(p.tm.clone())),
+            "bindings" => Value::from_object(BindingsValue(p.bindings.clone())),
+            _ => return None,
+        })
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let p = &self.0;
+        match method {
+            "raw_name" => no_args(args, p.raw_name()),
+            "module_cpp" => no_args(args, p.module_cpp()),
+            "cpp_namespace" => no_args(args, p.cpp_namespace()),
+            "cpp_filename" => no_args(args, p.cpp_filename()),
+            "podspec_filename" => no_args(args, p.podspec_filename()),
+            "codegen_filename" => no_args(args, p.codegen_filename()),
+            "spec_name" => no_args(args, p.spec_name()),
+            "repository" => no_args(args, p.repository()),
+            "project_version" => no_args(args, p.project_version()),
+            "ubrn_version" => no_args(args, p.ubrn_version()),
+            "project_root" => no_args(args, path_value(p.project_root().into())),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct AndroidValue(AndroidConfig);
+
+impl Object for AndroidValue {
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        let a = &self.0;
+        Some(match key.as_str()? {
+            "targets" => a.targets.iter().map(|t| t.to_string()).collect(),
+            "use_shared_library" => Value::from(a.use_shared_library),
+            "api_level" => Value::from(a.api_level),
+            _ => return None,
+        })
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let a = &self.0;
+        match method {
+            "clone" => no_args(args, Value::from_object(AndroidValue(a.clone()))),
+            "package_name" => no_args(args, a.package_name()),
+            "directory" => Ok(path_value(a.directory(&path_arg(args)?))),
+            "codegen_output_dir" => Ok(path_value(a.codegen_output_dir(&path_arg(args)?))),
+            "jni_libs" => Ok(path_value(a.jni_libs(&path_arg(args)?))),
+            "src_main_dir" => Ok(path_value(a.src_main_dir(&path_arg(args)?))),
+            "codegen_package_dir" => Ok(path_value(a.codegen_package_dir(&path_arg(args)?))),
+            "library_file" => {
+                let (rust_crate,): (Value,) = from_args(args)?;
+                let Some(rust_crate) = rust_crate.downcast_object_ref::<CrateValue>() else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidOperation,
+                        "library_file takes config.rust_crate",
+                    ));
+                };
+                Ok(Value::from(a.library_file(&rust_crate.0)))
+            }
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IosValue(IOsConfig);
+
+impl Object for IosValue {
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        match key.as_str()? {
+            "framework_name" => Some(Value::from(self.0.framework_name.clone())),
+            "deployment_target" => Some(Value::from(self.0.deployment_target.clone())),
+            _ => None,
+        }
+    }
+
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let i = &self.0;
+        match method {
+            "clone" => no_args(args, Value::from_object(IosValue(i.clone()))),
+            "directory" => Ok(path_value(i.directory(&path_arg(args)?))),
+            "codegen_output_dir" => Ok(path_value(i.codegen_output_dir(&path_arg(args)?))),
+            "framework_path" => Ok(path_value(i.framework_path(&path_arg(args)?))),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TurboModuleValue(TurboModulesConfig);
+
+impl Object for TurboModuleValue {
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let tm = &self.0;
+        match method {
+            "name" => no_args(args, tm.name()),
+            "spec_name" => no_args(args, tm.spec_name()),
+            "is_expo" => no_args(args, tm.is_expo()),
+            "cpp_path" => Ok(path_value(tm.cpp_path(&path_arg(args)?))),
+            "ts_path" => Ok(path_value(tm.ts_path(&path_arg(args)?))),
+            "entrypoint" => Ok(path_value(tm.entrypoint(&path_arg(args)?))),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BindingsValue(BindingsConfig);
+
+impl Object for BindingsValue {
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let b = &self.0;
+        match method {
+            "cpp_path" => Ok(path_value(b.cpp_path(&path_arg(args)?))),
+            "ts_path" => Ok(path_value(b.ts_path(&path_arg(args)?))),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CrateValue(CrateMetadata);
+
+impl Object for CrateValue {
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let c = &self.0;
+        match method {
+            "package_name" => no_args(args, c.package_name()),
+            "library_name" => no_args(args, c.library_name()),
+            "library_file" => {
+                let (target, use_shared_library): (Option<&str>, Option<bool>) = from_args(args)?;
+                Ok(Value::from(c.library_file(target, use_shared_library)))
+            }
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+struct ModuleValue(ModuleMetadata);
+
+impl fmt::Debug for ModuleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ModuleValue").field(&self.0.ts()).finish()
+    }
+}
+
+impl Object for ModuleValue {
+    fn call_method(
+        self: &Arc<Self>,
+        _: &State,
+        method: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let m = &self.0;
+        match method {
+            "ts" => no_args(args, m.ts()),
+            "cpp_module" => no_args(args, m.cpp_module()),
+            "cpp_filename" => no_args(args, m.cpp_filename()),
+            "hpp_filename" => no_args(args, m.hpp_filename()),
+            _ => Err(Error::from(ErrorKind::UnknownMethod)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{
+        codegen::tests::create_template_config, jsi, jsi::crossplatform::config::TurboModuleMode,
+    };
+
+    fn builtins_as_overrides(config: &TemplateConfig) -> TemplateOverrides {
+        let sources = BUILTIN_TEMPLATES
+            .iter()
+            .map(|(name, source)| (name.to_string(), source.to_string()))
+            .collect();
+        TemplateOverrides {
+            sources,
+            config: config_value(config),
+        }
+    }
+
+    // An ejected template must render exactly as the built-in one does.
+    //
+    // Rendering is strict, so a field or method the objects above lack fails
+    // here rather than in a user's project. Every combination of the settings
+    // the templates branch on is rendered, so no branch goes unchecked.
+    #[test]
+    fn builtin_templates_render_the_same_at_runtime() -> Result<()> {
+        let modes = [TurboModuleMode::TurboModule, TurboModuleMode::Expo];
+        let module_lists: [&[&str]; 2] = [&["alice", "bob"], &[]];
+        for mode in modes {
+            for use_shared_library in [false, true] {
+                for native_bindings in [false, true] {
+                    for modules in module_lists {
+                        let mut config = create_template_config("@my-org/my-module", modules)?;
+                        let c = Rc::get_mut(&mut config).unwrap();
+                        c.project.android.use_shared_library = use_shared_library;
+                        c.native_bindings = native_bindings;
+                        c.project.tm.mode = mode;
+                        let overrides = builtins_as_overrides(&config);
+                        let project_root = config.project.project_root();
+                        for file in jsi::get_files(config.clone()) {
+                            let expected = file.dyn_render()?;
+                            let actual = overrides.render(project_root, file.as_ref())?;
+                            assert_eq!(
+                                Some(expected),
+                                actual,
+                                "{} ({mode:?}, shared library: {use_shared_library}, \
+                                 native bindings: {native_bindings}, modules: {modules:?})",
+                                file.template_name()
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_fields_and_methods_fail_the_render() -> Result<()> {
+        let config = create_template_config("my-module", &["alice"])?;
+        let config = config_value(&config);
+        let env = environment();
+        for source in [
+            "{{ config.project.no_such_method() }}",
+            "{{ config.project.android.no_such_method() }}",
+            "{% for m in config.modules %}{{ m.no_such_method() }}{% endfor %}",
+            "{{ config.project.no_such_field }}",
+            "{% if config.project.no_such_field %}{% endif %}",
+        ] {
+            let result = env.render_str(source, context! { config => config.clone() });
+            assert!(result.is_err(), "{source} rendered: {result:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn every_overridable_template_is_built_in() -> Result<()> {
+        let config = create_template_config("my-module", &[])?;
+        let names: Vec<_> = jsi::get_files(config)
+            .iter()
+            .map(|f| f.template_name())
+            .collect();
+        for (name, _) in BUILTIN_TEMPLATES {
+            assert!(names.contains(name), "{name}");
+        }
+        assert_eq!(names.len(), BUILTIN_TEMPLATES.len());
+        Ok(())
+    }
+
+    #[test]
+    fn format_filters_fill_placeholders_in_order() {
+        let env = environment();
+        let text = env
+            .render_str(
+                r#"{{ "Foo"|fmt("{}Module") }} {{ "Java_{}_{}"|format("a.b".replace(".", "_"), "C") }}"#,
+                context! {},
+            )
+            .unwrap();
+        assert_eq!(text, "FooModule Java_a_b_C");
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::fmt::Write as _;
+
+use anyhow::{bail, Result};
+use camino::Utf8PathBuf;
+use clap::{Args, ValueEnum};
+use ubrn_bindgen::api_diff::{ApiChange, ApiDiff};
+
+#[derive(Args, Debug)]
+pub(crate) struct ApiDiffArgs {
+    /// The library, as previously released.
+    #[clap(long)]
+    old: Utf8PathBuf,
+
+    /// The library, as it is now.
+    #[clap(long)]
+    new: Utf8PathBuf,
+
+    /// The Cargo.toml of the crate, needed to find the UDL of crates that use one.
+    #[clap(long)]
+    manifest_path: Option<Utf8PathBuf>,
+
+    /// How to print the changes.
+    #[clap(long, value_enum, default_value_t = Format::Markdown)]
+    format: Format,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Format {
+    Markdown,
+    Json,
+}
+
+impl ApiDiffArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        let diff = ApiDiff::between_libraries(&self.old, &self.new, self.manifest_path.as_deref())?;
+        match self.format {
+            Format::Markdown => print!("{}", markdown(&diff)),
+            Format::Json => println!("{}", serde_json::to_string_pretty(&diff)?),
+        }
+        let breaking = diff.breaking().count();
+        if breaking > 0 {
+            bail!("{breaking} breaking API change(s)");
+        }
+        Ok(())
+    }
+}
+
+fn markdown(diff: &ApiDiff) -> String {
+    if diff.changes.is_empty() {
+        return "No API changes.\n".to_string();
+    }
+    let breaking: Vec<_> = diff.breaking().collect();
+    let non_breaking: Vec<_> = diff.non_breaking().collect();
+    let mut s = format!(
+        "# API changes: {} breaking, {} non-breaking\n",
+        breaking.len(),
+        non_breaking.len()
+    );
+    for (heading, changes) in [("Breaking", breaking), ("Non-breaking", non_breaking)] {
+        if changes.is_empty() {
+            continue;
+        }
+        let _ = write!(s, "\n## {heading}\n\n");
+        for change in changes {
+            s.push_str(&bullet(change));
+        }
+    }
+    s
+}
+
+pub(crate) fn bullet(change: &ApiChange) -> String {
+    let ApiChange {
+        item,
+        kind,
+        change,
+        detail,
+        ..
+    } = change;
+    match detail {
+        Some(detail) => format!("- {kind} `{item}` {change}: {detail}\n"),
+        None => format!("- {kind} `{item}` {change}\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ubrn_bindgen::api_diff::{ChangeKind, ItemKind};
+
+    use super::*;
+
+    fn change(item: &str, kind: ItemKind, change: ChangeKind, breaking: bool) -> ApiChange {
+        ApiChange {
+            item: item.to_string(),
+            kind,
+            change,
+            breaking,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_markdown_groups_by_breaking() {
+        let mut removed = change("ns::add", ItemKind::Function, ChangeKind::Removed, true);
+        removed.detail = Some("gone".to_string());
+        let diff = ApiDiff {
+            changes: vec![
+                removed,
+                change("ns::Point", ItemKind::Record, ChangeKind::Added, false),
+            ],
+        };
+        assert_eq!(
+            markdown(&diff),
+            "# API changes: 1 breaking, 1 non-breaking\n\
+             \n## Breaking\n\n\
+             - function `ns::add` removed: gone\n\
+             \n## Non-breaking\n\n\
+             - record `ns::Point` added\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_no_changes() {
+        assert_eq!(markdown(&ApiDiff::default()), "No API changes.\n");
+    }
+}
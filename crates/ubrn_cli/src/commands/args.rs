@@ -19,6 +19,15 @@ impl ConfigArgs {
     pub(crate) fn new(config: Option<Utf8PathBuf>) -> Self {
         Self { config }
     }
+
+    /// The config file given, or the one found in the current directory or
+    /// its parents.
+    pub(crate) fn path(&self) -> anyhow::Result<Utf8PathBuf> {
+        match &self.config {
+            Some(config) => Ok(config.clone()),
+            None => workspace::ubrn_config_yaml(),
+        }
+    }
 }
 
 fn default_config_path() -> Utf8PathBuf {
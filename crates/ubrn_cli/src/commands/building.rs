@@ -4,7 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use camino::Utf8PathBuf;
 use clap::{Args, Subcommand};
 
@@ -15,8 +15,12 @@ use crate::wasm::WebBuildArgs;
 #[cfg(feature = "wasm")]
 use crate::wasm2::Wasm2BuildArgs;
 use crate::{
-    commands::generate::GenerateAllCommand, config::ProjectConfig, jsi::android::AndroidBuildArgs,
-    jsi::ios::IosBuildArgs, Platform,
+    commands::{generate::GenerateAllCommand, DryRunArgs},
+    config::ProjectConfig,
+    jsi::android::AndroidBuildArgs,
+    jsi::ios::IosBuildArgs,
+    napi::NapiBuildArgs,
+    Platform,
 };
 
 #[derive(Args, Debug)]
@@ -39,28 +43,41 @@ pub(crate) enum BuildCmd {
     #[cfg(feature = "wasm")]
     #[clap(aliases = ["web2"])]
     Wasm2(Wasm2BuildArgs),
+    /// Build the crate's shared library for Node, once per target
+    #[clap(aliases = ["node"])]
+    Napi(NapiBuildArgs),
 }
 
 impl BuildArgs {
     pub(crate) fn build(&self) -> Result<()> {
+        let dry_run = &self.cmd.common_args().dry_run;
+        if dry_run.dry_run && !self.cmd.and_generate() {
+            bail!("--dry-run only applies to the generated files, so needs --and-generate");
+        }
         let lib_file = self.cmd.build()?;
         if self.cmd.and_generate() {
-            self.generate(lib_file)?;
+            self.generate(lib_file, dry_run)?;
         }
 
         Ok(())
     }
 
-    fn generate(&self, lib_file: Utf8PathBuf) -> Result<()> {
+    fn generate(&self, lib_file: Utf8PathBuf, dry_run: &DryRunArgs) -> Result<()> {
         eprintln!("Generating bindings and turbo module from lib file {lib_file}");
-        GenerateAllCommand::platform_specific(
-            lib_file,
-            self.cmd.project_config()?,
-            Platform::from(&self.cmd),
-            self.cmd.native_bindings(),
-        )
-        .run()?;
-
+        dry_run.run(|| {
+            GenerateAllCommand::platform_specific(
+                lib_file,
+                self.cmd.project_config()?,
+                Platform::from(&self.cmd),
+                self.cmd.native_bindings(),
+            )
+            .run()
+        })?;
+
+        // Nothing more is put into the project in a dry run.
+        if dry_run.dry_run {
+            return Ok(());
+        }
         self.cmd.then_build()
     }
 }
@@ -74,6 +91,7 @@ impl BuildCmd {
             Self::Web(a) => a.build()?,
             #[cfg(feature = "wasm")]
             Self::Wasm2(a) => a.build()?,
+            Self::Napi(a) => a.build()?,
         };
 
         files.sort(); // Sort so that we reproducibly pick the same file below
@@ -92,6 +110,19 @@ impl BuildCmd {
             Self::Web(a) => a.project_config(),
             #[cfg(feature = "wasm")]
             Self::Wasm2(a) => a.project_config(),
+            Self::Napi(a) => a.project_config(),
+        }
+    }
+
+    fn common_args(&self) -> &CommonBuildArgs {
+        match self {
+            Self::Android(a) => &a.common_args,
+            Self::Ios(a) => &a.common_args,
+            #[cfg(feature = "wasm")]
+            Self::Web(a) => &a.common_args,
+            #[cfg(feature = "wasm")]
+            Self::Wasm2(a) => &a.common_args,
+            Self::Napi(a) => &a.common_args,
         }
     }
 
@@ -103,30 +134,25 @@ impl BuildCmd {
             Self::Web(a) => !a.no_generate,
             #[cfg(feature = "wasm")]
             Self::Wasm2(a) => !a.no_generate,
+            Self::Napi(a) => a.common_args.and_generate,
         }
     }
 
-    #[cfg(feature = "wasm")]
     pub(crate) fn then_build(&self) -> Result<()> {
         match self {
-            Self::Web(a) => {
-                if !a.no_wasm_pack {
-                    a.then_build()?
-                }
-            }
+            #[cfg(feature = "wasm")]
+            Self::Web(a) if !a.no_wasm_pack => a.then_build()?,
             // Wasm2 built its wasm in `build()` — this stages it next to the
             // TypeScript that `generate` has just written.
+            #[cfg(feature = "wasm")]
             Self::Wasm2(a) => a.then_build()?,
+            // Likewise the library, if the bindings look for it there.
+            Self::Napi(a) => a.then_build()?,
             _ => {}
         }
         Ok(())
     }
 
-    #[cfg(not(feature = "wasm"))]
-    pub(crate) fn then_build(&self) -> Result<()> {
-        Ok(())
-    }
-
     pub(crate) fn native_bindings(&self) -> bool {
         match self {
             Self::Android(a) => a.native_bindings,
@@ -135,6 +161,7 @@ impl BuildCmd {
             Self::Web(_) => false, // Web does not support native bindings
             #[cfg(feature = "wasm")]
             Self::Wasm2(_) => false, // Wasm2 does not support native bindings
+            Self::Napi(_) => false,
         }
     }
 }
@@ -162,6 +189,9 @@ pub(crate) struct CommonBuildArgs {
     /// Optionally generate the bindings and turbo-module code for the crate
     #[clap(long = "and-generate", short = 'g')]
     pub(crate) and_generate: bool,
+
+    #[clap(flatten)]
+    pub(crate) dry_run: DryRunArgs,
 }
 
 impl CommonBuildArgs {
@@ -179,6 +209,7 @@ impl From<&BuildCmd> for Platform {
             BuildCmd::Web(..) => Self::Wasm,
             #[cfg(feature = "wasm")]
             BuildCmd::Wasm2(..) => Self::Wasm2,
+            BuildCmd::Napi(..) => Self::Napi,
         }
     }
 }
@@ -0,0 +1,576 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::{collections::BTreeSet, fmt::Display, process::Command};
+
+use anyhow::{bail, Result};
+use camino::Utf8PathBuf;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use which::which;
+
+use crate::{
+    config::{PackageJson, ProjectConfig},
+    workspace,
+};
+
+use super::ConfigArgs;
+
+#[derive(Args, Debug)]
+pub(crate) struct CheckArgs {
+    #[clap(flatten)]
+    config: ConfigArgs,
+
+    /// Only check the toolchain for these platforms. Defaults to all of them.
+    #[clap(long, value_delimiter = ',')]
+    platforms: Vec<CheckPlatform>,
+
+    /// Print the report as JSON, for CI.
+    #[clap(long)]
+    json: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CheckPlatform {
+    Android,
+    Ios,
+    Web,
+    Wasm2,
+}
+
+impl CheckPlatform {
+    fn all() -> Vec<Self> {
+        vec![Self::Android, Self::Ios, Self::Web, Self::Wasm2]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Ok => "ok",
+            Self::Warning => "warning",
+            Self::Error => "error",
+            Self::Skipped => "skipped",
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    /// `None` for checks every platform shares.
+    platform: Option<CheckPlatform>,
+    name: String,
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    detail: Option<String>,
+}
+
+impl Check {
+    fn new(platform: Option<CheckPlatform>, name: impl Into<String>, status: Status) -> Self {
+        Self {
+            platform,
+            name: name.into(),
+            status,
+            detail: None,
+        }
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+/// The names `generate` would derive from the config and `package.json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ResolvedNames {
+    name: String,
+    project_version: String,
+    cpp_namespace: String,
+    module_cpp: String,
+    codegen_filename: String,
+    android_package_name: String,
+}
+
+impl From<&ProjectConfig> for ResolvedNames {
+    fn from(config: &ProjectConfig) -> Self {
+        Self {
+            name: config.raw_name().to_string(),
+            project_version: config.project_version(),
+            cpp_namespace: config.cpp_namespace(),
+            module_cpp: config.module_cpp(),
+            codegen_filename: config.codegen_filename(),
+            android_package_name: config.android.package_name(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct Report {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<Utf8PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    names: Option<ResolvedNames>,
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn push(&mut self, check: Check) {
+        self.checks.push(check);
+    }
+
+    fn count(&self, status: Status) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(config) = &self.config {
+            writeln!(f, "Config: {config}")?;
+        }
+        if let Some(names) = &self.names {
+            writeln!(f, "Resolved names:")?;
+            for (label, value) in [
+                ("name", &names.name),
+                ("project version", &names.project_version),
+                ("C++ namespace", &names.cpp_namespace),
+                ("C++ module", &names.module_cpp),
+                ("codegen filename", &names.codegen_filename),
+                ("Android package", &names.android_package_name),
+            ] {
+                writeln!(f, "  {label:<18}{value}")?;
+            }
+        }
+        writeln!(f, "Checks:")?;
+        for check in &self.checks {
+            let name = match check.platform {
+                Some(p) => format!(
+                    "{}: {}",
+                    p.to_possible_value().unwrap().get_name(),
+                    check.name
+                ),
+                None => check.name.clone(),
+            };
+            write!(f, "  {:<9}{name}", check.status.to_string())?;
+            if let Some(detail) = &check.detail {
+                write!(f, "\n           {detail}")?;
+            }
+            writeln!(f)?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.count(Status::Error),
+            self.count(Status::Warning)
+        )
+    }
+}
+
+impl CheckArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        let report = self.report();
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{report}");
+        }
+        let errors = report.count(Status::Error);
+        if errors > 0 {
+            bail!("ubrn check found {errors} error(s)");
+        }
+        Ok(())
+    }
+
+    fn platforms(&self) -> Vec<CheckPlatform> {
+        if self.platforms.is_empty() {
+            CheckPlatform::all()
+        } else {
+            self.platforms.clone()
+        }
+    }
+
+    fn report(&self) -> Report {
+        let mut report = Report::default();
+
+        // The config's defaults are read from package.json, and panic if it
+        // is missing or malformed; find out first.
+        let package_json = match workspace::package_json_path()
+            .and_then(|p| ubrn_common::read_from_file::<_, PackageJson>(&p).map(|j| (p, j)))
+        {
+            Ok((path, json)) => {
+                report.push(Check::new(None, "package.json", Status::Ok).detail(path));
+                json
+            }
+            Err(e) => {
+                report.push(Check::new(None, "package.json", Status::Error).detail(e.to_string()));
+                return report;
+            }
+        };
+
+        let config_path = match self.config.path() {
+            Ok(path) => path,
+            Err(e) => {
+                report.push(Check::new(None, "config", Status::Error).detail(e.to_string()));
+                return report;
+            }
+        };
+        report.config = Some(config_path.clone());
+        let config = match ProjectConfig::try_from(config_path) {
+            Ok(config) => config,
+            Err(e) => {
+                report.push(Check::new(None, "config", Status::Error).detail(format!("{e:#}")));
+                return report;
+            }
+        };
+        report.push(Check::new(None, "config", Status::Ok));
+        report.names = Some((&config).into());
+
+        if package_json.version().is_none() && config.project_version == "0.1.0" {
+            report.push(
+                Check::new(None, "project version", Status::Warning)
+                    .detail("package.json has no version, so it defaulted to 0.1.0"),
+            );
+        }
+
+        let platforms = self.platforms();
+        report.checks.extend(crate_checks(&config, &platforms));
+        report.checks.extend(toolchain_checks(&config, &platforms));
+        report
+    }
+}
+
+fn crate_checks(config: &ProjectConfig, platforms: &[CheckPlatform]) -> Vec<Check> {
+    let metadata = match config.crate_.metadata() {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            return vec![Check::new(None, "crate", Status::Error).detail(format!("{e:#}"))];
+        }
+    };
+    let manifest_path = metadata.manifest_path();
+    let mut checks = vec![Check::new(None, "crate", Status::Ok).detail(manifest_path.as_str())];
+
+    let needs_cdylib =
+        platforms.contains(&CheckPlatform::Android) && config.android.use_shared_library;
+    checks.push(match (metadata.builds_cdylib(), needs_cdylib) {
+        (true, _) => Check::new(None, "cdylib", Status::Ok),
+        (false, true) => Check::new(Some(CheckPlatform::Android), "cdylib", Status::Error).detail(
+            "android.useSharedLibrary is set, but the crate's [lib] crate-type has no cdylib",
+        ),
+        (false, false) => Check::new(None, "cdylib", Status::Warning)
+            .detail("[lib] crate-type has no cdylib, which napi, deno and wasm2 load"),
+    });
+
+    #[cfg(feature = "wasm")]
+    if platforms.contains(&CheckPlatform::Wasm2) {
+        checks.push(match crate::wasm2::check_wasm_ready(&metadata) {
+            Ok(()) => Check::new(Some(CheckPlatform::Wasm2), "crate", Status::Ok),
+            Err(e) => Check::new(Some(CheckPlatform::Wasm2), "crate", Status::Error)
+                .detail(e.to_string().lines().next().unwrap_or_default()),
+        });
+    }
+    checks
+}
+
+fn toolchain_checks(config: &ProjectConfig, platforms: &[CheckPlatform]) -> Vec<Check> {
+    let installed = installed_targets();
+    let mut checks = Vec::new();
+    if let Err(e) = &installed {
+        checks.push(Check::new(None, "rustup", Status::Warning).detail(format!(
+            "cannot list installed targets, so they are not checked: {e}"
+        )));
+    }
+    let installed = installed.ok();
+    let installed = installed.as_ref();
+
+    for &platform in platforms {
+        match platform {
+            CheckPlatform::Android => {
+                let triples: Vec<_> = config.android.targets.iter().map(|t| t.triple()).collect();
+                checks.extend(target_checks(platform, &triples, installed));
+                checks.push(tool_check(platform, "cargo-ndk"));
+                checks.push(ndk_check());
+            }
+            CheckPlatform::Ios if !cfg!(target_os = "macos") => {
+                checks.push(
+                    Check::new(Some(platform), "toolchain", Status::Skipped)
+                        .detail("iOS builds need macOS"),
+                );
+            }
+            CheckPlatform::Ios => {
+                let triples: Vec<_> = config
+                    .ios
+                    .targets
+                    .iter()
+                    .map(|t| t.triple.as_str())
+                    .collect();
+                checks.extend(target_checks(platform, &triples, installed));
+                checks.push(tool_check(platform, "xcodebuild"));
+            }
+            #[cfg(feature = "wasm")]
+            CheckPlatform::Web => {
+                let triples: Vec<_> = config.wasm.targets.iter().map(|t| t.triple()).collect();
+                checks.extend(target_checks(platform, &triples, installed));
+                checks.push(wasm_bindgen_check(config));
+            }
+            #[cfg(feature = "wasm")]
+            CheckPlatform::Wasm2 => {
+                let triples: Vec<_> = config.wasm2.targets.iter().map(|t| t.triple()).collect();
+                checks.extend(target_checks(platform, &triples, installed));
+            }
+            #[cfg(not(feature = "wasm"))]
+            CheckPlatform::Web | CheckPlatform::Wasm2 => {
+                checks.push(
+                    Check::new(Some(platform), "toolchain", Status::Skipped)
+                        .detail("this ubrn was built without wasm support"),
+                );
+            }
+        }
+    }
+
+    // Only needed to format the generated C++, so never an error.
+    if platforms
+        .iter()
+        .any(|p| matches!(p, CheckPlatform::Android | CheckPlatform::Ios))
+    {
+        checks.push(match which("clang-format") {
+            Ok(_) => Check::new(None, "clang-format", Status::Ok),
+            Err(_) => Check::new(None, "clang-format", Status::Warning)
+                .detail("not found; generated C++ will not be formatted"),
+        });
+    }
+    checks
+}
+
+fn installed_targets() -> Result<BTreeSet<String>> {
+    let output = Command::new("rustup")
+        .args(["target", "list", "--installed"])
+        .output()?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(parse_installed_targets(&String::from_utf8(output.stdout)?))
+}
+
+fn parse_installed_targets(stdout: &str) -> BTreeSet<String> {
+    stdout
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn target_checks(
+    platform: CheckPlatform,
+    triples: &[&str],
+    installed: Option<&BTreeSet<String>>,
+) -> Vec<Check> {
+    let Some(installed) = installed else {
+        return Vec::new();
+    };
+    triples
+        .iter()
+        .map(|&triple| {
+            let name = format!("target {triple}");
+            if installed.contains(triple) {
+                Check::new(Some(platform), name, Status::Ok)
+            } else {
+                Check::new(Some(platform), name, Status::Error)
+                    .detail(format!("not installed: run `rustup target add {triple}`"))
+            }
+        })
+        .collect()
+}
+
+fn tool_check(platform: CheckPlatform, tool: &str) -> Check {
+    match which(tool) {
+        Ok(path) => Check::new(Some(platform), tool, Status::Ok).detail(path.to_string_lossy()),
+        Err(_) => Check::new(Some(platform), tool, Status::Error).detail("not found on the PATH"),
+    }
+}
+
+/// The `wasm-bindgen` ubrn rewrites the Web build with, pinned in the
+/// workspace's `Cargo.toml`.
+#[cfg(feature = "wasm")]
+const WASM_BINDGEN_VERSION: &str = "0.2.100";
+
+/// The generated wasm crate must lock the same `wasm-bindgen` as ubrn's own
+/// rewriter, or the rewrite fails on a schema mismatch after a full build.
+#[cfg(feature = "wasm")]
+fn wasm_bindgen_check(config: &ProjectConfig) -> Check {
+    let platform = Some(CheckPlatform::Web);
+    let crate_dir = config.wasm.crate_dir(config.project_root());
+    let Some(lock_file) = crate_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.exists())
+    else {
+        return Check::new(platform, "wasm-bindgen", Status::Skipped)
+            .detail("the wasm crate has not been built yet, so has no Cargo.lock");
+    };
+    let locked = match ubrn_common::read_to_string(&lock_file) {
+        Ok(contents) => locked_version(&contents, "wasm-bindgen"),
+        Err(e) => {
+            return Check::new(platform, "wasm-bindgen", Status::Warning).detail(e.to_string())
+        }
+    };
+    wasm_bindgen_version_check(locked.as_deref(), &lock_file)
+}
+
+#[cfg(feature = "wasm")]
+fn wasm_bindgen_version_check(locked: Option<&str>, lock_file: &camino::Utf8Path) -> Check {
+    let platform = Some(CheckPlatform::Web);
+    match locked {
+        Some(WASM_BINDGEN_VERSION) => {
+            Check::new(platform, "wasm-bindgen", Status::Ok).detail(WASM_BINDGEN_VERSION)
+        }
+        Some(version) => Check::new(platform, "wasm-bindgen", Status::Error).detail(format!(
+            "{lock_file} locks {version}, but ubrn uses {WASM_BINDGEN_VERSION}: \
+             run `cargo update -p wasm-bindgen --precise {WASM_BINDGEN_VERSION}`"
+        )),
+        None => Check::new(platform, "wasm-bindgen", Status::Skipped)
+            .detail(format!("{lock_file} has no wasm-bindgen")),
+    }
+}
+
+/// The version of `package` a `Cargo.lock` pins, if it has exactly one.
+#[cfg(feature = "wasm")]
+fn locked_version(lock_file: &str, package: &str) -> Option<String> {
+    let lock: toml::Table = toml::from_str(lock_file).ok()?;
+    let mut versions = lock
+        .get("package")?
+        .as_array()?
+        .iter()
+        .filter(|p| p.get("name").and_then(|n| n.as_str()) == Some(package))
+        .filter_map(|p| p.get("version")?.as_str());
+    let version = versions.next()?;
+    versions.next().is_none().then(|| version.to_string())
+}
+
+/// `cargo ndk` finds the NDK through any of these; it is a warning rather than
+/// an error because it also probes a handful of default install locations.
+fn ndk_check() -> Check {
+    let platform = Some(CheckPlatform::Android);
+    let vars = ["ANDROID_NDK_HOME", "ANDROID_NDK_ROOT", "ANDROID_HOME"];
+    match vars.iter().find(|v| std::env::var_os(v).is_some()) {
+        Some(var) => Check::new(platform, "NDK", Status::Ok).detail(format!("from ${var}")),
+        None => Check::new(platform, "NDK", Status::Warning).detail(format!(
+            "none of {} is set",
+            vars.map(|v| format!("${v}")).join(", ")
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rustup_installed_targets() {
+        let installed =
+            parse_installed_targets("aarch64-linux-android\n\nx86_64-unknown-linux-gnu\n");
+        assert_eq!(
+            installed.into_iter().collect::<Vec<_>>(),
+            ["aarch64-linux-android", "x86_64-unknown-linux-gnu"]
+        );
+    }
+
+    #[test]
+    fn a_missing_target_is_an_error_with_the_fix() {
+        let installed = parse_installed_targets("aarch64-linux-android\n");
+        let checks = target_checks(
+            CheckPlatform::Android,
+            &["aarch64-linux-android", "x86_64-linux-android"],
+            Some(&installed),
+        );
+        assert_eq!(
+            checks.iter().map(|c| c.status).collect::<Vec<_>>(),
+            [Status::Ok, Status::Error]
+        );
+        assert_eq!(
+            checks[1].detail.as_deref(),
+            Some("not installed: run `rustup target add x86_64-linux-android`")
+        );
+
+        // Without rustup, targets go unchecked rather than failing.
+        assert!(target_checks(CheckPlatform::Android, &["x86_64-linux-android"], None).is_empty());
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn the_web_build_needs_ubrns_wasm_bindgen() {
+        let lock_file = format!(
+            r#"
+            version = 3
+
+            [[package]]
+            name = "wasm-bindgen"
+            version = "{WASM_BINDGEN_VERSION}"
+
+            [[package]]
+            name = "wasm-bindgen-macro"
+            version = "0.2.93"
+            "#
+        );
+        assert_eq!(
+            locked_version(&lock_file, "wasm-bindgen").as_deref(),
+            Some(WASM_BINDGEN_VERSION)
+        );
+        assert_eq!(locked_version(&lock_file, "wasm-pack"), None);
+
+        let path = camino::Utf8Path::new("rust_modules/wasm/Cargo.lock");
+        let check = wasm_bindgen_version_check(Some(WASM_BINDGEN_VERSION), path);
+        assert_eq!(check.status, Status::Ok);
+        let check = wasm_bindgen_version_check(Some("0.2.93"), path);
+        assert_eq!(check.status, Status::Error);
+        assert!(check
+            .detail
+            .unwrap()
+            .ends_with("run `cargo update -p wasm-bindgen --precise 0.2.100`"));
+    }
+
+    #[test]
+    fn the_json_report_is_flat_and_lowercase() {
+        let mut report = Report::default();
+        report.push(Check::new(
+            Some(CheckPlatform::Wasm2),
+            "target wasm32-unknown-unknown",
+            Status::Ok,
+        ));
+        report.push(Check::new(None, "clang-format", Status::Warning).detail("not found"));
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "checks": [
+                    {
+                        "platform": "wasm2",
+                        "name": "target wasm32-unknown-unknown",
+                        "status": "ok",
+                    },
+                    {
+                        "platform": null,
+                        "name": "clang-format",
+                        "status": "warning",
+                        "detail": "not found",
+                    },
+                ],
+            })
+        );
+        assert_eq!(report.count(Status::Warning), 1);
+    }
+}
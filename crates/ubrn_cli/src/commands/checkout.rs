@@ -5,14 +5,17 @@
  */
 use std::process::Command;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::Args;
 use serde::Deserialize;
 
 use ubrn_common::run_cmd;
 
-use crate::{config::ProjectConfig, AsConfig};
+use crate::{
+    config::{rust_crate::RustSource, ProjectConfig},
+    AsConfig,
+};
 
 use super::ConfigArgs;
 
@@ -123,7 +126,21 @@ impl GitRepoArgs {
     }
 }
 
-impl TryFrom<ProjectConfig> for GitRepoArgs {
+/// The repositories to check out: the crate's, or, for a megazord, those of
+/// each of its crates which comes from one.
+#[derive(Debug)]
+pub(crate) struct GitRepos(Vec<GitRepoArgs>);
+
+impl GitRepos {
+    pub(crate) fn checkout(&self, project_root: &Utf8Path) -> Result<()> {
+        for repo in &self.0 {
+            repo.checkout(project_root)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<ProjectConfig> for GitRepos {
     type Error = anyhow::Error;
 
     fn try_from(value: ProjectConfig) -> Result<Self> {
@@ -131,15 +148,100 @@ impl TryFrom<ProjectConfig> for GitRepoArgs {
     }
 }
 
-impl AsConfig<GitRepoArgs> for CheckoutArgs {
+impl TryFrom<RustSource> for GitRepos {
+    type Error = anyhow::Error;
+
+    fn try_from(value: RustSource) -> Result<Self> {
+        let repos = match value {
+            RustSource::Megazord(m) => m
+                .crates
+                .into_iter()
+                .filter_map(|c| c.src.try_into().ok())
+                .collect(),
+            src => vec![src.try_into()?],
+        };
+        if repos.is_empty() {
+            bail!("None of the crates is from a Git repository");
+        }
+        Ok(Self(repos))
+    }
+}
+
+impl AsConfig<GitRepos> for CheckoutArgs {
     fn config_file(&self) -> ConfigArgs {
         ConfigArgs::new(self.config.clone())
     }
 
-    fn get(&self) -> Option<GitRepoArgs> {
+    fn get(&self) -> Option<GitRepos> {
         let args = self.repo.clone()?;
         let branch = args.branch;
         let repo = args.repo?;
-        Some(GitRepoArgs { repo, branch })
+        Some(GitRepos(vec![GitRepoArgs { repo, branch }]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{
+        megazord::MegazordArgs,
+        rust_crate::{CrateConfig, OnDiskArgs},
+    };
+
+    fn git_repo(repo: &str) -> RustSource {
+        RustSource::GitRepo(GitRepoArgs {
+            repo: repo.to_string(),
+            branch: GitRepoArgs::default_branch(),
+        })
+    }
+
+    fn on_disk(src: &str) -> RustSource {
+        RustSource::OnDisk(OnDiskArgs {
+            src: src.to_string(),
+        })
+    }
+
+    fn megazord(sources: Vec<RustSource>) -> RustSource {
+        let crates = sources
+            .into_iter()
+            .map(|src| CrateConfig {
+                project_root: Default::default(),
+                manifest_path: "Cargo.toml".to_string(),
+                src,
+            })
+            .collect();
+        RustSource::Megazord(MegazordArgs::new(crates))
+    }
+
+    fn repos(src: RustSource) -> Result<Vec<String>> {
+        let GitRepos(repos) = src.try_into()?;
+        Ok(repos.into_iter().map(|r| r.repo).collect())
+    }
+
+    #[test]
+    fn each_crate_of_a_megazord_is_checked_out() -> Result<()> {
+        let src = megazord(vec![
+            git_repo("https://github.com/org/one.git"),
+            on_disk("rust/two"),
+            git_repo("https://github.com/org/three.git"),
+        ]);
+        assert_eq!(
+            repos(src)?,
+            [
+                "https://github.com/org/one.git",
+                "https://github.com/org/three.git"
+            ]
+        );
+        assert_eq!(
+            repos(git_repo("https://github.com/org/one.git"))?,
+            ["https://github.com/org/one.git"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn crates_on_disk_have_nothing_to_check_out() {
+        assert!(repos(on_disk("rust/one")).is_err());
+        assert!(repos(megazord(vec![on_disk("rust/one"), on_disk("rust/two")])).is_err());
     }
 }
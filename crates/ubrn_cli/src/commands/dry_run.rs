@@ -0,0 +1,112 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::{bail, Result};
+use clap::Args;
+use diffy::DiffOptions;
+use ubrn_common::StagedFile;
+
+use crate::{codegen::is_last_generated, workspace};
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct DryRunArgs {
+    /// Generate everything, formatted as usual, but write nothing: print a
+    /// diff of what would change instead.
+    ///
+    /// Exits with an error if anything would change, so CI can check that
+    /// the generated code is up to date.
+    #[clap(long, global = true)]
+    pub(crate) dry_run: bool,
+
+    /// With --dry-run, list the files that would change, rather than how.
+    #[clap(long, global = true, requires = "dry_run")]
+    stat: bool,
+}
+
+impl DryRunArgs {
+    /// Run `generate`, or, with `--dry-run`, report what it would change.
+    pub(crate) fn run(&self, generate: impl FnOnce() -> Result<()>) -> Result<()> {
+        if !self.dry_run {
+            return generate();
+        }
+        let root = workspace::project_root().or_else(|_| ubrn_common::pwd())?;
+        ubrn_common::start_dry_run(&root)?;
+        let result = generate();
+        let files = ubrn_common::finish_dry_run();
+        result?;
+        // The copies kept for merging follow the files they're copies of.
+        let changed: Vec<_> = files?
+            .into_iter()
+            .filter(|f| f.is_changed() && !is_last_generated(&f.path))
+            .collect();
+        for file in &changed {
+            if self.stat {
+                println!("{}", stat(file));
+            } else {
+                print!("{}", diff(file));
+            }
+        }
+        if !changed.is_empty() {
+            bail!("{} generated file(s) would change", changed.len());
+        }
+        eprintln!("No generated files would change");
+        Ok(())
+    }
+}
+
+/// As `git diff --name-status`.
+fn stat(file: &StagedFile) -> String {
+    let status = if file.old.is_some() { 'M' } else { 'A' };
+    format!("{status}\t{}", file.path)
+}
+
+/// As `git diff`, without the colour.
+fn diff(file: &StagedFile) -> String {
+    let path = &file.path;
+    let old = file.old.as_deref().unwrap_or_default();
+    let (Ok(old), Ok(new)) = (std::str::from_utf8(old), std::str::from_utf8(&file.new)) else {
+        return format!("Binary files a/{path} and b/{path} differ\n");
+    };
+    let original = if file.old.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    DiffOptions::new()
+        .set_original_filename(original)
+        .set_modified_filename(format!("b/{path}"))
+        .create_patch(old, new)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn staged(path: &str, old: Option<&str>, new: &str) -> StagedFile {
+        StagedFile {
+            path: path.into(),
+            old: old.map(|s| s.as_bytes().to_vec()),
+            new: new.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn changes_are_shown_as_unified_diffs() {
+        let file = staged("src/index.ts", Some("one\ntwo\n"), "one\n2\n");
+        assert_eq!(
+            diff(&file),
+            "--- a/src/index.ts\n+++ b/src/index.ts\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n"
+        );
+        assert_eq!(stat(&file), "M\tsrc/index.ts");
+
+        let file = staged("cpp/new.h", None, "new\n");
+        assert_eq!(
+            diff(&file),
+            "--- /dev/null\n+++ b/cpp/new.h\n@@ -0,0 +1 @@\n+new\n"
+        );
+        assert_eq!(stat(&file), "A\tcpp/new.h");
+    }
+}
@@ -22,17 +22,20 @@ use crate::{
     jsi, napi, Platform,
 };
 
-use super::ConfigArgs;
+use super::{ConfigArgs, DryRunArgs};
 
 #[derive(Args, Debug)]
 pub(crate) struct GenerateArgs {
     #[clap(subcommand)]
     cmd: GenerateCmd,
+
+    #[clap(flatten)]
+    dry_run: DryRunArgs,
 }
 
 impl GenerateArgs {
     pub(crate) fn run(&self) -> Result<()> {
-        self.cmd.run()
+        self.dry_run.run(|| self.cmd.run())
     }
 }
 
@@ -56,6 +59,11 @@ pub(crate) enum GenerateCmd {
     #[clap(aliases = ["node"])]
     Napi(napi::CmdArg),
 
+    /// Commands to generate Deno and Bun bindings, which load the library
+    /// through the runtime's own FFI.
+    #[clap(aliases = ["bun"])]
+    Deno(napi::CmdArg),
+
     /// Commands to generate a WASM crate.
     #[cfg(feature = "wasm")]
     #[clap(aliases = ["web"])]
@@ -88,7 +96,11 @@ impl GenerateCmd {
                 Ok(())
             }
             Self::Napi(napi) => {
-                napi.run()?;
+                napi.run(AbiFlavor::Napi)?;
+                Ok(())
+            }
+            Self::Deno(deno) => {
+                deno.run(AbiFlavor::Deno)?;
                 Ok(())
             }
             #[cfg(feature = "wasm")]
@@ -208,6 +220,7 @@ impl GenerateAllCommand {
             Some(Platform::Wasm) => wasm::bindings(project, switches, lib_file)?,
             #[cfg(feature = "wasm")]
             Some(Platform::Wasm2) => wasm2::bindings(project, switches, lib_file)?,
+            Some(Platform::Napi) => napi::bindings(project, switches, lib_file)?,
             _ => jsi::bindings(project, switches, lib_file)?,
         })
     }
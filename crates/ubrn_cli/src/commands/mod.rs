@@ -4,12 +4,22 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 
+pub(crate) mod api_diff;
 mod args;
 pub(crate) mod building;
+pub(crate) mod check;
 pub(crate) mod checkout;
+pub(crate) mod dry_run;
 pub(crate) mod generate;
+pub(crate) mod packaging;
+pub(crate) mod watch;
 
+pub(crate) use api_diff::ApiDiffArgs;
 pub(crate) use args::ConfigArgs;
 pub(crate) use building::BuildArgs;
+pub(crate) use check::CheckArgs;
 pub(crate) use checkout::CheckoutArgs;
+pub(crate) use dry_run::DryRunArgs;
 pub(crate) use generate::GenerateArgs;
+pub(crate) use packaging::PackageArgs;
+pub(crate) use watch::WatchArgs;
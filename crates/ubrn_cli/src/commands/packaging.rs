@@ -0,0 +1,32 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+
+use crate::napi::NapiPackageArgs;
+
+#[derive(Args, Debug)]
+pub(crate) struct PackageArgs {
+    #[clap(subcommand)]
+    cmd: PackageCmd,
+}
+
+#[derive(Subcommand, Debug)]
+pub(crate) enum PackageCmd {
+    /// Build the crate for each Node target, and lay out an npm package for
+    /// each next to the project's own
+    #[clap(aliases = ["node"])]
+    Napi(NapiPackageArgs),
+}
+
+impl PackageArgs {
+    pub(crate) fn run(&self) -> Result<()> {
+        match &self.cmd {
+            PackageCmd::Napi(a) => a.run(),
+        }
+    }
+}
@@ -0,0 +1,489 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    process::Command,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Args;
+use globset::GlobSet;
+use ubrn_bindgen::api_diff::ApiDiff;
+use ubrn_common::{mk_dir, rm_dir, run_cmd, CrateMetadata};
+use uniffi_bindgen::ComponentInterface;
+
+use super::api_diff::bullet;
+use crate::{config::ProjectConfig, workspace};
+
+/// How often the crate is scanned for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Args, Clone, Debug)]
+pub(crate) struct WatchArgs {
+    /// Keep running: rebuild the crate and regenerate the bindings whenever
+    /// its source changes.
+    ///
+    /// If a `ubrn.config.yaml` is found, its `rust` crate is the one watched
+    /// and its `noOverwrite` globs are respected; otherwise, the crate in the
+    /// current directory.
+    #[clap(long)]
+    watch: bool,
+
+    /// With --watch, how long the source must be quiet, in milliseconds,
+    /// before a burst of edits triggers a rebuild.
+    #[clap(long, default_value = "300", requires = "watch")]
+    debounce_ms: u64,
+}
+
+impl WatchArgs {
+    /// Generate the bindings once, or, with `--watch`, every time the crate
+    /// changes.
+    pub(crate) fn run(&self, bindings: ubrn_bindgen::BindingsArgs) -> Result<()> {
+        if !self.watch {
+            bindings.run(None)?;
+            return Ok(());
+        }
+        Watcher::new(bindings, Duration::from_millis(self.debounce_ms))?.run()
+    }
+}
+
+struct Watcher {
+    bindings: ubrn_bindgen::BindingsArgs,
+    /// The library or UDL file, made absolute.
+    source: Utf8PathBuf,
+    crate_: CrateMetadata,
+    /// The crate's directory, or for a megazord, those of each of its crates.
+    source_dirs: Vec<Utf8PathBuf>,
+    /// Relative paths are against this, for `noOverwrite`.
+    project_root: Utf8PathBuf,
+    exclude_files: GlobSet,
+    debounce: Duration,
+    /// What the crate exported when last generated.
+    api: Option<Vec<ComponentInterface>>,
+    /// The files last generated, so those no longer generated can be removed.
+    generated: BTreeSet<Utf8PathBuf>,
+}
+
+impl Watcher {
+    fn new(bindings: ubrn_bindgen::BindingsArgs, debounce: Duration) -> Result<Self> {
+        let pwd = ubrn_common::pwd()?;
+        let (crate_, source_dirs, project_root, exclude_files) = match workspace::ubrn_config_yaml()
+        {
+            Ok(path) => {
+                let project = ProjectConfig::try_from(path)?;
+                (
+                    project.crate_.metadata()?,
+                    project.crate_.source_dirs()?,
+                    project.project_root().to_path_buf(),
+                    project.exclude_files().clone(),
+                )
+            }
+            Err(_) => {
+                let crate_ = CrateMetadata::try_from(pwd.join("Cargo.toml"))?;
+                let source_dirs = vec![crate_.crate_dir().to_path_buf()];
+                (crate_, source_dirs, pwd.clone(), GlobSet::empty())
+            }
+        };
+        // The paths are relative to where we started, but the crate's paths
+        // are absolute.
+        let ts_dir = pwd.join(bindings.ts_dir());
+        let cpp_dir = pwd.join(bindings.cpp_dir());
+        let source = pwd.join(bindings.source_path());
+        Ok(Self {
+            bindings: bindings.with_output_dirs(&ts_dir, &cpp_dir),
+            source,
+            crate_,
+            source_dirs,
+            project_root,
+            exclude_files,
+            debounce,
+            api: None,
+            generated: Default::default(),
+        })
+    }
+
+    fn run(mut self) -> Result<()> {
+        // The first time round, fail as a plain `generate` would.
+        self.regenerate()?;
+        let mut last = self.snapshot()?;
+        let dirs: Vec<_> = self.source_dirs.iter().map(|d| d.as_str()).collect();
+        eprintln!(
+            "Watching {} for changes; press Ctrl-C to stop",
+            dirs.join(", ")
+        );
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let mut current = self.snapshot()?;
+            if current == last {
+                continue;
+            }
+            // Wait out the rest of the burst.
+            loop {
+                thread::sleep(self.debounce);
+                let next = self.snapshot()?;
+                if next == current {
+                    break;
+                }
+                current = next;
+            }
+            let changed = changed_paths(&last, &current);
+            last = current;
+            match changed.as_slice() {
+                [] => continue,
+                [one] => eprintln!("Changed: {one}"),
+                [one, rest @ ..] => eprintln!("Changed: {one} and {} more", rest.len()),
+            }
+            // Later failures are reported, and the watch goes on.
+            if let Err(e) = self.regenerate() {
+                eprintln!("Error: {e:#}");
+            }
+        }
+    }
+
+    fn regenerate(&mut self) -> Result<()> {
+        let manifest_path = self.crate_.manifest_path().to_path_buf();
+        let mut cmd = Command::new("cargo");
+        cmd.arg("build")
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .args(cargo_build_args(&self.source, self.crate_.target_dir()));
+        run_cmd(&mut cmd)?;
+
+        // Generate beside the real output, so prettier and clang-format find
+        // the same config, then copy over only what changed.
+        let dirs = self.output_dirs();
+        for (_, staging) in &dirs {
+            rm_dir(staging)?;
+            mk_dir(staging)?;
+        }
+        let staged = |dir: &Utf8Path| &dirs.iter().find(|(d, _)| d == dir).unwrap().1;
+        let result = self
+            .bindings
+            .clone()
+            .with_output_dirs(
+                staged(self.bindings.ts_dir()),
+                staged(self.bindings.cpp_dir()),
+            )
+            .run(Some(&manifest_path))
+            .map(|_| ());
+        let mut summary = SyncSummary::default();
+        if result.is_ok() {
+            for (dir, staging) in &dirs {
+                sync_dir(
+                    staging,
+                    dir,
+                    &self.project_root,
+                    &self.exclude_files,
+                    &mut summary,
+                )?;
+            }
+            remove_stale(
+                &self.generated,
+                &self.project_root,
+                &self.exclude_files,
+                &mut summary,
+            )?;
+            self.generated = std::mem::take(&mut summary.generated);
+        }
+        for (_, staging) in &dirs {
+            rm_dir(staging)?;
+        }
+        result?;
+
+        let api = self.bindings.component_interfaces(Some(&manifest_path))?;
+        eprintln!("{summary}");
+        for line in api_changes(self.api.as_deref(), &api) {
+            eprintln!("{line}");
+        }
+        self.api = Some(api);
+        Ok(())
+    }
+
+    /// Each distinct output directory, with the hidden sibling it is staged
+    /// in.
+    fn output_dirs(&self) -> Vec<(Utf8PathBuf, Utf8PathBuf)> {
+        let mut dirs: Vec<(Utf8PathBuf, Utf8PathBuf)> = Vec::new();
+        for dir in [self.bindings.ts_dir(), self.bindings.cpp_dir()] {
+            if dirs.iter().any(|(d, _)| d == dir) {
+                continue;
+            }
+            let name = dir.file_name().unwrap_or("generated");
+            let staging = dir.with_file_name(format!(".{name}.ubrn-watch"));
+            dirs.push((dir.to_path_buf(), staging));
+        }
+        dirs
+    }
+
+    fn snapshot(&self) -> Result<Snapshot> {
+        let mut skip: Vec<Utf8PathBuf> = vec![self.crate_.target_dir().to_path_buf()];
+        for (dir, staging) in self.output_dirs() {
+            skip.extend([dir, staging]);
+        }
+        let mut snapshot = Snapshot::default();
+        for dir in &self.source_dirs {
+            snapshot.scan(dir, &skip)?;
+        }
+        Ok(snapshot)
+    }
+}
+
+/// The modification time of every file under a crate's directory.
+#[derive(Default, PartialEq)]
+struct Snapshot(BTreeMap<Utf8PathBuf, SystemTime>);
+
+impl Snapshot {
+    fn scan(&mut self, dir: &Utf8Path, skip: &[Utf8PathBuf]) -> Result<()> {
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            let path = entry.path();
+            let hidden = entry.file_name().starts_with('.');
+            if hidden || entry.file_name() == "node_modules" || skip.iter().any(|s| s == path) {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                self.scan(path, skip)?;
+            } else {
+                self.0.insert(path.to_path_buf(), metadata.modified()?);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn changed_paths(old: &Snapshot, new: &Snapshot) -> Vec<Utf8PathBuf> {
+    let keys: BTreeSet<_> = old.0.keys().chain(new.0.keys()).collect();
+    keys.into_iter()
+        .filter(|k| old.0.get(*k) != new.0.get(*k))
+        .cloned()
+        .collect()
+}
+
+/// The arguments to rebuild the library at `lib_path`, if it is one of
+/// cargo's outputs under `target_dir`: `<profile>/<lib>`, or
+/// `<triple>/<profile>/<lib>`.
+fn cargo_build_args(lib_path: &Utf8Path, target_dir: &Utf8Path) -> Vec<String> {
+    let Ok(rel) = lib_path.strip_prefix(target_dir) else {
+        return Vec::new();
+    };
+    let parts: Vec<&str> = rel.iter().collect();
+    let (triple, profile) = match parts.as_slice() {
+        [profile, _lib] => (None, *profile),
+        [triple, profile, _lib] => (Some(*triple), *profile),
+        _ => return Vec::new(),
+    };
+    let mut args = Vec::new();
+    if let Some(triple) = triple {
+        args.extend(["--target".to_string(), triple.to_string()]);
+    }
+    match profile {
+        "debug" => {}
+        "release" => args.push("--release".to_string()),
+        other => args.extend(["--profile".to_string(), other.to_string()]),
+    }
+    args
+}
+
+#[derive(Default)]
+struct SyncSummary {
+    /// Every file generated this time, wherever it ended up.
+    generated: BTreeSet<Utf8PathBuf>,
+    written: Vec<Utf8PathBuf>,
+    removed: Vec<Utf8PathBuf>,
+    unchanged: usize,
+    excluded: usize,
+}
+
+impl std::fmt::Display for SyncSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Regenerated: {} changed, {} unchanged",
+            self.written.len(),
+            self.unchanged
+        )?;
+        if !self.removed.is_empty() {
+            write!(f, ", {} removed", self.removed.len())?;
+        }
+        if self.excluded > 0 {
+            write!(f, ", {} kept by noOverwrite", self.excluded)?;
+        }
+        for path in &self.written {
+            write!(f, "\n  {path}")?;
+        }
+        for path in &self.removed {
+            write!(f, "\n  {path} (removed)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Copy each file under `from` to the same place under `to`, unless it is
+/// the same as what is there already, or `noOverwrite` excludes it.
+fn sync_dir(
+    from: &Utf8Path,
+    to: &Utf8Path,
+    project_root: &Utf8Path,
+    exclude_files: &GlobSet,
+    summary: &mut SyncSummary,
+) -> Result<()> {
+    for entry in from.read_dir_utf8()? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.metadata()?.is_dir() {
+            sync_dir(entry.path(), &dest, project_root, exclude_files, summary)?;
+            continue;
+        }
+        summary.generated.insert(dest.clone());
+        let rel = dest
+            .strip_prefix(project_root)
+            .map_or_else(|_| dest.clone(), Utf8Path::to_path_buf);
+        if exclude_files.is_match(&rel) {
+            summary.excluded += 1;
+            continue;
+        }
+        let contents = fs::read(entry.path())?;
+        if fs::read(&dest).is_ok_and(|old| old == contents) {
+            summary.unchanged += 1;
+            continue;
+        }
+        mk_dir(to)?;
+        ubrn_common::write_file(&dest, contents)?;
+        summary.written.push(rel);
+    }
+    Ok(())
+}
+
+/// Remove the files generated last time but not this, unless `noOverwrite`
+/// excludes them. Only files the watch generated are candidates: anything
+/// else in the output directories is left alone.
+fn remove_stale(
+    previous: &BTreeSet<Utf8PathBuf>,
+    project_root: &Utf8Path,
+    exclude_files: &GlobSet,
+    summary: &mut SyncSummary,
+) -> Result<()> {
+    for path in previous.difference(&summary.generated) {
+        let rel = path
+            .strip_prefix(project_root)
+            .map_or_else(|_| path.clone(), Utf8Path::to_path_buf);
+        if exclude_files.is_match(&rel) {
+            summary.excluded += 1;
+            continue;
+        }
+        if path.exists() {
+            fs::remove_file(path)?;
+            summary.removed.push(rel);
+        }
+    }
+    Ok(())
+}
+
+/// What changed in the API since the last build, one line per change, as
+/// `api-diff` lists them.
+fn api_changes(old: Option<&[ComponentInterface]>, new: &[ComponentInterface]) -> Vec<String> {
+    // Nothing to compare the first build with.
+    let Some(old) = old else {
+        return Vec::new();
+    };
+    ApiDiff::between(old, new)
+        .changes
+        .iter()
+        .map(|change| bullet(change).trim_end().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use globset::{Glob, GlobSetBuilder};
+
+    use super::*;
+
+    #[test]
+    fn rebuilds_the_profile_and_target_the_library_came_from() {
+        let target = Utf8Path::new("/w/target");
+        let args = |p: &str| cargo_build_args(Utf8Path::new(p), target);
+        assert!(args("/w/target/debug/libfoo.so").is_empty());
+        assert_eq!(args("/w/target/release/libfoo.so"), ["--release"]);
+        assert_eq!(
+            args("/w/target/wasm32-unknown-unknown/dev-opt/foo.wasm"),
+            ["--target", "wasm32-unknown-unknown", "--profile", "dev-opt"]
+        );
+        // Not one of cargo's outputs, so built the default way.
+        assert!(args("/elsewhere/libfoo.so").is_empty());
+    }
+
+    #[test]
+    fn syncs_only_changed_files_not_excluded() -> Result<()> {
+        let root = Utf8PathBuf::try_from(std::env::temp_dir())?
+            .join(format!("ubrn-watch-sync-{}", std::process::id()));
+        let (from, to) = (root.join(".out.ubrn-watch"), root.join("out"));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(from.join("sub"))?;
+        fs::create_dir_all(&to)?;
+        for (name, contents) in [("same.ts", "a"), ("new.ts", "b"), ("sub/kept.ts", "c")] {
+            fs::write(from.join(name), contents)?;
+        }
+        fs::write(to.join("same.ts"), "a")?;
+
+        let mut globs = GlobSetBuilder::new();
+        globs.add(Glob::new("out/sub/*")?);
+        let mut summary = SyncSummary::default();
+        sync_dir(&from, &to, &root, &globs.build()?, &mut summary)?;
+
+        assert_eq!(summary.written, [Utf8PathBuf::from("out/new.ts")]);
+        assert_eq!((summary.unchanged, summary.excluded), (1, 1));
+        assert_eq!(fs::read_to_string(to.join("new.ts"))?, "b");
+        assert!(!to.join("sub/kept.ts").exists());
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn removes_files_no_longer_generated_unless_excluded() -> Result<()> {
+        let root = Utf8PathBuf::try_from(std::env::temp_dir())?
+            .join(format!("ubrn-watch-stale-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("out/sub"))?;
+        for name in ["kept.ts", "gone.ts", "sub/excluded.ts", "hand-written.ts"] {
+            fs::write(root.join("out").join(name), "a")?;
+        }
+        let previous = ["kept.ts", "gone.ts", "sub/excluded.ts"]
+            .map(|name| root.join("out").join(name))
+            .into();
+
+        let mut globs = GlobSetBuilder::new();
+        globs.add(Glob::new("out/sub/*")?);
+        let mut summary = SyncSummary::default();
+        summary.generated.insert(root.join("out/kept.ts"));
+        remove_stale(&previous, &root, &globs.build()?, &mut summary)?;
+
+        assert_eq!(summary.removed, [Utf8PathBuf::from("out/gone.ts")]);
+        assert_eq!(summary.excluded, 1);
+        assert!(!root.join("out/gone.ts").exists());
+        for name in ["kept.ts", "sub/excluded.ts", "hand-written.ts"] {
+            assert!(root.join("out").join(name).exists());
+        }
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn diffs_the_api_as_api_diff_does() {
+        let ci = |udl: &str| ComponentInterface::from_webidl(udl, "crate_name").unwrap();
+        let old = [ci("namespace m { u32 add(u32 a, u32 b); };")];
+        let new = [ci("namespace m { u32 mul(u32 a, u32 b); };")];
+        assert_eq!(
+            api_changes(Some(&old), &new),
+            ["- function `m::add` removed", "- function `m::mul` added"]
+        );
+        assert!(api_changes(None, &new).is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! When the `rust` section lists more than one crate, they are built into
+//! one library—a megazord—from a generated crate which depends on each of
+//! them.
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use anyhow::Result;
+use askama::Template;
+use camino::{Utf8Path, Utf8PathBuf};
+use heck::ToKebabCase;
+use path_slash::PathExt;
+use ubrn_common::{mk_dir, read_to_string, without_dry_run, write_file};
+
+use crate::{config::rust_crate::CrateConfig, workspace};
+
+const MEGAZORD_DIR: &str = "rust_modules/megazord";
+
+#[derive(Clone, Debug)]
+pub(crate) struct MegazordArgs {
+    pub(crate) crates: Vec<CrateConfig>,
+    /// The crate is written once, the first time it's needed, whichever
+    /// copy of the config needs it.
+    written: Arc<AtomicBool>,
+}
+
+impl MegazordArgs {
+    pub(crate) fn new(crates: Vec<CrateConfig>) -> Self {
+        Self {
+            crates,
+            written: Default::default(),
+        }
+    }
+
+    pub(crate) fn directory(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        project_root.join(MEGAZORD_DIR)
+    }
+
+    fn package_name() -> String {
+        format!(
+            "{}-megazord",
+            workspace::package_json().name().to_kebab_case()
+        )
+    }
+
+    /// Write the megazord crate, if it has changed since it was last written.
+    ///
+    /// Cargo needs the crate on disk to build it or to read its metadata, so
+    /// unlike the generated project files, it is written even in a dry run.
+    pub(crate) fn write(&self, project_root: &Utf8Path) -> Result<()> {
+        if self.written.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let dir = self.directory(project_root);
+        let crates = self
+            .crates
+            .iter()
+            .map(|c| MegazordDependency::new(c, &dir))
+            .collect::<Result<Vec<_>>>()?;
+
+        let cargo_toml = MegazordCargoToml {
+            name: Self::package_name(),
+            crates: &crates,
+        };
+        let lib_rs = MegazordLibRs { crates: &crates };
+        without_dry_run(|| {
+            write_if_changed(&dir.join("Cargo.toml"), &cargo_toml.render()?)?;
+            write_if_changed(&dir.join("src/lib.rs"), &lib_rs.render()?)
+        })?;
+        self.written.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Leave the file, and so cargo's fingerprint of it, alone if nothing changed.
+fn write_if_changed(path: &Utf8Path, contents: &str) -> Result<()> {
+    if path.exists() && read_to_string(path)? == contents {
+        return Ok(());
+    }
+    mk_dir(path.parent().expect("Parent for path"))?;
+    write_file(path, contents)
+}
+
+struct MegazordDependency {
+    /// The name the megazord knows the crate by: its library name, as it
+    /// would be known to any other crate.
+    name: String,
+    package: String,
+    path: String,
+}
+
+impl MegazordDependency {
+    fn new(crate_: &CrateConfig, megazord_dir: &Utf8Path) -> Result<Self> {
+        let metadata = crate_.metadata()?;
+        let path = pathdiff::diff_utf8_paths(metadata.crate_dir(), megazord_dir)
+            .expect("Should be able to find a relative path");
+        Ok(Self {
+            name: metadata.library_name().to_string(),
+            package: metadata.package_name().to_string(),
+            path: path.as_std_path().to_slash_lossy().into_owned(),
+        })
+    }
+}
+
+#[derive(Template)]
+#[template(path = "Cargo.megazord.toml", escape = "none")]
+struct MegazordCargoToml<'a> {
+    name: String,
+    crates: &'a [MegazordDependency],
+}
+
+#[derive(Template)]
+#[template(path = "lib.megazord.rs", escape = "none")]
+struct MegazordLibRs<'a> {
+    crates: &'a [MegazordDependency],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependencies() -> Vec<MegazordDependency> {
+        vec![
+            MegazordDependency {
+                name: "arithmetical".to_string(),
+                package: "uniffi-example-arithmetic".to_string(),
+                path: "../../rust/arithmetic".to_string(),
+            },
+            MegazordDependency {
+                name: "uniffi_rondpoint".to_string(),
+                package: "uniffi-example-rondpoint".to_string(),
+                path: "../../rust/rondpoint".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn the_megazord_depends_on_each_crate() -> Result<()> {
+        let crates = dependencies();
+        let cargo_toml = MegazordCargoToml {
+            name: "my-lib-megazord".to_string(),
+            crates: &crates,
+        }
+        .render()?;
+        assert!(cargo_toml.contains("name = \"my-lib-megazord\""));
+        assert!(cargo_toml.contains("crate-type = [\"lib\", \"staticlib\", \"cdylib\"]"));
+        assert!(cargo_toml.contains(
+            "arithmetical = { package = \"uniffi-example-arithmetic\", path = \"../../rust/arithmetic\" }\n\
+             uniffi_rondpoint = { package = \"uniffi-example-rondpoint\", path = \"../../rust/rondpoint\" }\n"
+        ));
+
+        let lib_rs = MegazordLibRs { crates: &crates }.render()?;
+        assert!(lib_rs.contains("pub use arithmetical;\npub use uniffi_rondpoint;\n"));
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
+pub(crate) mod megazord;
 mod npm;
 pub(crate) mod rust_crate;
 
@@ -32,6 +33,7 @@ pub(crate) struct ProjectConfig {
     pub(crate) repository: String,
 
     #[serde(rename = "rust", alias = "crate")]
+    #[serde(deserialize_with = "CrateConfig::one_or_megazord")]
     pub(crate) crate_: CrateConfig,
 
     #[serde(default)]
@@ -48,6 +50,9 @@ pub(crate) struct ProjectConfig {
     #[serde(default, alias = "web2")]
     pub(crate) wasm2: crate::wasm2::Wasm2Config,
 
+    #[serde(default, alias = "node")]
+    pub(crate) napi: crate::napi::NapiConfig,
+
     #[serde(default)]
     pub(crate) bindings: BindingsConfig,
 
@@ -58,6 +63,11 @@ pub(crate) struct ProjectConfig {
     /// the `generate` commands.
     #[serde(default, rename = "noOverwrite")]
     pub(crate) exclude_files: GlobSet,
+
+    /// A directory of templates to use in place of the built-in templates
+    /// with the same names.
+    #[serde(default, deserialize_with = "ProjectConfig::opt_relative_path")]
+    pub(crate) templates: Option<String>,
 }
 
 impl ProjectConfig {
@@ -136,7 +146,6 @@ impl ProjectConfig {
         env!("CARGO_PKG_VERSION").to_string()
     }
 
-    #[allow(dead_code)]
     pub(crate) fn project_version(&self) -> String {
         self.project_version.clone()
     }
@@ -196,6 +205,12 @@ impl ProjectConfig {
         &self.exclude_files
     }
 
+    pub(crate) fn templates_dir(&self) -> Option<Utf8PathBuf> {
+        self.templates
+            .as_deref()
+            .map(|dir| self.project_root().join(dir))
+    }
+
     #[cfg(feature = "wasm")]
     pub(crate) fn wasm_bindings_ts_path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
         self.wasm
@@ -213,6 +228,14 @@ impl ProjectConfig {
             .map(|ts| project_root.join(ts))
             .unwrap_or_else(|| self.bindings.ts_path(project_root))
     }
+
+    pub(crate) fn napi_bindings_ts_path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        self.napi
+            .ts_bindings
+            .as_deref()
+            .map(|ts| project_root.join(ts))
+            .unwrap_or_else(|| self.bindings.ts_path(project_root))
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
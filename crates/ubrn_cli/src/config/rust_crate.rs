@@ -8,17 +8,23 @@ use std::{convert::TryFrom, path::Path};
 
 use anyhow::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
-use serde::{Deserialize, Deserializer};
+use serde::{
+    de::{self, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
 
 use ubrn_common::{path_or_shim, CrateMetadata};
 
-use crate::{commands::checkout::GitRepoArgs, workspace};
+use crate::{commands::checkout::GitRepoArgs, config::megazord::MegazordArgs, workspace};
 
 #[derive(Clone, Debug, Deserialize)]
 #[serde(untagged)]
 pub(crate) enum RustSource {
     OnDisk(OnDiskArgs),
     GitRepo(GitRepoArgs),
+    /// From a list of crates, rather than from the config file.
+    #[serde(skip)]
+    Megazord(MegazordArgs),
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -32,6 +38,7 @@ impl RustSource {
         Ok(match self {
             Self::OnDisk(OnDiskArgs { src }) => project_root.join(src),
             Self::GitRepo(c) => c.directory(project_root)?,
+            Self::Megazord(m) => m.directory(project_root),
         })
     }
 }
@@ -69,6 +76,45 @@ impl CrateConfig {
         "Cargo.toml".to_string()
     }
 
+    /// The `rust` section is either one crate, or a list of crates to build
+    /// into one library.
+    pub(crate) fn one_or_megazord<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct OneOrMegazord;
+
+        impl<'de> Visitor<'de> for OneOrMegazord {
+            type Value = CrateConfig;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a crate, or a list of crates")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                CrateConfig::deserialize(de::value::MapAccessDeserializer::new(map))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let crates = Vec::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+                if crates.is_empty() {
+                    return Err(de::Error::custom("The list of crates is empty"));
+                }
+                Ok(CrateConfig::megazord(crates))
+            }
+        }
+
+        deserializer.deserialize_any(OneOrMegazord)
+    }
+
+    fn megazord(crates: Vec<CrateConfig>) -> Self {
+        Self {
+            project_root: Self::default_project_root(),
+            manifest_path: Self::default_manifest_path(),
+            src: RustSource::Megazord(MegazordArgs::new(crates)),
+        }
+    }
+
     pub(crate) fn validate_manifest_path<'de, D>(deserializer: D) -> Result<String, D::Error>
     where
         D: Deserializer<'de>,
@@ -99,6 +145,9 @@ impl CrateConfig {
     }
 
     pub(crate) fn manifest_path(&self) -> Result<Utf8PathBuf> {
+        if let RustSource::Megazord(m) = &self.src {
+            m.write(&self.project_root)?;
+        }
         let manifest_path = path_or_shim(&self.directory()?.join(&self.manifest_path))?;
         Ok(manifest_path)
     }
@@ -109,6 +158,15 @@ impl CrateConfig {
         Ok(dir.into())
     }
 
+    /// The directories of the crates' source: for a megazord, each of the
+    /// crates it is made from.
+    pub(crate) fn source_dirs(&self) -> Result<Vec<Utf8PathBuf>> {
+        match &self.src {
+            RustSource::Megazord(m) => m.crates.iter().map(Self::crate_dir).collect(),
+            _ => Ok(vec![self.crate_dir()?]),
+        }
+    }
+
     #[allow(dead_code)]
     pub(crate) fn crate_dir_relative(&self, project_root: &Utf8Path) -> Utf8PathBuf {
         let manifest = self
@@ -25,12 +25,18 @@ pub(crate) fn get_files(config: Rc<TemplateConfig>) -> Vec<Rc<dyn RenderedFile>>
         KtModule::rc_new(config.clone()),
         KtPackage::rc_new(config.clone()),
         KtBuildGradle::rc_new(config.clone()),
+        // Android with Expo
+        ExpoKtModule::rc_new(config.clone()),
     ]
 }
 
 impl TemplateConfig {
     pub(crate) fn uses_kotlin(self: &Rc<Self>) -> bool {
         *self.uses_kotlin.get_or_init(|| {
+            // Expo modules are written in Kotlin.
+            if self.project.tm.is_expo() {
+                return true;
+            }
             let project_root = self.project.project_root();
             let gradle_file = BuildGradle::new(self.clone()).path(project_root);
             if gradle_file.exists() {
@@ -128,7 +134,7 @@ impl RenderedFile for KtModule {
             .join(filename)
     }
     fn filter_by(&self) -> bool {
-        self.config.uses_kotlin()
+        self.config.uses_kotlin() && !self.config.project.tm.is_expo()
     }
 }
 
@@ -144,7 +150,7 @@ impl RenderedFile for KtPackage {
             .join(filename)
     }
     fn filter_by(&self) -> bool {
-        self.config.uses_kotlin()
+        self.config.uses_kotlin() && !self.config.project.tm.is_expo()
     }
 }
 
@@ -163,6 +169,22 @@ impl RenderedFile for KtBuildGradle {
     }
 }
 
+templated_file!(ExpoKtModule, "ExpoModuleTemplate.kt");
+impl RenderedFile for ExpoKtModule {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        let name = self.config.project.module_cpp();
+        let filename = format!("{name}Module.kt");
+        self.config
+            .project
+            .android
+            .codegen_package_dir(project_root)
+            .join(filename)
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
+
 templated_file!(CMakeLists, "CMakeLists.txt");
 impl RenderedFile for CMakeLists {
     fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
@@ -8,6 +8,7 @@ use std::{fmt::Display, str::FromStr};
 use anyhow::{Error, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::Deserialize;
+use ubrn_common::CrateMetadata;
 
 use crate::{config::ExtraArgs, workspace};
 
@@ -103,6 +104,11 @@ impl AndroidConfig {
         self.directory(project_root).join(&self.jni_libs)
     }
 
+    /// The file name of the Rust library in each of the `jni_libs` directories.
+    pub(crate) fn library_file(&self, rust_crate: &CrateMetadata) -> String {
+        rust_crate.library_file(Some("android"), Some(self.use_shared_library))
+    }
+
     fn main_src(&self) -> String {
         "src/main".to_string()
     }
@@ -19,6 +19,10 @@ pub(crate) fn get_files(config: Rc<TemplateConfig>) -> Vec<Rc<dyn RenderedFile>>
         TMCpp::rc_new(config.clone()),
         // Codegen (for installer)
         NativeCodegenTs::rc_new(config.clone()),
+        // Expo (for installer)
+        NativeExpoTs::rc_new(config.clone()),
+        ExpoModuleConfig::rc_new(config.clone()),
+        ExpoConfigPlugin::rc_new(config.clone()),
     ]
 }
 
@@ -51,4 +55,40 @@ impl RenderedFile for NativeCodegenTs {
         let filename = format!("{}.ts", self.config.project.codegen_filename());
         self.config.project.tm.ts_path(project_root).join(filename)
     }
+    fn filter_by(&self) -> bool {
+        !self.config.project.tm.is_expo()
+    }
+}
+
+// The same file as `NativeCodegenTs`, as `index.tsx` imports it, but finding
+// the Expo module.
+templated_file!(NativeExpoTs, "NativeExpoTemplate.ts");
+impl RenderedFile for NativeExpoTs {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        let filename = format!("{}.ts", self.config.project.codegen_filename());
+        self.config.project.tm.ts_path(project_root).join(filename)
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
+
+templated_file!(ExpoModuleConfig, "expo-module.config.json");
+impl RenderedFile for ExpoModuleConfig {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        project_root.join("expo-module.config.json")
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
+
+templated_file!(ExpoConfigPlugin, "app.plugin.js");
+impl RenderedFile for ExpoConfigPlugin {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        project_root.join("app.plugin.js")
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
 }
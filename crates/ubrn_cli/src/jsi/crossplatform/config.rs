@@ -28,6 +28,21 @@ pub(crate) struct TurboModulesConfig {
     #[serde(default = "TurboModulesConfig::default_entrypoint")]
     #[serde(deserialize_with = "ProjectConfig::relative_path")]
     pub(crate) entrypoint: String,
+
+    /// What kind of native module installs the bindings into the app.
+    #[serde(default)]
+    pub(crate) mode: TurboModuleMode,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum TurboModuleMode {
+    /// A turbo-module, found by React Native's codegen.
+    #[default]
+    TurboModule,
+    /// An Expo module, found by Expo's autolinking, with a config plugin for
+    /// the app's build settings.
+    Expo,
 }
 
 impl TurboModulesConfig {
@@ -90,4 +105,8 @@ impl TurboModulesConfig {
     pub(crate) fn name(&self) -> String {
         self.name.clone()
     }
+
+    pub(crate) fn is_expo(&self) -> bool {
+        self.mode == TurboModuleMode::Expo
+    }
 }
@@ -9,8 +9,8 @@ use clap::{Args, Subcommand};
 use ubrn_bindgen::{ModuleMetadata, OutputArgs, SourceArgs, SwitchArgs};
 
 use crate::{
-    codegen::{get_template_config, render_files},
-    commands::ConfigArgs,
+    codegen::{eject, get_template_config, render_files},
+    commands::{ConfigArgs, WatchArgs},
     jsi, ProjectConfig,
 };
 
@@ -36,11 +36,7 @@ enum Cmd {
 impl Cmd {
     fn run(&self) -> Result<()> {
         match self {
-            Self::Bindings(b) => {
-                let b = ubrn_bindgen::BindingsArgs::from(b);
-                b.run(None)?;
-                Ok(())
-            }
+            Self::Bindings(b) => b.watch.run(ubrn_bindgen::BindingsArgs::from(b)),
             Self::TurboModule(t) => {
                 t.run()?;
                 Ok(())
@@ -55,6 +51,8 @@ pub(crate) struct BindingsArgs {
     pub(crate) source: SourceArgs,
     #[command(flatten)]
     pub(crate) output: OutputArgs,
+    #[command(flatten)]
+    pub(crate) watch: WatchArgs,
 }
 
 impl From<&BindingsArgs> for ubrn_bindgen::BindingsArgs {
@@ -78,11 +76,21 @@ pub(crate) struct TurboModuleArgs {
     /// Whether to generate native bindings or not.
     #[clap(long, default_value = "false")]
     native_bindings: bool,
+
+    /// Instead of generating, write the named built-in template into the
+    /// `templates` directory, as a starting point for an override.
+    #[clap(long, value_name = "TEMPLATE")]
+    eject: Option<String>,
 }
 
 impl TurboModuleArgs {
     pub(crate) fn run(&self) -> Result<()> {
         let project = ProjectConfig::try_from(self.config.clone())?;
+        if let Some(name) = &self.eject {
+            let path = eject(&project, name)?;
+            println!("Ejected {name} to {path}");
+            return Ok(());
+        }
         let modules = self
             .namespaces
             .iter()
@@ -15,6 +15,10 @@ pub(crate) fn get_files(config: Rc<TemplateConfig>) -> Vec<Rc<dyn RenderedFile>>
         ModuleTemplateH::rc_new(config.clone()),
         ModuleTemplateMm::rc_new(config.clone()),
         PodspecTemplate::rc_new(config.clone()),
+        // Expo
+        ExpoModuleSwift::rc_new(config.clone()),
+        ExpoInstallerH::rc_new(config.clone()),
+        ExpoInstallerMm::rc_new(config.clone()),
     ]
 }
 
@@ -29,6 +33,9 @@ impl RenderedFile for ModuleTemplateH {
             .directory(project_root)
             .join(filename)
     }
+    fn filter_by(&self) -> bool {
+        !self.config.project.tm.is_expo()
+    }
 }
 
 templated_file!(ModuleTemplateMm, "ModuleTemplate.mm");
@@ -42,6 +49,9 @@ impl RenderedFile for ModuleTemplateMm {
             .directory(project_root)
             .join(filename)
     }
+    fn filter_by(&self) -> bool {
+        !self.config.project.tm.is_expo()
+    }
 }
 
 templated_file!(PodspecTemplate, "module-template.podspec");
@@ -52,3 +62,51 @@ impl RenderedFile for PodspecTemplate {
         project_root.join(filename)
     }
 }
+
+templated_file!(ExpoModuleSwift, "ExpoModuleTemplate.swift");
+impl RenderedFile for ExpoModuleSwift {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        let name = self.config.project.module_cpp();
+        let filename = format!("{name}Module.swift");
+        self.config
+            .project
+            .ios
+            .directory(project_root)
+            .join(filename)
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
+
+templated_file!(ExpoInstallerH, "ExpoInstallerTemplate.h");
+impl RenderedFile for ExpoInstallerH {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        let name = self.config.project.module_cpp();
+        let filename = format!("{name}Installer.h");
+        self.config
+            .project
+            .ios
+            .directory(project_root)
+            .join(filename)
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
+
+templated_file!(ExpoInstallerMm, "ExpoInstallerTemplate.mm");
+impl RenderedFile for ExpoInstallerMm {
+    fn path(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        let name = self.config.project.module_cpp();
+        let filename = format!("{name}Installer.mm");
+        self.config
+            .project
+            .ios
+            .directory(project_root)
+            .join(filename)
+    }
+    fn filter_by(&self) -> bool {
+        self.config.project.tm.is_expo()
+    }
+}
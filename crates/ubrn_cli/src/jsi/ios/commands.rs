@@ -83,10 +83,10 @@ impl IosBuildArgs {
             if !files.is_empty() {
                 files
             } else {
-                self.cargo_build_all(crate_, &targets, &ios.cargo_extras)?
+                self.cargo_build_all(crate_, &targets, &ios.cargo_extras, &ios.deployment_target)?
             }
         } else {
-            self.cargo_build_all(crate_, &targets, &ios.cargo_extras)?
+            self.cargo_build_all(crate_, &targets, &ios.cargo_extras, &ios.deployment_target)?
         };
 
         Ok(if !self.no_xcodebuild {
@@ -107,13 +107,20 @@ impl IosBuildArgs {
         crate_: &CrateConfig,
         targets: &[Target],
         cargo_extras: &ExtraArgs,
+        deployment_target: &str,
     ) -> Result<HashMap<Target, Utf8PathBuf>> {
         let mut target_files = HashMap::new();
         let metadata = crate_.metadata()?;
         let rust_dir = crate_.directory()?;
         let manifest_path = crate_.manifest_path()?;
         for target in targets {
-            self.cargo_build(&manifest_path, target, cargo_extras, &rust_dir)?;
+            self.cargo_build(
+                &manifest_path,
+                target,
+                cargo_extras,
+                deployment_target,
+                &rust_dir,
+            )?;
 
             // Now we need to get the path to the lib.a file, to feed to xcodebuild.
             let library =
@@ -128,6 +135,7 @@ impl IosBuildArgs {
         manifest_path: &Utf8PathBuf,
         target: &Target,
         cargo_extras: &ExtraArgs,
+        deployment_target: &str,
         rust_dir: &Utf8PathBuf,
     ) -> Result<()> {
         let mut cmd = Command::new("cargo");
@@ -135,7 +143,8 @@ impl IosBuildArgs {
             .arg("--manifest-path")
             .arg(manifest_path)
             .arg("--target")
-            .arg(&target.triple);
+            .arg(&target.triple)
+            .env("IPHONEOS_DEPLOYMENT_TARGET", deployment_target);
         let profile = self.common_args.profile();
         if profile != "debug" {
             cmd.args(["--profile", profile]);
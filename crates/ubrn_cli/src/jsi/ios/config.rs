@@ -36,6 +36,9 @@ pub(crate) struct IOsConfig {
 
     #[serde(default = "IOsConfig::default_codegen_output_dir")]
     pub(crate) codegen_output_dir: String,
+
+    #[serde(default = "IOsConfig::default_deployment_target")]
+    pub(crate) deployment_target: String,
 }
 
 impl IOsConfig {
@@ -77,6 +80,13 @@ impl IOsConfig {
     fn default_codegen_output_dir() -> String {
         workspace::package_json().ios_codegen_output_dir()
     }
+
+    fn default_deployment_target() -> String {
+        // This is min_ios_version_supported for 0.75.4.
+        // As with the Android API level, we should not raise this while we
+        // still support 0.75.4.
+        "13.4".to_string()
+    }
 }
 
 impl Default for IOsConfig {
@@ -59,6 +59,7 @@ pub enum Platform {
     Wasm,
     #[cfg(feature = "wasm")]
     Wasm2,
+    Napi,
 }
 
 impl From<&Platform> for AbiFlavor {
@@ -68,6 +69,7 @@ impl From<&Platform> for AbiFlavor {
             Platform::Wasm => AbiFlavor::Wasm,
             #[cfg(feature = "wasm")]
             Platform::Wasm2 => AbiFlavor::Wasm2,
+            Platform::Napi => AbiFlavor::Napi,
             _ => AbiFlavor::Jsi,
         }
     }
@@ -0,0 +1,35 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+use camino::Utf8PathBuf;
+
+use ubrn_bindgen::{BindingsArgs, OutputArgs, SourceArgs, SwitchArgs};
+
+use crate::config::ProjectConfig;
+
+pub(crate) fn bindings(
+    project: &ProjectConfig,
+    switches: SwitchArgs,
+    lib_file: &Utf8PathBuf,
+) -> Result<BindingsArgs> {
+    let root = project.project_root();
+    let config = project.bindings.uniffi_toml_path(root);
+    if let Some(ref file) = config {
+        if !file.exists() {
+            anyhow::bail!("uniffi.toml file {:?} does not exist. Either delete the uniffiToml property or supply a file", file)
+        }
+    }
+    let source = SourceArgs::library(lib_file).with_config(config);
+
+    let ts_dir = project.napi_bindings_ts_path(root);
+    let resolution = project.napi.lib_resolution(project.raw_name(), lib_file);
+    // No C++ here either: point the native-output directory at the
+    // TypeScript one, as wasm2 does.
+    Ok(
+        BindingsArgs::new(switches, source, OutputArgs::new(&ts_dir, &ts_dir, false))
+            .with_lib_resolution(resolution),
+    )
+}
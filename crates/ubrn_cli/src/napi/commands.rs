@@ -0,0 +1,344 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Args;
+use serde_json::{json, Map, Value};
+use ubrn_bindgen::ffi_module_player_lib_resolution::TripleStyle;
+use ubrn_common::{mk_dir, run_cmd};
+
+use super::{config::LibResolutionStrategy, target::Target, NapiConfig};
+use crate::{
+    commands::{building::CommonBuildArgs, generate::GenerateAllCommand, ConfigArgs},
+    config::ProjectConfig,
+    Platform,
+};
+
+#[derive(Args, Debug)]
+pub(crate) struct NapiBuildArgs {
+    #[clap(flatten)]
+    config: ConfigArgs,
+
+    /// Comma separated list of targets, overriding the list in the config
+    /// file. Either cargo or node spellings: `aarch64-apple-darwin` or
+    /// `darwin-arm64`.
+    #[clap(long, value_parser, num_args = 1.., value_delimiter = ',')]
+    pub(crate) targets: Vec<Target>,
+
+    #[clap(flatten)]
+    pub(crate) common_args: CommonBuildArgs,
+}
+
+impl NapiBuildArgs {
+    /// Build the crate's cdylib for every target, and return the library node
+    /// can load here: the bindings are generated from it.
+    pub(crate) fn build(&self) -> Result<Vec<Utf8PathBuf>> {
+        let config = self.project_config()?;
+        let built = self.build_all(&config)?;
+        let local = self.local_target(&config)?;
+        Ok(built
+            .into_iter()
+            .filter(|(target, _)| *target == local)
+            .map(|(_, library)| library)
+            .collect())
+    }
+
+    /// Build the crate's cdylib once per target, in the order of the targets.
+    pub(crate) fn build_all(&self, config: &ProjectConfig) -> Result<Vec<(Target, Utf8PathBuf)>> {
+        let crate_ = &config.crate_;
+        let metadata = crate_.metadata()?;
+        if !metadata.builds_cdylib() {
+            bail!(
+                "{} does not build a cdylib, which node loads.\n\
+                 Add it to the [lib] section:\n\
+                 \n    [lib]\n    crate-type = [\"lib\", \"cdylib\"]\n",
+                metadata.manifest_path()
+            );
+        }
+        let manifest_path = crate_.manifest_path()?;
+        let rust_dir = crate_.crate_dir()?;
+        let profile = self.common_args.profile();
+        let mut libraries = Vec::new();
+        for target in self.targets(&config.napi) {
+            if !self.common_args.no_cargo {
+                self.cargo_build(&manifest_path, &config.napi, &target, &rust_dir)?;
+            }
+            let library = metadata.library_path(Some(target.triple()), profile, None);
+            libraries.push((target, library));
+        }
+        Ok(libraries)
+    }
+
+    /// Copy the library next to the generated TypeScript, where colocated
+    /// bindings look for it.
+    ///
+    /// Runs after `generate`, which creates the destination.
+    pub(crate) fn then_build(&self) -> Result<()> {
+        let config = self.project_config()?;
+        if config.napi.lib_resolution != LibResolutionStrategy::Colocated {
+            return Ok(());
+        }
+        let target = self.local_target(&config)?;
+        let metadata = config.crate_.metadata()?;
+        let library =
+            metadata.library_path(Some(target.triple()), self.common_args.profile(), None);
+        let ts_dir = config.napi_bindings_ts_path(config.project_root());
+        let staged = ts_dir.join(metadata.library_file(Some(target.triple()), None));
+        ubrn_common::cp_file(&library, &staged)?;
+        println!("Staged {staged}");
+        Ok(())
+    }
+
+    fn local_target(&self, config: &ProjectConfig) -> Result<Target> {
+        Target::local(&self.targets(&config.napi))
+            .ok_or_else(|| anyhow!("No targets were specified"))
+    }
+
+    pub(crate) fn targets(&self, napi: &NapiConfig) -> Vec<Target> {
+        if self.targets.is_empty() {
+            napi.targets.clone()
+        } else {
+            self.targets.clone()
+        }
+    }
+
+    pub(crate) fn project_config(&self) -> Result<ProjectConfig> {
+        let config: ProjectConfig = self.config.clone().try_into()?;
+        Ok(config)
+    }
+
+    fn cargo_build(
+        &self,
+        manifest_path: &Utf8Path,
+        napi_config: &NapiConfig,
+        target: &Target,
+        rust_dir: &Utf8Path,
+    ) -> Result<()> {
+        let triple = target.triple();
+        println!("Compiling {manifest_path} for {triple}");
+        let mut cmd = Command::new("cargo");
+        cmd.arg("build")
+            .arg("--lib")
+            .arg("--manifest-path")
+            .arg(manifest_path)
+            .arg("--target")
+            .arg(triple);
+        let profile = self.common_args.profile();
+        if profile != "debug" {
+            cmd.arg("--profile").arg(profile);
+        }
+        if let Some(features) = &napi_config.features {
+            cmd.arg("--features").arg(features.join(","));
+        }
+        if napi_config.default_features == Some(false) {
+            cmd.arg("--no-default-features");
+        }
+        cmd.args(napi_config.cargo_extras.clone())
+            .current_dir(rust_dir);
+        run_cmd(&mut cmd)?;
+        Ok(())
+    }
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct NapiPackageArgs {
+    #[clap(flatten)]
+    build: NapiBuildArgs,
+}
+
+impl NapiPackageArgs {
+    /// Build for each target, then lay out one npm package per target and
+    /// point the project's `optionalDependencies` at them. With
+    /// `--and-generate`, the bindings are generated between the two, as
+    /// `build napi` would.
+    pub(crate) fn run(&self) -> Result<()> {
+        if self.build.common_args.dry_run.dry_run {
+            bail!("--dry-run is for `build`: packaging always writes the packages");
+        }
+        let config = self.build.project_config()?;
+        let libraries = self.build.build_all(&config)?;
+        if self.build.common_args.and_generate {
+            let local = self.build.local_target(&config)?;
+            let (_, lib_file) = libraries
+                .iter()
+                .find(|(target, _)| *target == local)
+                .expect("the local target is one of the targets");
+            GenerateAllCommand::platform_specific(
+                lib_file.clone(),
+                config.clone(),
+                Platform::Napi,
+                false,
+            )
+            .run()?;
+            self.build.then_build()?;
+        }
+        let metadata = config.crate_.metadata()?;
+        let project_root = config.project_root();
+        let packages = &config.napi.packages;
+        let base = packages.base(config.raw_name());
+        let out_dir = packages.directory(project_root);
+
+        // Read it before writing anything, so a malformed one stops us early.
+        let package_json_path = project_root.join("package.json");
+        let mut umbrella: Value =
+            serde_json::from_str(&ubrn_common::read_to_string(&package_json_path)?)?;
+
+        let mut names = Vec::new();
+        for (target, library) in &libraries {
+            let triple = target.styled_triple(packages.triple_style);
+            let name = package_name(&base, triple)?;
+            let dir = out_dir.join(triple);
+            mk_dir(&dir)?;
+            let lib_file = metadata.library_file(Some(target.triple()), None);
+            ubrn_common::cp_file(library, dir.join(&lib_file))?;
+            let contents = platform_package_json(&config, &umbrella, &name, target, &lib_file);
+            ubrn_common::write_file(dir.join("package.json"), to_json_file(&contents)?)?;
+            println!("Packaged {name} in {dir}");
+            names.push(name);
+        }
+
+        set_optional_dependencies(&mut umbrella, &base, &names, &config.project_version());
+        ubrn_common::write_file(&package_json_path, to_json_file(&umbrella)?)?;
+        Ok(())
+    }
+}
+
+/// The subpath layout `@scope/foo/<triple>` resolves, but is not something
+/// npm can publish.
+fn package_name(base: &str, triple: &str) -> Result<String> {
+    let name = format!("{base}{triple}");
+    let slashes = name.matches('/').count();
+    let scoped = name.starts_with('@');
+    if (scoped && slashes != 1) || (!scoped && slashes != 0) {
+        bail!(
+            "{name} is not a valid npm package name. \
+             Set napi.packages.base to a prefix ending in `-` or `_`."
+        );
+    }
+    Ok(name)
+}
+
+fn platform_package_json(
+    config: &ProjectConfig,
+    umbrella: &Value,
+    name: &str,
+    target: &Target,
+    lib_file: &str,
+) -> Value {
+    let mut package = json!({
+        "name": name,
+        "version": config.project_version(),
+        "repository": {
+            "type": "git",
+            "url": config.repository(),
+        },
+    });
+    let fields = package.as_object_mut().expect("a json object");
+    if let Some(license) = umbrella.get("license") {
+        fields.insert("license".to_string(), license.clone());
+    }
+    fields.insert("os".to_string(), json!([target.os()]));
+    fields.insert("cpu".to_string(), json!([target.cpu()]));
+    if let Some(libc) = target.libc() {
+        fields.insert("libc".to_string(), json!([libc]));
+    }
+    fields.insert("files".to_string(), json!([lib_file]));
+    package
+}
+
+/// Replace the entries for this project's platform packages, leaving any
+/// other optional dependency where it is.
+fn set_optional_dependencies(umbrella: &mut Value, base: &str, names: &[String], version: &str) {
+    let Some(fields) = umbrella.as_object_mut() else {
+        return;
+    };
+    let deps = fields
+        .entry("optionalDependencies")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !deps.is_object() {
+        *deps = Value::Object(Map::new());
+    }
+    let deps = deps.as_object_mut().expect("a json object");
+    deps.retain(|name, _| !is_platform_package(base, name));
+    for name in names {
+        deps.insert(name.clone(), Value::String(version.to_string()));
+    }
+}
+
+fn is_platform_package(base: &str, name: &str) -> bool {
+    name.strip_prefix(base).is_some_and(|triple| {
+        Target::all().iter().any(|t| {
+            t.styled_triple(TripleStyle::Cargo) == triple
+                || t.styled_triple(TripleStyle::Node) == triple
+        })
+    })
+}
+
+fn to_json_file(value: &Value) -> Result<String> {
+    Ok(serde_json::to_string_pretty(value)? + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn package_names_must_be_publishable() {
+        assert_eq!(
+            package_name("@scope/foo-", "darwin-arm64").unwrap(),
+            "@scope/foo-darwin-arm64"
+        );
+        assert_eq!(
+            package_name("foo_", "x86_64-pc-windows-msvc").unwrap(),
+            "foo_x86_64-pc-windows-msvc"
+        );
+        assert!(package_name("@scope/foo/", "darwin-arm64").is_err());
+        assert!(package_name("foo/", "darwin-arm64").is_err());
+    }
+
+    #[test]
+    fn optional_dependencies_replace_only_platform_packages() {
+        let mut umbrella = json!({
+            "name": "foo",
+            "optionalDependencies": {
+                "fsevents": "^2.0.0",
+                "foo-linux-x64-gnu": "0.0.1",
+                "foo-x86_64-unknown-linux-gnu": "0.0.1",
+            },
+            "license": "MIT",
+        });
+        set_optional_dependencies(
+            &mut umbrella,
+            "foo-",
+            &["foo-darwin-arm64".to_string()],
+            "1.2.3",
+        );
+        assert_eq!(
+            umbrella["optionalDependencies"],
+            json!({ "fsevents": "^2.0.0", "foo-darwin-arm64": "1.2.3" })
+        );
+        // The other fields keep their place, so the diff is only the change.
+        let keys: Vec<_> = umbrella.as_object().unwrap().keys().collect();
+        assert_eq!(keys, ["name", "optionalDependencies", "license"]);
+    }
+
+    #[test]
+    fn optional_dependencies_are_added_when_missing() {
+        let mut umbrella = json!({ "name": "foo" });
+        set_optional_dependencies(
+            &mut umbrella,
+            "foo-",
+            &["foo-linux-arm64-musl".to_string()],
+            "0.1.0",
+        );
+        assert_eq!(
+            umbrella["optionalDependencies"],
+            json!({ "foo-linux-arm64-musl": "0.1.0" })
+        );
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+use ubrn_bindgen::ffi_module_player_lib_resolution::{LibResolution, TripleStyle};
+
+use super::{generate::normalize_package_base, target::Target};
+use crate::config::{ExtraArgs, ProjectConfig};
+
+/// How to build the crate for Node, where its bindings go and how they find
+/// the library, and how to publish it as one npm package per platform.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NapiConfig {
+    #[serde(default)]
+    pub(crate) features: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub(crate) default_features: Option<bool>,
+
+    #[serde(default = "NapiConfig::default_targets")]
+    pub(crate) targets: Vec<Target>,
+
+    #[serde(default = "NapiConfig::default_cargo_extras")]
+    pub(crate) cargo_extras: ExtraArgs,
+
+    #[serde(alias = "ts", alias = "typescript")]
+    #[serde(deserialize_with = "ProjectConfig::opt_relative_path")]
+    #[serde(default)]
+    pub(crate) ts_bindings: Option<String>,
+
+    #[serde(default)]
+    pub(crate) lib_resolution: LibResolutionStrategy,
+
+    #[serde(default)]
+    pub(crate) packages: PackagesConfig,
+}
+
+impl Default for NapiConfig {
+    fn default() -> Self {
+        ubrn_common::default()
+    }
+}
+
+impl NapiConfig {
+    fn default_targets() -> Vec<Target> {
+        Target::host().into_iter().collect()
+    }
+    fn default_cargo_extras() -> ExtraArgs {
+        let args: &[&str] = &[];
+        args.into()
+    }
+
+    /// How the generated bindings find `lib_file`, or its copies, at runtime.
+    pub(crate) fn lib_resolution(&self, project_name: &str, lib_file: &Utf8Path) -> LibResolution {
+        match self.lib_resolution {
+            LibResolutionStrategy::Colocated => LibResolution::Colocated,
+            // Forward slashes, which node accepts on every platform, so the
+            // path is a valid TS string on Windows too.
+            LibResolutionStrategy::Absolute => {
+                LibResolution::Absolute(lib_file.as_str().replace('\\', "/").into())
+            }
+            LibResolutionStrategy::Packages => LibResolution::Require {
+                base: self.packages.base(project_name),
+                triple_style: self.packages.triple_style,
+            },
+        }
+    }
+}
+
+/// The config spelling of the `--lib-*` flags of `generate napi bindings`.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum LibResolutionStrategy {
+    /// The library is copied next to the generated bindings.
+    #[default]
+    Colocated,
+    /// The bindings load the library from where cargo built it.
+    Absolute,
+    /// The bindings load the library from the platform packages.
+    Packages,
+}
+
+/// The per-platform packages that `ubrn package napi` lays out, and that the
+/// generated bindings find with `--lib-package-base`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PackagesConfig {
+    /// The prefix each package name starts with, before the triple. Defaults
+    /// to the project's name.
+    #[serde(default)]
+    pub(crate) base: Option<String>,
+
+    #[serde(default)]
+    pub(crate) triple_style: TripleStyle,
+
+    #[serde(default = "PackagesConfig::default_directory")]
+    #[serde(deserialize_with = "ProjectConfig::relative_path")]
+    pub(crate) directory: String,
+}
+
+impl Default for PackagesConfig {
+    fn default() -> Self {
+        ubrn_common::default()
+    }
+}
+
+impl PackagesConfig {
+    fn default_directory() -> String {
+        "npm".to_string()
+    }
+
+    /// The literal prefix of each package name, with the same separator rules
+    /// as `--lib-package-base`.
+    pub(crate) fn base(&self, project_name: &str) -> String {
+        normalize_package_base(self.base.as_deref().unwrap_or(project_name))
+    }
+
+    pub(crate) fn directory(&self, project_root: &Utf8Path) -> Utf8PathBuf {
+        project_root.join(&self.directory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_reads_targets_and_packages() {
+        let config: NapiConfig = serde_json::from_str(
+            r#"{
+                "targets": ["aarch64-apple-darwin", "linux-x64-gnu"],
+                "features": ["node"],
+                "ts": "./node-src/generated",
+                "libResolution": "packages",
+                "packages": {
+                    "base": "@scope/foo",
+                    "tripleStyle": "node",
+                    "directory": "./dist/npm"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.targets,
+            vec![Target::Aarch64AppleDarwin, Target::X86_64UnknownLinuxGnu]
+        );
+        assert_eq!(config.features, Some(vec!["node".to_string()]));
+        assert_eq!(config.packages.base("ignored"), "@scope/foo-");
+        assert_eq!(config.packages.triple_style, TripleStyle::Node);
+        assert_eq!(config.packages.directory, "dist/npm");
+        assert_eq!(config.ts_bindings.as_deref(), Some("node-src/generated"));
+        match config.lib_resolution("ignored", Utf8Path::new("/lib/libfoo.so")) {
+            LibResolution::Require { base, triple_style } => {
+                assert_eq!(base, "@scope/foo-");
+                assert_eq!(triple_style, TripleStyle::Node);
+            }
+            other => panic!("expected Require, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn absolute_resolution_bakes_the_library_path() {
+        let config: NapiConfig =
+            serde_json::from_str(r#"{ "libResolution": "absolute" }"#).unwrap();
+        match config.lib_resolution("foo", Utf8Path::new("C:\\target\\foo.dll")) {
+            LibResolution::Absolute(path) => assert_eq!(path, "C:/target/foo.dll"),
+            other => panic!("expected Absolute, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn config_defaults() {
+        let config = NapiConfig::default();
+        assert_eq!(config.targets, NapiConfig::default_targets());
+        assert_eq!(config.packages.base("my-lib"), "my-lib-");
+        assert_eq!(config.packages.triple_style, TripleStyle::Cargo);
+        assert_eq!(config.packages.directory, "npm");
+        assert_eq!(config.ts_bindings, None);
+        assert_eq!(config.lib_resolution, LibResolutionStrategy::Colocated);
+    }
+}
@@ -12,6 +12,12 @@ use ubrn_bindgen::{
     AbiFlavor, OutputArgs, SourceArgs, SwitchArgs,
 };
 
+use super::config::{LibResolutionStrategy, NapiConfig};
+use crate::{
+    commands::{ConfigArgs, WatchArgs},
+    config::ProjectConfig,
+};
+
 #[derive(Args, Debug)]
 pub(crate) struct CmdArg {
     #[clap(subcommand)]
@@ -19,37 +25,44 @@ pub(crate) struct CmdArg {
 }
 
 impl CmdArg {
-    pub(crate) fn run(&self) -> Result<()> {
-        self.cmd.run()
+    /// Runs for either of the player flavors that load the cdylib at
+    /// runtime: `Napi`, or `Deno` (which covers Bun).
+    pub(crate) fn run(&self, flavor: AbiFlavor) -> Result<()> {
+        self.cmd.run(flavor)
     }
 }
 
 #[derive(Debug, Subcommand)]
 enum Cmd {
-    /// Generate just the Typescript bindings for N-API
+    /// Generate just the Typescript bindings
     Bindings(BindingsArgs),
 }
 
 impl Cmd {
-    fn run(&self) -> Result<()> {
+    fn run(&self, flavor: AbiFlavor) -> Result<()> {
         match self {
             Self::Bindings(b) => {
                 // Validate before any I/O.
-                let resolution = b.resolve_lib_resolution()?;
-                let bb = ubrn_bindgen::BindingsArgs::from(b).with_lib_resolution(resolution);
-                bb.run(None)?;
-                Ok(())
+                let project = b.project_config()?;
+                let resolution =
+                    b.resolve_lib_resolution(project.as_ref().map(|p| (&p.napi, p.raw_name())))?;
+                let bb = b
+                    .to_bindgen_args(flavor, project.as_ref())
+                    .with_lib_resolution(resolution);
+                b.watch.run(bb)
             }
         }
     }
 }
 
+/// Without `--ts-dir`, or without one of the `--lib-*` flags, the project's
+/// `ubrn.config.yaml` supplies them, from `napi.tsBindings` and
+/// `napi.libResolution`, as `build napi --and-generate` would.
 #[derive(Args, Debug)]
 #[command(group(
     ArgGroup::new("lib_resolution")
         .args(["lib_colocated", "lib_absolute", "lib_package_base"])
         .multiple(false)
-        .required(true)
 ))]
 pub(crate) struct BindingsArgs {
     #[command(flatten)]
@@ -59,9 +72,16 @@ pub(crate) struct BindingsArgs {
     #[clap(long)]
     pub(crate) no_format: bool,
 
-    /// The directory in which to put the generated Typescript.
+    /// The directory in which to put the generated Typescript. Defaults to
+    /// the project config's `napi.tsBindings`.
     #[clap(long)]
-    pub(crate) ts_dir: Utf8PathBuf,
+    pub(crate) ts_dir: Option<Utf8PathBuf>,
+
+    /// The project's `ubrn.config.yaml`, read when `--ts-dir` or the `--lib-*`
+    /// flags are left out. Defaults to the one in the current directory or
+    /// its parents.
+    #[clap(long = "project-config", value_name = "FILE")]
+    pub(crate) project_config: Option<Utf8PathBuf>,
 
     /// Generated bindings call resolveLibPath in colocated mode.
     /// The binary must sit next to the generated `.js` file at runtime.
@@ -93,10 +113,28 @@ pub(crate) struct BindingsArgs {
     /// with --lib-colocated or --lib-absolute.
     #[clap(long = "lib-node-triple")]
     pub(crate) lib_node_triple: bool,
+
+    #[command(flatten)]
+    pub(crate) watch: WatchArgs,
 }
 
 impl BindingsArgs {
-    fn resolve_lib_resolution(&self) -> Result<LibResolution> {
+    /// The project config, read only when a flag is left for it to supply.
+    fn project_config(&self) -> Result<Option<ProjectConfig>> {
+        if self.ts_dir.is_some() && self.has_lib_flag() {
+            return Ok(None);
+        }
+        let path = ConfigArgs::new(self.project_config.clone()).path()?;
+        Ok(Some(ProjectConfig::try_from(path)?))
+    }
+
+    fn has_lib_flag(&self) -> bool {
+        self.lib_colocated || self.lib_absolute || self.lib_package_base.is_some()
+    }
+
+    /// The `--lib-*` flag given, or else the `napi` section of the project
+    /// config, with the project's name.
+    fn resolve_lib_resolution(&self, config: Option<(&NapiConfig, &str)>) -> Result<LibResolution> {
         if self.lib_node_triple && self.lib_package_base.is_none() {
             anyhow::bail!("--lib-node-triple requires --lib-package-base");
         }
@@ -104,14 +142,7 @@ impl BindingsArgs {
             return Ok(LibResolution::Colocated);
         }
         if self.lib_absolute {
-            // SourceArgs.source carries --library's path when --library is set.
-            let path = self.source.source();
-            if !path.is_absolute() {
-                anyhow::bail!(
-                    "--lib-absolute requires --library to be an absolute path; got: {}",
-                    path,
-                );
-            }
+            let path = self.absolute_library("--lib-absolute")?;
             // Normalize backslashes to forward slashes so the rendered TS string
             // is valid on Windows (Node accepts forward slashes on all platforms).
             // Explicit replace, not path-slash, since path-slash's behavior is
@@ -132,8 +163,37 @@ impl BindingsArgs {
             };
             return Ok(LibResolution::Require { base, triple_style });
         }
-        // clap's ArgGroup(required = true) on lib_resolution rejects this case at parse.
-        unreachable!("clap should have rejected: no --lib-* flag passed")
+        let Some((napi, project_name)) = config else {
+            anyhow::bail!("no --lib-* flag passed, and no project config to fall back to");
+        };
+        // Like the flags, the config's strategies besides `colocated` name the
+        // library, so need --library.
+        let path = match napi.lib_resolution {
+            LibResolutionStrategy::Colocated => return Ok(LibResolution::Colocated),
+            LibResolutionStrategy::Absolute => {
+                self.absolute_library("napi.libResolution: absolute")?
+            }
+            LibResolutionStrategy::Packages => self.library("napi.libResolution: packages")?,
+        };
+        Ok(napi.lib_resolution(project_name, path))
+    }
+
+    /// The path given with --library, which `what` needs.
+    fn library(&self, what: &str) -> Result<&Utf8PathBuf> {
+        if !self.source.is_library() {
+            anyhow::bail!("{what} requires --library");
+        }
+        // SourceArgs.source carries --library's path when --library is set.
+        Ok(self.source.source())
+    }
+
+    /// The path given with --library, which `what` needs to be absolute.
+    fn absolute_library(&self, what: &str) -> Result<&Utf8PathBuf> {
+        let path = self.library(what)?;
+        if !path.is_absolute() {
+            anyhow::bail!("{what} requires --library to be an absolute path; got: {path}");
+        }
+        Ok(path)
     }
 }
 
@@ -142,22 +202,32 @@ impl BindingsArgs {
 /// If the last character is alphanumeric (ASCII), append `-` so the runtime
 /// produces `BASE-<triple>`. Otherwise leave the value untouched and let the
 /// trailing punctuation (`/`, `_`, `-`, …) act as the separator.
-fn normalize_package_base(base: &str) -> String {
+pub(super) fn normalize_package_base(base: &str) -> String {
     match base.chars().next_back() {
         Some(c) if c.is_ascii_alphanumeric() => format!("{base}-"),
         _ => base.to_string(),
     }
 }
 
-impl From<&BindingsArgs> for ubrn_bindgen::BindingsArgs {
-    fn from(value: &BindingsArgs) -> Self {
-        // Napi doesn't generate C++, so we pass ts_dir as a dummy for cpp_dir.
+impl BindingsArgs {
+    /// The bindgen args, with `--ts-dir` or else the project's `napi.tsBindings`.
+    ///
+    /// `project` is read whenever `ts_dir` is `None`; see [`Self::project_config`].
+    fn to_bindgen_args(
+        &self,
+        flavor: AbiFlavor,
+        project: Option<&ProjectConfig>,
+    ) -> ubrn_bindgen::BindingsArgs {
+        let ts_dir = match (&self.ts_dir, project) {
+            (Some(ts_dir), _) => ts_dir.clone(),
+            (None, Some(project)) => project.napi_bindings_ts_path(project.project_root()),
+            (None, None) => unreachable!("project config is read when --ts-dir is missing"),
+        };
+        // Neither flavor generates C++, so we pass ts_dir as a dummy for cpp_dir.
         ubrn_bindgen::BindingsArgs::new(
-            SwitchArgs {
-                flavor: AbiFlavor::Napi,
-            },
-            value.source.clone(),
-            OutputArgs::new(&value.ts_dir, &value.ts_dir, value.no_format),
+            SwitchArgs { flavor },
+            self.source.clone(),
+            OutputArgs::new(&ts_dir, &ts_dir, self.no_format),
         )
     }
 }
@@ -214,7 +284,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        assert!(b.resolve_lib_resolution().is_err());
+        assert!(b.resolve_lib_resolution(None).is_err());
     }
 
     #[test]
@@ -225,11 +295,82 @@ mod tests {
     }
 
     #[test]
-    fn neither_flag_errors() {
-        // ArgGroup is required; missing both --lib-colocated and --lib-absolute
-        // must fail at parse time.
-        let r = parse(&["--ts-dir", "/tmp/ts", "--library", "/tmp/foo.dylib"]);
-        assert!(r.is_err());
+    fn neither_flag_needs_the_project_config() {
+        // No --lib-* flag parses: the project config supplies one.
+        let cli = parse(&["--ts-dir", "/tmp/ts", "--library", "/tmp/foo.dylib"])
+            .expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+        assert!(b.resolve_lib_resolution(None).is_err());
+    }
+
+    #[test]
+    fn neither_flag_nor_ts_dir_parses() {
+        let cli = parse(&["--library", "/tmp/foo.dylib"]).expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+        assert_eq!(b.ts_dir, None);
+    }
+
+    fn napi_config(json: &str) -> NapiConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn config_supplies_the_lib_resolution() {
+        let cli = parse(&["--library", "/tmp/foo.dylib"]).expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+
+        let colocated = napi_config("{}");
+        let res = b.resolve_lib_resolution(Some((&colocated, "foo")));
+        assert!(matches!(res, Ok(LibResolution::Colocated)), "got: {res:?}");
+
+        let absolute = napi_config(r#"{ "libResolution": "absolute" }"#);
+        match b
+            .resolve_lib_resolution(Some((&absolute, "foo")))
+            .expect("resolve")
+        {
+            LibResolution::Absolute(path) => assert_eq!(path, "/tmp/foo.dylib"),
+            other => panic!("expected Absolute, got {other:?}"),
+        }
+
+        let packages = napi_config(
+            r#"{ "libResolution": "packages", "packages": { "tripleStyle": "node" } }"#,
+        );
+        match b
+            .resolve_lib_resolution(Some((&packages, "foo")))
+            .expect("resolve")
+        {
+            LibResolution::Require { base, triple_style } => {
+                assert_eq!(base, "foo-");
+                assert_eq!(triple_style, TripleStyle::Node);
+            }
+            other => panic!("expected Require, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn flags_override_the_config() {
+        let cli =
+            parse(&["--lib-colocated", "--library", "/tmp/foo.dylib"]).expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+        let packages = napi_config(r#"{ "libResolution": "packages" }"#);
+        let res = b.resolve_lib_resolution(Some((&packages, "foo")));
+        assert!(matches!(res, Ok(LibResolution::Colocated)), "got: {res:?}");
+    }
+
+    #[test]
+    fn config_absolute_needs_an_absolute_library() {
+        let absolute = napi_config(r#"{ "libResolution": "absolute" }"#);
+
+        let cli = parse(&["--library", "rel/foo.dylib"]).expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+        assert!(b.resolve_lib_resolution(Some((&absolute, "foo"))).is_err());
+
+        let cli = parse(&["/tmp/foo.udl"]).expect("clap should accept");
+        let Cmd::Bindings(b) = cli.cmd;
+        let err = b
+            .resolve_lib_resolution(Some((&absolute, "foo")))
+            .expect_err("should need --library");
+        assert!(err.to_string().contains("--library"), "got: {err}");
     }
 
     #[test]
@@ -257,7 +398,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        let res = b.resolve_lib_resolution().expect("resolve");
+        let res = b.resolve_lib_resolution(None).expect("resolve");
         match res {
             LibResolution::Require { base, triple_style } => {
                 assert_eq!(base, "@scope/foo-");
@@ -280,7 +421,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        let res = b.resolve_lib_resolution().expect("resolve");
+        let res = b.resolve_lib_resolution(None).expect("resolve");
         match res {
             LibResolution::Require { base, triple_style } => {
                 assert_eq!(base, "@scope/foo-");
@@ -304,7 +445,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        let err = b.resolve_lib_resolution().expect_err("should reject");
+        let err = b.resolve_lib_resolution(None).expect_err("should reject");
         assert!(err.to_string().contains("--lib-node-triple"), "got: {err}");
     }
 
@@ -321,7 +462,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        match b.resolve_lib_resolution().expect("resolve") {
+        match b.resolve_lib_resolution(None).expect("resolve") {
             LibResolution::Require { base, .. } => assert_eq!(base, "@scope/foo/"),
             other => panic!("expected Require, got {other:?}"),
         }
@@ -340,7 +481,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        match b.resolve_lib_resolution().expect("resolve") {
+        match b.resolve_lib_resolution(None).expect("resolve") {
             LibResolution::Require { base, .. } => assert_eq!(base, "@scope/foo-"),
             other => panic!("expected Require, got {other:?}"),
         }
@@ -399,7 +540,7 @@ mod tests {
         ])
         .expect("clap should accept");
         let Cmd::Bindings(b) = cli.cmd;
-        assert!(b.resolve_lib_resolution().is_err());
+        assert!(b.resolve_lib_resolution(None).is_err());
     }
 
     #[test]
@@ -3,6 +3,13 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
+mod bindings;
+mod commands;
+mod config;
 mod generate;
+mod target;
 
+pub(crate) use bindings::bindings;
+pub(crate) use commands::{NapiBuildArgs, NapiPackageArgs};
+pub(crate) use config::NapiConfig;
 pub(crate) use generate::CmdArg;
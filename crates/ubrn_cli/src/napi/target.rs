@@ -0,0 +1,193 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Error, Result};
+use serde::Deserialize;
+use ubrn_bindgen::ffi_module_player_lib_resolution::TripleStyle;
+
+/// The platforms the napi runtime can detect at load time; see
+/// `resolve-lib.ts` in `@ubjs/node`.
+#[derive(Debug, Deserialize, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Target {
+    #[serde(rename = "aarch64-apple-darwin", alias = "darwin-arm64")]
+    Aarch64AppleDarwin,
+    #[serde(rename = "x86_64-apple-darwin", alias = "darwin-x64")]
+    X86_64AppleDarwin,
+    #[serde(rename = "aarch64-unknown-linux-gnu", alias = "linux-arm64-gnu")]
+    Aarch64UnknownLinuxGnu,
+    #[serde(rename = "x86_64-unknown-linux-gnu", alias = "linux-x64-gnu")]
+    X86_64UnknownLinuxGnu,
+    #[serde(rename = "aarch64-unknown-linux-musl", alias = "linux-arm64-musl")]
+    Aarch64UnknownLinuxMusl,
+    #[serde(rename = "x86_64-unknown-linux-musl", alias = "linux-x64-musl")]
+    X86_64UnknownLinuxMusl,
+    #[serde(rename = "aarch64-pc-windows-msvc", alias = "win32-arm64-msvc")]
+    Aarch64PcWindowsMsvc,
+    #[serde(rename = "x86_64-pc-windows-msvc", alias = "win32-x64-msvc")]
+    X86_64PcWindowsMsvc,
+}
+
+impl FromStr for Target {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|t| t.triple() == s || t.node_triple() == s)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("Unsupported target: '{s}'"))
+    }
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.triple())
+    }
+}
+
+impl Target {
+    pub(crate) fn all() -> &'static [Target] {
+        &[
+            Self::Aarch64AppleDarwin,
+            Self::X86_64AppleDarwin,
+            Self::Aarch64UnknownLinuxGnu,
+            Self::X86_64UnknownLinuxGnu,
+            Self::Aarch64UnknownLinuxMusl,
+            Self::X86_64UnknownLinuxMusl,
+            Self::Aarch64PcWindowsMsvc,
+            Self::X86_64PcWindowsMsvc,
+        ]
+    }
+
+    /// Of `targets`, the one whose library node can load on this machine, or
+    /// the first if none can.
+    pub(crate) fn local(targets: &[Target]) -> Option<Self> {
+        Self::host()
+            .filter(|host| targets.contains(host))
+            .or(targets.first().copied())
+    }
+
+    /// The target this copy of ubrn was built for, if node can load it.
+    pub(crate) fn host() -> Option<Self> {
+        let arm = cfg!(target_arch = "aarch64");
+        if !arm && !cfg!(target_arch = "x86_64") {
+            return None;
+        }
+        Some(match (arm, std::env::consts::OS) {
+            (true, "macos") => Self::Aarch64AppleDarwin,
+            (false, "macos") => Self::X86_64AppleDarwin,
+            (true, "linux") if cfg!(target_env = "musl") => Self::Aarch64UnknownLinuxMusl,
+            (false, "linux") if cfg!(target_env = "musl") => Self::X86_64UnknownLinuxMusl,
+            (true, "linux") => Self::Aarch64UnknownLinuxGnu,
+            (false, "linux") => Self::X86_64UnknownLinuxGnu,
+            (true, "windows") => Self::Aarch64PcWindowsMsvc,
+            (false, "windows") => Self::X86_64PcWindowsMsvc,
+            _ => return None,
+        })
+    }
+
+    pub fn triple(&self) -> &'static str {
+        match self {
+            Self::Aarch64AppleDarwin => "aarch64-apple-darwin",
+            Self::X86_64AppleDarwin => "x86_64-apple-darwin",
+            Self::Aarch64UnknownLinuxGnu => "aarch64-unknown-linux-gnu",
+            Self::X86_64UnknownLinuxGnu => "x86_64-unknown-linux-gnu",
+            Self::Aarch64UnknownLinuxMusl => "aarch64-unknown-linux-musl",
+            Self::X86_64UnknownLinuxMusl => "x86_64-unknown-linux-musl",
+            Self::Aarch64PcWindowsMsvc => "aarch64-pc-windows-msvc",
+            Self::X86_64PcWindowsMsvc => "x86_64-pc-windows-msvc",
+        }
+    }
+
+    /// The napi-rs spelling, which is what `TripleStyle::Node` detects.
+    pub fn node_triple(&self) -> &'static str {
+        match self {
+            Self::Aarch64AppleDarwin => "darwin-arm64",
+            Self::X86_64AppleDarwin => "darwin-x64",
+            Self::Aarch64UnknownLinuxGnu => "linux-arm64-gnu",
+            Self::X86_64UnknownLinuxGnu => "linux-x64-gnu",
+            Self::Aarch64UnknownLinuxMusl => "linux-arm64-musl",
+            Self::X86_64UnknownLinuxMusl => "linux-x64-musl",
+            Self::Aarch64PcWindowsMsvc => "win32-arm64-msvc",
+            Self::X86_64PcWindowsMsvc => "win32-x64-msvc",
+        }
+    }
+
+    pub fn styled_triple(&self, style: TripleStyle) -> &'static str {
+        match style {
+            TripleStyle::Cargo => self.triple(),
+            TripleStyle::Node => self.node_triple(),
+        }
+    }
+
+    /// `process.platform`, for the `os` field of `package.json`.
+    pub fn os(&self) -> &'static str {
+        match self {
+            Self::Aarch64AppleDarwin | Self::X86_64AppleDarwin => "darwin",
+            Self::Aarch64PcWindowsMsvc | Self::X86_64PcWindowsMsvc => "win32",
+            _ => "linux",
+        }
+    }
+
+    /// `process.arch`, for the `cpu` field of `package.json`.
+    pub fn cpu(&self) -> &'static str {
+        match self {
+            Self::Aarch64AppleDarwin
+            | Self::Aarch64UnknownLinuxGnu
+            | Self::Aarch64UnknownLinuxMusl
+            | Self::Aarch64PcWindowsMsvc => "arm64",
+            _ => "x64",
+        }
+    }
+
+    /// The `libc` field of `package.json`, which npm only checks on linux.
+    pub fn libc(&self) -> Option<&'static str> {
+        match self {
+            Self::Aarch64UnknownLinuxGnu | Self::X86_64UnknownLinuxGnu => Some("glibc"),
+            Self::Aarch64UnknownLinuxMusl | Self::X86_64UnknownLinuxMusl => Some("musl"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_target_parses_from_either_spelling() {
+        for t in Target::all() {
+            assert_eq!(t.triple().parse::<Target>().unwrap(), *t);
+            assert_eq!(t.node_triple().parse::<Target>().unwrap(), *t);
+        }
+        assert!("wasm32-unknown-unknown".parse::<Target>().is_err());
+    }
+
+    #[test]
+    fn package_json_fields_match_node() {
+        let t = Target::Aarch64UnknownLinuxMusl;
+        assert_eq!(
+            (t.os(), t.cpu(), t.libc()),
+            ("linux", "arm64", Some("musl"))
+        );
+        let t = Target::X86_64AppleDarwin;
+        assert_eq!((t.os(), t.cpu(), t.libc()), ("darwin", "x64", None));
+        let t = Target::X86_64PcWindowsMsvc;
+        assert_eq!((t.os(), t.cpu(), t.libc()), ("win32", "x64", None));
+    }
+
+    #[test]
+    fn local_target_prefers_the_host() {
+        assert_eq!(Target::local(&[]), None);
+        let mut targets = vec![Target::Aarch64PcWindowsMsvc];
+        assert_eq!(Target::local(&targets), Some(targets[0]));
+        if let Some(host) = Target::host() {
+            targets.push(host);
+            assert_eq!(Target::local(&targets), Some(host));
+        }
+    }
+}
@@ -10,7 +10,7 @@ use ubrn_bindgen::{ModuleMetadata, OutputArgs, SourceArgs, SwitchArgs};
 
 use crate::{
     codegen::{get_template_config, render_files},
-    commands::ConfigArgs,
+    commands::{ConfigArgs, WatchArgs},
     wasm, ProjectConfig,
 };
 
@@ -37,11 +37,7 @@ enum Cmd {
 impl Cmd {
     fn run(&self) -> Result<()> {
         match self {
-            Self::Bindings(b) => {
-                let b = ubrn_bindgen::BindingsArgs::from(b);
-                b.run(None)?;
-                Ok(())
-            }
+            Self::Bindings(b) => b.watch.run(ubrn_bindgen::BindingsArgs::from(b)),
             Self::WasmCrate(c) => {
                 c.run()?;
                 Ok(())
@@ -56,6 +52,8 @@ struct BindingsArgs {
     pub(crate) source: SourceArgs,
     #[command(flatten)]
     pub(crate) output: OutputArgs,
+    #[command(flatten)]
+    pub(crate) watch: WatchArgs,
 }
 
 impl From<&BindingsArgs> for ubrn_bindgen::BindingsArgs {
@@ -138,7 +138,7 @@ impl Wasm2BuildArgs {
 
 /// Reject a crate the player cannot load, before `cargo build` fails less
 /// legibly — or worse, succeeds and defers the failure to the first call.
-fn check_wasm_ready(crate_: &CrateMetadata) -> Result<()> {
+pub(crate) fn check_wasm_ready(crate_: &CrateMetadata) -> Result<()> {
     let manifest_path = crate_.manifest_path();
     if !crate_.builds_cdylib() {
         bail!(
@@ -22,7 +22,6 @@ pub(crate) struct Wasm2Config {
     #[serde(default)]
     pub(crate) default_features: Option<bool>,
 
-    #[allow(unused)]
     #[serde(default = "Wasm2Config::default_targets")]
     pub(crate) targets: Vec<Target>,
 
@@ -11,7 +11,7 @@ use ubrn_bindgen::{ModuleMetadata, OutputArgs, SourceArgs, SwitchArgs};
 
 use crate::{
     codegen::{get_template_config, render_files},
-    commands::ConfigArgs,
+    commands::{ConfigArgs, WatchArgs},
     wasm2, ProjectConfig,
 };
 
@@ -38,11 +38,7 @@ enum Cmd {
 impl Cmd {
     fn run(&self) -> Result<()> {
         match self {
-            Self::Bindings(b) => {
-                let b = ubrn_bindgen::BindingsArgs::from(b);
-                b.run(None)?;
-                Ok(())
-            }
+            Self::Bindings(b) => b.watch.run(ubrn_bindgen::BindingsArgs::from(b)),
             Self::WasmCrate(c) => {
                 c.run()?;
                 Ok(())
@@ -66,6 +62,9 @@ struct BindingsArgs {
     /// The directory in which to put the generated Typescript.
     #[clap(long)]
     pub(crate) ts_dir: Utf8PathBuf,
+
+    #[command(flatten)]
+    pub(crate) watch: WatchArgs,
 }
 
 impl From<&BindingsArgs> for ubrn_bindgen::BindingsArgs {
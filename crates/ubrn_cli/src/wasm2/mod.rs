@@ -12,7 +12,7 @@ mod generate;
 pub(crate) use bindings::bindings;
 pub(crate) use codegen::get_files;
 #[allow(unused_imports)]
-pub(crate) use commands::Wasm2BuildArgs;
+pub(crate) use commands::{check_wasm_ready, Wasm2BuildArgs};
 #[allow(unused_imports)]
 pub(crate) use config::Wasm2Config;
 pub(crate) use generate::CmdArg;
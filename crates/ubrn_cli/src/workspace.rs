@@ -17,10 +17,14 @@ fn find_file_in_parents(file_suffix: &str) -> Result<Utf8PathBuf> {
 }
 
 pub(crate) fn package_json() -> PackageJson {
-    let file = find_file_in_parents("package.json").expect("Cannot find package.json");
+    let file = package_json_path().expect("Cannot find package.json");
     ubrn_common::read_from_file(file).expect("Cannot load package.json")
 }
 
+pub(crate) fn package_json_path() -> Result<Utf8PathBuf> {
+    find_file_in_parents("package.json")
+}
+
 pub(crate) fn project_root() -> Result<Utf8PathBuf> {
     let package_json = find_file_in_parents("package.json")?;
     let dir = package_json.parent().expect("Must be a directory");
@@ -0,0 +1,39 @@
+{%- set name = config.project.module_cpp() %}
+{%- set module_class_name = name|fmt("{}Module") -%}
+// Generated by uniffi-bindgen-react-native
+package {{ config.project.android.package_name() }}
+
+import com.facebook.react.turbomodule.core.interfaces.CallInvokerHolder
+import expo.modules.kotlin.modules.Module
+import expo.modules.kotlin.modules.ModuleDefinition
+
+class {{ module_class_name }} : Module() {
+  override fun definition() = ModuleDefinition {
+    Name("{{ config.project.spec_name() }}")
+
+    Function("installRustCrate") {
+      val context = appContext.reactContext ?: return@Function false
+      nativeInstallRustCrate(
+        context.javaScriptContextHolder!!.get(),
+        context.jsCallInvokerHolder!!
+      )
+    }
+
+    Function("cleanupRustCrate") {
+      val context = appContext.reactContext ?: return@Function false
+      nativeCleanupRustCrate(context.javaScriptContextHolder!!.get())
+    }
+  }
+
+  // Two native methods implemented in cpp-adapter.cpp, and ultimately
+  // {{ config.project.cpp_filename() }}.cpp
+
+  external fun nativeInstallRustCrate(runtimePointer: Long, callInvoker: CallInvokerHolder): Boolean
+  external fun nativeCleanupRustCrate(runtimePointer: Long): Boolean
+
+  companion object {
+    init {
+      System.loadLibrary("{{ config.project.cpp_filename() }}")
+    }
+  }
+}
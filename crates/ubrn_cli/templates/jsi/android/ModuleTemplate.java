@@ -1,6 +1,6 @@
-{%- let android = self.config.project.android.clone() %}
-{%- let name = self.config.project.module_cpp() %}
-{%- let module_class_name = name|fmt("{}Module") -%}
+{%- set android = config.project.android.clone() %}
+{%- set name = config.project.module_cpp() %}
+{%- set module_class_name = name|fmt("{}Module") -%}
 // Generated by uniffi-bindgen-react-native
 package {{ android.package_name() }};
 
@@ -11,7 +11,7 @@ import com.facebook.react.module.annotations.ReactModule;
 import com.facebook.react.turbomodule.core.interfaces.CallInvokerHolder;
 
 @ReactModule(name = {{ module_class_name }}.NAME)
-public class {{ module_class_name }} extends {{ self.config.project.codegen_filename() }}Spec {
+public class {{ module_class_name }} extends {{ config.project.codegen_filename() }}Spec {
   public static final String NAME = "{{ name }}";
 
   public {{ module_class_name }}(ReactApplicationContext reactContext) {
@@ -25,7 +25,7 @@ public class {{ module_class_name }} extends {{ self.config.project.codegen_file
   }
 
   static {
-    System.loadLibrary("{{ self.config.project.cpp_filename() }}");
+    System.loadLibrary("{{ config.project.cpp_filename() }}");
   }
 
   private static native boolean nativeInstallRustCrate(long rtPtr, CallInvokerHolder callInvoker);
@@ -1,6 +1,6 @@
-{%- let android = self.config.project.android.clone() %}
-{%- let name = self.config.project.module_cpp() %}
-{%- let module_class_name = name|fmt("{}Module") -%}
+{%- set android = config.project.android.clone() %}
+{%- set name = config.project.module_cpp() %}
+{%- set module_class_name = name|fmt("{}Module") -%}
 // Generated by uniffi-bindgen-react-native
 package {{ android.package_name() }}
 
@@ -10,14 +10,14 @@ import com.facebook.react.turbomodule.core.interfaces.CallInvokerHolder
 
 @ReactModule(name = {{ module_class_name }}.NAME)
 class {{ module_class_name }}(reactContext: ReactApplicationContext) :
-  {{ self.config.project.codegen_filename() }}Spec(reactContext) {
+  {{ config.project.codegen_filename() }}Spec(reactContext) {
 
   override fun getName(): String {
     return NAME
   }
 
   // Two native methods implemented in cpp-adapter.cpp, and ultimately
-  // {{ self.config.project.cpp_filename() }}.cpp
+  // {{ config.project.cpp_filename() }}.cpp
 
   external fun nativeInstallRustCrate(runtimePointer: Long, callInvoker: CallInvokerHolder): Boolean
   external fun nativeCleanupRustCrate(runtimePointer: Long): Boolean
@@ -40,7 +40,7 @@ class {{ module_class_name }}(reactContext: ReactApplicationContext) :
     const val NAME = "{{ name }}"
 
     init {
-      System.loadLibrary("{{ self.config.project.cpp_filename() }}")
+      System.loadLibrary("{{ config.project.cpp_filename() }}")
     }
   }
 }
@@ -1,8 +1,8 @@
-{%- let name = self.config.project.module_cpp() %}
-{%- let package_class_name = name|fmt("{}Package") %}
-{%- let module_class_name = name|fmt("{}Module") -%}
+{%- set name = config.project.module_cpp() %}
+{%- set package_class_name = name|fmt("{}Package") %}
+{%- set module_class_name = name|fmt("{}Module") -%}
 // Generated by uniffi-bindgen-react-native
-package {{ self.config.project.android.package_name() }};
+package {{ config.project.android.package_name() }};
 
 import androidx.annotation.Nullable;
 
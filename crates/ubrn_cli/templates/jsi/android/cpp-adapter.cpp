@@ -2,12 +2,12 @@
 #include <jni.h>
 #include <jsi/jsi.h>
 #include <ReactCommon/CallInvokerHolder.h>
-#include "{{ self.config.project.cpp_filename() }}.h"
-{%- let package_name = self.config.project.android.package_name().replace(".", "_") %}
-{%- let name = self.config.project.module_cpp() %}
-{%- let module_class_name = name|fmt("{}Module") %}
-{%- let prefix = "Java_{}_{}"|format(package_name, module_class_name) %}
-{%- let ns = self.config.project.cpp_namespace() %}
+#include "{{ config.project.cpp_filename() }}.h"
+{%- set package_name = config.project.android.package_name().replace(".", "_") %}
+{%- set name = config.project.module_cpp() %}
+{%- set module_class_name = name|fmt("{}Module") %}
+{%- set prefix = "Java_{}_{}"|format(package_name, module_class_name) %}
+{%- set ns = config.project.cpp_namespace() %}
 
 namespace jsi = facebook::jsi;
 namespace react = facebook::react;
@@ -7,4 +7,4 @@ export interface Spec extends TurboModule {
   cleanupRustCrate(): boolean;
 }
 
-export default TurboModuleRegistry.getEnforcing<Spec>('{{ self.config.project.spec_name() }}');
+export default TurboModuleRegistry.getEnforcing<Spec>('{{ config.project.spec_name() }}');
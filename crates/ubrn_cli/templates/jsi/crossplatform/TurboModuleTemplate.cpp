@@ -1,18 +1,18 @@
 // Generated by uniffi-bindgen-react-native
-#include "{{ self.config.project.cpp_filename() }}.h"
-{%- let root = self.project_root() %}
-{%- let bindings = self.config.project.bindings.cpp_path(root) %}
-{%- for m in self.config.modules %}
-{%- let hpp_filename = bindings.join(m.hpp_filename()) %}
-{%- let hpp_filename = self.relative_to(root, hpp_filename) %}
+#include "{{ config.project.cpp_filename() }}.h"
+{%- set root = project_root() %}
+{%- set bindings = config.project.bindings.cpp_path(root) %}
+{%- for m in config.modules %}
+{%- set hpp_filename = bindings.join(m.hpp_filename()) %}
+{%- set hpp_filename = relative_to(root, hpp_filename) %}
 #include "{{ hpp_filename }}"
 {%- endfor %}
 
-namespace {{ self.config.project.cpp_namespace() }} {
+namespace {{ config.project.cpp_namespace() }} {
 	using namespace facebook;
 
 	uint8_t installRustCrate(jsi::Runtime &runtime, std::shared_ptr<react::CallInvoker> callInvoker) {
-        {%- for m in self.config.modules %}
+        {%- for m in config.modules %}
 		{{ m.cpp_module() }}::registerModule(runtime, callInvoker);
         {%- endfor %}
 		return true;
@@ -1,5 +1,5 @@
-{%- let ns = self.config.project.cpp_namespace() %}
-{%- let marker = ns|upper|fmt("{}_H") -%}
+{%- set ns = config.project.cpp_namespace() %}
+{%- set marker = ns|upper|fmt("{}_H") -%}
 #ifndef {{ marker }}
 #define {{ marker }}
 // Generated by uniffi-bindgen-react-native
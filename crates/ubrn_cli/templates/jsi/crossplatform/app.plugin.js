@@ -0,0 +1,61 @@
+// Generated by uniffi-bindgen-react-native
+const {
+  createRunOncePlugin,
+  withGradleProperties,
+  withPodfileProperties,
+} = require('expo/config-plugins');
+
+const pkg = require('./package.json');
+
+// The Rust library is built for this Android API level, so the app must
+// support nothing older.
+const MIN_SDK_VERSION = {{ config.project.android.api_level }};
+
+const withMinSdkVersion = (config) =>
+  withGradleProperties(config, (config) => {
+    const key = 'android.minSdkVersion';
+    const property = config.modResults.find(
+      (item) => item.type === 'property' && item.key === key
+    );
+    if (!property) {
+      config.modResults.push({
+        type: 'property',
+        key,
+        value: String(MIN_SDK_VERSION),
+      });
+    } else if (Number(property.value) < MIN_SDK_VERSION) {
+      property.value = String(MIN_SDK_VERSION);
+    }
+    return config;
+  });
+
+// Likewise, the Rust library is built for this iOS version.
+const DEPLOYMENT_TARGET = '{{ config.project.ios.deployment_target }}';
+
+// Compares dotted versions, e.g. '13.4' and '15.1', part by part.
+const isOlderThan = (version, than) => {
+  const a = String(version).split('.').map(Number);
+  const b = than.split('.').map(Number);
+  for (let i = 0; i < Math.max(a.length, b.length); i++) {
+    const diff = (a[i] || 0) - (b[i] || 0);
+    if (diff !== 0) {
+      return diff < 0;
+    }
+  }
+  return false;
+};
+
+const withDeploymentTarget = (config) =>
+  withPodfileProperties(config, (config) => {
+    const key = 'ios.deploymentTarget';
+    const value = config.modResults[key];
+    if (!value || isOlderThan(value, DEPLOYMENT_TARGET)) {
+      config.modResults[key] = DEPLOYMENT_TARGET;
+    }
+    return config;
+  });
+
+const withRustLibrary = (config) =>
+  withDeploymentTarget(withMinSdkVersion(config));
+
+module.exports = createRunOncePlugin(withRustLibrary, pkg.name, pkg.version);
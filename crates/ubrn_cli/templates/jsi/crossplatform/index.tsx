@@ -1,5 +1,5 @@
 // Generated by uniffi-bindgen-react-native
-import installer from './{{ self.config.project.codegen_filename() }}';
+import installer from './{{ config.project.codegen_filename() }}';
 
 // Register the rust crate with Hermes
 // - the boolean flag ensures this loads exactly once, even if the JS
@@ -11,17 +11,17 @@ if (!rustInstalled) {
 }
 
 // Export the generated bindings to the app.
-{%- let root = self.project_root() %}
-{%- let bindings = self.config.project.bindings.ts_path(root) %}
-{%- let bindings = self.relative_to(root, bindings) %}
-{%- for m in self.config.modules %}
+{%- set root = project_root() %}
+{%- set bindings = config.project.bindings.ts_path(root) %}
+{%- set bindings = relative_to(root, bindings) %}
+{%- for m in config.modules %}
 export * from './{{ bindings }}/{{ m.ts() }}';
 {%- endfor %}
 
 // Now import the bindings so we can:
 // - intialize them
 // - export them as namespaced objects as the default export.
-{%- for m in self.config.modules %}
+{%- for m in config.modules %}
 import * as {{ m.ts() }} from './{{ bindings }}/{{ m.ts() }}';
 {%- endfor %}
 
@@ -30,7 +30,7 @@ import * as {{ m.ts() }} from './{{ bindings }}/{{ m.ts() }}';
 //   is reloaded (e.g. during development with metro).
 let initialized = false;
 if (!initialized) {
-  {%- for m in self.config.modules %}
+  {%- for m in config.modules %}
   {{ m.ts() }}.default.initialize();
   {%- endfor %}
   initialized = true;
@@ -45,7 +45,7 @@ export async function uniffiInitAsync() {
 
 // Export the crates as individually namespaced objects.
 export default {
-{%- for m in self.config.modules %}
+{%- for m in config.modules %}
   {{ m.ts() }},
 {%- endfor %}
 };
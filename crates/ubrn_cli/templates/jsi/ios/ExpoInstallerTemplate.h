@@ -0,0 +1,14 @@
+{%- set installer = config.project.module_cpp()|fmt("{}Installer") -%}
+// Generated by uniffi-bindgen-react-native
+#import <Foundation/Foundation.h>
+
+@class EXJavaScriptRuntime;
+
+// Plain Objective-C, so the Swift module can call into the C++ installer
+// in {{ config.project.cpp_filename() }}.cpp.
+@interface {{ installer }} : NSObject
+
++ (BOOL)installRustCrate:(nonnull EXJavaScriptRuntime *)runtime;
++ (BOOL)cleanupRustCrate:(nonnull EXJavaScriptRuntime *)runtime;
+
+@end
@@ -0,0 +1,18 @@
+{%- set installer = config.project.module_cpp()|fmt("{}Installer") %}
+{%- set ns = config.project.cpp_namespace() -%}
+// Generated by uniffi-bindgen-react-native
+#import "{{ installer }}.h"
+#import <ExpoModulesCore/EXJavaScriptRuntime.h>
+#import "{{ config.project.cpp_filename() }}.h"
+
+@implementation {{ installer }}
+
++ (BOOL)installRustCrate:(nonnull EXJavaScriptRuntime *)runtime {
+    return {{ ns }}::installRustCrate(*[runtime get], [runtime callInvoker]);
+}
+
++ (BOOL)cleanupRustCrate:(nonnull EXJavaScriptRuntime *)runtime {
+    return {{ ns }}::cleanupRustCrate(*[runtime get]);
+}
+
+@end
@@ -0,0 +1,24 @@
+{%- set name = config.project.module_cpp() %}
+{%- set installer = name|fmt("{}Installer") -%}
+// Generated by uniffi-bindgen-react-native
+import ExpoModulesCore
+
+public class {{ name }}Module: Module {
+  public func definition() -> ModuleDefinition {
+    Name("{{ config.project.spec_name() }}")
+
+    Function("installRustCrate") { () -> Bool in
+      guard let runtime = try appContext?.runtime else {
+        return false
+      }
+      return {{ installer }}.installRustCrate(runtime)
+    }
+
+    Function("cleanupRustCrate") { () -> Bool in
+      guard let runtime = try appContext?.runtime else {
+        return false
+      }
+      return {{ installer }}.cleanupRustCrate(runtime)
+    }
+  }
+}
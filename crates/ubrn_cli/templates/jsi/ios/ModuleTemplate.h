@@ -1,16 +1,16 @@
 // Generated by uniffi-bindgen-react-native
 #ifdef __cplusplus
-#import "{{ self.config.project.cpp_filename() }}.h"
+#import "{{ config.project.cpp_filename() }}.h"
 #endif
 
 #ifdef RCT_NEW_ARCH_ENABLED
-#import "{{ self.config.project.tm.name() }}.h"
+#import "{{ config.project.tm.name() }}.h"
 
-@interface {{ self.config.project.module_cpp() }} : NSObject <{{ self.config.project.codegen_filename() }}Spec>
+@interface {{ config.project.module_cpp() }} : NSObject <{{ config.project.codegen_filename() }}Spec>
 #else
 #import <React/RCTBridgeModule.h>
 
-@interface {{ self.config.project.module_cpp() }} : NSObject <RCTBridgeModule>
+@interface {{ config.project.module_cpp() }} : NSObject <RCTBridgeModule>
 #endif
 
 @end
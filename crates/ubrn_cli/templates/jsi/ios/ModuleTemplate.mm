@@ -1,15 +1,15 @@
-{%- let module_name = self.config.project.module_cpp() %}
-{%- let spec_jsi = self.config.project.codegen_filename()|fmt("{}SpecJSI") %}
-{%- let ns = self.config.project.cpp_namespace() %}
-{%- let uniffi_ns = "uniffi_generated" %}
-{%- let fn_prefix = "__hostFunction_{}"|format(module_name) -%}
+{%- set module_name = config.project.module_cpp() %}
+{%- set spec_jsi = config.project.codegen_filename()|fmt("{}SpecJSI") %}
+{%- set ns = config.project.cpp_namespace() %}
+{%- set uniffi_ns = "uniffi_generated" %}
+{%- set fn_prefix = "__hostFunction_{}"|format(module_name) -%}
 // Generated by uniffi-bindgen-react-native
 #import "{{ module_name }}.h"
 
 namespace {{ uniffi_ns }} {
     using namespace facebook::react;
     /**
-    * ObjC++ class for module '{{ self.config.project.codegen_filename() }}'
+    * ObjC++ class for module '{{ config.project.codegen_filename() }}'
     */
     class JSI_EXPORT {{ spec_jsi }} : public ObjCTurboModule {
     public:
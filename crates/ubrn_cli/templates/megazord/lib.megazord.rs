@@ -0,0 +1,7 @@
+// Generated by uniffi-bindgen-react-native
+//
+// Each crate is linked into this one library, with its scaffolding.
+{%- for c in crates %}
+pub use {{ c.name }};
+{%- endfor %}
+{# space #}
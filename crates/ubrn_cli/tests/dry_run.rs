@@ -0,0 +1,82 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::{collections::BTreeMap, env, fs};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+
+// A dry run stages what it generates on disk, which recording the files
+// written would skip, so this runs in a real project directory.
+#[test]
+fn test_dry_run_fails_only_if_something_would_change() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let lib_file = target_crate.library_path(None, "debug", Some(true));
+
+    let project = Utf8PathBuf::try_from(env::temp_dir())?
+        .join(format!("ubrn-dry-run-cli-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(&project)?;
+    fs::copy(
+        fixtures_dir().join("defaults/package.json"),
+        project.join("package.json"),
+    )?;
+    fs::write(
+        project.join("ubrn.config.yaml"),
+        format!(
+            "rust:\n  directory: {}\n  manifestPath: Cargo.toml\n\
+             napi:\n  tsBindings: node-src/generated\n  libResolution: packages\n",
+            target_crate.crate_dir()
+        ),
+    )?;
+
+    let current_dir = env::current_dir()?;
+    env::set_current_dir(&project)?;
+    let result = [
+        format!("ubrn generate all --config ubrn.config.yaml {lib_file}"),
+        "ubrn build napi --and-generate --config ubrn.config.yaml --targets linux-x64-gnu"
+            .to_string(),
+    ]
+    .iter()
+    .try_for_each(|command| check_dry_run(&project, command));
+    env::set_current_dir(current_dir)?;
+    fs::remove_dir_all(&project)?;
+    result
+}
+
+fn check_dry_run(project: &Utf8Path, command: &str) -> Result<()> {
+    let dry_run = format!("{command} --dry-run");
+
+    // Nothing has been generated yet, so it would all change.
+    let before = files_in(project)?;
+    assert!(run_cli(&dry_run).is_err(), "{dry_run} should fail");
+    assert_eq!(before, files_in(project)?, "{dry_run} changed the project");
+
+    run_cli(command)?;
+    let after = files_in(project)?;
+    assert_ne!(before, after, "{command} generated nothing");
+
+    // Generating again would change nothing.
+    run_cli(&dry_run)?;
+    assert_eq!(after, files_in(project)?, "{dry_run} changed the project");
+    Ok(())
+}
+
+/// Every file in the directory, with what's in it.
+fn files_in(dir: &Utf8Path) -> Result<BTreeMap<Utf8PathBuf, Vec<u8>>> {
+    let mut files = BTreeMap::new();
+    for entry in dir.read_dir_utf8()? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            files.extend(files_in(path)?);
+        } else {
+            files.insert(path.to_owned(), fs::read(path)?);
+        }
+    }
+    Ok(files)
+}
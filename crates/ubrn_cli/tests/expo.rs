@@ -0,0 +1,63 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, root_dir, run_cli};
+use ubrn_cli_testing::{assert_files, files_match, shim_path, with_fixture, File};
+
+#[test]
+fn test_expo_module() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    let snapshots = root_dir().join("tests/snapshots/expo");
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-expo.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+
+        // Run the command under test
+        run_cli("ubrn generate jsi turbo-module --config ubrn.config.yaml arithmetic")?;
+
+        let snapshot = |path: &str| {
+            let name = path.rsplit('/').next().unwrap();
+            File::new(path).matches_snapshot(snapshots.join(name))
+        };
+        assert_files(&[
+            snapshot("src/NativeDefaultFixture.ts"),
+            snapshot("expo-module.config.json"),
+            snapshot("app.plugin.js"),
+            snapshot("android/src/main/java/com/defaultfixture/DefaultFixtureModule.kt"),
+            snapshot("android/build.gradle"),
+            snapshot("ios/DefaultFixtureModule.swift"),
+            snapshot("ios/DefaultFixtureInstaller.h"),
+            snapshot("ios/DefaultFixtureInstaller.mm"),
+            // The version changes with each release, so isn't snapshotted.
+            File::new("DefaultFixture.podspec")
+                .contains("s.dependency \"ExpoModulesCore\"")
+                .contains("s.public_header_files = \"ios/DefaultFixtureInstaller.h\""),
+            // The C++ is shared with the turbo-module.
+            File::new("cpp/default-fixture.cpp").contains("NativeArithmetic::registerModule"),
+            File::new("android/cpp-adapter.cpp")
+                .contains("Java_com_defaultfixture_DefaultFixtureModule_nativeInstallRustCrate"),
+        ]);
+
+        // The turbo-module's own files are left out.
+        for path in [
+            "DefaultFixturePackage.kt",
+            "ios/DefaultFixture.h",
+            "ios/DefaultFixture.mm",
+        ] {
+            assert!(!files_match(&[File::new(path)]), "{path}");
+        }
+
+        Ok(())
+    })
+}
@@ -34,7 +34,8 @@ fn test_happy_path_ios() -> Result<()> {
             Command::new("cargo")
                 .arg("build")
                 .arg_pair_suffix("--manifest-path", "arithmetic/Cargo.toml")
-                .arg_pair("--target", "aarch64-apple-ios"),
+                .arg_pair("--target", "aarch64-apple-ios")
+                .env("IPHONEOS_DEPLOYMENT_TARGET", "13.4"),
             Command::new("cargo")
                 .arg("build")
                 .arg_pair_suffix("--manifest-path", "arithmetic/Cargo.toml")
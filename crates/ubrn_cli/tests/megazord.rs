@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+use ubrn_cli_testing::{assert_files, shim_path, with_fixture, File};
+
+#[test]
+fn test_megazord_of_crates() -> Result<()> {
+    let arithmetic = cargo_build("arithmetic")?;
+    let procmacro = cargo_build("arithmetic-procmacro")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-megazord.config.yaml"),
+        );
+        shim_path("rust/arithmetic/Cargo.toml", arithmetic.manifest_path());
+        shim_path(
+            "rust/arithmetic-procmacro/Cargo.toml",
+            procmacro.manifest_path(),
+        );
+        // The megazord crate is only recorded, so cargo is pointed at one of
+        // its crates in its place. Building it is tested in
+        // megazord_build.rs.
+        shim_path(
+            "rust_modules/megazord/Cargo.toml",
+            arithmetic.manifest_path(),
+        );
+
+        // Run the command under test
+        run_cli("ubrn generate jsi turbo-module --config ubrn.config.yaml")?;
+
+        assert_files(&[
+            File::new("rust_modules/megazord/Cargo.toml")
+                .contains("name = \"default-fixture-megazord\"")
+                .contains("crate-type = [\"lib\", \"staticlib\", \"cdylib\"]")
+                .contains("arithmetical = { package = \"uniffi-example-arithmetic\", path = \"")
+                .contains("examples/arithmetic\" }")
+                .contains(
+                    "arithmeticpm = { package = \"uniffi-example-arithmetic-procmacro\", path = \"",
+                )
+                .contains("examples/arithmetic-procmacro\" }"),
+            File::new("rust_modules/megazord/src/lib.rs")
+                .contains("pub use arithmetical;\npub use arithmeticpm;\n"),
+        ]);
+
+        Ok(())
+    })
+}
@@ -0,0 +1,83 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use std::{env, fs};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+
+// The megazord is written, built and generated from for real, so this runs in
+// a real project directory rather than recording the files written.
+#[test]
+fn test_building_a_megazord() -> Result<()> {
+    let arithmetic = cargo_build("arithmetic")?;
+    let procmacro = cargo_build("arithmetic-procmacro")?;
+
+    let project = Utf8PathBuf::try_from(env::temp_dir())?
+        .join(format!("ubrn-megazord-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&project);
+    fs::create_dir_all(&project)?;
+    fs::copy(
+        fixtures_dir().join("defaults/package.json"),
+        project.join("package.json"),
+    )?;
+    fs::write(
+        project.join("ubrn.config.yaml"),
+        format!(
+            "rust:\n  - directory: {}\n  - directory: {}\n\
+             napi:\n  tsBindings: node-src/generated\n  libResolution: packages\n",
+            arithmetic.crate_dir(),
+            procmacro.crate_dir()
+        ),
+    )?;
+    // Share the crates' target directory, rather than building everything
+    // again in the project's.
+    env::set_var("CARGO_TARGET_DIR", arithmetic.project_root().join("target"));
+
+    let current_dir = env::current_dir()?;
+    env::set_current_dir(&project)?;
+    let result = build_and_generate(&project);
+    env::set_current_dir(current_dir)?;
+    fs::remove_dir_all(&project)?;
+    result
+}
+
+fn build_and_generate(project: &Utf8Path) -> Result<()> {
+    run_cli("ubrn build napi --and-generate --config ubrn.config.yaml --targets linux-x64-gnu")?;
+
+    // The crates' namespaces are read from the megazord library itself.
+    let lib_file = Utf8PathBuf::from(env::var("CARGO_TARGET_DIR")?)
+        .join("x86_64-unknown-linux-gnu/debug/libdefault_fixture_megazord.so");
+    assert!(lib_file.exists(), "{lib_file}");
+    for namespace in ["arithmetic", "arithmeticpm"] {
+        let path = project.join(format!("node-src/generated/{namespace}.ts"));
+        assert!(path.exists(), "{path}");
+    }
+
+    run_cli(format!(
+        "ubrn generate all --config ubrn.config.yaml {lib_file}"
+    ))?;
+
+    // Each crate is re-exported from the one entrypoint.
+    let index = fs::read_to_string(project.join("src/index.tsx"))?;
+    for expected in [
+        "export * from './generated/arithmetic'",
+        "export * from './generated/arithmeticpm'",
+        "arithmetic.default.initialize()",
+        "arithmeticpm.default.initialize()",
+    ] {
+        assert!(index.contains(expected), "{expected}");
+    }
+    let cpp = fs::read_to_string(project.join("cpp/default-fixture.cpp"))?;
+    for expected in [
+        "#include \"generated/arithmetic.hpp\"",
+        "#include \"generated/arithmeticpm.hpp\"",
+    ] {
+        assert!(cpp.contains(expected), "{expected}");
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+use ubrn_cli_testing::{assert_files, shim_path, with_fixture, File};
+
+#[test]
+fn test_regenerating_merges_edits() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "merging-edits", |_fixture_dir| {
+        // Set up file shims
+        shim_path(
+            "package.json",
+            fixtures_dir.join("merging-edits/package.json"),
+        );
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("merging-edits/ubrn.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+
+        // Run the command under test: it fails, listing the conflicted files.
+        let error = run_cli("ubrn generate jsi turbo-module --config ubrn.config.yaml arithmetic")
+            .expect_err("conflicts should fail the command")
+            .to_string();
+        assert!(error.contains("cpp/default-fixture.cpp"), "{error}");
+        assert!(error.contains("ios/DefaultFixture.h"), "{error}");
+        assert!(!error.contains("cpp/default-fixture.h"), "{error}");
+
+        assert_files(&[
+            // The header was edited by hand, and the template has changed
+            // since: both changes are kept.
+            File::new("cpp/default-fixture.h")
+                .contains("#include <cstdint>")
+                .contains("uint8_t installExtras(jsi::Runtime &runtime);"),
+            // The same line was changed in both: it is left for the user to
+            // resolve.
+            File::new("cpp/default-fixture.cpp")
+                .contains("<<<<<<< ours\n\t\treturn cleanupExtras(runtime);\n")
+                .contains("||||||| original\n\t\treturn true;\n")
+                .contains("=======\n\t\treturn false;\n>>>>>>> theirs\n"),
+            // What was generated is recorded, for next time.
+            File::new(".ubrn/generated/cpp/default-fixture.h")
+                .contains("#include <cstdint>")
+                .does_not_contain("installExtras"),
+            File::new(".ubrn/generated/cpp/default-fixture.cpp")
+                .contains("return false;")
+                .does_not_contain("<<<<<<<"),
+            // Without a record of what was last generated, a file which
+            // differs from the generated one is kept, and left to resolve.
+            File::new("ios/DefaultFixture.h")
+                .contains("<<<<<<< ours\n// Written before .ubrn/generated was kept.\n")
+                .contains(">>>>>>> theirs\n"),
+            File::new(".ubrn/generated/ios/DefaultFixture.h").does_not_contain("<<<<<<<"),
+            // Files which had not been generated before are written as they
+            // always were.
+            File::new("ios/DefaultFixture.mm"),
+            File::new(".ubrn/generated/ios/DefaultFixture.mm"),
+        ]);
+
+        Ok(())
+    })
+}
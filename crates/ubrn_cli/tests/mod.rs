@@ -4,5 +4,11 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 
+mod expo;
 mod happy_path;
+mod megazord;
+mod merging_edits;
+mod napi_build;
+mod napi_package;
+mod templates;
 mod web_variants;
@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+use ubrn_cli_testing::{assert_commands, assert_files, shim_path, with_fixture, Command, File};
+
+#[test]
+fn test_build_napi_and_generate() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-napi.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+        shim_path(
+            "x86_64-unknown-linux-gnu/debug/libarithmetical.so",
+            target_crate.library_path(None, "debug", Some(true)),
+        );
+
+        // Run the command under test
+        run_cli(
+            "ubrn build napi --and-generate --config ubrn.config.yaml --targets linux-x64-gnu",
+        )?;
+
+        // The --targets override the ones in the config.
+        assert_commands(&[
+            Command::new("cargo")
+                .arg("build")
+                .arg("--lib")
+                .arg_pair_suffix("--manifest-path", "arithmetic/Cargo.toml")
+                .arg_pair("--target", "x86_64-unknown-linux-gnu"),
+            Command::new("prettier"),
+        ]);
+
+        assert_files(&[
+            // The bindings go to napi/tsBindings, and find the library in
+            // the platform packages, as napi/libResolution says.
+            File::new("node-src/generated/arithmetic-ffi.ts")
+                .contains(r#"npmPackageBase: "default-fixture-""#)
+                .contains(r#"tripleStyle: "node""#),
+            File::new("node-src/generated/arithmetic.ts"),
+        ]);
+
+        Ok(())
+    })
+}
@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+use ubrn_cli_testing::{assert_commands, assert_files, shim_path, with_fixture, Command, File};
+
+#[test]
+fn test_package_napi() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-napi.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+
+        // Run the command under test
+        run_cli("ubrn package napi --config ubrn.config.yaml --release")?;
+
+        // One build per target in the config.
+        assert_commands(&[
+            Command::new("cargo")
+                .arg("build")
+                .arg("--lib")
+                .arg_pair_suffix("--manifest-path", "arithmetic/Cargo.toml")
+                .arg_pair("--target", "aarch64-apple-darwin")
+                .arg_pair("--profile", "release"),
+            Command::new("cargo")
+                .arg("build")
+                .arg("--lib")
+                .arg_pair_suffix("--manifest-path", "arithmetic/Cargo.toml")
+                .arg_pair("--target", "x86_64-unknown-linux-gnu")
+                .arg_pair("--profile", "release"),
+        ]);
+
+        assert_files(&[
+            // The package names are the node triples, because of the
+            // tripleStyle in the config.
+            File::new("npm/darwin-arm64/package.json")
+                .contains(r#""name": "default-fixture-darwin-arm64""#)
+                .contains(r#""version": "0.1.0""#)
+                .contains(r#""os": ["#)
+                .contains(r#""darwin""#)
+                .contains(r#""arm64""#)
+                .contains("libarithmetical.dylib")
+                .does_not_contain("libc"),
+            File::new("npm/linux-x64-gnu/package.json")
+                .contains(r#""name": "default-fixture-linux-x64-gnu""#)
+                .contains(r#""linux""#)
+                .contains(r#""x64""#)
+                .contains(r#""glibc""#)
+                .contains("libarithmetical.so"),
+            // The umbrella package depends on each of them, after the
+            // fields it already had.
+            File::new("defaults/package.json")
+                .contains(r#""optionalDependencies": {"#)
+                .contains(r#""default-fixture-darwin-arm64": "0.1.0""#)
+                .contains(r#""default-fixture-linux-x64-gnu": "0.1.0""#)
+                .contains(r#""name": "default-fixture","#),
+        ]);
+
+        Ok(())
+    })
+}
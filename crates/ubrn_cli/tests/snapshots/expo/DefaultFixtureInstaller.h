@@ -0,0 +1,13 @@
+// Generated by uniffi-bindgen-react-native
+#import <Foundation/Foundation.h>
+
+@class EXJavaScriptRuntime;
+
+// Plain Objective-C, so the Swift module can call into the C++ installer
+// in default-fixture.cpp.
+@interface DefaultFixtureInstaller : NSObject
+
++ (BOOL)installRustCrate:(nonnull EXJavaScriptRuntime *)runtime;
++ (BOOL)cleanupRustCrate:(nonnull EXJavaScriptRuntime *)runtime;
+
+@end
\ No newline at end of file
@@ -0,0 +1,16 @@
+// Generated by uniffi-bindgen-react-native
+#import "DefaultFixtureInstaller.h"
+#import <ExpoModulesCore/EXJavaScriptRuntime.h>
+#import "default-fixture.h"
+
+@implementation DefaultFixtureInstaller
+
++ (BOOL)installRustCrate:(nonnull EXJavaScriptRuntime *)runtime {
+    return defaultfixture::installRustCrate(*[runtime get], [runtime callInvoker]);
+}
+
++ (BOOL)cleanupRustCrate:(nonnull EXJavaScriptRuntime *)runtime {
+    return defaultfixture::cleanupRustCrate(*[runtime get]);
+}
+
+@end
\ No newline at end of file
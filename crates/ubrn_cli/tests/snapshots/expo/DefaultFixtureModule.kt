@@ -0,0 +1,37 @@
+// Generated by uniffi-bindgen-react-native
+package com.defaultfixture
+
+import com.facebook.react.turbomodule.core.interfaces.CallInvokerHolder
+import expo.modules.kotlin.modules.Module
+import expo.modules.kotlin.modules.ModuleDefinition
+
+class DefaultFixtureModule : Module() {
+  override fun definition() = ModuleDefinition {
+    Name("DefaultFixture")
+
+    Function("installRustCrate") {
+      val context = appContext.reactContext ?: return@Function false
+      nativeInstallRustCrate(
+        context.javaScriptContextHolder!!.get(),
+        context.jsCallInvokerHolder!!
+      )
+    }
+
+    Function("cleanupRustCrate") {
+      val context = appContext.reactContext ?: return@Function false
+      nativeCleanupRustCrate(context.javaScriptContextHolder!!.get())
+    }
+  }
+
+  // Two native methods implemented in cpp-adapter.cpp, and ultimately
+  // default-fixture.cpp
+
+  external fun nativeInstallRustCrate(runtimePointer: Long, callInvoker: CallInvokerHolder): Boolean
+  external fun nativeCleanupRustCrate(runtimePointer: Long): Boolean
+
+  companion object {
+    init {
+      System.loadLibrary("default-fixture")
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,22 @@
+// Generated by uniffi-bindgen-react-native
+import ExpoModulesCore
+
+public class DefaultFixtureModule: Module {
+  public func definition() -> ModuleDefinition {
+    Name("DefaultFixture")
+
+    Function("installRustCrate") { () -> Bool in
+      guard let runtime = try appContext?.runtime else {
+        return false
+      }
+      return DefaultFixtureInstaller.installRustCrate(runtime)
+    }
+
+    Function("cleanupRustCrate") { () -> Bool in
+      guard let runtime = try appContext?.runtime else {
+        return false
+      }
+      return DefaultFixtureInstaller.cleanupRustCrate(runtime)
+    }
+  }
+}
\ No newline at end of file
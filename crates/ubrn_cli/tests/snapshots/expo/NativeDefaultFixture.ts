@@ -0,0 +1,9 @@
+// Generated by uniffi-bindgen-react-native
+import { requireNativeModule } from 'expo-modules-core';
+
+export interface Spec {
+  installRustCrate(): boolean;
+  cleanupRustCrate(): boolean;
+}
+
+export default requireNativeModule<Spec>('DefaultFixture');
\ No newline at end of file
@@ -0,0 +1,68 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+use anyhow::Result;
+
+use ubrn_cli::test_utils::{cargo_build, fixtures_dir, run_cli};
+use ubrn_cli_testing::{assert_files, shim_path, with_fixture, File};
+
+#[test]
+fn test_template_overrides() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-templates.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+
+        // Run the command under test
+        run_cli("ubrn generate jsi turbo-module --config ubrn.config.yaml arithmetic")?;
+
+        assert_files(&[
+            // templates/TurboModuleTemplate.h replaces the built-in header…
+            File::new("cpp/default-fixture.h")
+                .contains("// Overridden by the project")
+                .contains("namespace defaultfixture {")
+                .does_not_contain("#ifndef DEFAULTFIXTURE_H"),
+            // …and leaves the others alone.
+            File::new("cpp/default-fixture.cpp")
+                .contains("#include \"default-fixture.h\"")
+                .contains("NativeArithmetic::registerModule"),
+        ]);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn test_eject_template() -> Result<()> {
+    let target_crate = cargo_build("arithmetic")?;
+    let fixtures_dir = fixtures_dir();
+    with_fixture(fixtures_dir.clone(), "defaults", |_fixture_dir| {
+        // Set up file shims
+        shim_path("package.json", fixtures_dir.join("defaults/package.json"));
+        shim_path(
+            "ubrn.config.yaml",
+            fixtures_dir.join("defaults/ubrn-templates.config.yaml"),
+        );
+        shim_path("rust/shim/Cargo.toml", target_crate.manifest_path());
+        shim_path("rust/shim", target_crate.project_root());
+
+        // Run the command under test
+        run_cli("ubrn generate jsi turbo-module --config ubrn.config.yaml --eject CMakeLists.txt")?;
+
+        // The template itself, not the rendered file.
+        assert_files(&[File::new("templates/CMakeLists.txt")
+            .contains("project({{ config.project.module_cpp() }})")
+            .contains("{%- if is_so_lib %}")]);
+
+        Ok(())
+    })
+}
@@ -21,6 +21,10 @@ pub enum ContentMatcher {
     Contains(String),
     /// Match file content that does not contain the specified substring
     DoesNotContain(String),
+    /// Match file content that is exactly that of the snapshot file.
+    ///
+    /// With `UBRN_UPDATE_SNAPSHOTS` set, the snapshot is written instead.
+    Snapshot(Utf8PathBuf),
 }
 
 impl File {
@@ -46,6 +50,15 @@ impl File {
         self
     }
 
+    /// Add a matcher to check the file is exactly the same as a snapshot
+    pub fn matches_snapshot<P: AsRef<Path>>(mut self, snapshot: P) -> Self {
+        self.content_matchers
+            .push(ContentMatcher::Snapshot(Utf8PathBuf::from(
+                snapshot.as_ref().to_string_lossy().to_string(),
+            )));
+        self
+    }
+
     /// Get the file path
     pub fn path(&self) -> &Utf8PathBuf {
         &self.path
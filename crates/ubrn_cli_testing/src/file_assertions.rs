@@ -3,10 +3,17 @@
  * License, v. 2.0. If a copy of the MPL was not distributed with this
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
+use std::fs;
+
+use camino::Utf8Path;
+
 use crate::file::{ContentMatcher, File};
 use ubrn_common::get_recorded_files;
 use ubrn_common::RecordedFile;
 
+/// Set this to write the snapshots, rather than compare with them.
+const UPDATE_SNAPSHOTS: &str = "UBRN_UPDATE_SNAPSHOTS";
+
 /// Helper function to check if file matchers match the recorded files
 /// Returns Ok(()) if files match, Err(error_message) with detailed error otherwise
 fn check_files(expected_files: &[File]) -> Result<(), String> {
@@ -94,12 +101,42 @@ fn check_file_content(recorded: &RecordedFile, expected: &File) -> Option<String
                     ));
                 }
             }
+            ContentMatcher::Snapshot(snapshot) => {
+                if let Some(error) = check_snapshot(recorded, snapshot) {
+                    return Some(error);
+                }
+            }
         }
     }
 
     None
 }
 
+/// Compare the file with its snapshot, or, with `UBRN_UPDATE_SNAPSHOTS` set,
+/// write the snapshot.
+fn check_snapshot(recorded: &RecordedFile, snapshot: &Utf8Path) -> Option<String> {
+    if std::env::var_os(UPDATE_SNAPSHOTS).is_some() {
+        let written = snapshot
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(snapshot, &recorded.content));
+        return written
+            .err()
+            .map(|e| format!("Cannot write snapshot '{snapshot}': {e}"));
+    }
+    match fs::read_to_string(snapshot) {
+        Ok(expected) if expected == recorded.content => None,
+        Ok(expected) => Some(format!(
+            "File '{}' doesn't match its snapshot '{snapshot}'; \
+             rerun with {UPDATE_SNAPSHOTS}=1 to update it.\nExpected: {expected}\nContent: {}",
+            recorded.path, recorded.content
+        )),
+        Err(e) => Some(format!(
+            "Cannot read snapshot '{snapshot}' ({e}); rerun with {UPDATE_SNAPSHOTS}=1 to write it."
+        )),
+    }
+}
+
 /// Assert that a set of files were written with expected content
 /// Panics with a detailed error message if files don't match
 pub fn assert_files(expected_files: &[File]) {
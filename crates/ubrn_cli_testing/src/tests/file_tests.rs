@@ -97,3 +97,32 @@ fn test_file_assertion_fails_when_file_missing() {
     clear_recorded_files();
     stop_recording();
 }
+
+#[test]
+fn test_file_assertion_matches_snapshot() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let snapshot = temp_dir.path().join("file.kt");
+    std::fs::write(&snapshot, "This is a test file with some content").unwrap();
+
+    start_recording();
+
+    write_file("/path/to/file.kt", "This is a test file with some content").unwrap();
+    assert!(files_match(&[
+        File::new("file.kt").matches_snapshot(&snapshot)
+    ]));
+
+    write_file(
+        "/path/to/other.kt",
+        "This is a test file with other content",
+    )
+    .unwrap();
+    assert!(!files_match(&[
+        File::new("other.kt").matches_snapshot(&snapshot)
+    ]));
+    assert!(!files_match(&[
+        File::new("other.kt").matches_snapshot(temp_dir.path().join("missing.kt"))
+    ]));
+
+    clear_recorded_files();
+    stop_recording();
+}
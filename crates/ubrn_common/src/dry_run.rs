@@ -0,0 +1,234 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! In a dry run, files are written to a staging directory inside the project
+//! instead of over the real ones, so the formatters find the same config as
+//! they otherwise would. Once done, the staged files are compared with those
+//! on disk, and the staging directory is removed.
+use std::{cell::RefCell, fs};
+
+use anyhow::{Context, Result};
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+
+use crate::{pwd, rm_dir};
+
+const STAGING_DIR: &str = ".ubrn/dry-run";
+
+/// Files outside the project root are staged under here, by absolute path.
+const EXTERNAL_DIR: &str = "_external";
+
+thread_local! {
+    static DRY_RUN: RefCell<Option<DryRun>> = const { RefCell::new(None) };
+}
+
+struct DryRun {
+    root: Utf8PathBuf,
+    staging: Utf8PathBuf,
+    /// The real paths, in the order they were first written.
+    written: Vec<Utf8PathBuf>,
+}
+
+/// A file a dry run would have written, with what's there now.
+#[derive(Debug, PartialEq)]
+pub struct StagedFile {
+    /// The path of the file, relative to the project root if it is in it.
+    pub path: Utf8PathBuf,
+    /// `None` if the file doesn't yet exist.
+    pub old: Option<Vec<u8>>,
+    pub new: Vec<u8>,
+}
+
+impl StagedFile {
+    pub fn is_changed(&self) -> bool {
+        self.old.as_ref() != Some(&self.new)
+    }
+}
+
+/// Start staging writes under `project_root`, rather than making them.
+pub fn start_dry_run(project_root: &Utf8Path) -> Result<()> {
+    let root = Utf8PathBuf::try_from(dunce::canonicalize(project_root)?)?;
+    let staging = root.join(STAGING_DIR);
+    rm_dir(&staging)?;
+    fs::create_dir_all(&staging)?;
+    DRY_RUN.with(|d| {
+        *d.borrow_mut() = Some(DryRun {
+            root,
+            staging,
+            written: Default::default(),
+        })
+    });
+    Ok(())
+}
+
+/// Stop staging writes, and return each staged file alongside the one on
+/// disk. Nothing is returned if no dry run was started.
+pub fn finish_dry_run() -> Result<Vec<StagedFile>> {
+    let Some(dry_run) = DRY_RUN.with(|d| d.borrow_mut().take()) else {
+        return Ok(Vec::new());
+    };
+    let mut files = Vec::new();
+    for path in &dry_run.written {
+        let new = fs::read(dry_run.staged(path))
+            .with_context(|| format!("Failed to read the staged copy of {path}"))?;
+        let old = if path.is_file() {
+            Some(fs::read(path)?)
+        } else {
+            None
+        };
+        let path = path
+            .strip_prefix(&dry_run.root)
+            .map_or_else(|_| path.clone(), Utf8Path::to_path_buf);
+        files.push(StagedFile { path, old, new });
+    }
+    rm_dir(&dry_run.staging)?;
+    // Only removed if the dry run was all that was in it.
+    let _ = fs::remove_dir(dry_run.staging.parent().expect("Parent for path"));
+    Ok(files)
+}
+
+pub fn is_dry_run() -> bool {
+    DRY_RUN.with(|d| d.borrow().is_some())
+}
+
+/// Run `f` with its writes made for real, even in a dry run: for the files
+/// a tool run in the meantime needs to find on disk.
+pub fn without_dry_run<T>(f: impl FnOnce() -> T) -> T {
+    let dry_run = DRY_RUN.with(|d| d.borrow_mut().take());
+    let result = f();
+    DRY_RUN.with(|d| *d.borrow_mut() = dry_run);
+    result
+}
+
+impl DryRun {
+    fn staged(&self, path: &Utf8Path) -> Utf8PathBuf {
+        match path.strip_prefix(&self.root) {
+            Ok(rel) => self.staging.join(rel),
+            Err(_) => {
+                let rel: Utf8PathBuf = path
+                    .components()
+                    .filter(|c| matches!(c, Utf8Component::Normal(_)))
+                    .collect();
+                self.staging.join(EXTERNAL_DIR).join(rel)
+            }
+        }
+    }
+}
+
+/// Where a file or directory is staged in a dry run, if one is in progress.
+pub(crate) fn staged_path(path: &Utf8Path) -> Result<Option<Utf8PathBuf>> {
+    if !is_dry_run() {
+        return Ok(None);
+    }
+    let path = normalize(&pwd()?.join(path));
+    Ok(DRY_RUN.with(|d| d.borrow().as_ref().map(|d| d.staged(&path))))
+}
+
+/// Where to write a file in a dry run, if one is in progress, remembering
+/// that it was written.
+pub(crate) fn stage_write(path: &Utf8Path) -> Result<Option<Utf8PathBuf>> {
+    if !is_dry_run() {
+        return Ok(None);
+    }
+    let path = normalize(&pwd()?.join(path));
+    let staged = DRY_RUN.with(|d| {
+        let mut d = d.borrow_mut();
+        let d = d.as_mut().expect("a dry run is in progress");
+        if !d.written.contains(&path) {
+            d.written.push(path.clone());
+        }
+        d.staged(&path)
+    });
+    if let Some(parent) = staged.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(Some(staged))
+}
+
+/// The canonical path of a file or directory which, in a dry run, has only
+/// been made in the staging directory. Its nearest ancestor on disk is
+/// canonicalized instead.
+pub(crate) fn unmade_path(path: &Utf8Path) -> Result<Option<Utf8PathBuf>> {
+    if !is_dry_run() || path.exists() {
+        return Ok(None);
+    }
+    let path = normalize(&pwd()?.join(path));
+    let Some(ancestor) = path.ancestors().find(|p| p.exists()) else {
+        return Ok(None);
+    };
+    let rest = path.strip_prefix(ancestor)?;
+    let ancestor = Utf8PathBuf::try_from(dunce::canonicalize(ancestor)?)?;
+    Ok(Some(ancestor.join(rest)))
+}
+
+/// Resolve `.` and `..` without touching the filesystem, as the staged paths
+/// may not exist yet.
+fn normalize(path: &Utf8Path) -> Utf8PathBuf {
+    let mut normalized = Utf8PathBuf::new();
+    for component in path.components() {
+        match component {
+            Utf8Component::CurDir => {}
+            Utf8Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mk_dir, read_to_string, write_file, Utf8PathBufExt};
+
+    #[test]
+    fn writes_are_staged_and_compared() -> Result<()> {
+        let root = Utf8PathBuf::try_from(std::env::temp_dir())?
+            .join(format!("ubrn-dry-run-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/same.ts"), "a")?;
+        fs::write(root.join("src/changed.ts"), "b")?;
+
+        start_dry_run(&root)?;
+        mk_dir(root.join("cpp"))?;
+        for (name, contents) in [
+            ("src/same.ts", "a"),
+            ("src/changed.ts", "c"),
+            ("cpp/new.h", "d"),
+        ] {
+            write_file(root.join(name), contents)?;
+        }
+        // Later reads see what was staged.
+        assert_eq!(read_to_string(root.join("src/changed.ts"))?, "c");
+        // What was only made in the staging directory still has a path.
+        assert_eq!(
+            root.join("cpp").canonicalize_utf8_or_shim()?,
+            root.canonicalize_utf8_or_shim()?.join("cpp")
+        );
+        let files = finish_dry_run()?;
+
+        let changed: Vec<_> = files
+            .iter()
+            .filter(|f| f.is_changed())
+            .map(|f| (f.path.as_str(), f.old.is_some()))
+            .collect();
+        assert_eq!(changed, [("src/changed.ts", true), ("cpp/new.h", false)]);
+        // Nothing on disk was touched.
+        assert_eq!(fs::read_to_string(root.join("src/changed.ts"))?, "b");
+        assert!(!root.join("cpp").exists());
+        assert!(!root.join(STAGING_DIR).exists());
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn paths_are_normalized() {
+        assert_eq!(
+            normalize("/a/b/../c/./d".into()),
+            Utf8PathBuf::from("/a/c/d")
+        );
+    }
+}
@@ -9,7 +9,10 @@ use anyhow::{bail, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::Deserialize;
 
-use crate::testing::is_recording_enabled;
+use crate::{
+    dry_run::{stage_write, staged_path, unmade_path},
+    testing::is_recording_enabled,
+};
 
 /// Finds a file in the given directory.
 ///
@@ -86,11 +89,14 @@ pub fn cp_file<P: AsRef<Utf8Path>, D: AsRef<Utf8Path>>(src: P, dst: D) -> Result
     if is_recording_enabled() {
         return Ok(());
     }
-    let src = src.as_ref();
-    let dst = dst.as_ref();
+    // In a dry run, copy from and to the staged copies.
+    let src = &staged_path(src.as_ref())?
+        .filter(|p| p.exists())
+        .unwrap_or(src.as_ref().into());
     if !src.exists() {
         bail!("File {src} does not exist");
     }
+    let dst = &stage_write(dst.as_ref())?.unwrap_or(dst.as_ref().into());
     fs::copy(src, dst).with_context(|| format!("Failed to copy {src} to {dst}"))?;
     Ok(())
 }
@@ -99,7 +105,7 @@ pub fn mk_dir<P: AsRef<Utf8Path>>(dir: P) -> Result<()> {
     if is_recording_enabled() {
         return Ok(());
     }
-    let dir = pwd()?.join(dir);
+    let dir = staged_path(dir.as_ref())?.unwrap_or(pwd()?.join(dir));
     if dir.exists() {
         if dir.is_dir() {
             Ok(())
@@ -173,6 +179,10 @@ where
             }
         }
     }
+    if let Some(staged) = staged_path(file)?.filter(|p| p.exists()) {
+        return fs::read_to_string(&staged)
+            .with_context(|| format!("Failed to read from {file:?}"));
+    }
     if !file.exists() {
         anyhow::bail!("File {file} does not exist");
     }
@@ -204,6 +214,8 @@ pub fn write_file<P: AsRef<Utf8Path>, C: AsRef<[u8]>>(path: P, contents: C) -> R
         crate::testing::record_file(path, &contents);
         return Ok(());
     }
+    // In a dry run, write to the staged copy instead.
+    let path = &stage_write(path)?.unwrap_or(path.into());
 
     fs::write(path, contents).with_context(|| format!("Failed to write to {path}"))?;
     Ok(())
@@ -227,6 +239,8 @@ pub impl Utf8Path {
     fn canonicalize_utf8_or_shim(&self) -> Result<Utf8PathBuf> {
         Ok(if is_recording_enabled() {
             self.to_path_buf()
+        } else if let Some(path) = unmade_path(self)? {
+            path
         } else {
             Utf8PathBuf::try_from(dunce::canonicalize(self)?)?
         })
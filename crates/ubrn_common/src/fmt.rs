@@ -8,7 +8,7 @@ use camino::Utf8Path;
 use std::process::Command;
 use which::which;
 
-use crate::{command, file_paths, resolve};
+use crate::{command, dry_run::staged_path, file_paths, resolve};
 
 pub fn clang_format<P: AsRef<Utf8Path>>(path: P, check_only: bool) -> Result<Option<Command>> {
     if which("clang-format").is_err() {
@@ -19,7 +19,8 @@ pub fn clang_format<P: AsRef<Utf8Path>>(path: P, check_only: bool) -> Result<Opt
         return Ok(None);
     }
 
-    let path = path.as_ref();
+    // In a dry run, format the staged copies.
+    let path = &staged_path(path.as_ref())?.unwrap_or(path.as_ref().into());
     let mut cmd = Command::new("clang-format");
     if check_only {
         cmd.arg("--dry-run").arg("--Werror");
@@ -35,6 +36,7 @@ pub fn clang_format<P: AsRef<Utf8Path>>(path: P, check_only: bool) -> Result<Opt
 }
 
 pub fn prettier<P: AsRef<Utf8Path>>(out_dir: P, check_only: bool) -> Result<Option<Command>> {
+    let out_dir = staged_path(out_dir.as_ref())?.unwrap_or(out_dir.as_ref().into());
     let prettier = resolve(&out_dir, "node_modules/.bin/prettier")?;
     Ok(if let Some(prettier) = prettier {
         let mut cmd = command(prettier);
@@ -49,7 +51,7 @@ pub fn prettier<P: AsRef<Utf8Path>>(out_dir: P, check_only: bool) -> Result<Opti
             "**/*.json",
             "--no-error-on-unmatched-pattern",
         ])
-        .current_dir(out_dir.as_ref());
+        .current_dir(&out_dir);
         Some(cmd)
     } else {
         use crate::testing::{is_recording_enabled, record_command};
@@ -4,6 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 mod commands;
+mod dry_run;
 mod files;
 pub mod fmt;
 mod rust_crate;
@@ -13,6 +14,7 @@ mod testing;
 mod wasm;
 
 pub use commands::*;
+pub use dry_run::{finish_dry_run, is_dry_run, start_dry_run, without_dry_run, StagedFile};
 pub use files::*;
 pub use rust_crate::*;
 pub use serde::*;
@@ -181,6 +181,8 @@ fn so_extension_from_target<'a>(target: &str, use_shared_library: Option<bool>)
         "a"
     } else if target.contains("wasm") {
         "wasm"
+    } else if target.contains("linux") {
+        "so"
     } else {
         unimplemented!("Building targeting only on android and ios supported right now")
     }
@@ -8,6 +8,7 @@
 //   cargo test -p uniffi-example-custom-types -- wasm
 
 import {
+  CustomTypesDemoJson,
   getCustomTypesDemo,
   getMaybeCustomDefault,
   Handle,
@@ -89,3 +90,22 @@ test("Rust EnumWrapper structs --> via MyEnum --> string", (t) => {
     }
   }
 });
+
+test("Custom types are written to JSON as their builtin types", (t) => {
+  const demo = getCustomTypesDemo(undefined);
+  const json = CustomTypesDemoJson.toJSON(demo);
+  t.assertEqual(json, {
+    url: "http://example.com/",
+    handle: "123",
+    // TimeIntervalMs has toJson and fromJson in uniffi.toml.
+    timeIntervalMs: "1970-01-01T00:07:36.000Z",
+    timeIntervalSecDbl: 456,
+    timeIntervalSecFlt: 777,
+  });
+
+  const decoded = CustomTypesDemoJson.fromJSON(
+    JSON.parse(JSON.stringify(json)),
+  );
+  t.assertEqual(decoded, demo);
+  t.assertEqual(getCustomTypesDemo(decoded), demo);
+});
@@ -267,6 +267,17 @@ fn get_maybe_simple_dict(index: i8) -> MaybeSimpleDict {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct NestedDict {
+    counts: HashMap<String, Option<Vec<u32>>>,
+    color: Option<Color>,
+    maybe: Option<MaybeSimpleDict>,
+}
+
+fn echo_nested_dict(d: NestedDict) -> NestedDict {
+    d
+}
+
 // UDL can not describe this as a "flat" enum, but we'll keep it here to help demonstrate that!
 #[derive(Debug, Clone)]
 pub enum SimpleFlatEnum {
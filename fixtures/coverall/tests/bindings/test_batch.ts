@@ -0,0 +1,60 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// The `{name}Batch` companions the `batch` config adds to the generated API.
+//
+// To run:
+//   cargo test -p uniffi-fixture-coverall -- napi
+
+import coverall, {
+  ComplexError,
+  CoverallError,
+  Coveralls,
+  divideByText,
+  divideByTextBatch,
+  getNumAlive,
+} from "@/generated/coverall";
+import { test } from "@/asserts";
+import "@/polyfills";
+
+coverall.initialize();
+
+test("a batched function lowers its arguments and lifts its results", (t) => {
+  const results = divideByTextBatch([
+    [1.0, "2"],
+    [9.0, "3"],
+    [5.0, "0.5"],
+  ]);
+  t.assertEqual(results, [0.5, 3.0, 10.0]);
+  t.assertEqual(divideByTextBatch([]), []);
+});
+
+test("a batched function throws the first call's error", (t) => {
+  t.assertThrows(ComplexError.UnknownError.instanceOf, () =>
+    divideByTextBatch([
+      [1.0, "2"],
+      [1.0, "zero"],
+      [1.0, "0"],
+    ]),
+  );
+  t.assertEqual(divideByText(1.0, "4"), 0.25);
+});
+
+test("a batched method calls on its object", (t) => {
+  const initialAlive = getNumAlive();
+  const coveralls = new Coveralls("Batched");
+  // `status` is also a name the generated call plumbing might use.
+  const statuses = coveralls.getStatusBatch([["one"], ["two"], ["three"]]);
+  t.assertEqual(statuses, ["status: one", "status: two", "status: three"]);
+
+  t.assertEqual(coveralls.maybeThrowBatch([[false], [false]]), [true, true]);
+  t.assertThrows(CoverallError.TooManyHoles.instanceOf, () =>
+    coveralls.maybeThrowBatch([[false], [true], [false]]),
+  );
+
+  // Each call clones the pointer it hands to Rust, and Rust drops each clone.
+  coveralls.uniffiDestroy();
+  t.assertEqual(getNumAlive(), initialAlive);
+});
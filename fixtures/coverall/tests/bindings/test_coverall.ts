@@ -31,7 +31,20 @@ import coverall, {
   testGetters,
   testRoundTripThroughRust,
   testRoundTripThroughForeign,
+  echoNestedDict,
+  ColorJson,
+  ColorValidator,
+  CoverallErrorJson,
+  CoverallErrorValidator,
+  MaybeSimpleDict,
+  MaybeSimpleDictValidator,
+  NestedDict,
+  NestedDictJson,
+  NestedDictValidator,
+  SimpleDictJson,
+  SimpleDictValidator,
 } from "@/generated/coverall";
+import { UniffiValidationError } from "@ubjs/core";
 import { test } from "@/asserts";
 import "@/polyfills";
 
@@ -272,6 +285,109 @@ test("Error Values", (t) => {
   coveralls.uniffiDestroy();
 });
 
+test("Validators accept values of the generated types", (t) => {
+  const d: NestedDict = {
+    counts: new Map([
+      ["a", [1, 2, 3]],
+      ["b", undefined],
+    ]),
+    color: Color.Green,
+    maybe: new MaybeSimpleDict.Yeah({ d: createSomeDict() }),
+  };
+  t.assertEqual(NestedDictValidator.parse(d), d);
+  t.assertTrue(NestedDictValidator.is({ counts: new Map() }));
+  t.assertTrue(SimpleDictValidator.is(createNoneDict()));
+  t.assertTrue(MaybeSimpleDictValidator.is(new MaybeSimpleDict.Nah()));
+  t.assertTrue(ColorValidator.is(Color.Red));
+
+  const echoed = echoNestedDict(NestedDictValidator.parse(d));
+  t.assertEqual(echoed.counts.get("a"), [1, 2, 3]);
+  t.assertTrue(echoed.counts.has("b"));
+  t.assertEqual(echoed.color, Color.Green);
+  t.assertTrue(MaybeSimpleDict.Yeah.instanceOf(echoed.maybe));
+});
+
+test("Validators reject malformed values with a path", (t) => {
+  const checkPath = (value: unknown, path: string) =>
+    t.assertThrows(
+      (e) => UniffiValidationError.instanceOf(e) && e.path === path,
+      () => NestedDictValidator.parse(value),
+    );
+
+  checkPath(null, "NestedDict");
+  checkPath({ counts: {} }, "NestedDict.counts");
+  checkPath(
+    { counts: new Map([["a", [1, -2]]]) },
+    'NestedDict.counts.get("a")[1]',
+  );
+  checkPath({ counts: new Map([["a", 1]]) }, 'NestedDict.counts.get("a")');
+  checkPath({ counts: new Map(), color: "Purple" }, "NestedDict.color");
+  checkPath(
+    { counts: new Map(), maybe: { tag: "Maybe" } },
+    "NestedDict.maybe.tag",
+  );
+  checkPath(
+    { counts: new Map(), maybe: { tag: "Yeah", inner: { d: {} } } },
+    "NestedDict.maybe.inner.d.text",
+  );
+
+  const result = SimpleDictValidator.safeParse({ text: 1 });
+  t.assertFalse(result.success);
+  if (!result.success) {
+    t.assertEqual(result.error.path, "SimpleDict.text");
+    t.assertEqual(result.error.expected, "string");
+  }
+  t.assertFalse(CoverallErrorValidator.is({ tag: "TooManyHoles" }));
+});
+
+test("Records and enums round-trip through JSON", (t) => {
+  const d: NestedDict = {
+    counts: new Map([
+      ["a", [1, 2, 3]],
+      ["b", undefined],
+    ]),
+    color: Color.Green,
+    maybe: new MaybeSimpleDict.Yeah({
+      d: { ...createSomeDict(), coveralls: undefined },
+    }),
+  };
+  const text = JSON.stringify(NestedDictJson.toJSON(d));
+  const decoded = NestedDictJson.fromJSON(JSON.parse(text));
+  t.assertTrue(NestedDictValidator.is(decoded));
+  t.assertEqual(decoded.counts.get("a"), [1, 2, 3]);
+  t.assertTrue(decoded.counts.has("b"));
+  t.assertEqual(decoded.color, Color.Green);
+  t.assertTrue(MaybeSimpleDict.Yeah.instanceOf(decoded.maybe));
+  t.assertEqual(echoNestedDict(decoded).color, Color.Green);
+
+  const json = SimpleDictJson.toJSON({
+    ...createSomeDict(),
+    coveralls: undefined,
+  }) as any;
+  t.assertEqual(json.unsigned64, "18446744073709551615");
+  t.assertEqual(json.someBytes, "c29tZV9ieXRlcw==");
+  t.assertEqual(json.coveralls, null);
+  t.assertEqual(ColorJson.toJSON(Color.Blue), "Blue");
+
+  // Objects have no JSON encoding.
+  t.assertThrows(
+    () => true,
+    () => SimpleDictJson.toJSON(createSomeDict()),
+  );
+
+  const error = new CoverallError.TooManyHoles("Too many holes");
+  t.assertEqual(CoverallErrorJson.toJSON(error), {
+    tag: "TooManyHoles",
+    message: "Too many holes",
+  });
+  const decodedError = CoverallErrorJson.fromJSON({
+    tag: "TooManyHoles",
+    message: "Too many holes",
+  });
+  t.assertTrue(CoverallError.TooManyHoles.instanceOf(decodedError));
+  t.assertEqual(decodedError.message, error.message);
+});
+
 test("Interfaces in dicts", (t) => {
   const coveralls = new Coveralls("Testing interfaces in dicts");
   coveralls.addPatch(new Patch(Color.Red));
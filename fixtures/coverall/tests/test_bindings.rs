@@ -5,4 +5,5 @@
  */
 ubrn_macros::build_foreign_language_testcases! {
     "tests/bindings/test_coverall.ts" => [Jsi, Wasm, Napi, Wasm2],
+    "tests/bindings/test_batch.ts" => [Napi],
 }
@@ -14,6 +14,7 @@ import {
   syncFn,
 } from "@/generated/uniffi_force_async_list";
 import { asyncTest, test } from "@/asserts";
+import { tracedCalls } from "@/tracer";
 
 // Members left out of the forceAsync list keep their synchronous surface.
 test("un-named object and function are synchronous", (t) => {
@@ -50,4 +51,24 @@ test("un-named object and function are synchronous", (t) => {
     t.assertEqual(await fnResult, 42);
     t.end();
   });
+
+  await asyncTest("forced calls are traced as async", async (t) => {
+    tracedCalls.length = 0;
+    syncFn(1);
+    await asyncFn(1);
+    const a = (await AsyncObj.create("hi")) as AsyncObj;
+    await a.label();
+
+    const [sync, async_, create, label] = tracedCalls;
+    t.assertEqual(tracedCalls.length, 4);
+    t.assertEqual(sync.name, "syncFn");
+    t.assertFalse(sync.isAsync);
+    t.assertEqual(async_.name, "asyncFn");
+    t.assertTrue(async_.isAsync);
+    t.assertEqual(create.name, "AsyncObj.create");
+    t.assertTrue(create.isAsync);
+    t.assertEqual(label.name, "AsyncObj.label");
+    t.assertTrue(label.isAsync);
+    t.end();
+  });
 })();
@@ -41,6 +41,7 @@ import {
   uniffiForeignFutureHandleCount,
 } from "@ubjs/core";
 import "@/polyfills";
+import { tracedCalls } from "@/tracer";
 
 // Initialize the callbacks for the module.
 // This will be hidden in the installation process.
@@ -660,4 +661,36 @@ function checkRemainingFutures(t: Asserts) {
     );
     t.end();
   });
+
+  await asyncTest("Calls into Rust are traced", async (t) => {
+    tracedCalls.length = 0;
+    greet("Tracer");
+    await sayAfter(1, "Tracer");
+    await t.assertThrowsAsync(MyError.Foo.instanceOf, () => fallibleMe(true));
+    const megaphone = newMegaphone();
+    await megaphone.sayAfter(1, "Tracer");
+
+    const [sync, async_, failed, , method] = tracedCalls;
+    t.assertEqual(tracedCalls.length, 5);
+    t.assertEqual(sync.module, "futures");
+    t.assertEqual(sync.name, "greet");
+    t.assertEqual(sync.args, '("Tracer")');
+    t.assertFalse(sync.isAsync);
+    t.assertTrue(sync.ok);
+
+    t.assertEqual(async_.name, "sayAfter");
+    t.assertEqual(async_.args, '(1, "Tracer")');
+    t.assertTrue(async_.isAsync);
+    t.assertTrue(async_.ok);
+    t.assertTrue(async_.durationMs >= 0);
+
+    t.assertEqual(failed.name, "fallibleMe");
+    t.assertFalse(failed.ok);
+    t.assertTrue(MyError.Foo.instanceOf(failed.error));
+
+    t.assertEqual(method.name, "Megaphone.sayAfter");
+    t.assertTrue(method.ok);
+    checkRemainingFutures(t);
+    t.end();
+  });
 })();
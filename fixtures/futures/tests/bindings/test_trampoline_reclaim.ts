@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// Reclaiming the trampolines of continuation callbacks, under the spec the
+// generator wrote for this crate.
+//
+// To run:
+//   cargo test -p uniffi-fixture-futures -- napi
+//
+// The generated spec declares `RustFutureContinuationCallback` `once`, so the
+// runtime frees a continuation's trampoline after its function is collected.
+// We poll through the raw scaffolding functions (rather than the generated
+// wrapper, whose continuation is one module-level function) so that every poll
+// passes a fresh closure, which nothing can cache.
+import myModule, { void_ } from "@/generated/futures";
+import getNativeModule, { uniffiMetrics } from "@/generated/futures-ffi";
+import { asyncTest } from "@/asserts";
+import "@/polyfills";
+import { setImmediate as tick } from "node:timers/promises";
+import v8 from "node:v8";
+import vm from "node:vm";
+
+v8.setFlagsFromString("--expose-gc");
+const gc: () => void = vm.runInNewContext("gc");
+
+myModule.initialize();
+
+const nm: any = getNativeModule();
+const CRATE = "uniffi_futures";
+const POLL_READY = 0;
+
+// Polls `void()` with a fresh continuation, then completes and frees it.
+async function callVoidWithFreshClosure(data: bigint): Promise<number> {
+  const handle = nm[`uniffi_${CRATE}_fn_func_void`]();
+  const pollResult = await new Promise<number>((resolve) =>
+    nm[`ffi_${CRATE}_rust_future_poll_void`](
+      handle,
+      (_data: bigint, result: number) => resolve(result),
+      data,
+    ),
+  );
+  const status = { code: 0 };
+  nm[`ffi_${CRATE}_rust_future_complete_void`](handle, status);
+  nm[`ffi_${CRATE}_rust_future_free_void`](handle);
+  return pollResult === POLL_READY ? status.code : -1;
+}
+
+// Finalizers run on the event loop after a collection, and core frees a
+// released trampoline only at a later release, so this takes a few rounds.
+async function collect(below: number): Promise<number> {
+  for (let round = 0; round < 50; round++) {
+    gc();
+    await tick();
+    if (uniffiMetrics().liveTrampolines < below) {
+      break;
+    }
+  }
+  return uniffiMetrics().liveTrampolines;
+}
+
+(async () => {
+  await asyncTest(
+    "100k fresh continuations do not leak their trampolines",
+    async (t) => {
+      for (let i = 0; i < 100_000; i++) {
+        t.assertEqual(await callVoidWithFreshClosure(BigInt(i)), 0);
+      }
+      const live = await collect(1000);
+      t.assertTrue(
+        live < 1000,
+        `expected collected continuations to free their trampolines, ${live} live`,
+      );
+      t.end();
+    },
+    120_000,
+  );
+
+  await asyncTest(
+    "the generated continuation keeps its one trampoline",
+    async (t) => {
+      const before = await collect(0);
+      for (let i = 0; i < 1000; i++) {
+        await void_();
+      }
+      const after = await collect(0);
+      t.assertTrue(
+        after <= before + 1,
+        `expected one trampoline for the generated continuation, ${after - before} more`,
+      );
+      t.end();
+    },
+  );
+})();
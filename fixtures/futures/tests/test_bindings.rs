@@ -9,4 +9,6 @@ ubrn_macros::build_foreign_language_testcases! {
     // aborts mid-call holding a Rust mutex, and `freeFunc` then aborts again
     // trying to re-lock it. Re-enable once TimerFuture drops host threads.
     "tests/bindings/test_futures.ts" => [Jsi, Napi],
+    // Napi only: it reads the runtime's trampoline counts.
+    "tests/bindings/test_trampoline_reclaim.ts" => [Napi],
 }
@@ -175,12 +175,10 @@ pub enum CallReturn {
 use crate::module::Module;
 
 impl Module {
-    /// Create a zeroed [`PreparedCall`] for the named function.
+    /// Create a zeroed [`PreparedCall`] for the named function, resolving the function
+    /// first if this is its first call.
     pub fn prepare_call(&self, fn_name: &str) -> Result<PreparedCall<'_>> {
-        let function = self
-            .functions
-            .get(fn_name)
-            .ok_or_else(|| Error::UnknownFunction(fn_name.to_string()))?;
+        let function = self.resolved_function(fn_name)?;
         Ok(PreparedCall {
             function,
             bytes: vec![0u8; function.arg_layout.total_size],
@@ -191,21 +189,36 @@ impl Module {
     /// bridge layer.
     ///
     /// Guards the call with lifecycle checks: returns `Err(Unloading)` if the
-    /// module is shutting down. The `PreparedCall` is consumed.
+    /// module is shutting down, or `Err(Reloaded)` if it has been reloaded. The `PreparedCall` is consumed.
     pub fn call(&self, args: PreparedCall<'_>) -> Result<CallReturn> {
         if !self.lifecycle.try_begin_call() {
-            return Err(Error::Unloading);
+            return Err(self.lifecycle.closed_error());
         }
         let result = args.invoke();
         self.lifecycle.end_call();
         result
     }
 
+    /// Invoke several [`PreparedCall`]s in order, under one lifecycle check.
+    ///
+    /// For a frontend whose every crossing into native code has a fixed cost, this pays it once
+    /// for the batch. All the calls run, whatever each returns: a failure is reported in that
+    /// call's own `RustCallStatus`, or in its entry of the returned `Vec`, and does not stop the
+    /// rest. Returns `Err(Unloading)`, having run none, if the module is shutting down.
+    pub fn call_batch(&self, calls: Vec<PreparedCall<'_>>) -> Result<Vec<Result<CallReturn>>> {
+        if !self.lifecycle.try_begin_call() {
+            return Err(self.lifecycle.closed_error());
+        }
+        let results = calls.into_iter().map(PreparedCall::invoke).collect();
+        self.lifecycle.end_call();
+        Ok(results)
+    }
+
     /// Copy JS-owned bytes into a new Rust-allocated `RustBufferC`.
     pub fn rustbuffer_from_bytes(&self, data: *const u8, len: usize) -> Result<RustBufferC> {
         use crate::ffi_c_types::{ForeignBytesC, RustBufferFromBytesFn, RustCallStatusC};
         if !self.lifecycle.try_begin_call() {
-            return Err(Error::Unloading);
+            return Err(self.lifecycle.closed_error());
         }
         // SAFETY: from_bytes_ptr was resolved via dlsym and transmuted to the correct
         // fn signature. data/len are caller-guaranteed valid. status is stack-allocated.
@@ -232,19 +245,28 @@ impl Module {
 
     /// Free a Rust-allocated `RustBufferC`.
     pub fn rustbuffer_free(&self, rb: RustBufferC) -> Result<()> {
-        use crate::ffi_c_types::{RustBufferFreeFn, RustCallStatusC};
         if !self.lifecycle.try_begin_call() {
-            return Err(Error::Unloading);
-        }
-        // SAFETY: free_ptr was resolved via dlsym; rb was allocated by the same library.
-        unsafe {
-            let func: RustBufferFreeFn = std::mem::transmute(self.rb_ops.free_ptr);
-            let mut status = RustCallStatusC::default();
-            func(rb, &mut status);
+            return Err(self.lifecycle.closed_error());
         }
+        // SAFETY: the call guard keeps the library open; rb was allocated by the same library.
+        unsafe { self.free_rust_buffer_unchecked(rb) };
         self.lifecycle.end_call();
         Ok(())
     }
+
+    /// Free `rb` through the library's `rustbuffer_free`, without a lifecycle check.
+    ///
+    /// # Safety
+    ///
+    /// The library must still be open, and `rb` must have been allocated by it.
+    pub(crate) unsafe fn free_rust_buffer_unchecked(&self, rb: RustBufferC) {
+        use crate::ffi_c_types::{RustBufferFreeFn, RustCallStatusC};
+        // SAFETY: free_ptr was resolved via dlsym, and the caller guarantees the library is
+        // still open. status is stack-allocated.
+        let func: RustBufferFreeFn = std::mem::transmute(self.rb_ops.free_ptr);
+        let mut status = RustCallStatusC::default();
+        func(rb, &mut status);
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -4,18 +4,35 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 //! Callback trampolines: C fn pointers the loaded library can invoke.
+//!
+//! A trampoline lives until the frontend releases it with
+//! [`Module::release_callback_trampoline`], which it may only do once the library can
+//! no longer call it: most are never released, as the library may hold them forever.
+//! A released trampoline is freed at a later release, once nothing is running through
+//! it; see [`Trampolines::sweep`].
+//!
+//! A call from another thread normally waits for the JS thread to run it. A
+//! [fire-and-forget](CallbackDef::is_fire_and_forget) callback need not, when the
+//! frontend can [post](PostDispatch) work to the JS thread: its invocations are queued
+//! on the trampoline and run there in the order they were made, and the calling thread
+//! returns at once. Past the queue limit, a call waits as any other would, which holds
+//! back a thread that makes them faster than the JS thread can run them. A call made on
+//! the JS thread itself is queued too while earlier ones are, rather than overtake them.
+//! Unloading the module drops whatever is queued, freeing the buffers those calls own.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::ffi::c_void;
-use std::sync::atomic::AtomicBool;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 use libffi::low;
 use libffi::middle::{Cif, Closure, Type};
 
 use crate::call::ArgLayout;
 use crate::cif::ffi_type_for;
+use crate::ffi_c_types::{RustBufferFreeFn, RustCallStatusC};
 use crate::module::Module;
+use crate::slot;
 use crate::spec::{CallbackDef, StructDef};
 use crate::{Error, FfiTypeDesc, Result};
 
@@ -36,7 +53,39 @@ pub type IsJsThreadFn = extern "C" fn(user_data: *const c_void) -> bool;
 /// An opaque function pointer suitable for storing in a VTable slot.
 pub type CallbackFnPtr = *const c_void;
 
-/// Per-trampoline state leaked to a stable address for the lifetime of the module.
+/// Signature for the function that frees the frontend's user data once its trampoline is freed.
+pub type FreeUserDataFn = extern "C" fn(user_data: *const c_void);
+
+/// Signature for the function a [`PostFn`] has the JS thread call: it runs the invocations queued
+/// on one trampoline.
+pub type DrainFn = extern "C" fn(drain_data: *const c_void);
+
+/// Signature for the function that has the JS thread call `drain(drain_data)` soon, without
+/// waiting for it. Returns `false` if it cannot, as when the JS thread is going away.
+pub type PostFn =
+    extern "C" fn(drain: DrainFn, drain_data: *const c_void, user_data: *const c_void) -> bool;
+
+/// The invocations a trampoline queues before a call waits, unless the frontend says otherwise.
+pub const DEFAULT_CALLBACK_QUEUE_LIMIT: usize = 1024;
+
+/// How a frontend runs [fire-and-forget](CallbackDef::is_fire_and_forget) callbacks without
+/// making the calling thread wait.
+#[derive(Debug, Clone, Copy)]
+pub struct PostDispatch {
+    pub post: PostFn,
+    /// Invocations queued on one trampoline, beyond which a call waits for its turn.
+    pub queue_limit: usize,
+}
+
+/// Invocations posted to the JS thread and not yet run.
+#[derive(Default)]
+struct PostQueue {
+    pending: VecDeque<Vec<u8>>,
+    /// Whether a drain has been posted that has not yet emptied the queue.
+    scheduled: bool,
+}
+
+/// Per-trampoline state at a stable address, until the trampoline is freed.
 pub(crate) struct TrampolineUserdata {
     arg_layout: ArgLayout,
     ret_size: usize,
@@ -45,15 +94,265 @@ pub(crate) struct TrampolineUserdata {
     is_js_thread: IsJsThreadFn,
     frontend_user_data: *const c_void,
     unloading_flag: Arc<AtomicBool>,
+    /// Invocations currently running through this trampoline, which must not be freed under them.
+    /// A posted drain counts as one until it finishes.
+    active: AtomicUsize,
+    /// Set only for fire-and-forget callbacks.
+    post: Option<PostDispatch>,
+    queue: Mutex<PostQueue>,
+    /// Offsets in the args of the `RustBuffer`s a call hands over, which must be freed if a
+    /// queued call is dropped rather than run.
+    owned_buffers: Vec<usize>,
+    /// The library's `rustbuffer_free`, for those.
+    rustbuffer_free: *const c_void,
+}
+
+impl TrampolineUserdata {
+    /// Recovered rather than propagated, as for [`Trampolines::state`]: the queue has no
+    /// invariant a panic could break that is worse than a dropped invocation.
+    fn queue(&self) -> MutexGuard<'_, PostQueue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Queue an invocation for the JS thread, posting a drain if none is pending.
+    ///
+    /// Gives the args back if the queue is full, for the caller to dispatch and wait as usual.
+    /// That keeps the order: the queued invocations are drained before the JS thread gets to it.
+    fn enqueue(&self, post: PostDispatch, args: Vec<u8>) -> std::result::Result<(), Vec<u8>> {
+        let mut queue = self.queue();
+        if queue.pending.len() >= post.queue_limit {
+            return Err(args);
+        }
+        queue.pending.push_back(args);
+        if queue.scheduled {
+            return Ok(());
+        }
+        // Counted as running until the drain finishes, so the trampoline is not freed under it.
+        self.active.fetch_add(1, Ordering::AcqRel);
+        // Posted under the lock, so a failure cannot lose invocations another thread queued
+        // behind a drain it believed was coming.
+        let drain_data = self as *const Self as *const c_void;
+        if (post.post)(drain_posted, drain_data, self.frontend_user_data) {
+            queue.scheduled = true;
+        } else {
+            // The JS thread is going away, and with it any chance to run these.
+            for args in queue.pending.drain(..) {
+                // SAFETY: the library is calling this trampoline, so it is still open.
+                unsafe { self.discard(args) };
+            }
+            self.active.fetch_sub(1, Ordering::AcqRel);
+        }
+        Ok(())
+    }
+
+    /// Queue an invocation made on the JS thread behind any still queued, so it cannot run
+    /// before calls made earlier. Gives the args back if none is, for the caller to run now.
+    ///
+    /// The queue limit does not apply: the JS thread cannot wait for its own drain.
+    fn enqueue_behind(&self, args: Vec<u8>) -> std::result::Result<(), Vec<u8>> {
+        let mut queue = self.queue();
+        if queue.pending.is_empty() {
+            return Err(args);
+        }
+        // Invocations are only ever queued with a drain scheduled, which will reach this one.
+        queue.pending.push_back(args);
+        Ok(())
+    }
+
+    /// Drop the queued invocations, which will now never run, freeing what they own.
+    ///
+    /// # Safety
+    ///
+    /// The library must still be open.
+    unsafe fn discard_queued(&self) {
+        let pending = std::mem::take(&mut self.queue().pending);
+        for args in pending {
+            self.discard(args);
+        }
+    }
+
+    /// Free the `RustBuffer`s that the invocation with `args` would have handed over.
+    ///
+    /// # Safety
+    ///
+    /// The library must still be open.
+    unsafe fn discard(&self, args: Vec<u8>) {
+        // SAFETY: resolved by dlsym from the open library.
+        let free: RustBufferFreeFn = std::mem::transmute(self.rustbuffer_free);
+        for &offset in &self.owned_buffers {
+            let mut status = RustCallStatusC::default();
+            free(slot::read_rust_buffer(&args[offset..]), &mut status);
+        }
+    }
+}
+
+/// The [`DrainFn`] posted by [`TrampolineUserdata::enqueue`]: runs queued invocations in order
+/// until none is left.
+extern "C" fn drain_posted(drain_data: *const c_void) {
+    // SAFETY: `drain_data` is the userdata that posted this drain, which counted itself active
+    // until the `fetch_sub` below, so it has not been freed.
+    let userdata = unsafe { &*(drain_data as *const TrampolineUserdata) };
+    loop {
+        let args = {
+            let mut queue = userdata.queue();
+            match queue.pending.pop_front() {
+                Some(args) => args,
+                None => {
+                    queue.scheduled = false;
+                    break;
+                }
+            }
+        };
+        // Unloading discards what is queued, so this was queued since; the library may be
+        // closed by now, so it is dropped without freeing what it owns.
+        if !userdata.unloading_flag.load(Ordering::Acquire) {
+            // Fire-and-forget callbacks return nothing.
+            let mut ret_buf = [0u8; 0];
+            (userdata.on_js_thread)(
+                args.as_ptr(),
+                ret_buf.as_mut_ptr(),
+                userdata.frontend_user_data,
+            );
+        }
+    }
+    // The last use of `userdata`, which may be freed from here on.
+    userdata.active.fetch_sub(1, Ordering::AcqRel);
 }
 
-// SAFETY: TrampolineUserdata is leaked with a stable address. Its function pointers
-// and frontend_user_data are stable for the program lifetime. The unloading_flag Arc
-// is Send+Sync.
+// SAFETY: TrampolineUserdata has a stable address until its trampoline is freed, which only
+// happens once the library can no longer call it. Its function pointers and
+// frontend_user_data are stable for that long. The unloading_flag Arc is Send+Sync.
 unsafe impl Send for TrampolineUserdata {}
-// SAFETY: All fields are immutable after construction; see Send impl above.
+// SAFETY: All fields are immutable after construction, but for the atomic `active`; see Send
+// impl above.
 unsafe impl Sync for TrampolineUserdata {}
 
+/// Counts an invocation in [`TrampolineUserdata::active`] for as long as it runs.
+struct ActiveInvocation<'a>(&'a AtomicUsize);
+
+impl<'a> ActiveInvocation<'a> {
+    fn begin(active: &'a AtomicUsize) -> Self {
+        active.fetch_add(1, Ordering::AcqRel);
+        Self(active)
+    }
+}
+
+impl Drop for ActiveInvocation<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// A trampoline: the libffi closure, and the userdata it was built over.
+struct Trampoline {
+    closure: Closure<'static>,
+    userdata: *mut TrampolineUserdata,
+}
+
+impl Trampoline {
+    fn is_idle(&self) -> bool {
+        // SAFETY: `userdata` stays valid until `free` below.
+        unsafe { (*self.userdata).active.load(Ordering::Acquire) == 0 }
+    }
+
+    /// Free the closure, then its userdata, then the frontend's user data.
+    ///
+    /// # Safety
+    ///
+    /// The library must no longer be able to call the trampoline, and no call may be running.
+    unsafe fn free(self, free_user_data: FreeUserDataFn) {
+        let Self { closure, userdata } = self;
+        drop(closure);
+        // SAFETY: created by `Box::into_raw` in `make_callback_trampoline`, and the closure
+        // borrowing it is gone.
+        let userdata = unsafe { Box::from_raw(userdata) };
+        free_user_data(userdata.frontend_user_data);
+    }
+}
+
+/// A released trampoline, waiting until it is safe to free.
+struct Released {
+    trampoline: Trampoline,
+    free_user_data: FreeUserDataFn,
+    /// Whether a sweep has already found this idle once.
+    seen_idle: bool,
+}
+
+/// The trampolines a module has built, by code pointer.
+#[derive(Default)]
+pub(crate) struct Trampolines(Mutex<TrampolineState>);
+
+#[derive(Default)]
+struct TrampolineState {
+    live: HashMap<usize, Trampoline>,
+    released: Vec<Released>,
+}
+
+// SAFETY: the raw pointers are only dereferenced under the mutex, or by the trampolines
+// themselves, and are freed only once those have stopped; see `Trampolines::sweep`.
+unsafe impl Send for TrampolineState {}
+
+impl Trampolines {
+    /// Recovered rather than propagated: a panic while the lock was held leaves at worst a
+    /// trampoline not yet freed, which is the behaviour of never releasing it.
+    fn state(&self) -> MutexGuard<'_, TrampolineState> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Drop every trampoline's queued invocations, freeing what they own.
+    ///
+    /// # Safety
+    ///
+    /// The library must still be open.
+    pub(crate) unsafe fn discard_queued(&self) {
+        let state = self.state();
+        let live = state.live.values();
+        let released = state.released.iter().map(|released| &released.trampoline);
+        for trampoline in live.chain(released) {
+            // SAFETY: `userdata` stays valid until the trampoline is freed, which takes the lock.
+            (*trampoline.userdata).discard_queued();
+        }
+    }
+
+    /// Free the released trampolines that are safe to free.
+    ///
+    /// Idle is not quite enough: an invocation counts itself out just before it returns
+    /// through the closure's code, so a trampoline is freed at the sweep *after* the one
+    /// which first found it idle.
+    fn sweep(state: &mut TrampolineState) {
+        let mut kept = Vec::with_capacity(state.released.len());
+        for mut released in state.released.drain(..) {
+            if !released.trampoline.is_idle() {
+                released.seen_idle = false;
+                kept.push(released);
+            } else if !released.seen_idle {
+                released.seen_idle = true;
+                kept.push(released);
+            } else {
+                // SAFETY: released, so the library can no longer call it, and idle across two
+                // sweeps, so nothing is running through it.
+                unsafe { released.trampoline.free(released.free_user_data) };
+            }
+        }
+        state.released = kept;
+    }
+}
+
+impl Drop for Trampolines {
+    fn drop(&mut self) {
+        // The library may still call a live trampoline after its module is gone, so those are
+        // leaked rather than freed. Released ones are not freed here either: an invocation may
+        // still be returning through one.
+        let state = std::mem::take(&mut *self.state());
+        for (_, trampoline) in state.live {
+            std::mem::forget(trampoline);
+        }
+        for released in state.released {
+            std::mem::forget(released);
+        }
+    }
+}
+
 /// Build a libffi CIF matching the callback's signature.
 ///
 /// CIF arg ordering: [declared_args, out_return_ptr?, RCS_ptr?]
@@ -96,8 +395,7 @@ unsafe extern "C" fn trampoline_body(
     args: *const *const c_void,
     userdata: &TrampolineUserdata,
 ) {
-    use std::sync::atomic::Ordering;
-
+    let _active = ActiveInvocation::begin(&userdata.active);
     let ret_ptr = ret as *mut c_void as *mut u8;
 
     // If the module is shutting down, zero the return value and bail.
@@ -126,9 +424,21 @@ unsafe extern "C" fn trampoline_body(
         );
     }
 
+    let is_js = (userdata.is_js_thread)(userdata.frontend_user_data);
+    let args_buf = match userdata.post {
+        Some(post) if !is_js => match userdata.enqueue(post, args_buf) {
+            Ok(()) => return,
+            Err(args_buf) => args_buf,
+        },
+        Some(_) => match userdata.enqueue_behind(args_buf) {
+            Ok(()) => return,
+            Err(args_buf) => args_buf,
+        },
+        None => args_buf,
+    };
+
     let mut ret_buf = vec![0u8; userdata.ret_size];
 
-    let is_js = (userdata.is_js_thread)(userdata.frontend_user_data);
     if is_js {
         (userdata.on_js_thread)(
             args_buf.as_ptr(),
@@ -166,15 +476,18 @@ fn return_size(def: &CallbackDef) -> Result<usize> {
 impl Module {
     /// Create a libffi closure that acts as a C callback the loaded library can invoke.
     ///
-    /// The closure checks the unloading flag, packs args, and dispatches to the JS thread.
-    /// Both the closure and its userdata are leaked (stable address, never freed) because
-    /// the Rust library may invoke the callback from any thread at any future time.
+    /// The closure checks the unloading flag, packs args, and dispatches to the JS thread. Given
+    /// `post`, a fire-and-forget callback is queued there rather than waited for.
+    /// Both the closure and its userdata live until the trampoline is released with
+    /// [`release_callback_trampoline`](Self::release_callback_trampoline), because the
+    /// Rust library may invoke the callback from any thread until then.
     pub fn make_callback_trampoline(
         self: &Arc<Self>,
         callback_name: &str,
         on_js_thread: OnJsThreadFn,
         dispatch: DispatchFn,
         is_js_thread: IsJsThreadFn,
+        post: Option<PostDispatch>,
         user_data: *const c_void,
     ) -> Result<CallbackFnPtr> {
         let def = self
@@ -195,27 +508,83 @@ impl Module {
         let ret_size = return_size(def)?;
 
         let userdata = Box::new(TrampolineUserdata {
-            arg_layout,
             ret_size,
             on_js_thread,
             dispatch,
             is_js_thread,
             frontend_user_data: user_data,
             unloading_flag: self.lifecycle.unloading_flag_arc(),
+            active: AtomicUsize::new(0),
+            post: post.filter(|_| def.is_fire_and_forget()),
+            queue: Mutex::default(),
+            owned_buffers: def
+                .args
+                .iter()
+                .zip(&arg_layout.arg_slots)
+                .filter(|(arg, _)| **arg == FfiTypeDesc::RustBuffer)
+                .map(|(_, slot)| slot.offset)
+                .collect(),
+            rustbuffer_free: self.rb_ops.free_ptr,
+            arg_layout,
         });
-        let userdata_ref: &'static TrampolineUserdata = Box::leak(userdata);
-
-        // Build the CIF for the closure (Closure takes ownership).
+        // Build the CIF before taking the userdata apart, so an error cannot leak it.
         let cif = build_callback_cif(def, &self.spec.structs)?;
+        let userdata = Box::into_raw(userdata);
+        // SAFETY: `userdata` is freed only after the closure borrowing it; see `Trampoline::free`.
+        let userdata_ref: &'static TrampolineUserdata = unsafe { &*userdata };
         let closure = Closure::new(cif, trampoline_body, userdata_ref);
 
-        // Extract the code pointer before forgetting the closure.
         // `code_ptr()` returns `&extern "C" fn()`—dereference to get the fn ptr value.
         let fn_ptr = *closure.code_ptr() as *const c_void;
-        std::mem::forget(closure);
+        self.trampolines
+            .state()
+            .live
+            .insert(fn_ptr as usize, Trampoline { closure, userdata });
 
         Ok(fn_ptr)
     }
+
+    /// Give up a trampoline made by [`make_callback_trampoline`](Self::make_callback_trampoline).
+    ///
+    /// It is freed at a later release, once no invocation is running through it, and
+    /// `free_user_data` is then called with the user data it was made with, on whichever thread
+    /// made that release. Releasing also frees what earlier releases left waiting.
+    ///
+    /// # Safety
+    ///
+    /// The library must no longer be able to call `fn_ptr`, whether or not it is running now:
+    /// it must have been told the pointer is dead, or have said itself that it is done with it.
+    pub unsafe fn release_callback_trampoline(
+        &self,
+        fn_ptr: CallbackFnPtr,
+        free_user_data: FreeUserDataFn,
+    ) {
+        let mut state = self.trampolines.state();
+        if let Some(trampoline) = state.live.remove(&(fn_ptr as usize)) {
+            state.released.push(Released {
+                trampoline,
+                free_user_data,
+                seen_idle: false,
+            });
+        }
+        Trampolines::sweep(&mut state);
+    }
+
+    /// The user data a trampoline was made with, while it is live: `None` for a pointer this
+    /// module did not make, or has released.
+    pub fn callback_trampoline_user_data(&self, fn_ptr: CallbackFnPtr) -> Option<*const c_void> {
+        let state = self.trampolines.state();
+        let trampoline = state.live.get(&(fn_ptr as usize))?;
+        // SAFETY: `userdata` stays valid until the trampoline is freed, and a live one is not.
+        Some(unsafe { (*trampoline.userdata).frontend_user_data })
+    }
+
+    /// The trampolines this module has made and not yet freed, including those released but
+    /// waiting to be freed.
+    pub fn live_callback_trampolines(&self) -> usize {
+        let state = self.trampolines.state();
+        state.live.len() + state.released.len()
+    }
 }
 
 /// One field in a VTable: the callback name it corresponds to and its function pointer.
@@ -15,7 +15,24 @@ pub enum Error {
     UnknownCallback(String),
     UnknownStruct(String),
     UnsupportedType(String),
+    InvalidSpec(String),
     Unloading,
+    /// The library follows a different uniffi contract than the bindings expect.
+    ContractVersionMismatch {
+        expected: u32,
+        found: u32,
+    },
+    /// An API checksum from the library differs from the one the bindings were made with.
+    ChecksumMismatch {
+        function: String,
+        expected: u16,
+        found: u16,
+    },
+    /// The module was replaced by [`Module::reload`](crate::Module::reload).
+    Reloaded,
+    /// [`Module::reload`](crate::Module::reload) was given a library that is already
+    /// loaded, so it would get the old code back.
+    AlreadyLoaded(String),
     Other(String),
 }
 
@@ -28,7 +45,32 @@ impl fmt::Display for Error {
             Error::UnknownCallback(s) => write!(f, "unknown callback: {s}"),
             Error::UnknownStruct(s) => write!(f, "unknown struct: {s}"),
             Error::UnsupportedType(s) => write!(f, "unsupported type: {s}"),
+            Error::InvalidSpec(s) => write!(f, "invalid module spec: {s}"),
             Error::Unloading => write!(f, "module is unloading or unloaded"),
+            Error::ContractVersionMismatch { expected, found } => write!(
+                f,
+                "uniffi contract version mismatch: the bindings expect {expected}, but the \
+                 library has {found}"
+            ),
+            Error::ChecksumMismatch {
+                function,
+                expected,
+                found,
+            } => write!(
+                f,
+                "API checksum mismatch for {function}: the bindings expect {expected}, but the \
+                 library has {found}; regenerate the bindings against this build"
+            ),
+            Error::Reloaded => write!(
+                f,
+                "module was reloaded: functions, objects and buffers from before the reload \
+                 can no longer be used"
+            ),
+            Error::AlreadyLoaded(s) => write!(
+                f,
+                "{s}: library is already loaded, so reloading it would not pick up a rebuild; \
+                 reload a copy at a new path"
+            ),
             Error::Other(s) => write!(f, "{s}"),
         }
     }
@@ -5,7 +5,7 @@
  */
 //! The abstract type language that drives the entire bridge.
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FfiTypeDesc {
     UInt8,
     Int8,
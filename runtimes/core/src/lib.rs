@@ -20,9 +20,14 @@ pub mod slot;
 pub mod spec;
 
 pub use call::{slot_size_align, ArgLayout, CallReturn, PreparedCall, SlotLayout};
-pub use callback::{CallbackFnPtr, DispatchFn, IsJsThreadFn, OnJsThreadFn, VTableField};
+pub use callback::{
+    CallbackFnPtr, DispatchFn, DrainFn, FreeUserDataFn, IsJsThreadFn, OnJsThreadFn, PostDispatch,
+    PostFn, VTableField, DEFAULT_CALLBACK_QUEUE_LIMIT,
+};
 pub use error::{Error, Result};
 pub use ffi_type::FfiTypeDesc;
 pub use library::LibraryHandle;
-pub use module::{AbortCallbacksFn, Module, StructFieldLayout, StructLayout};
-pub use spec::{CallbackDef, FunctionDef, ModuleSpec, RustBufferSymbols, StructDef, StructField};
+pub use module::{AbortCallbacksFn, Module, Resolution, StructFieldLayout, StructLayout};
+pub use spec::{
+    ApiChecks, CallbackDef, FunctionDef, ModuleSpec, RustBufferSymbols, StructDef, StructField,
+};
@@ -29,6 +29,36 @@ impl LibraryHandle {
         })
     }
 
+    /// Whether the system loader already has the library at `path` loaded, by this path
+    /// or another naming the same file. Opening it again would hand back that loaded
+    /// image, not the file as it is now.
+    ///
+    /// Only POSIX loaders can be asked without loading the library; elsewhere this
+    /// reports `false`.
+    #[cfg(unix)]
+    pub fn is_loaded(path: &str) -> bool {
+        // Not through dlopen2: on a miss it reads `dlerror()`, which glibc leaves NULL
+        // for RTLD_NOLOAD, and dereferences it.
+        let Ok(cpath) = std::ffi::CString::new(path) else {
+            return false;
+        };
+        // SAFETY: `cpath` is a valid C string. With RTLD_NOLOAD nothing is loaded, so
+        // no initializers run.
+        let handle = unsafe { libc::dlopen(cpath.as_ptr(), libc::RTLD_LAZY | libc::RTLD_NOLOAD) };
+        if handle.is_null() {
+            return false;
+        }
+        // SAFETY: A handle comes back only for a library already loaded. Closing it gives
+        // back the reference it took, which leaves that library loaded.
+        unsafe { libc::dlclose(handle) };
+        true
+    }
+
+    #[cfg(not(unix))]
+    pub fn is_loaded(_path: &str) -> bool {
+        false
+    }
+
     pub fn lookup_symbol(&self, name: &str) -> Result<*const c_void> {
         // SAFETY: symbol name came from a ModuleSpec; returned pointer is valid
         // for the lifetime of the LibraryHandle.
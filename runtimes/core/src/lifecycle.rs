@@ -5,12 +5,20 @@
  */
 //! Lifecycle state for orderly module shutdown.
 
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
 pub(crate) struct UnloadState {
     unloading: Arc<AtomicBool>,
     in_flight: AtomicU64,
+    /// Returned `RustBuffer`s the frontend has yet to release. See
+    /// [`Module::hold_external_rust_buffer`].
+    external_buffers: AtomicU64,
+    /// `unload_force` found buffers still held, and left the last release to close the library.
+    close_deferred: AtomicBool,
+    /// Unloaded by [`Module::reload`], so calls report [`Error::Reloaded`].
+    reloaded: AtomicBool,
 }
 
 impl UnloadState {
@@ -18,6 +26,9 @@ impl UnloadState {
         Self {
             unloading: Arc::new(AtomicBool::new(false)),
             in_flight: AtomicU64::new(0),
+            external_buffers: AtomicU64::new(0),
+            close_deferred: AtomicBool::new(false),
+            reloaded: AtomicBool::new(false),
         }
     }
 
@@ -32,6 +43,15 @@ impl UnloadState {
         Arc::clone(&self.unloading)
     }
 
+    /// The error for a call refused by [`try_begin_call`](Self::try_begin_call).
+    pub(crate) fn closed_error(&self) -> Error {
+        if self.reloaded.load(Ordering::Acquire) {
+            Error::Reloaded
+        } else {
+            Error::Unloading
+        }
+    }
+
     pub(crate) fn try_begin_call(&self) -> bool {
         if self.is_unloading() {
             return false;
@@ -61,8 +81,10 @@ impl UnloadState {
     }
 }
 
-use crate::module::Module;
-use crate::Result;
+use crate::ffi_c_types::RustBufferC;
+use crate::library::LibraryHandle;
+use crate::module::{Module, Resolution};
+use crate::{Error, Result};
 
 impl Module {
     /// Returns true if this module is shutting down or already shut down.
@@ -79,6 +101,8 @@ impl Module {
             return Ok(()); // already unloading/unloaded
         }
         (self.abort_callbacks)(self.abort_user_data);
+        // Queued callbacks will never run now. SAFETY: the library is closed only after this.
+        unsafe { self.trampolines.discard_queued() };
         self.lifecycle.wait_for_drain();
         Ok(())
     }
@@ -89,10 +113,19 @@ impl Module {
     ///
     /// The caller must ensure no code from the library is still executing after
     /// this call returns. In practice, `unload()` drains in-flight calls first.
+    ///
+    /// While the frontend still holds returned buffers (see
+    /// [`hold_external_rust_buffer`](Self::hold_external_rust_buffer)), the library stays
+    /// open: their memory, and the function that frees it, belong to it. Releasing the last
+    /// one closes it instead.
     pub fn unload_force(&self) -> Result<()> {
         self.unload()?;
-        let lib = self.library.lock().expect("library mutex poisoned").take();
-        if let Some(lib) = lib {
+        let mut library = self.library.lock().expect("library mutex poisoned");
+        if self.lifecycle.external_buffers.load(Ordering::Acquire) > 0 {
+            self.lifecycle.close_deferred.store(true, Ordering::Release);
+            return Ok(());
+        }
+        if let Some(lib) = library.take() {
             // SAFETY: unload() has drained all in-flight calls and set the
             // unloading flag, so no code from the library should be executing.
             unsafe {
@@ -101,4 +134,87 @@ impl Module {
         }
         Ok(())
     }
+
+    /// Open a rebuilt copy of the library at `library_path` against this module's spec, and
+    /// force-unload this module in favour of the one returned.
+    ///
+    /// Every function in the spec is resolved in the new library first, so one that no
+    /// longer matches is reported with this module still in service. Once the new module
+    /// is built, this one drains its in-flight calls and closes, and anything still using
+    /// it fails with [`Error::Reloaded`]. That includes object handles it returned: they
+    /// point into the old library's memory, so the frontend must not pass them to the new
+    /// module.
+    ///
+    /// The system loader hands back a library it already has loaded, so `library_path`
+    /// must name a file it has not: not this module's, and not one an earlier module still
+    /// holds open for its returned buffers. Otherwise this fails with
+    /// [`Error::AlreadyLoaded`], keeping this module in service.
+    pub fn reload(&self, library_path: &Path) -> Result<Arc<Module>> {
+        if self.is_unloading() {
+            return Err(self.lifecycle.closed_error());
+        }
+        let path_str = library_path
+            .to_str()
+            .ok_or_else(|| Error::LibraryOpen("path is not valid UTF-8".into()))?;
+        if LibraryHandle::is_loaded(path_str) {
+            return Err(Error::AlreadyLoaded(path_str.into()));
+        }
+        let next = Module::new(
+            library_path,
+            self.spec.clone(),
+            Resolution::Eager,
+            self.abort_callbacks,
+            self.abort_user_data,
+        )?;
+        // Before unloading, so a call refused from here on says why.
+        self.lifecycle.reloaded.store(true, Ordering::Release);
+        self.unload_force()?;
+        Ok(next)
+    }
+
+    /// Record that the frontend has taken a returned `RustBufferC` to release itself, with
+    /// [`release_external_rust_buffer`](Self::release_external_rust_buffer), once it is no
+    /// longer reachable — from a garbage collector's finalizer, say.
+    ///
+    /// Until then, [`unload_force`](Self::unload_force) leaves the library open, so the
+    /// buffer's memory stays valid however long it is held.
+    pub fn hold_external_rust_buffer(&self) {
+        // Under the library lock, so `unload_force` sees either this hold or a closed library.
+        let _library = self.library.lock().expect("library mutex poisoned");
+        self.lifecycle
+            .external_buffers
+            .fetch_add(1, Ordering::AcqRel);
+    }
+
+    /// Free a buffer taken with [`hold_external_rust_buffer`](Self::hold_external_rust_buffer).
+    ///
+    /// Unlike [`rustbuffer_free`](Self::rustbuffer_free), this works after `unload`: the
+    /// library is still open, since `unload_force` defers closing it to the last release.
+    pub fn release_external_rust_buffer(&self, rb: RustBufferC) {
+        let mut library = self.library.lock().expect("library mutex poisoned");
+        if library.is_some() {
+            // SAFETY: the library is open, and `rb` was returned by one of its functions.
+            unsafe { self.free_rust_buffer_unchecked(rb) };
+        }
+        let remaining = self
+            .lifecycle
+            .external_buffers
+            .fetch_sub(1, Ordering::AcqRel)
+            - 1;
+        if remaining == 0 && self.lifecycle.close_deferred.load(Ordering::Acquire) {
+            if let Some(lib) = library.take() {
+                // SAFETY: `unload_force` drained in-flight calls before deferring this, and
+                // the buffer just freed was the last of the library's memory in use.
+                unsafe {
+                    lib.close();
+                }
+            }
+        }
+    }
+
+    /// How many buffers taken with [`hold_external_rust_buffer`](Self::hold_external_rust_buffer)
+    /// have yet to be released.
+    pub fn live_external_rust_buffers(&self) -> u64 {
+        self.lifecycle.external_buffers.load(Ordering::Acquire)
+    }
 }
@@ -20,7 +20,7 @@
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 use libffi::low::CodePtr;
 use libffi::middle::Cif;
@@ -29,16 +29,38 @@ use crate::call::{ArgLayout, CallReturn};
 use crate::cif::ffi_type_for;
 use crate::ffi_c_types::RustBufferOps;
 use crate::library::LibraryHandle;
-use crate::spec::{CallbackDef, FunctionDef, ModuleSpec, StructDef};
+use crate::spec::{ApiChecks, CallbackDef, FunctionDef, ModuleSpec, StructDef};
 use crate::{Error, FfiTypeDesc, Result};
 
+/// When [`Module::new`] looks up each function's symbol and builds its CIF.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Resolution {
+    /// On the function's first [`prepare_call`](Module::prepare_call), cached thereafter.
+    ///
+    /// An app typically calls a small fraction of a crate's exported scaffolding, so
+    /// this keeps start-up cost proportional to what is used. A missing symbol
+    /// surfaces as an error from that first call rather than from construction.
+    #[default]
+    Lazy,
+    /// Every function during construction, which then fails on the first missing
+    /// symbol or unsupported signature. Meant for CI, to verify a spec against its
+    /// library without having to call everything in it.
+    Eager,
+}
+
+/// A function in the spec, and its resolution once something has asked for it.
+pub(crate) struct FunctionEntry {
+    def: FunctionDef,
+    resolved: OnceLock<ResolvedFunction>,
+}
+
 /// A function that has been resolved against a loaded library and pre-flighted with a CIF.
 ///
 /// All fields are immutable after construction. The [`invoke`](Self::invoke)
 /// method builds a stack-local `Arg` array from a filled byte buffer and
 /// dispatches through the pre-built CIF — the `Arg` pointers cannot escape.
 pub(crate) struct ResolvedFunction {
-    def: FunctionDef,
+    ret: FfiTypeDesc,
     symbol: *const c_void,
     cif: Cif,
     pub(crate) arg_layout: ArgLayout,
@@ -74,7 +96,7 @@ impl ResolvedFunction {
         // Each ffi_arg points into arg_bytes (alive for this call). code_ptr is a
         // resolved symbol from a loaded library alive for the Module's lifetime.
         let ret = unsafe {
-            match &self.def.ret {
+            match &self.ret {
                 FfiTypeDesc::Void => {
                     self.cif.call::<()>(code_ptr, ffi_args);
                     CallReturn::Void
@@ -127,22 +149,25 @@ pub type AbortCallbacksFn = extern "C" fn(user_data: *const c_void);
 /// A loaded UniFFI library with resolved symbols, pre-built CIFs, and lifecycle state.
 ///
 /// The `library` field is wrapped in `Mutex<Option<_>>` so that `unload_force` can
-/// take ownership and close it. All symbol lookups happen at construction time, so
-/// the hot path (call/rustbuffer ops) never touches the mutex.
+/// take ownership and close it. Symbols are looked up at construction time, or on a
+/// function's first call under [`Resolution::Lazy`], so the hot path (call/rustbuffer
+/// ops on an already-resolved function) never touches the mutex.
 pub struct Module {
     pub(crate) library: Mutex<Option<LibraryHandle>>,
     pub(crate) spec: ModuleSpec,
-    pub(crate) functions: HashMap<String, ResolvedFunction>,
+    pub(crate) functions: HashMap<String, FunctionEntry>,
     pub(crate) callback_cifs: HashMap<String, Cif>,
     pub(crate) struct_layouts: HashMap<String, StructLayout>,
     pub(crate) rb_ops: RustBufferOps,
     pub(crate) abort_callbacks: AbortCallbacksFn,
     pub(crate) abort_user_data: *const c_void,
     pub(crate) lifecycle: crate::lifecycle::UnloadState,
+    pub(crate) trampolines: crate::callback::Trampolines,
 }
 
-// SAFETY: all interior mutability is via atomics in UnloadState; ResolvedFunction is immutable
-// after construction. Raw pointers (abort_user_data, rb_ops) are stable for the Module lifetime.
+// SAFETY: All interior mutability is via atomics in UnloadState, the write-once `OnceLock` in each
+// FunctionEntry, and the mutex in Trampolines. ResolvedFunction is immutable after construction.
+// The raw pointers (abort_user_data, rb_ops) are stable for the Module lifetime.
 unsafe impl Send for Module {}
 // SAFETY: Mutex guards library; all other fields are immutable; see Send impl above.
 unsafe impl Sync for Module {}
@@ -152,10 +177,13 @@ unsafe impl Sync for Module {}
 // ---------------------------------------------------------------------------
 
 impl Module {
-    /// Open a library, resolve all symbols in `spec`, build CIFs, and return a ready Module.
+    /// Open a library, check it against the spec's [`ApiChecks`], resolve the RustBuffer
+    /// symbols, build callback CIFs and struct layouts, and return a ready Module.
+    /// `resolution` decides whether the functions in `spec` are resolved now or on first use.
     pub fn new(
         library_path: &Path,
         spec: ModuleSpec,
+        resolution: Resolution,
         abort_callbacks: AbortCallbacksFn,
         abort_user_data: *const c_void,
     ) -> Result<Arc<Self>> {
@@ -163,6 +191,9 @@ impl Module {
             .to_str()
             .ok_or_else(|| Error::LibraryOpen("path is not valid UTF-8".into()))?;
         let library = LibraryHandle::open(path_str)?;
+        if let Some(checks) = &spec.api_checks {
+            verify_api(&library, checks)?;
+        }
 
         // Resolve RustBuffer helper symbols.
         let alloc_ptr = library.lookup_symbol(&spec.rustbuffer_symbols.alloc)?;
@@ -174,30 +205,22 @@ impl Module {
             free_ptr,
         };
 
-        // Resolve each function + build its CIF.
-        let mut functions = HashMap::with_capacity(spec.functions.len());
-        for (name, def) in &spec.functions {
-            let symbol = library.lookup_symbol(name)?;
-            let mut cif_args: Vec<libffi::middle::Type> = def
-                .args
-                .iter()
-                .map(|t| ffi_type_for(t, &spec.structs))
-                .collect::<Result<Vec<_>>>()?;
-            if def.has_rust_call_status {
-                cif_args.push(libffi::middle::Type::pointer());
-            }
-            let cif_ret = ffi_type_for(&def.ret, &spec.structs)?;
-            let cif = Cif::new(cif_args, cif_ret);
-            let arg_layout = ArgLayout::compute(&def.args, def.has_rust_call_status)?;
-            functions.insert(
-                name.clone(),
-                ResolvedFunction {
+        let functions = spec
+            .functions
+            .iter()
+            .map(|(name, def)| {
+                let entry = FunctionEntry {
                     def: def.clone(),
-                    symbol,
-                    cif,
-                    arg_layout,
-                },
-            );
+                    resolved: OnceLock::new(),
+                };
+                (name.clone(), entry)
+            })
+            .collect::<HashMap<_, _>>();
+        if resolution == Resolution::Eager {
+            for (name, entry) in &functions {
+                let resolved = resolve_function(&library, name, &entry.def, &spec.structs)?;
+                let _ = entry.resolved.set(resolved);
+            }
         }
 
         // Pre-build CIFs for all callbacks (used by call_callback_ptr).
@@ -229,6 +252,7 @@ impl Module {
             abort_callbacks,
             abort_user_data,
             lifecycle: crate::lifecycle::UnloadState::new(),
+            trampolines: Default::default(),
         }))
     }
 }
@@ -243,9 +267,43 @@ impl Module {
         &self.rb_ops
     }
 
-    /// Look up the definition of a resolved function by name.
+    /// The names of the functions in the spec, in no particular order.
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(String::as_str)
+    }
+
+    /// Look up the definition of a function by name, whether or not it has been resolved.
     pub fn function_def(&self, fn_name: &str) -> Option<&FunctionDef> {
-        self.functions.get(fn_name).map(|r| &r.def)
+        self.functions.get(fn_name).map(|entry| &entry.def)
+    }
+
+    /// The resolved form of the named function, resolving it on first use.
+    ///
+    /// Two threads making the first call at once may both resolve it; one result is
+    /// kept and the other dropped, which is harmless as both describe the same symbol.
+    /// A failed resolution is not cached, so it is retried (and fails again) next time.
+    pub(crate) fn resolved_function(&self, fn_name: &str) -> Result<&ResolvedFunction> {
+        let entry = self
+            .functions
+            .get(fn_name)
+            .ok_or_else(|| Error::UnknownFunction(fn_name.to_string()))?;
+        if let Some(resolved) = entry.resolved.get() {
+            return Ok(resolved);
+        }
+        let resolved = {
+            let library = self.library.lock().expect("library mutex poisoned");
+            // `unload_force` has closed the library, so there is nothing to look up in.
+            let library = library
+                .as_ref()
+                .ok_or_else(|| self.lifecycle.closed_error())?;
+            resolve_function(library, fn_name, &entry.def, &self.spec.structs)?
+        };
+        Ok(entry.resolved.get_or_init(|| resolved))
+    }
+
+    /// The [checksum](ModuleSpec::checksum) of the spec this module was built from.
+    pub fn spec_checksum(&self) -> u64 {
+        self.spec.checksum()
     }
 
     /// Access the struct definitions from the module spec.
@@ -268,13 +326,74 @@ impl Module {
 }
 
 // Call methods (prepare_call, call, rustbuffer_*, call_callback_ptr) live in call.rs.
-// Callback methods (make_callback_trampoline, build_vtable) live in callback.rs.
-// Lifecycle methods (is_unloading, unload, unload_force) live in lifecycle.rs.
+// Callback methods (make_callback_trampoline, release_callback_trampoline, build_vtable) live
+// in callback.rs.
+// Lifecycle methods (is_unloading, unload, unload_force, reload) live in lifecycle.rs.
 
 // ---------------------------------------------------------------------------
 // Internal helpers
 // ---------------------------------------------------------------------------
 
+/// Call the library's contract version and checksum functions, failing on the first that
+/// disagrees with `checks`. Checksums are compared in name order, so the same mismatch is
+/// reported each time.
+fn verify_api(library: &LibraryHandle, checks: &ApiChecks) -> Result<()> {
+    let symbol = library.lookup_symbol(&checks.contract_version_symbol)?;
+    // SAFETY: uniffi's contract version function takes no arguments and returns a `u32`.
+    let contract_version: extern "C" fn() -> u32 = unsafe { std::mem::transmute(symbol) };
+    let found = contract_version();
+    if found != checks.contract_version {
+        return Err(Error::ContractVersionMismatch {
+            expected: checks.contract_version,
+            found,
+        });
+    }
+
+    let mut checksums: Vec<_> = checks.checksums.iter().collect();
+    checksums.sort();
+    for (name, &expected) in checksums {
+        let symbol = library.lookup_symbol(name)?;
+        // SAFETY: uniffi's checksum functions take no arguments and return a `u16`.
+        let checksum: extern "C" fn() -> u16 = unsafe { std::mem::transmute(symbol) };
+        let found = checksum();
+        if found != expected {
+            return Err(Error::ChecksumMismatch {
+                function: name.clone(),
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Look up a function's symbol in `library` and build its CIF and argument layout.
+fn resolve_function(
+    library: &LibraryHandle,
+    name: &str,
+    def: &FunctionDef,
+    structs: &HashMap<String, StructDef>,
+) -> Result<ResolvedFunction> {
+    let symbol = library.lookup_symbol(name)?;
+    let mut cif_args: Vec<libffi::middle::Type> = def
+        .args
+        .iter()
+        .map(|t| ffi_type_for(t, structs))
+        .collect::<Result<Vec<_>>>()?;
+    if def.has_rust_call_status {
+        cif_args.push(libffi::middle::Type::pointer());
+    }
+    let cif_ret = ffi_type_for(&def.ret, structs)?;
+    let cif = Cif::new(cif_args, cif_ret);
+    let arg_layout = ArgLayout::compute(&def.args, def.has_rust_call_status)?;
+    Ok(ResolvedFunction {
+        ret: def.ret.clone(),
+        symbol,
+        cif,
+        arg_layout,
+    })
+}
+
 /// Compute the C struct layout for a struct definition using libffi.
 fn compute_struct_layout(
     def: &StructDef,
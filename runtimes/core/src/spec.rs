@@ -6,15 +6,17 @@
 use crate::FfiTypeDesc;
 use std::collections::HashMap;
 
+pub mod binary;
+
 /// A single field in a struct definition.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructField {
     pub name: String,
     pub field_type: FfiTypeDesc,
 }
 
 /// A parsed struct definition (list of fields).
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct StructDef {
     pub fields: Vec<StructField>,
 }
@@ -23,35 +25,180 @@ pub struct StructDef {
 pub type StructDefs = HashMap<String, StructDef>;
 
 /// Describes the signature of a Rust FFI function exported by a UniFFI library.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionDef {
     pub args: Vec<FfiTypeDesc>,
     pub ret: FfiTypeDesc,
     pub has_rust_call_status: bool,
+    /// A returned `RustBuffer` is handed to the frontend to release when it is garbage
+    /// collected, rather than freed by the caller. See [`Module::hold_external_rust_buffer`].
+    ///
+    /// [`Module::hold_external_rust_buffer`]: crate::Module::hold_external_rust_buffer
+    pub external_return: bool,
 }
 
 /// Describes the signature of a callback interface method that JS must implement.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CallbackDef {
     pub args: Vec<FfiTypeDesc>,
     pub ret: FfiTypeDesc,
     pub has_rust_call_status: bool,
     pub out_return: bool,
+    /// The library calls each function pointer it is given for this callback exactly once,
+    /// and never after: a continuation, say, or a `free`. Its trampolines can then be freed.
+    pub once: bool,
+}
+
+impl CallbackDef {
+    /// Whether the library learns nothing from a call, and so need not wait for it to run: it
+    /// returns nothing, has no `RustCallStatus` or out-return, and takes every argument by
+    /// value, so none points into memory the library may reuse once the call returns.
+    pub fn is_fire_and_forget(&self) -> bool {
+        self.ret == FfiTypeDesc::Void
+            && !self.has_rust_call_status
+            && !self.out_return
+            && self.args.iter().all(|arg| {
+                !matches!(
+                    arg,
+                    FfiTypeDesc::ForeignBytes
+                        | FfiTypeDesc::RustCallStatus
+                        | FfiTypeDesc::Reference(_)
+                        | FfiTypeDesc::MutReference(_)
+                        | FfiTypeDesc::VoidPointer
+                )
+            })
+    }
 }
 
 /// The three RustBuffer lifecycle symbols that every UniFFI library exports.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RustBufferSymbols {
     pub alloc: String,
     pub free: String,
     pub from_bytes: String,
 }
 
+/// The uniffi contract version and API checksums the bindings were generated against,
+/// with the symbols the library reports its own through.
+///
+/// Each symbol is a no-argument function: the contract version one returns a `u32`, and
+/// each checksum one a `u16`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChecks {
+    pub contract_version_symbol: String,
+    pub contract_version: u32,
+    /// Expected checksums, keyed by the symbol that returns each.
+    pub checksums: HashMap<String, u16>,
+}
+
 /// Complete specification for a loaded UniFFI module.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ModuleSpec {
     pub rustbuffer_symbols: RustBufferSymbols,
     pub functions: HashMap<String, FunctionDef>,
     pub callbacks: HashMap<String, CallbackDef>,
     pub structs: HashMap<String, StructDef>,
+    /// Checked against the library before any of its functions are used. `None` skips
+    /// the check, as hand-written specs may.
+    pub api_checks: Option<ApiChecks>,
+}
+
+impl ModuleSpec {
+    /// A 64-bit FNV-1a hash of the spec's [binary encoding](binary::encode).
+    ///
+    /// Two specs have the same checksum when they name the same symbols with the same
+    /// signatures and flags, however their maps happen to be ordered.
+    pub fn checksum(&self) -> u64 {
+        binary::encode(self)
+            .iter()
+            .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+                (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callback(
+        args: Vec<FfiTypeDesc>,
+        ret: FfiTypeDesc,
+        has_rust_call_status: bool,
+    ) -> CallbackDef {
+        CallbackDef {
+            args,
+            ret,
+            has_rust_call_status,
+            out_return: false,
+            once: false,
+        }
+    }
+
+    #[test]
+    fn only_callbacks_returning_nothing_by_value_are_fire_and_forget() {
+        let continuation = callback(
+            vec![FfiTypeDesc::UInt64, FfiTypeDesc::Int8],
+            FfiTypeDesc::Void,
+            false,
+        );
+        assert!(continuation.is_fire_and_forget());
+
+        let owned_buffer = callback(
+            vec![FfiTypeDesc::UInt64, FfiTypeDesc::RustBuffer],
+            FfiTypeDesc::Void,
+            false,
+        );
+        assert!(owned_buffer.is_fire_and_forget());
+
+        let returns = callback(vec![FfiTypeDesc::UInt64], FfiTypeDesc::Int32, false);
+        assert!(!returns.is_fire_and_forget());
+
+        let reports = callback(vec![FfiTypeDesc::UInt64], FfiTypeDesc::Void, true);
+        assert!(!reports.is_fire_and_forget());
+
+        let borrows = callback(
+            vec![FfiTypeDesc::Reference(Box::new(FfiTypeDesc::UInt64))],
+            FfiTypeDesc::Void,
+            false,
+        );
+        assert!(!borrows.is_fire_and_forget());
+
+        let out_return = CallbackDef {
+            out_return: true,
+            ..callback(vec![FfiTypeDesc::UInt64], FfiTypeDesc::Void, false)
+        };
+        assert!(!out_return.is_fire_and_forget());
+    }
+
+    #[test]
+    fn checksum_tracks_signatures_not_map_order() {
+        let function = |ret| FunctionDef {
+            args: vec![FfiTypeDesc::Int32],
+            ret,
+            has_rust_call_status: true,
+            external_return: false,
+        };
+        let spec = |names: &[&str], ret: FfiTypeDesc| ModuleSpec {
+            rustbuffer_symbols: RustBufferSymbols {
+                alloc: "alloc".into(),
+                free: "free".into(),
+                from_bytes: "from_bytes".into(),
+            },
+            functions: names
+                .iter()
+                .map(|name| (name.to_string(), function(ret.clone())))
+                .collect(),
+            callbacks: HashMap::new(),
+            structs: HashMap::new(),
+            api_checks: None,
+        };
+
+        let forward = spec(&["a", "b", "c"], FfiTypeDesc::Int32);
+        let backward = spec(&["c", "b", "a"], FfiTypeDesc::Int32);
+        assert_eq!(forward.checksum(), backward.checksum());
+
+        let changed = spec(&["a", "b", "c"], FfiTypeDesc::Int64);
+        assert_ne!(forward.checksum(), changed.checksum());
+    }
 }
@@ -0,0 +1,553 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+//! A compact, versioned binary encoding of [`ModuleSpec`].
+//!
+//! Building a spec from a JS object costs one property lookup per field of
+//! every function, callback and struct, which adds up for crates with
+//! thousands of FFI functions. Codegen can instead emit the spec in this
+//! form, and a frontend hands the bytes straight to [`decode`].
+//!
+//! # Layout
+//!
+//! All integers are little-endian.
+//!
+//! ```text
+//! spec     := MAGIC version:u16 symbols functions callbacks structs checks
+//! symbols  := str(alloc) str(free) str(from_bytes)
+//! functions:= count:u32 { str(name) types(args) type(ret) flags:u8 }*
+//! callbacks:= count:u32 { str(name) types(args) type(ret) flags:u8 }*
+//! structs  := count:u32 { str(name) count:u32 { str(field) type }* }*
+//! checks   := 0:u8 | 1:u8 str(symbol) version:u32 count:u32 { str(symbol) checksum:u16 }*
+//! types    := count:u32 type*
+//! type     := tag:u8 [str(name) if Callback/Struct] [type if (Mut)Reference]
+//! str      := len:u32 utf8-bytes
+//! ```
+//!
+//! Function flags: bit 0 is `has_rust_call_status`, and bit 3 is
+//! `external_return`. Callback flags add bit 1 for `out_return` and bit 2 for
+//! `once`. The tag values are listed in [`tag`]; they are part of
+//! the format, so codegen that writes these bytes must use the same numbers.
+//! Any change to the layout bumps [`FORMAT_VERSION`].
+
+use std::collections::HashMap;
+
+use crate::spec::{
+    ApiChecks, CallbackDef, FunctionDef, ModuleSpec, RustBufferSymbols, StructDef, StructField,
+};
+use crate::{Error, FfiTypeDesc, Result};
+
+/// The leading bytes of every encoded spec.
+pub const MAGIC: &[u8; 4] = b"UBSP";
+
+/// The layout version this build reads and writes.
+pub const FORMAT_VERSION: u16 = 3;
+
+const FLAG_RUST_CALL_STATUS: u8 = 0b01;
+const FLAG_OUT_RETURN: u8 = 0b10;
+const FLAG_ONCE: u8 = 0b100;
+const FLAG_EXTERNAL_RETURN: u8 = 0b1000;
+
+/// The one-byte tag that opens each encoded [`FfiTypeDesc`].
+pub mod tag {
+    pub const UINT8: u8 = 0;
+    pub const INT8: u8 = 1;
+    pub const UINT16: u8 = 2;
+    pub const INT16: u8 = 3;
+    pub const UINT32: u8 = 4;
+    pub const INT32: u8 = 5;
+    pub const UINT64: u8 = 6;
+    pub const INT64: u8 = 7;
+    pub const FLOAT32: u8 = 8;
+    pub const FLOAT64: u8 = 9;
+    pub const HANDLE: u8 = 10;
+    pub const RUST_BUFFER: u8 = 11;
+    pub const FOREIGN_BYTES: u8 = 12;
+    pub const RUST_CALL_STATUS: u8 = 13;
+    pub const VOID_POINTER: u8 = 14;
+    pub const VOID: u8 = 15;
+    pub const CALLBACK: u8 = 16;
+    pub const STRUCT: u8 = 17;
+    pub const REFERENCE: u8 = 18;
+    pub const MUT_REFERENCE: u8 = 19;
+}
+
+/// Encode `spec` into the binary form [`decode`] reads.
+///
+/// Entries are written in name order, so the same spec always produces the
+/// same bytes.
+pub fn encode(spec: &ModuleSpec) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+
+    write_str(&mut out, &spec.rustbuffer_symbols.alloc);
+    write_str(&mut out, &spec.rustbuffer_symbols.free);
+    write_str(&mut out, &spec.rustbuffer_symbols.from_bytes);
+
+    write_count(&mut out, spec.functions.len());
+    for (name, def) in sorted(&spec.functions) {
+        write_str(&mut out, name);
+        write_types(&mut out, &def.args);
+        write_type(&mut out, &def.ret);
+        let mut flags = 0;
+        if def.has_rust_call_status {
+            flags |= FLAG_RUST_CALL_STATUS;
+        }
+        if def.external_return {
+            flags |= FLAG_EXTERNAL_RETURN;
+        }
+        out.push(flags);
+    }
+
+    write_count(&mut out, spec.callbacks.len());
+    for (name, def) in sorted(&spec.callbacks) {
+        write_str(&mut out, name);
+        write_types(&mut out, &def.args);
+        write_type(&mut out, &def.ret);
+        let mut flags = 0;
+        if def.has_rust_call_status {
+            flags |= FLAG_RUST_CALL_STATUS;
+        }
+        if def.out_return {
+            flags |= FLAG_OUT_RETURN;
+        }
+        if def.once {
+            flags |= FLAG_ONCE;
+        }
+        out.push(flags);
+    }
+
+    write_count(&mut out, spec.structs.len());
+    for (name, def) in sorted(&spec.structs) {
+        write_str(&mut out, name);
+        write_count(&mut out, def.fields.len());
+        for field in &def.fields {
+            write_str(&mut out, &field.name);
+            write_type(&mut out, &field.field_type);
+        }
+    }
+
+    match &spec.api_checks {
+        None => out.push(0),
+        Some(checks) => {
+            out.push(1);
+            write_str(&mut out, &checks.contract_version_symbol);
+            out.extend_from_slice(&checks.contract_version.to_le_bytes());
+            write_count(&mut out, checks.checksums.len());
+            for (symbol, checksum) in sorted(&checks.checksums) {
+                write_str(&mut out, symbol);
+                out.extend_from_slice(&checksum.to_le_bytes());
+            }
+        }
+    }
+
+    out
+}
+
+/// Decode a spec written by [`encode`], or by codegen following the same layout.
+///
+/// Fails with [`Error::InvalidSpec`] on a wrong magic, an unsupported
+/// version, truncated input, or trailing bytes.
+pub fn decode(bytes: &[u8]) -> Result<ModuleSpec> {
+    let mut r = Reader { bytes, pos: 0 };
+
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(Error::InvalidSpec("not an encoded module spec".into()));
+    }
+    let version = u16::from_le_bytes(r.array()?);
+    if version != FORMAT_VERSION {
+        return Err(Error::InvalidSpec(format!(
+            "format version {version} is not supported (expected {FORMAT_VERSION})"
+        )));
+    }
+
+    let rustbuffer_symbols = RustBufferSymbols {
+        alloc: r.string()?,
+        free: r.string()?,
+        from_bytes: r.string()?,
+    };
+
+    let count = r.count()?;
+    let mut functions = HashMap::with_capacity(count);
+    for _ in 0..count {
+        let name = r.string()?;
+        let args = r.types()?;
+        let ret = r.ffi_type()?;
+        let flags = r.u8()?;
+        functions.insert(
+            name,
+            FunctionDef {
+                args,
+                ret,
+                has_rust_call_status: flags & FLAG_RUST_CALL_STATUS != 0,
+                external_return: flags & FLAG_EXTERNAL_RETURN != 0,
+            },
+        );
+    }
+
+    let count = r.count()?;
+    let mut callbacks = HashMap::with_capacity(count);
+    for _ in 0..count {
+        let name = r.string()?;
+        let args = r.types()?;
+        let ret = r.ffi_type()?;
+        let flags = r.u8()?;
+        callbacks.insert(
+            name,
+            CallbackDef {
+                args,
+                ret,
+                has_rust_call_status: flags & FLAG_RUST_CALL_STATUS != 0,
+                out_return: flags & FLAG_OUT_RETURN != 0,
+                once: flags & FLAG_ONCE != 0,
+            },
+        );
+    }
+
+    let count = r.count()?;
+    let mut structs = HashMap::with_capacity(count);
+    for _ in 0..count {
+        let name = r.string()?;
+        let field_count = r.count()?;
+        let mut fields = Vec::with_capacity(field_count);
+        for _ in 0..field_count {
+            fields.push(StructField {
+                name: r.string()?,
+                field_type: r.ffi_type()?,
+            });
+        }
+        structs.insert(name, StructDef { fields });
+    }
+
+    let api_checks = match r.u8()? {
+        0 => None,
+        1 => {
+            let contract_version_symbol = r.string()?;
+            let contract_version = u32::from_le_bytes(r.array()?);
+            let count = r.count()?;
+            let mut checksums = HashMap::with_capacity(count);
+            for _ in 0..count {
+                let symbol = r.string()?;
+                checksums.insert(symbol, u16::from_le_bytes(r.array()?));
+            }
+            Some(ApiChecks {
+                contract_version_symbol,
+                contract_version,
+                checksums,
+            })
+        }
+        other => {
+            return Err(Error::InvalidSpec(format!(
+                "checks marker {other} at byte {} is neither 0 nor 1",
+                r.pos - 1
+            )))
+        }
+    };
+
+    if r.pos != bytes.len() {
+        return Err(Error::InvalidSpec(format!(
+            "{} trailing bytes after the spec",
+            bytes.len() - r.pos
+        )));
+    }
+
+    Ok(ModuleSpec {
+        rustbuffer_symbols,
+        functions,
+        callbacks,
+        structs,
+        api_checks,
+    })
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> Vec<(&String, &V)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    entries
+}
+
+fn write_count(out: &mut Vec<u8>, n: usize) {
+    let n = u32::try_from(n).expect("spec entry count exceeds u32");
+    out.extend_from_slice(&n.to_le_bytes());
+}
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    write_count(out, s.len());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn write_types(out: &mut Vec<u8>, types: &[FfiTypeDesc]) {
+    write_count(out, types.len());
+    for t in types {
+        write_type(out, t);
+    }
+}
+
+fn write_type(out: &mut Vec<u8>, desc: &FfiTypeDesc) {
+    match desc {
+        FfiTypeDesc::UInt8 => out.push(tag::UINT8),
+        FfiTypeDesc::Int8 => out.push(tag::INT8),
+        FfiTypeDesc::UInt16 => out.push(tag::UINT16),
+        FfiTypeDesc::Int16 => out.push(tag::INT16),
+        FfiTypeDesc::UInt32 => out.push(tag::UINT32),
+        FfiTypeDesc::Int32 => out.push(tag::INT32),
+        FfiTypeDesc::UInt64 => out.push(tag::UINT64),
+        FfiTypeDesc::Int64 => out.push(tag::INT64),
+        FfiTypeDesc::Float32 => out.push(tag::FLOAT32),
+        FfiTypeDesc::Float64 => out.push(tag::FLOAT64),
+        FfiTypeDesc::Handle => out.push(tag::HANDLE),
+        FfiTypeDesc::RustBuffer => out.push(tag::RUST_BUFFER),
+        FfiTypeDesc::ForeignBytes => out.push(tag::FOREIGN_BYTES),
+        FfiTypeDesc::RustCallStatus => out.push(tag::RUST_CALL_STATUS),
+        FfiTypeDesc::VoidPointer => out.push(tag::VOID_POINTER),
+        FfiTypeDesc::Void => out.push(tag::VOID),
+        FfiTypeDesc::Callback(name) => {
+            out.push(tag::CALLBACK);
+            write_str(out, name);
+        }
+        FfiTypeDesc::Struct(name) => {
+            out.push(tag::STRUCT);
+            write_str(out, name);
+        }
+        FfiTypeDesc::Reference(inner) => {
+            out.push(tag::REFERENCE);
+            write_type(out, inner);
+        }
+        FfiTypeDesc::MutReference(inner) => {
+            out.push(tag::MUT_REFERENCE);
+            write_type(out, inner);
+        }
+    }
+}
+
+/// A cursor over the encoded bytes. Every read is bounds-checked, so a
+/// truncated spec is an error rather than a panic.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| Error::InvalidSpec(format!("truncated at byte {}", self.pos)))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Read a length or entry count. Everything counted takes at least one byte,
+    /// so a count larger than what remains is corrupt; rejecting it here keeps a
+    /// bad count from driving a huge allocation.
+    fn count(&mut self) -> Result<usize> {
+        let at = self.pos;
+        let count = u32::from_le_bytes(self.array()?) as usize;
+        if count > self.bytes.len() - self.pos {
+            return Err(Error::InvalidSpec(format!(
+                "count {count} at byte {at} exceeds the input"
+            )));
+        }
+        Ok(count)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = self.count()?;
+        let at = self.pos;
+        let bytes = self.take(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| Error::InvalidSpec(format!("invalid UTF-8 in string at byte {at}")))
+    }
+
+    fn types(&mut self) -> Result<Vec<FfiTypeDesc>> {
+        let count = self.count()?;
+        (0..count).map(|_| self.ffi_type()).collect()
+    }
+
+    fn ffi_type(&mut self) -> Result<FfiTypeDesc> {
+        let at = self.pos;
+        Ok(match self.u8()? {
+            tag::UINT8 => FfiTypeDesc::UInt8,
+            tag::INT8 => FfiTypeDesc::Int8,
+            tag::UINT16 => FfiTypeDesc::UInt16,
+            tag::INT16 => FfiTypeDesc::Int16,
+            tag::UINT32 => FfiTypeDesc::UInt32,
+            tag::INT32 => FfiTypeDesc::Int32,
+            tag::UINT64 => FfiTypeDesc::UInt64,
+            tag::INT64 => FfiTypeDesc::Int64,
+            tag::FLOAT32 => FfiTypeDesc::Float32,
+            tag::FLOAT64 => FfiTypeDesc::Float64,
+            tag::HANDLE => FfiTypeDesc::Handle,
+            tag::RUST_BUFFER => FfiTypeDesc::RustBuffer,
+            tag::FOREIGN_BYTES => FfiTypeDesc::ForeignBytes,
+            tag::RUST_CALL_STATUS => FfiTypeDesc::RustCallStatus,
+            tag::VOID_POINTER => FfiTypeDesc::VoidPointer,
+            tag::VOID => FfiTypeDesc::Void,
+            tag::CALLBACK => FfiTypeDesc::Callback(self.string()?),
+            tag::STRUCT => FfiTypeDesc::Struct(self.string()?),
+            tag::REFERENCE => FfiTypeDesc::Reference(Box::new(self.ffi_type()?)),
+            tag::MUT_REFERENCE => FfiTypeDesc::MutReference(Box::new(self.ffi_type()?)),
+            other => {
+                return Err(Error::InvalidSpec(format!(
+                    "unknown type tag {other} at byte {at}"
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_spec() -> ModuleSpec {
+        let mut functions = HashMap::new();
+        functions.insert(
+            "uniffi_test_fn_add".to_string(),
+            FunctionDef {
+                args: vec![
+                    FfiTypeDesc::UInt32,
+                    FfiTypeDesc::Int64,
+                    FfiTypeDesc::RustBuffer,
+                ],
+                ret: FfiTypeDesc::Float64,
+                has_rust_call_status: true,
+                external_return: false,
+            },
+        );
+        functions.insert(
+            "uniffi_test_fn_void".to_string(),
+            FunctionDef {
+                args: vec![],
+                ret: FfiTypeDesc::Void,
+                has_rust_call_status: false,
+                external_return: false,
+            },
+        );
+        functions.insert(
+            "uniffi_test_fn_bytes".to_string(),
+            FunctionDef {
+                args: vec![],
+                ret: FfiTypeDesc::RustBuffer,
+                has_rust_call_status: true,
+                external_return: true,
+            },
+        );
+
+        let mut callbacks = HashMap::new();
+        callbacks.insert(
+            "CallbackInterfaceGetValue".to_string(),
+            CallbackDef {
+                args: vec![
+                    FfiTypeDesc::Handle,
+                    FfiTypeDesc::Callback("Complete".into()),
+                ],
+                ret: FfiTypeDesc::MutReference(Box::new(FfiTypeDesc::Struct("Future".into()))),
+                has_rust_call_status: true,
+                out_return: true,
+                once: false,
+            },
+        );
+        callbacks.insert(
+            "Continuation".to_string(),
+            CallbackDef {
+                args: vec![FfiTypeDesc::UInt64, FfiTypeDesc::Int8],
+                ret: FfiTypeDesc::Void,
+                has_rust_call_status: false,
+                out_return: false,
+                once: true,
+            },
+        );
+
+        let mut structs = HashMap::new();
+        structs.insert(
+            "VTable".to_string(),
+            StructDef {
+                fields: vec![
+                    StructField {
+                        name: "get_value".into(),
+                        field_type: FfiTypeDesc::Callback("CallbackInterfaceGetValue".into()),
+                    },
+                    StructField {
+                        name: "status".into(),
+                        field_type: FfiTypeDesc::Reference(Box::new(FfiTypeDesc::RustCallStatus)),
+                    },
+                ],
+            },
+        );
+
+        ModuleSpec {
+            rustbuffer_symbols: RustBufferSymbols {
+                alloc: "ffi_test_rustbuffer_alloc".into(),
+                free: "ffi_test_rustbuffer_free".into(),
+                from_bytes: "ffi_test_rustbuffer_from_bytes".into(),
+            },
+            functions,
+            callbacks,
+            structs,
+            api_checks: Some(ApiChecks {
+                contract_version_symbol: "ffi_test_uniffi_contract_version".into(),
+                contract_version: 29,
+                checksums: [
+                    ("uniffi_test_checksum_func_add".to_string(), 4021),
+                    ("uniffi_test_checksum_func_void".to_string(), 65535),
+                ]
+                .into_iter()
+                .collect(),
+            }),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let spec = sample_spec();
+        let bytes = encode(&spec);
+        assert_eq!(&bytes[..4], MAGIC);
+        assert_eq!(decode(&bytes).unwrap(), spec);
+    }
+
+    #[test]
+    fn round_trip_without_checks() {
+        let spec = ModuleSpec {
+            api_checks: None,
+            ..sample_spec()
+        };
+        assert_eq!(decode(&encode(&spec)).unwrap(), spec);
+    }
+
+    #[test]
+    fn encoding_is_deterministic() {
+        assert_eq!(encode(&sample_spec()), encode(&sample_spec()));
+    }
+
+    #[test]
+    fn rejects_wrong_version() {
+        let mut bytes = encode(&sample_spec());
+        bytes[4..6].copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(decode(&bytes), Err(Error::InvalidSpec(_))));
+    }
+
+    #[test]
+    fn rejects_truncated_and_trailing_input() {
+        let bytes = encode(&sample_spec());
+        for len in 0..bytes.len() {
+            assert!(decode(&bytes[..len]).is_err(), "accepted {len} bytes");
+        }
+        let mut padded = bytes;
+        padded.push(0);
+        assert!(matches!(decode(&padded), Err(Error::InvalidSpec(_))));
+    }
+}
@@ -0,0 +1,209 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type { NativeSignature, NativeType } from "./host.js";
+
+// `bun:ffi` cannot pass a struct by value, and UniFFI passes RustBuffers that
+// way in both directions. These write the C that bridges the gap: every struct
+// crosses into JS as a pointer to its bytes, and a struct result is written
+// through a trailing out pointer instead of being returned.
+
+/** The signature JS sees for a C function with `signature`. */
+export function flattenedSignature(
+  signature: NativeSignature,
+): NativeSignature {
+  const parameters: NativeType[] = signature.parameters.map((p) =>
+    isStruct(p) ? "pointer" : p,
+  );
+  if (isStruct(signature.result)) {
+    return { parameters: [...parameters, "pointer"], result: "void" };
+  }
+  return { parameters, result: signature.result };
+}
+
+export function hasStruct(signature: NativeSignature): boolean {
+  return signature.parameters.some(isStruct) || isStruct(signature.result);
+}
+
+export function isStruct(t: NativeType): t is { struct: NativeType[] } {
+  return typeof t === "object";
+}
+
+export function nativeSize(t: NativeType): number {
+  return nativeSizeAlign(t)[0];
+}
+
+function nativeSizeAlign(t: NativeType): [number, number] {
+  if (isStruct(t)) {
+    let offset = 0;
+    let align = 1;
+    for (const field of t.struct) {
+      const [size, fieldAlign] = nativeSizeAlign(field);
+      offset = Math.ceil(offset / fieldAlign) * fieldAlign + size;
+      align = Math.max(align, fieldAlign);
+    }
+    return [Math.ceil(offset / align) * align, align];
+  }
+  switch (t) {
+    case "u8":
+    case "i8":
+      return [1, 1];
+    case "u16":
+    case "i16":
+      return [2, 2];
+    case "u32":
+    case "i32":
+    case "f32":
+      return [4, 4];
+    case "void":
+      return [0, 1];
+    default:
+      return [8, 8];
+  }
+}
+
+/**
+ * C for a function `call(fn, ...)` that calls the function pointer `fn`,
+ * which has `signature`, with the flattened arguments.
+ */
+export function callShimSource(signature: NativeSignature): string {
+  const types = new StructTypes();
+  const real = cFunctionType(types, signature);
+  const params = signature.parameters.map(
+    (p, i) => `${isStruct(p) ? `const ${types.name(p)} *` : cScalar(p)} a${i}`,
+  );
+  const args = signature.parameters.map((p, i) =>
+    isStruct(p) ? `*a${i}` : `a${i}`,
+  );
+  const call = `((${real})fn)(${args.join(", ")})`;
+  let body: string;
+  if (isStruct(signature.result)) {
+    params.push(`${types.name(signature.result)} *out`);
+    body = `*out = ${call};`;
+  } else if (signature.result === "void") {
+    body = `${call};`;
+  } else {
+    body = `return ${call};`;
+  }
+  const result = isStruct(signature.result)
+    ? "void"
+    : cScalar(signature.result);
+  return [
+    PRELUDE,
+    ...types.definitions(),
+    `${result} call(void *fn${params.map((p) => `, ${p}`).join("")}) {`,
+    `  ${body}`,
+    `}`,
+    "",
+  ].join("\n");
+}
+
+/**
+ * C for a trampoline with `signature` that forwards to a flattened JS
+ * callback, installed with `set_target` and found with `address`.
+ */
+export function callbackShimSource(signature: NativeSignature): string {
+  const types = new StructTypes();
+  const flat = flattenedSignature(signature);
+  const target = `${cScalar(flat.result)} (*)(${
+    flat.parameters.map(cScalar).join(", ") || "void"
+  })`;
+  const params = signature.parameters.map(
+    (p, i) => `${cType(types, p)} a${i}`,
+  );
+  const args = signature.parameters.map((p, i) =>
+    isStruct(p) ? `&a${i}` : `a${i}`,
+  );
+  const lines: string[] = [];
+  if (isStruct(signature.result)) {
+    const name = types.name(signature.result);
+    lines.push(`  ${name} out;`);
+    lines.push(`  ((${target})target)(${[...args, "&out"].join(", ")});`);
+    lines.push(`  return out;`);
+  } else if (signature.result === "void") {
+    lines.push(`  ((${target})target)(${args.join(", ")});`);
+  } else {
+    lines.push(`  return ((${target})target)(${args.join(", ")});`);
+  }
+  return [
+    PRELUDE,
+    ...types.definitions(),
+    `static void *target;`,
+    `void set_target(void *p) { target = p; }`,
+    `static ${cType(types, signature.result)} trampoline(${
+      params.join(", ") || "void"
+    }) {`,
+    ...lines,
+    `}`,
+    `void *address(void) { return (void *)trampoline; }`,
+    "",
+  ].join("\n");
+}
+
+const PRELUDE = "#include <stdint.h>";
+
+function cFunctionType(types: StructTypes, signature: NativeSignature): string {
+  const params = signature.parameters.map((p) => cType(types, p));
+  return `${cType(types, signature.result)} (*)(${
+    params.join(", ") || "void"
+  })`;
+}
+
+function cType(types: StructTypes, t: NativeType): string {
+  return isStruct(t) ? types.name(t) : cScalar(t);
+}
+
+function cScalar(t: NativeType): string {
+  switch (t) {
+    case "u8":
+      return "uint8_t";
+    case "i8":
+      return "int8_t";
+    case "u16":
+      return "uint16_t";
+    case "i16":
+      return "int16_t";
+    case "u32":
+      return "uint32_t";
+    case "i32":
+      return "int32_t";
+    case "u64":
+      return "uint64_t";
+    case "i64":
+      return "int64_t";
+    case "f32":
+      return "float";
+    case "f64":
+      return "double";
+    case "pointer":
+      return "void *";
+    case "void":
+      return "void";
+    default:
+      throw new Error("structs have no scalar C type");
+  }
+}
+
+/** Names each distinct struct shape, and emits typedefs in dependency order. */
+class StructTypes {
+  private names = new Map<string, string>();
+  private defs: string[] = [];
+
+  name(t: { struct: NativeType[] }): string {
+    const key = JSON.stringify(t);
+    let name = this.names.get(key);
+    if (name === undefined) {
+      const fields = t.struct.map((f, i) => `${cType(this, f)} f${i};`);
+      name = `s${this.names.size}`;
+      this.names.set(key, name);
+      this.defs.push(`typedef struct { ${fields.join(" ")} } ${name};`);
+    }
+    return name;
+  }
+
+  definitions(): string[] {
+    return this.defs;
+  }
+}
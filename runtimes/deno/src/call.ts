@@ -0,0 +1,253 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type { FfiTypeDesc } from "./ffi-type.js";
+import type { Host, NativeFunction, NativeSignature } from "./host.js";
+import {
+  RUST_CALL_STATUS_SIZE,
+  dataView,
+  encodeForeignBytes,
+  nativeType,
+  readCallStatusCode,
+  readCallStatusErrorBuf,
+  readScalar,
+  sizeAlign,
+  structLayout,
+  writeRustBuffer,
+  writeScalar,
+  type StructLayout,
+} from "./layout.js";
+import type { RustBuffers } from "./rust-buffer.js";
+import {
+  callbackPointer,
+  type CallbackDef,
+  type CallbackCache,
+} from "./callback.js";
+
+export interface FunctionDef {
+  args: readonly FfiTypeDesc[];
+  ret: FfiTypeDesc;
+  hasRustCallStatus: boolean;
+}
+
+/** The trailing status argument, set in place after the call. */
+export interface CallStatus {
+  code: number;
+  errorBuf?: Uint8Array;
+}
+
+/** Everything marshalling needs, shared by one registered module. */
+export interface Context {
+  host: Host;
+  structs: Map<string, StructLayout>;
+  callbackDefs: Map<string, CallbackDef>;
+  buffers: RustBuffers;
+  callbacks: CallbackCache;
+  /** Structs passed by reference, which the library may keep forever. */
+  pinned: Map<object, Uint8Array>;
+}
+
+export function functionSignature(
+  def: FunctionDef,
+  structs: Map<string, StructLayout>,
+): NativeSignature {
+  const parameters = def.args.map((t) => nativeType(t, structs));
+  if (def.hasRustCallStatus) parameters.push("pointer");
+  return { parameters, result: nativeType(def.ret, structs) };
+}
+
+/**
+ * The JS function for `name`. `resolve` binds the native symbol, and is left
+ * until the first call so a library missing a symbol only fails when that
+ * function is used.
+ */
+export function makeFunction(
+  ctx: Context,
+  name: string,
+  def: FunctionDef,
+  resolve: () => NativeFunction,
+): (...args: any[]) => any {
+  let native: NativeFunction | undefined;
+  return (...args: any[]) => {
+    native ??= resolve();
+    return invoke(ctx, native, name, def, args);
+  };
+}
+
+export function invoke(
+  ctx: Context,
+  native: NativeFunction,
+  name: string,
+  def: FunctionDef,
+  args: any[],
+): any {
+  const expected = def.args.length + (def.hasRustCallStatus ? 1 : 0);
+  if (args.length !== expected) {
+    throw new TypeError(
+      `${name}: expected ${expected} arguments, got ${args.length}`,
+    );
+  }
+  const lowered = def.args.map((t, i) => lowerArg(ctx, t, args[i]));
+  let status: Uint8Array | undefined;
+  if (def.hasRustCallStatus) {
+    status = new Uint8Array(RUST_CALL_STATUS_SIZE);
+    lowered.push(ctx.host.addressOf(status));
+  }
+  const result = native(...lowered);
+  if (status !== undefined) {
+    const out: CallStatus = args[def.args.length];
+    const code = readCallStatusCode(status);
+    out.code = code;
+    if (code !== 0) {
+      out.errorBuf = ctx.buffers.takeParts(readCallStatusErrorBuf(status));
+      return undefined;
+    }
+  }
+  return liftReturn(ctx, def.ret, result);
+}
+
+/** A JS value as an argument the library receives. */
+export function lowerArg(ctx: Context, t: FfiTypeDesc, value: any): unknown {
+  switch (t.tag) {
+    case "UInt64":
+    case "Int64":
+    case "Handle":
+    case "VoidPointer":
+      return BigInt(value ?? 0);
+    case "RustBuffer":
+      return ctx.buffers.lower(value);
+    case "ForeignBytes":
+      return encodeForeignBytes(value.byteLength, ctx.host.addressOf(value));
+    case "Struct":
+      return encodeStruct(ctx, t.name, value);
+    case "Callback":
+      // Passed for this call alone, like a future's continuation, so it holds
+      // the event loop open until the library calls it.
+      return callbackPointer(ctx, t.name, value, { awaited: true });
+    case "Reference":
+    case "MutReference":
+      return lowerReference(ctx, t.inner, value);
+    case "RustCallStatus":
+      throw new TypeError("RustCallStatus cannot be passed by value");
+    default:
+      return value;
+  }
+}
+
+function lowerReference(ctx: Context, inner: FfiTypeDesc, value: any): bigint {
+  if (typeof value === "bigint") return value;
+  if (value instanceof Uint8Array) return ctx.host.addressOf(value);
+  if (inner.tag !== "Struct") {
+    throw new TypeError(
+      `expected a bigint pointer or Uint8Array for a reference to ${inner.tag}`,
+    );
+  }
+  let bytes = ctx.pinned.get(value);
+  if (bytes === undefined) {
+    bytes = encodeStruct(ctx, inner.name, value);
+    ctx.pinned.set(value, bytes);
+  }
+  return ctx.host.addressOf(bytes);
+}
+
+/** A value the library returned, as JS sees it. */
+export function liftReturn(ctx: Context, t: FfiTypeDesc, value: any): any {
+  switch (t.tag) {
+    case "RustBuffer":
+      return ctx.buffers.adopt(value);
+    case "Struct":
+      return decodeStruct(ctx, t.name, value);
+    case "Void":
+      return undefined;
+    default:
+      return value;
+  }
+}
+
+/** Lay out a JS object as struct `name`, fields keyed by their raw names. */
+export function encodeStruct(
+  ctx: Context,
+  name: string,
+  value: Record<string, any>,
+): Uint8Array {
+  const layout = structLayout(ctx.structs, name);
+  const bytes = new Uint8Array(layout.size);
+  for (const field of layout.fields) {
+    writeValue(ctx, bytes, field.offset, field.type, value[field.name]);
+  }
+  return bytes;
+}
+
+function decodeStruct(
+  ctx: Context,
+  name: string,
+  bytes: Uint8Array,
+): Record<string, any> {
+  const layout = structLayout(ctx.structs, name);
+  const result: Record<string, any> = {};
+  for (const field of layout.fields) {
+    const [size] = sizeAlign(field.type, ctx.structs);
+    const slice = bytes.subarray(field.offset, field.offset + size);
+    result[field.name] =
+      field.type.tag === "RustBuffer" || field.type.tag === "Struct"
+        ? liftReturn(ctx, field.type, slice)
+        : readScalar(dataView(bytes), field.offset, field.type);
+  }
+  return result;
+}
+
+/** Write `value` as type `t` into native-layout memory. */
+export function writeValue(
+  ctx: Context,
+  bytes: Uint8Array,
+  offset: number,
+  t: FfiTypeDesc,
+  value: any,
+): void {
+  switch (t.tag) {
+    case "Void":
+      return;
+    case "RustBuffer":
+      bytes.set(ctx.buffers.lower(value ?? new Uint8Array(0)), offset);
+      return;
+    case "RustCallStatus": {
+      const status: CallStatus = value ?? { code: 0 };
+      dataView(bytes).setInt8(offset, status.code);
+      if (status.errorBuf !== undefined) {
+        writeRustBuffer(
+          bytes,
+          ctx.buffers.fromBytes(status.errorBuf),
+          offset + 8,
+        );
+      }
+      return;
+    }
+    case "ForeignBytes":
+    case "Struct":
+      bytes.set(lowerArg(ctx, t, value) as Uint8Array, offset);
+      return;
+    case "Callback":
+      writeScalar(
+        dataView(bytes),
+        offset,
+        t,
+        value === undefined || value === null
+          ? 0n
+          : callbackPointer(ctx, t.name, value, { awaited: false }),
+      );
+      return;
+    case "Reference":
+    case "MutReference":
+      writeScalar(
+        dataView(bytes),
+        offset,
+        t,
+        lowerReference(ctx, t.inner, value),
+      );
+      return;
+    default:
+      writeScalar(dataView(bytes), offset, t, value ?? 0);
+  }
+}
@@ -0,0 +1,248 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type { FfiTypeDesc } from "./ffi-type.js";
+import type { HostCallback, NativeSignature } from "./host.js";
+import {
+  RUST_CALL_STATUS_SIZE,
+  nativeType,
+  sizeAlign,
+  writeCallStatusCode,
+  writeCallStatusErrorBuf,
+  type StructLayout,
+} from "./layout.js";
+import {
+  invoke,
+  functionSignature,
+  writeValue,
+  type CallStatus,
+  type Context,
+} from "./call.js";
+
+export interface CallbackDef {
+  args: readonly FfiTypeDesc[];
+  ret: FfiTypeDesc;
+  hasRustCallStatus: boolean;
+  outReturn?: boolean;
+}
+
+/**
+ * What a sync vtable method returns when it has both an out-return and a
+ * status.
+ */
+interface UniffiResult {
+  code: number;
+  pointee?: unknown;
+  errorBuf?: Uint8Array;
+}
+
+const CALL_STATUS_UNEXPECTED_ERROR = 2;
+
+/**
+ * Native callbacks, per callback definition and JS function. The library may
+ * call any of them at any later time, so none is ever released.
+ */
+export type CallbackCache = Map<string, Map<Function, Entry>>;
+
+interface Entry {
+  callback: HostCallback;
+  /** Calls the library has yet to make through an awaited pass. */
+  pending: number;
+}
+
+/**
+ * The C function pointer for `fn`, implementing callback `name`.
+ *
+ * An `awaited` callback, handed to a single call, holds the event loop open
+ * until the library calls it; one stored in a struct does not.
+ */
+export function callbackPointer(
+  ctx: Context,
+  name: string,
+  fn: (...args: any[]) => any,
+  { awaited }: { awaited: boolean },
+): bigint {
+  let byFn = ctx.callbacks.get(name);
+  if (byFn === undefined) {
+    byFn = new Map();
+    ctx.callbacks.set(name, byFn);
+  }
+  let entry = byFn.get(fn);
+  if (entry === undefined) {
+    const def = callbackDef(ctx, name);
+    const created: Entry = { callback: undefined!, pending: 0 };
+    created.callback = ctx.host.callback(
+      callbackSignature(def, ctx.structs),
+      (...args: any[]) => {
+        if (created.pending > 0) {
+          created.pending--;
+          created.callback.unref();
+        }
+        return dispatch(ctx, name, def, fn, args);
+      },
+    );
+    entry = created;
+    byFn.set(fn, entry);
+  }
+  if (awaited) {
+    entry.pending++;
+    entry.callback.ref();
+  }
+  return entry.callback.pointer;
+}
+
+/**
+ * The native signature: the declared args, then the out-return pointer, then
+ * the status pointer. With an out-return the callback itself returns void.
+ */
+export function callbackSignature(
+  def: CallbackDef,
+  structs: Map<string, StructLayout>,
+): NativeSignature {
+  const parameters = def.args.map((t) => nativeType(t, structs));
+  if (def.outReturn) parameters.push("pointer");
+  if (def.hasRustCallStatus) parameters.push("pointer");
+  return {
+    parameters,
+    result: def.outReturn ? "void" : nativeType(def.ret, structs),
+  };
+}
+
+function callbackDef(ctx: Context, name: string): CallbackDef {
+  const def = ctx.callbackDefs.get(name);
+  if (def === undefined) {
+    throw new Error(`unknown callback "${name}"`);
+  }
+  return def;
+}
+
+function dispatch(
+  ctx: Context,
+  name: string,
+  def: CallbackDef,
+  fn: (...args: any[]) => any,
+  native: any[],
+): unknown {
+  let idx = def.args.length;
+  const outPtr: bigint | undefined = def.outReturn ? native[idx++] : undefined;
+  const statusPtr: bigint | undefined = def.hasRustCallStatus
+    ? native[idx++]
+    : undefined;
+  const args = def.args.map((t, i) => liftArg(ctx, t, native[i]));
+
+  try {
+    if (outPtr !== undefined) {
+      if (statusPtr !== undefined) {
+        const result: UniffiResult = fn(...args);
+        const code = result?.code ?? 0;
+        if (code === 0) writeOut(ctx, outPtr, def.ret, result?.pointee);
+        writeStatus(ctx, statusPtr, { code, errorBuf: result?.errorBuf });
+      } else {
+        writeOut(ctx, outPtr, def.ret, fn(...args));
+      }
+      return undefined;
+    }
+    if (statusPtr !== undefined) {
+      const status: CallStatus = { code: 0 };
+      const result = fn(...args, status);
+      writeStatus(ctx, statusPtr, status);
+      return lowerReturn(ctx, def.ret, result);
+    }
+    return lowerReturn(ctx, def.ret, fn(...args));
+  } catch (e) {
+    // An exception cannot unwind into the library. Report it through the
+    // status where there is one, and otherwise log it.
+    if (statusPtr !== undefined) {
+      writeStatus(ctx, statusPtr, {
+        code: CALL_STATUS_UNEXPECTED_ERROR,
+        errorBuf: new TextEncoder().encode(String(e)),
+      });
+    } else {
+      // eslint-disable-next-line no-console
+      console.error(`uncaught exception in callback ${name}:`, e);
+    }
+    return def.outReturn ? undefined : zeroReturn(ctx, def.ret);
+  }
+}
+
+/** A value the library passed to a callback, as JS sees it. */
+function liftArg(ctx: Context, t: FfiTypeDesc, value: any): any {
+  switch (t.tag) {
+    case "RustBuffer":
+      // The library handed us ownership; JS gets a copy and the buffer goes.
+      return ctx.buffers.take(value);
+    case "Callback":
+      return wrapCallback(ctx, t.name, value);
+    default:
+      return value;
+  }
+}
+
+/** A function pointer from the library, callable like a registered function. */
+function wrapCallback(
+  ctx: Context,
+  name: string,
+  pointer: bigint,
+): (...args: any[]) => any {
+  const def = callbackDef(ctx, name);
+  if (def.outReturn) {
+    throw new Error(
+      `calling library callback "${name}", which has an out-return, ` +
+        "is not supported",
+    );
+  }
+  const fnDef = {
+    args: def.args,
+    ret: def.ret,
+    hasRustCallStatus: def.hasRustCallStatus,
+  };
+  const native = ctx.host.fnPointer(
+    pointer,
+    functionSignature(fnDef, ctx.structs),
+  );
+  return (...args: any[]) => invoke(ctx, native, name, fnDef, args);
+}
+
+function lowerReturn(ctx: Context, t: FfiTypeDesc, value: any): unknown {
+  if (t.tag === "Void") return undefined;
+  if (t.tag === "RustBuffer" || t.tag === "Struct") {
+    const [size] = sizeAlign(t, ctx.structs);
+    const bytes = new Uint8Array(size);
+    writeValue(ctx, bytes, 0, t, value);
+    return bytes;
+  }
+  if (t.tag === "UInt64" || t.tag === "Int64" || t.tag === "Handle") {
+    return BigInt(value ?? 0);
+  }
+  return value ?? 0;
+}
+
+function zeroReturn(ctx: Context, t: FfiTypeDesc): unknown {
+  const native = nativeType(t, ctx.structs);
+  if (native === "void") return undefined;
+  if (typeof native === "object") {
+    return new Uint8Array(sizeAlign(t, ctx.structs)[0]);
+  }
+  return native === "u64" || native === "i64" || native === "pointer" ? 0n : 0;
+}
+
+function writeOut(
+  ctx: Context,
+  pointer: bigint,
+  t: FfiTypeDesc,
+  value: unknown,
+): void {
+  const [size] = sizeAlign(t, ctx.structs);
+  if (size === 0) return;
+  writeValue(ctx, ctx.host.view(pointer, size), 0, t, value);
+}
+
+function writeStatus(ctx: Context, pointer: bigint, status: CallStatus): void {
+  const bytes = ctx.host.view(pointer, RUST_CALL_STATUS_SIZE);
+  writeCallStatusCode(bytes, status.code);
+  if (status.code !== 0 && status.errorBuf !== undefined) {
+    writeCallStatusErrorBuf(bytes, ctx.buffers.fromBytes(status.errorBuf));
+  }
+}
@@ -0,0 +1,206 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { mkdtempSync, writeFileSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+
+import {
+  callShimSource,
+  callbackShimSource,
+  flattenedSignature,
+  hasStruct,
+  isStruct,
+  nativeSize,
+} from "./c-shim.js";
+import type {
+  Host,
+  HostCallback,
+  HostLibrary,
+  NativeFunction,
+  NativeSignature,
+  NativeType,
+} from "./host.js";
+
+/**
+ * Bun's FFI has no struct-by-value, so any signature with a struct goes
+ * through a C shim compiled at runtime with `bun:ffi`'s `cc` (Bun 1.1.28 or
+ * later). Each distinct signature is compiled once.
+ *
+ * Bun's thread-safe callbacks cannot hand a value back to a calling thread
+ * other than the JS thread, so a callback Rust calls from another thread must
+ * return `void`; UniFFI's future continuations do.
+ */
+export function bunHost(): Host {
+  // `bun:ffi` only resolves under Bun, and `import.meta.require` keeps this
+  // synchronous so the generated getter can stay synchronous too.
+  const ffi = (import.meta as any).require("bun:ffi");
+  const shims = new ShimCache(ffi);
+
+  const bind = (pointer: bigint, signature: NativeSignature) => {
+    if (!hasStruct(signature)) {
+      const fn = ffi.CFunction({
+        ptr: Number(pointer),
+        args: signature.parameters.map(toBun),
+        returns: toBun(signature.result),
+      });
+      return adaptCall(fn, signature);
+    }
+    const call = shims.call(signature);
+    return (...args: unknown[]) => {
+      const lowered: unknown[] = [Number(pointer)];
+      signature.parameters.forEach((p, i) =>
+        lowered.push(isStruct(p) ? args[i] : toBunArg(args[i], p)),
+      );
+      if (isStruct(signature.result)) {
+        const out = new Uint8Array(nativeSize(signature.result));
+        call(...lowered, out);
+        return out;
+      }
+      return fromBun(call(...lowered), signature.result);
+    };
+  };
+
+  return {
+    name: "bun",
+    open(path): HostLibrary {
+      const handles: { close(): void }[] = [ffi.dlopen(path, {})];
+      return {
+        symbol(name, signature) {
+          // Bind with a placeholder signature only to learn the address.
+          const handle = ffi.dlopen(path, {
+            [name]: { args: [], returns: "void" },
+          });
+          handles.push(handle);
+          return bind(BigInt(handle.symbols[name].ptr), signature);
+        },
+        close() {
+          for (const handle of handles.splice(0)) handle.close();
+        },
+      };
+    },
+    fnPointer: bind,
+    callback(signature, fn): HostCallback {
+      const flat = flattenedSignature(signature);
+      const adapted = (...args: unknown[]) => {
+        const lifted = signature.parameters.map((p, i) =>
+          isStruct(p)
+            ? copyStruct(ffi, args[i] as number, p)
+            : fromBun(args[i], p),
+        );
+        const result = fn(...lifted);
+        if (isStruct(signature.result)) {
+          const out = args[signature.parameters.length] as number;
+          new Uint8Array(
+            ffi.toArrayBuffer(out, 0, nativeSize(signature.result)),
+          ).set(result as Uint8Array);
+          return undefined;
+        }
+        return toBunArg(result, signature.result);
+      };
+      const callback = new ffi.JSCallback(adapted, {
+        args: flat.parameters.map(toBun),
+        returns: toBun(flat.result),
+        threadsafe: true,
+      });
+      let pointer = BigInt(callback.ptr);
+      if (hasStruct(signature)) {
+        pointer = shims.trampoline(signature, pointer);
+      }
+      return { pointer, ref() {}, unref() {} };
+    },
+    addressOf(bytes) {
+      return bytes.byteLength === 0 ? 0n : BigInt(ffi.ptr(bytes));
+    },
+    view(pointer, length) {
+      if (length === 0) return new Uint8Array(0);
+      return new Uint8Array(ffi.toArrayBuffer(Number(pointer), 0, length));
+    },
+  };
+}
+
+class ShimCache {
+  private calls = new Map<string, NativeFunction>();
+  private dir: string | undefined;
+  private count = 0;
+
+  constructor(private ffi: any) {}
+
+  call(signature: NativeSignature): NativeFunction {
+    const key = JSON.stringify(signature);
+    let call = this.calls.get(key);
+    if (call === undefined) {
+      const flat = flattenedSignature(signature);
+      const compiled = this.compile(callShimSource(signature), {
+        call: {
+          args: ["ptr", ...flat.parameters.map(toBun)],
+          returns: toBun(flat.result),
+        },
+      });
+      call = compiled.symbols.call as NativeFunction;
+      this.calls.set(key, call);
+    }
+    return call;
+  }
+
+  /**
+   * A C function with `signature` that forwards to `target`. Each one owns
+   * its target, so each is compiled afresh.
+   */
+  trampoline(signature: NativeSignature, target: bigint): bigint {
+    const compiled = this.compile(callbackShimSource(signature), {
+      set_target: { args: ["ptr"], returns: "void" },
+      address: { args: [], returns: "ptr" },
+    });
+    compiled.symbols.set_target(Number(target));
+    return BigInt(compiled.symbols.address());
+  }
+
+  private compile(source: string, symbols: object): any {
+    this.dir ??= mkdtempSync(join(tmpdir(), "ubjs-deno-"));
+    const path = join(this.dir, `shim${this.count++}.c`);
+    writeFileSync(path, source);
+    return this.ffi.cc({ source: path, symbols });
+  }
+}
+
+function copyStruct(
+  ffi: any,
+  pointer: number,
+  type: { struct: NativeType[] },
+): Uint8Array {
+  const size = nativeSize(type);
+  return new Uint8Array(ffi.toArrayBuffer(pointer, 0, size)).slice();
+}
+
+function adaptCall(fn: NativeFunction, signature: NativeSignature) {
+  return (...args: unknown[]) =>
+    fromBun(
+      fn(...args.map((arg, i) => toBunArg(arg, signature.parameters[i]))),
+      signature.result,
+    );
+}
+
+function toBun(t: NativeType): string {
+  return t === "pointer" ? "ptr" : (t as string);
+}
+
+function toBunArg(value: unknown, type: NativeType): unknown {
+  return type === "pointer" ? Number((value as bigint) ?? 0n) : value;
+}
+
+function fromBun(value: unknown, type: NativeType): unknown {
+  switch (type) {
+    case "pointer":
+      return value === null ? 0n : BigInt(value as number);
+    case "u64":
+    case "i64":
+      return BigInt(value as number | bigint);
+    case "void":
+      return undefined;
+    default:
+      return value;
+  }
+}
@@ -0,0 +1,158 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type {
+  Host,
+  HostCallback,
+  HostLibrary,
+  NativeFunction,
+  NativeSignature,
+  NativeType,
+} from "./host.js";
+
+// Typed loosely so the package builds against Node's types; this file only
+// runs under Deno, with `--allow-ffi`.
+const Deno = (globalThis as any).Deno;
+
+/**
+ * Deno's FFI passes structs by value natively, so this is a thin adapter:
+ * pointer objects become `bigint`s, and 64-bit integers, which Deno returns
+ * as a `number` when they fit, always come back as `bigint`s.
+ */
+export function denoHost(): Host {
+  return {
+    name: "deno",
+    open(path) {
+      return openLibrary(path);
+    },
+    fnPointer(pointer, signature) {
+      const fn = new Deno.UnsafeFnPointer(
+        Deno.UnsafePointer.create(pointer),
+        toDeno(signature),
+      );
+      return adaptCall((...args) => fn.call(...args), signature);
+    },
+    callback(signature, fn) {
+      return makeCallback(signature, fn);
+    },
+    addressOf(bytes) {
+      return pointerValue(Deno.UnsafePointer.of(bytes));
+    },
+    view(pointer, length) {
+      if (length === 0) return new Uint8Array(0);
+      return new Uint8Array(
+        Deno.UnsafePointerView.getArrayBuffer(
+          Deno.UnsafePointer.create(pointer),
+          length,
+        ),
+      );
+    },
+  };
+}
+
+function openLibrary(path: string): HostLibrary {
+  // Fail here, rather than on the first call, if the path is wrong.
+  let probe: { close(): void } | undefined = Deno.dlopen(path, {});
+  // `Deno.dlopen` wants every symbol up front, so the library is opened once
+  // with all the symbols the module declares. Those are optional, so one
+  // missing symbol throws when it is looked up, not for the whole module.
+  let library: { symbols: Record<string, any>; close(): void } | undefined;
+  // Anything not declared gets a handle of its own; `dlopen`
+  // reference-counts them, so the library still loads once. So does the
+  // handle of an earlier `declare()`, whose symbols may still be called.
+  const extra: { close(): void }[] = [];
+  return {
+    declare(symbols) {
+      const definitions: Record<string, object> = {};
+      for (const [name, signature] of Object.entries(symbols)) {
+        definitions[name] = { ...toDeno(signature), optional: true };
+      }
+      if (library !== undefined) extra.push(library);
+      library = Deno.dlopen(path, definitions);
+      probe?.close();
+      probe = undefined;
+    },
+    symbol(name, signature) {
+      if (library !== undefined && name in library.symbols) {
+        const fn = library.symbols[name];
+        if (fn === null) throw new Error(`symbol not found: ${name}`);
+        return adaptCall(fn, signature);
+      }
+      const handle = Deno.dlopen(path, { [name]: toDeno(signature) });
+      extra.push(handle);
+      return adaptCall(handle.symbols[name], signature);
+    },
+    close() {
+      probe?.close();
+      library?.close();
+      for (const handle of extra.splice(0)) handle.close();
+      probe = library = undefined;
+    },
+  };
+}
+
+function makeCallback(
+  signature: NativeSignature,
+  fn: NativeFunction,
+): HostCallback {
+  const adapted = (...args: unknown[]) => {
+    const lifted = args.map((arg, i) =>
+      fromDeno(arg, signature.parameters[i]),
+    );
+    return toDenoArg(fn(...lifted), signature.result);
+  };
+  // Thread-safe, since Rust may call back from any thread. A thread-safe
+  // callback holds the event loop open, which a vtable registered for the
+  // life of the process must not do; callers `ref()` around the calls they
+  // are waiting on.
+  const callback = Deno.UnsafeCallback.threadSafe(toDeno(signature), adapted);
+  callback.unref();
+  return {
+    pointer: pointerValue(callback.pointer),
+    ref: () => callback.ref(),
+    unref: () => callback.unref(),
+  };
+}
+
+function adaptCall(
+  call: (...args: unknown[]) => unknown,
+  signature: NativeSignature,
+): NativeFunction {
+  return (...args) => {
+    const lowered = args.map((arg, i) =>
+      toDenoArg(arg, signature.parameters[i]),
+    );
+    return fromDeno(call(...lowered), signature.result);
+  };
+}
+
+function toDeno(signature: NativeSignature): object {
+  return { parameters: signature.parameters, result: signature.result };
+}
+
+function toDenoArg(value: unknown, type: NativeType): unknown {
+  if (type === "pointer") {
+    return Deno.UnsafePointer.create(BigInt((value as bigint) ?? 0n));
+  }
+  return value;
+}
+
+function fromDeno(value: unknown, type: NativeType): unknown {
+  switch (type) {
+    case "pointer":
+      return pointerValue(value);
+    case "u64":
+    case "i64":
+      return BigInt(value as number | bigint);
+    case "void":
+      return undefined;
+    default:
+      return value;
+  }
+}
+
+function pointerValue(pointer: unknown): bigint {
+  return pointer === null ? 0n : BigInt(Deno.UnsafePointer.value(pointer));
+}
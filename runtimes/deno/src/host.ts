@@ -0,0 +1,78 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+/**
+ * A type as the host's FFI passes it. Structs go by value, as their bytes.
+ */
+export type NativeType =
+  | "u8"
+  | "i8"
+  | "u16"
+  | "i16"
+  | "u32"
+  | "i32"
+  | "u64"
+  | "i64"
+  | "f32"
+  | "f64"
+  | "pointer"
+  | "void"
+  | { struct: NativeType[] };
+
+export interface NativeSignature {
+  parameters: NativeType[];
+  result: NativeType;
+}
+
+/**
+ * A function the host can call, or a callback it exposes, speaks in host
+ * values:
+ *
+ * - 8- to 32-bit integers and floats are `number`s;
+ * - `u64`, `i64` and pointers are `bigint`s, with `0n` for null;
+ * - structs are a `Uint8Array` of their native bytes.
+ */
+export type NativeFunction = (...args: any[]) => any;
+
+export interface HostLibrary {
+  /** Look up `name` and bind it to `signature`. Throws if it is missing. */
+  symbol(name: string, signature: NativeSignature): NativeFunction;
+  /**
+   * Every symbol `symbol()` may be asked for, for a host which binds them all
+   * when it loads the library. Symbols missing from the library still only
+   * throw when looked up.
+   */
+  declare?(symbols: Record<string, NativeSignature>): void;
+  close(): void;
+}
+
+export interface HostCallback {
+  readonly pointer: bigint;
+  /** Keep the event loop alive until a matching `unref()`. */
+  ref(): void;
+  unref(): void;
+}
+
+/**
+ * The few primitives the player needs from a JS runtime's FFI. Everything
+ * else, the UniFFI calling conventions included, is written against this so
+ * Deno and Bun share it.
+ */
+export interface Host {
+  readonly name: string;
+  open(path: string): HostLibrary;
+  /** Bind a function pointer the library handed us. */
+  fnPointer(pointer: bigint, signature: NativeSignature): NativeFunction;
+  /**
+   * Expose `fn` as a C function pointer. It may be called from any thread,
+   * and lives as long as the process.
+   */
+  callback(signature: NativeSignature, fn: NativeFunction): HostCallback;
+  /** The address of `bytes`, valid while `bytes` is reachable. */
+  addressOf(bytes: Uint8Array): bigint;
+  /** A writable view aliasing `length` bytes of native memory. */
+  view(pointer: bigint, length: number): Uint8Array;
+}
@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+export { FfiType, type FfiTypeDesc } from "./ffi-type.js";
+export type {
+  Host,
+  HostCallback,
+  HostLibrary,
+  NativeFunction,
+  NativeSignature,
+  NativeType,
+} from "./host.js";
+export {
+  UniffiNativeModule,
+  detectHost,
+  type ModuleDefinitions,
+  type NativeModuleInterface,
+  type RegisterOptions,
+} from "./module.js";
+export {
+  resolveLibPath,
+  ResolveLibPathError,
+  type ResolveLibPathOptions,
+  type ResolveMode,
+  type TripleStyle,
+} from "./resolve-lib.js";
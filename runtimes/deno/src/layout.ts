@@ -0,0 +1,328 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type { FfiTypeDesc } from "./ffi-type.js";
+import type { NativeType } from "./host.js";
+
+// Native layouts on the 64-bit targets the hosts run on. They match the C
+// structs `uniffi_runtime_core` lays out for libffi:
+//
+//   RustBuffer     { u64 capacity; u64 len; u8 *data }   24 bytes
+//   ForeignBytes   { i32 len; const u8 *data }           16 bytes
+//   RustCallStatus { i8 code; RustBuffer error_buf }     32 bytes
+export const POINTER_SIZE = 8;
+export const RUST_BUFFER_SIZE = 24;
+export const FOREIGN_BYTES_SIZE = 16;
+export const RUST_CALL_STATUS_SIZE = 32;
+const RUST_CALL_STATUS_BUF_OFFSET = 8;
+
+export interface FieldDesc {
+  name: string;
+  type: FfiTypeDesc;
+}
+
+export interface FieldLayout extends FieldDesc {
+  offset: number;
+}
+
+export interface StructLayout {
+  size: number;
+  align: number;
+  fields: FieldLayout[];
+  native: NativeType;
+}
+
+export interface RustBufferParts {
+  capacity: bigint;
+  len: bigint;
+  data: bigint;
+}
+
+const RUST_BUFFER_NATIVE: NativeType = { struct: ["u64", "u64", "pointer"] };
+const FOREIGN_BYTES_NATIVE: NativeType = { struct: ["i32", "pointer"] };
+const RUST_CALL_STATUS_NATIVE: NativeType = {
+  struct: ["i8", RUST_BUFFER_NATIVE],
+};
+
+/** The type the host's FFI passes for a value of type `t`. */
+export function nativeType(
+  t: FfiTypeDesc,
+  structs: Map<string, StructLayout>,
+): NativeType {
+  switch (t.tag) {
+    case "UInt8":
+      return "u8";
+    case "Int8":
+      return "i8";
+    case "UInt16":
+      return "u16";
+    case "Int16":
+      return "i16";
+    case "UInt32":
+      return "u32";
+    case "Int32":
+      return "i32";
+    case "UInt64":
+    case "Handle":
+      return "u64";
+    case "Int64":
+      return "i64";
+    case "Float32":
+      return "f32";
+    case "Float64":
+      return "f64";
+    case "RustBuffer":
+      return RUST_BUFFER_NATIVE;
+    case "ForeignBytes":
+      return FOREIGN_BYTES_NATIVE;
+    case "RustCallStatus":
+      return RUST_CALL_STATUS_NATIVE;
+    case "Struct":
+      return structLayout(structs, t.name).native;
+    case "VoidPointer":
+    case "Callback":
+    case "Reference":
+    case "MutReference":
+      return "pointer";
+    case "Void":
+      return "void";
+  }
+}
+
+/** Size and alignment of a value of type `t` held in native memory. */
+export function sizeAlign(
+  t: FfiTypeDesc,
+  structs: Map<string, StructLayout>,
+): [number, number] {
+  switch (t.tag) {
+    case "UInt8":
+    case "Int8":
+      return [1, 1];
+    case "UInt16":
+    case "Int16":
+      return [2, 2];
+    case "UInt32":
+    case "Int32":
+    case "Float32":
+      return [4, 4];
+    case "UInt64":
+    case "Int64":
+    case "Float64":
+    case "Handle":
+    case "VoidPointer":
+    case "Callback":
+    case "Reference":
+    case "MutReference":
+      return [8, 8];
+    case "RustBuffer":
+      return [RUST_BUFFER_SIZE, 8];
+    case "ForeignBytes":
+      return [FOREIGN_BYTES_SIZE, 8];
+    case "RustCallStatus":
+      return [RUST_CALL_STATUS_SIZE, 8];
+    case "Struct": {
+      const layout = structLayout(structs, t.name);
+      return [layout.size, layout.align];
+    }
+    case "Void":
+      return [0, 1];
+  }
+}
+
+/**
+ * Lay out each struct with C rules, in dependency order so a struct field can
+ * refer to another struct by value.
+ */
+export function compileStructLayouts(
+  defs: Record<string, FieldDesc[]>,
+): Map<string, StructLayout> {
+  const layouts = new Map<string, StructLayout>();
+  const visiting = new Set<string>();
+  const visit = (name: string): void => {
+    if (layouts.has(name)) return;
+    const fields = defs[name];
+    if (fields === undefined) {
+      throw new Error(`unknown struct "${name}"`);
+    }
+    if (visiting.has(name)) {
+      throw new Error(`struct "${name}" contains itself by value`);
+    }
+    visiting.add(name);
+    for (const field of fields) {
+      if (field.type.tag === "Struct") visit(field.type.name);
+    }
+    visiting.delete(name);
+
+    let offset = 0;
+    let align = 1;
+    const laidOut: FieldLayout[] = [];
+    const native: NativeType[] = [];
+    for (const field of fields) {
+      const [size, fieldAlign] = sizeAlign(field.type, layouts);
+      offset = alignTo(offset, fieldAlign);
+      laidOut.push({ ...field, offset });
+      native.push(nativeType(field.type, layouts));
+      offset += size;
+      align = Math.max(align, fieldAlign);
+    }
+    layouts.set(name, {
+      size: alignTo(offset, align),
+      align,
+      fields: laidOut,
+      native: { struct: native },
+    });
+  };
+  for (const name of Object.keys(defs)) visit(name);
+  return layouts;
+}
+
+export function structLayout(
+  structs: Map<string, StructLayout>,
+  name: string,
+): StructLayout {
+  const layout = structs.get(name);
+  if (layout === undefined) {
+    throw new Error(`unknown struct "${name}"`);
+  }
+  return layout;
+}
+
+function alignTo(offset: number, align: number): number {
+  return Math.ceil(offset / align) * align;
+}
+
+export function dataView(bytes: Uint8Array): DataView {
+  return new DataView(bytes.buffer, bytes.byteOffset, bytes.byteLength);
+}
+
+/** Read a scalar or pointer; 64-bit values come back as `bigint`. */
+export function readScalar(
+  view: DataView,
+  offset: number,
+  t: FfiTypeDesc,
+): number | bigint {
+  switch (t.tag) {
+    case "UInt8":
+      return view.getUint8(offset);
+    case "Int8":
+      return view.getInt8(offset);
+    case "UInt16":
+      return view.getUint16(offset, true);
+    case "Int16":
+      return view.getInt16(offset, true);
+    case "UInt32":
+      return view.getUint32(offset, true);
+    case "Int32":
+      return view.getInt32(offset, true);
+    case "Int64":
+      return view.getBigInt64(offset, true);
+    case "Float32":
+      return view.getFloat32(offset, true);
+    case "Float64":
+      return view.getFloat64(offset, true);
+    case "UInt64":
+    case "Handle":
+    case "VoidPointer":
+    case "Callback":
+    case "Reference":
+    case "MutReference":
+      return view.getBigUint64(offset, true);
+    default:
+      throw new Error(`${t.tag} is not a scalar`);
+  }
+}
+
+export function writeScalar(
+  view: DataView,
+  offset: number,
+  t: FfiTypeDesc,
+  value: number | bigint,
+): void {
+  switch (t.tag) {
+    case "UInt8":
+      return view.setUint8(offset, Number(value));
+    case "Int8":
+      return view.setInt8(offset, Number(value));
+    case "UInt16":
+      return view.setUint16(offset, Number(value), true);
+    case "Int16":
+      return view.setInt16(offset, Number(value), true);
+    case "UInt32":
+      return view.setUint32(offset, Number(value), true);
+    case "Int32":
+      return view.setInt32(offset, Number(value), true);
+    case "Int64":
+      return view.setBigInt64(offset, BigInt(value), true);
+    case "Float32":
+      return view.setFloat32(offset, Number(value), true);
+    case "Float64":
+      return view.setFloat64(offset, Number(value), true);
+    case "UInt64":
+    case "Handle":
+    case "VoidPointer":
+    case "Callback":
+    case "Reference":
+    case "MutReference":
+      return view.setBigUint64(offset, BigInt(value), true);
+    default:
+      throw new Error(`${t.tag} is not a scalar`);
+  }
+}
+
+export function readRustBuffer(
+  bytes: Uint8Array,
+  offset = 0,
+): RustBufferParts {
+  const view = dataView(bytes);
+  return {
+    capacity: view.getBigUint64(offset, true),
+    len: view.getBigUint64(offset + 8, true),
+    data: view.getBigUint64(offset + 16, true),
+  };
+}
+
+export function writeRustBuffer(
+  bytes: Uint8Array,
+  rb: RustBufferParts,
+  offset = 0,
+): void {
+  const view = dataView(bytes);
+  view.setBigUint64(offset, rb.capacity, true);
+  view.setBigUint64(offset + 8, rb.len, true);
+  view.setBigUint64(offset + 16, rb.data, true);
+}
+
+export function encodeRustBuffer(rb: RustBufferParts): Uint8Array {
+  const bytes = new Uint8Array(RUST_BUFFER_SIZE);
+  writeRustBuffer(bytes, rb);
+  return bytes;
+}
+
+export function encodeForeignBytes(len: number, data: bigint): Uint8Array {
+  const bytes = new Uint8Array(FOREIGN_BYTES_SIZE);
+  const view = dataView(bytes);
+  view.setInt32(0, len, true);
+  view.setBigUint64(8, data, true);
+  return bytes;
+}
+
+export function readCallStatusCode(status: Uint8Array): number {
+  return dataView(status).getInt8(0);
+}
+
+export function writeCallStatusCode(status: Uint8Array, code: number): void {
+  dataView(status).setInt8(0, code);
+}
+
+export function readCallStatusErrorBuf(status: Uint8Array): RustBufferParts {
+  return readRustBuffer(status, RUST_CALL_STATUS_BUF_OFFSET);
+}
+
+export function writeCallStatusErrorBuf(
+  status: Uint8Array,
+  rb: RustBufferParts,
+): void {
+  writeRustBuffer(status, rb, RUST_CALL_STATUS_BUF_OFFSET);
+}
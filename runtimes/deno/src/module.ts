@@ -0,0 +1,146 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import {
+  functionSignature,
+  makeFunction,
+  type Context,
+  type FunctionDef,
+} from "./call.js";
+import type { CallbackDef } from "./callback.js";
+import { bunHost } from "./host-bun.js";
+import { denoHost } from "./host-deno.js";
+import type { Host, HostLibrary, NativeSignature } from "./host.js";
+import { compileStructLayouts, type FieldDesc } from "./layout.js";
+import { RustBuffers } from "./rust-buffer.js";
+
+export interface ModuleDefinitions {
+  symbols: {
+    rustbuffer_alloc: string;
+    rustbuffer_free: string;
+    rustbuffer_from_bytes: string;
+  };
+  functions: Record<string, FunctionDef>;
+  callbacks: Record<string, CallbackDef>;
+  structs: Record<string, readonly FieldDesc[]>;
+}
+
+export interface RegisterOptions {
+  /**
+   * Look up every function symbol now, and throw for the first one missing,
+   * rather than on each function's first call.
+   */
+  verifySymbols?: boolean;
+}
+
+export type NativeModuleInterface = Record<string, (...args: any[]) => any>;
+
+const RUSTBUFFER_ALLOC: NativeSignature = {
+  parameters: ["u64", "pointer"],
+  result: { struct: ["u64", "u64", "pointer"] },
+};
+const RUSTBUFFER_FREE: NativeSignature = {
+  parameters: [{ struct: ["u64", "u64", "pointer"] }, "pointer"],
+  result: "void",
+};
+const RUSTBUFFER_FROM_BYTES: NativeSignature = {
+  parameters: [{ struct: ["i32", "pointer"] }, "pointer"],
+  result: { struct: ["u64", "u64", "pointer"] },
+};
+
+/** The host this process is running under. */
+export function detectHost(): Host {
+  if ((globalThis as any).Deno !== undefined) return denoHost();
+  if ((globalThis as any).Bun !== undefined) return bunHost();
+  throw new Error(
+    "@ubjs/deno runs under Deno or Bun; under Node.js, use @ubjs/node",
+  );
+}
+
+/**
+ * A loaded library, driven by the same definitions the napi player takes in
+ * object form.
+ */
+export class UniffiNativeModule {
+  private constructor(
+    private readonly host: Host,
+    private readonly library: HostLibrary,
+  ) {}
+
+  /** Open the library at `path`. `host` defaults to the current runtime's. */
+  static open(path: string, options?: { host?: Host }): UniffiNativeModule {
+    const host = options?.host ?? detectHost();
+    return new UniffiNativeModule(host, host.open(path));
+  }
+
+  register(
+    definitions: ModuleDefinitions,
+    options?: RegisterOptions,
+  ): NativeModuleInterface {
+    const { symbols } = definitions;
+    for (const key of [
+      "rustbuffer_alloc",
+      "rustbuffer_free",
+      "rustbuffer_from_bytes",
+    ] as const) {
+      if (!symbols[key]) {
+        throw new Error(
+          `register: definitions.symbols.${key} must be a non-empty string`,
+        );
+      }
+    }
+    const structs = compileStructLayouts(
+      definitions.structs as Record<string, FieldDesc[]>,
+    );
+    const signatures: Record<string, NativeSignature> = {
+      [symbols.rustbuffer_alloc]: RUSTBUFFER_ALLOC,
+      [symbols.rustbuffer_free]: RUSTBUFFER_FREE,
+      [symbols.rustbuffer_from_bytes]: RUSTBUFFER_FROM_BYTES,
+    };
+    for (const [name, def] of Object.entries(definitions.functions)) {
+      signatures[name] = functionSignature(def, structs);
+    }
+    this.library.declare?.(signatures);
+    const buffers = new RustBuffers(
+      this.host,
+      this.library.symbol(symbols.rustbuffer_alloc, RUSTBUFFER_ALLOC),
+      this.library.symbol(symbols.rustbuffer_free, RUSTBUFFER_FREE),
+      this.library.symbol(
+        symbols.rustbuffer_from_bytes,
+        RUSTBUFFER_FROM_BYTES,
+      ),
+    );
+    const ctx: Context = {
+      host: this.host,
+      structs,
+      callbackDefs: new Map(Object.entries(definitions.callbacks)),
+      buffers,
+      callbacks: new Map(),
+      pinned: new Map(),
+    };
+
+    const result: NativeModuleInterface = Object.create(null);
+    for (const [name, def] of Object.entries(definitions.functions)) {
+      const resolve = () => this.library.symbol(name, signatures[name]);
+      if (options?.verifySymbols) {
+        const native = resolve();
+        result[name] = makeFunction(ctx, name, def, () => native);
+      } else {
+        result[name] = makeFunction(ctx, name, def, resolve);
+      }
+    }
+    result.rustbuffer_alloc = (n: number): Uint8Array => buffers.alloc(n);
+    result.rustbuffer_free = (view: Uint8Array): void => buffers.free(view);
+    return result;
+  }
+
+  /**
+   * Release this module's handles on the library. Functions registered from
+   * it must not be called afterwards.
+   */
+  close(): void {
+    this.library.close();
+  }
+}
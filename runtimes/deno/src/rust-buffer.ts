@@ -0,0 +1,113 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type { Host, NativeFunction } from "./host.js";
+import {
+  RUST_CALL_STATUS_SIZE,
+  encodeForeignBytes,
+  encodeRustBuffer,
+  readCallStatusCode,
+  readRustBuffer,
+  type RustBufferParts,
+} from "./layout.js";
+
+/**
+ * The library's RustBuffers, as JS sees them.
+ *
+ * A buffer the library allocated, whether from `rustbuffer_alloc` or as a
+ * return value, is a `Uint8Array` aliasing its native memory. We remember
+ * each such view so `free` can find its capacity, and so passing it back
+ * hands the allocation over without a copy. Any other `Uint8Array` is owned
+ * by JS, and is copied into a fresh RustBuffer when it crosses.
+ */
+export class RustBuffers {
+  private owned = new WeakMap<Uint8Array, RustBufferParts>();
+
+  constructor(
+    private host: Host,
+    private nativeAlloc: NativeFunction,
+    private nativeFree: NativeFunction,
+    private nativeFromBytes: NativeFunction,
+  ) {}
+
+  alloc(n: number): Uint8Array {
+    const status = new Uint8Array(RUST_CALL_STATUS_SIZE);
+    const rb = this.nativeAlloc(BigInt(n), this.host.addressOf(status));
+    checkStatus(status, "rustbuffer_alloc");
+    return this.adopt(rb);
+  }
+
+  /** Release a view from `alloc` or a return value; a no-op for any other. */
+  free(view: Uint8Array): void {
+    const rb = this.owned.get(view);
+    if (rb === undefined) return;
+    this.owned.delete(view);
+    this.freeParts(rb);
+  }
+
+  /** Wrap a RustBuffer the library handed us, without copying. */
+  adopt(bytes: Uint8Array): Uint8Array {
+    const rb = readRustBuffer(bytes);
+    const view =
+      rb.data === 0n || rb.len === 0n
+        ? new Uint8Array(0)
+        : this.host.view(rb.data, Number(rb.len));
+    this.owned.set(view, rb);
+    return view;
+  }
+
+  /**
+   * A RustBuffer, by value, for `view`. The library takes ownership of it, so
+   * a view we adopted is forgotten rather than freed again later.
+   */
+  lower(view: Uint8Array): Uint8Array {
+    const rb = this.owned.get(view);
+    if (rb !== undefined) {
+      this.owned.delete(view);
+      return encodeRustBuffer(rb);
+    }
+    return encodeRustBuffer(this.fromBytes(view));
+  }
+
+  /** Copy out a RustBuffer the library handed us, then free it. */
+  take(bytes: Uint8Array): Uint8Array {
+    const rb = readRustBuffer(bytes);
+    return this.takeParts(rb);
+  }
+
+  takeParts(rb: RustBufferParts): Uint8Array {
+    const copy =
+      rb.data === 0n || rb.len === 0n
+        ? new Uint8Array(0)
+        : this.host.view(rb.data, Number(rb.len)).slice();
+    this.freeParts(rb);
+    return copy;
+  }
+
+  fromBytes(view: Uint8Array): RustBufferParts {
+    const status = new Uint8Array(RUST_CALL_STATUS_SIZE);
+    const foreign = encodeForeignBytes(
+      view.byteLength,
+      this.host.addressOf(view),
+    );
+    const rb = this.nativeFromBytes(foreign, this.host.addressOf(status));
+    checkStatus(status, "rustbuffer_from_bytes");
+    return readRustBuffer(rb);
+  }
+
+  private freeParts(rb: RustBufferParts): void {
+    if (rb.data === 0n) return;
+    const status = new Uint8Array(RUST_CALL_STATUS_SIZE);
+    this.nativeFree(encodeRustBuffer(rb), this.host.addressOf(status));
+    checkStatus(status, "rustbuffer_free");
+  }
+}
+
+function checkStatus(status: Uint8Array, what: string): void {
+  const code = readCallStatusCode(status);
+  if (code !== 0) {
+    throw new Error(`${what} failed with status code ${code}`);
+  }
+}
@@ -0,0 +1,153 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import type {
+  Host,
+  HostCallback,
+  HostLibrary,
+  NativeFunction,
+  NativeSignature,
+} from "../src/host.js";
+import {
+  RUST_CALL_STATUS_SIZE,
+  dataView,
+  encodeRustBuffer,
+  readRustBuffer,
+  writeCallStatusErrorBuf,
+  type RustBufferParts,
+} from "../src/layout.js";
+
+/**
+ * A host whose "native memory" is JS `ArrayBuffer`s at made-up addresses, and
+ * whose "library" is JS functions written to the host-value convention. It
+ * lets the player's marshalling run under plain Node.
+ */
+export class FakeHost implements Host {
+  readonly name = "fake";
+  readonly freed: bigint[] = [];
+  readonly refs = { count: 0 };
+  /** The symbol names each `declare()` was given. */
+  readonly declared: string[][] = [];
+
+  private regions: { base: bigint; buffer: ArrayBuffer }[] = [];
+  private bases = new WeakMap<ArrayBuffer, bigint>();
+  private nextBase = 0x1000n;
+  private pointers = new Map<bigint, NativeFunction>();
+  private nextPointer = 0x10n;
+
+  constructor(
+    private symbols: Record<string, (host: FakeHost) => NativeFunction>,
+  ) {}
+
+  open(_path: string): HostLibrary {
+    return {
+      symbol: (name: string, _signature: NativeSignature) => {
+        const make = this.symbols[name];
+        if (make === undefined) throw new Error(`symbol not found: ${name}`);
+        return make(this);
+      },
+      declare: (symbols: Record<string, NativeSignature>) => {
+        this.declared.push(Object.keys(symbols));
+      },
+      close() {},
+    };
+  }
+
+  fnPointer(pointer: bigint, _signature: NativeSignature): NativeFunction {
+    const fn = this.pointers.get(pointer);
+    if (fn === undefined) throw new Error(`no function at ${pointer}`);
+    return fn;
+  }
+
+  callback(_signature: NativeSignature, fn: NativeFunction): HostCallback {
+    const pointer = this.exposeFunction(fn);
+    const refs = this.refs;
+    return {
+      pointer,
+      ref: () => void refs.count++,
+      unref: () => void refs.count--,
+    };
+  }
+
+  exposeFunction(fn: NativeFunction): bigint {
+    const pointer = this.nextPointer++;
+    this.pointers.set(pointer, fn);
+    return pointer;
+  }
+
+  addressOf(bytes: Uint8Array): bigint {
+    const buffer = bytes.buffer as ArrayBuffer;
+    let base = this.bases.get(buffer);
+    if (base === undefined) {
+      base = this.nextBase;
+      this.nextBase += BigInt(buffer.byteLength) + 0x100n;
+      this.bases.set(buffer, base);
+      this.regions.push({ base, buffer });
+    }
+    return base + BigInt(bytes.byteOffset);
+  }
+
+  view(pointer: bigint, length: number): Uint8Array {
+    for (const { base, buffer } of this.regions) {
+      if (pointer >= base && pointer < base + BigInt(buffer.byteLength)) {
+        return new Uint8Array(buffer, Number(pointer - base), length);
+      }
+    }
+    throw new Error(`no memory at ${pointer}`);
+  }
+
+  // Helpers for writing fake library functions.
+
+  allocRustBuffer(contents: Uint8Array): Uint8Array {
+    const data = new Uint8Array(contents.byteLength + 8);
+    data.set(contents);
+    return encodeRustBuffer({
+      capacity: BigInt(data.byteLength),
+      len: BigInt(contents.byteLength),
+      data: this.addressOf(data),
+    });
+  }
+
+  readRustBuffer(bytes: Uint8Array): Uint8Array {
+    const rb = readRustBuffer(bytes);
+    return rb.len === 0n
+      ? new Uint8Array(0)
+      : this.view(rb.data, Number(rb.len)).slice();
+  }
+
+  setError(statusPtr: bigint, code: number, message: string): void {
+    const status = this.view(statusPtr, RUST_CALL_STATUS_SIZE);
+    dataView(status).setInt8(0, code);
+    const rb: RustBufferParts = readRustBuffer(
+      this.allocRustBuffer(new TextEncoder().encode(message)),
+    );
+    writeCallStatusErrorBuf(status, rb);
+  }
+}
+
+/** The three RustBuffer symbols every module needs. */
+export const rustBufferSymbols = {
+  test_rustbuffer_alloc: (host: FakeHost) => (n: bigint, _status: bigint) =>
+    host.allocRustBuffer(new Uint8Array(Number(n))),
+  test_rustbuffer_free:
+    (host: FakeHost) => (rb: Uint8Array, _status: bigint) => {
+      host.freed.push(readRustBuffer(rb).data);
+    },
+  test_rustbuffer_from_bytes:
+    (host: FakeHost) => (foreign: Uint8Array, _status: bigint) => {
+      const view = dataView(foreign);
+      const len = view.getInt32(0, true);
+      const data = view.getBigUint64(8, true);
+      return host.allocRustBuffer(
+        len === 0 ? new Uint8Array(0) : host.view(data, len).slice(),
+      );
+    },
+};
+
+export const symbols = {
+  rustbuffer_alloc: "test_rustbuffer_alloc",
+  rustbuffer_free: "test_rustbuffer_free",
+  rustbuffer_from_bytes: "test_rustbuffer_from_bytes",
+};
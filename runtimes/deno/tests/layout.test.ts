@@ -0,0 +1,96 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import assert from "node:assert";
+import { FfiType } from "../src/ffi-type.js";
+import { compileStructLayouts, nativeType } from "../src/layout.js";
+import {
+  callShimSource,
+  callbackShimSource,
+  flattenedSignature,
+} from "../src/c-shim.js";
+
+test("struct fields are laid out with C alignment", () => {
+  const layouts = compileStructLayouts({
+    Outer: [
+      { name: "flag", type: FfiType.Int8 },
+      { name: "inner", type: FfiType.Struct("Inner") },
+      { name: "buf", type: FfiType.RustBuffer },
+    ],
+    Inner: [
+      { name: "a", type: FfiType.UInt16 },
+      { name: "b", type: FfiType.Callback("Cb") },
+    ],
+  });
+  const inner = layouts.get("Inner")!;
+  assert.deepStrictEqual(
+    inner.fields.map((f) => f.offset),
+    [0, 8],
+  );
+  assert.strictEqual(inner.size, 16);
+
+  const outer = layouts.get("Outer")!;
+  assert.deepStrictEqual(
+    outer.fields.map((f) => f.offset),
+    [0, 8, 24],
+  );
+  assert.strictEqual(outer.size, 48);
+  assert.deepStrictEqual(nativeType(FfiType.Struct("Inner"), layouts), {
+    struct: ["u16", "pointer"],
+  });
+});
+
+test("a struct that contains itself is rejected", () => {
+  assert.throws(
+    () =>
+      compileStructLayouts({
+        Loop: [{ name: "me", type: FfiType.Struct("Loop") }],
+      }),
+    /contains itself/,
+  );
+});
+
+const RUST_BUFFER = { struct: ["u64", "u64", "pointer"] } as const;
+
+test("a struct result is flattened to a trailing out pointer", () => {
+  const signature = {
+    parameters: [RUST_BUFFER as any, "pointer" as const],
+    result: RUST_BUFFER as any,
+  };
+  assert.deepStrictEqual(flattenedSignature(signature), {
+    parameters: ["pointer", "pointer", "pointer"],
+    result: "void",
+  });
+
+  const source = callShimSource(signature);
+  assert.match(
+    source,
+    /typedef struct \{ uint64_t f0; uint64_t f1; void \* f2; \} s0;/,
+  );
+  assert.match(
+    source,
+    /void call\(void \*fn, const s0 \* a0, void \* a1, s0 \*out\) \{/,
+  );
+  assert.match(
+    source,
+    /\*out = \(\(s0 \(\*\)\(s0, void \*\)\)fn\)\(\*a0, a1\);/,
+  );
+});
+
+test("a callback trampoline hands struct args over by address", () => {
+  const source = callbackShimSource({
+    parameters: ["u64", RUST_BUFFER as any, "pointer"],
+    result: "void",
+  });
+  assert.match(
+    source,
+    /static void trampoline\(uint64_t a0, s0 a1, void \* a2\) \{/,
+  );
+  assert.match(
+    source,
+    /\(\(void \(\*\)\(uint64_t, void \*, void \*\)\)target\)\(a0, &a1, a2\);/,
+  );
+});
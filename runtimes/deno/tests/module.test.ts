@@ -0,0 +1,266 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import assert from "node:assert";
+import { FfiType } from "../src/ffi-type.js";
+import { UniffiNativeModule } from "../src/module.js";
+import {
+  RUST_CALL_STATUS_SIZE,
+  dataView,
+  readRustBuffer,
+} from "../src/layout.js";
+import { FakeHost, rustBufferSymbols, symbols } from "./fake-host.js";
+
+const encoder = new TextEncoder();
+const decoder = new TextDecoder();
+
+function open(host: FakeHost): UniffiNativeModule {
+  return UniffiNativeModule.open("/fake/libtest.so", { host });
+}
+
+test("scalars pass through and the status is set in place", () => {
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_add: () => (a: number, b: number, _status: bigint) => a + b,
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_add: {
+        args: [FfiType.Int32, FfiType.Int32],
+        ret: FfiType.Int32,
+        hasRustCallStatus: true,
+      },
+    },
+    callbacks: {},
+    structs: {},
+  });
+  const status = { code: -1 };
+  assert.strictEqual(nm.test_fn_add(2, 3, status), 5);
+  assert.strictEqual(status.code, 0);
+});
+
+test("a RustBuffer crosses both ways and its view is freed once", () => {
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_echo: (h: FakeHost) => (rb: Uint8Array, _status: bigint) =>
+      h.allocRustBuffer(h.readRustBuffer(rb)),
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_echo: {
+        args: [FfiType.RustBuffer],
+        ret: FfiType.RustBuffer,
+        hasRustCallStatus: true,
+      },
+    },
+    callbacks: {},
+    structs: {},
+  });
+  const view: Uint8Array = nm.test_fn_echo(encoder.encode("hello"), {
+    code: 0,
+  });
+  assert.strictEqual(decoder.decode(view), "hello");
+
+  nm.rustbuffer_free(view);
+  nm.rustbuffer_free(view);
+  assert.strictEqual(host.freed.length, 1);
+  assert.strictEqual(host.freed[0], host.addressOf(view));
+});
+
+test("a view from rustbuffer_alloc is handed over, not copied", () => {
+  let received: bigint | undefined;
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_consume: () => (rb: Uint8Array, _status: bigint) => {
+      received = readRustBuffer(rb).data;
+    },
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_consume: {
+        args: [FfiType.RustBuffer],
+        ret: FfiType.Void,
+        hasRustCallStatus: true,
+      },
+    },
+    callbacks: {},
+    structs: {},
+  });
+  const view: Uint8Array = nm.rustbuffer_alloc(4);
+  assert.strictEqual(view.byteLength, 4);
+  nm.test_fn_consume(view, { code: 0 });
+  assert.strictEqual(received, host.addressOf(view));
+
+  // The library owns it now, so freeing the view does nothing.
+  nm.rustbuffer_free(view);
+  assert.deepStrictEqual(host.freed, []);
+});
+
+test("an error status copies out errorBuf and frees the native one", () => {
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_fail: (h: FakeHost) => (status: bigint) => {
+      h.setError(status, 1, "boom");
+      return 0;
+    },
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_fail: {
+        args: [],
+        ret: FfiType.Int32,
+        hasRustCallStatus: true,
+      },
+    },
+    callbacks: {},
+    structs: {},
+  });
+  const status: { code: number; errorBuf?: Uint8Array } = { code: 0 };
+  assert.strictEqual(nm.test_fn_fail(status), undefined);
+  assert.strictEqual(status.code, 1);
+  assert.strictEqual(decoder.decode(status.errorBuf), "boom");
+  assert.strictEqual(host.freed.length, 1);
+});
+
+test("a missing symbol throws on each call, not at register()", () => {
+  const host = new FakeHost({ ...rustBufferSymbols });
+  const definitions = {
+    symbols,
+    functions: {
+      test_fn_missing: {
+        args: [],
+        ret: FfiType.Void,
+        hasRustCallStatus: false,
+      },
+    },
+    callbacks: {},
+    structs: {},
+  };
+  const nm = open(host).register(definitions);
+  assert.throws(() => nm.test_fn_missing(), /symbol not found/);
+  assert.throws(() => nm.test_fn_missing(), /symbol not found/);
+
+  assert.throws(
+    () => open(host).register(definitions, { verifySymbols: true }),
+    /symbol not found/,
+  );
+});
+
+test("register() declares every symbol to the host at once", () => {
+  const host = new FakeHost({ ...rustBufferSymbols });
+  open(host).register({
+    symbols,
+    functions: {
+      test_fn_one: { args: [], ret: FfiType.Void, hasRustCallStatus: false },
+      test_fn_two: { args: [], ret: FfiType.Void, hasRustCallStatus: true },
+    },
+    callbacks: {},
+    structs: {},
+  });
+  assert.deepStrictEqual(host.declared, [
+    [
+      symbols.rustbuffer_alloc,
+      symbols.rustbuffer_free,
+      symbols.rustbuffer_from_bytes,
+      "test_fn_one",
+      "test_fn_two",
+    ],
+  ]);
+});
+
+test("a vtable method writes its out-return and status", () => {
+  let out: { value: bigint; code: number } | undefined;
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_init: (h: FakeHost) => (vtable: bigint, _status: bigint) => {
+      const method = dataView(h.view(vtable, 8)).getBigUint64(0, true);
+      const outReturn = new BigUint64Array(1);
+      const status = new Uint8Array(RUST_CALL_STATUS_SIZE);
+      h.fnPointer(method, { parameters: [], result: "void" })(
+        42n,
+        h.allocRustBuffer(encoder.encode("arg")),
+        h.addressOf(new Uint8Array(outReturn.buffer)),
+        h.addressOf(status),
+      );
+      out = { value: outReturn[0], code: dataView(status).getInt8(0) };
+    },
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_init: {
+        args: [FfiType.Reference(FfiType.Struct("VTable"))],
+        ret: FfiType.Void,
+        hasRustCallStatus: true,
+      },
+    },
+    callbacks: {
+      Method: {
+        args: [FfiType.UInt64, FfiType.RustBuffer],
+        ret: FfiType.UInt64,
+        hasRustCallStatus: true,
+        outReturn: true,
+      },
+    },
+    structs: {
+      VTable: [{ name: "method", type: FfiType.Callback("Method") }],
+    },
+  });
+
+  let seen: [bigint, string] | undefined;
+  nm.test_fn_init(
+    {
+      method: (handle: bigint, arg: Uint8Array) => {
+        seen = [handle, decoder.decode(arg)];
+        return { code: 0, pointee: 7n };
+      },
+    },
+    { code: 0 },
+  );
+  assert.deepStrictEqual(seen, [42n, "arg"]);
+  assert.deepStrictEqual(out, { value: 7n, code: 0 });
+  // The RustBuffer argument was copied for JS and then freed.
+  assert.strictEqual(host.freed.length, 1);
+});
+
+test("a callback passed to a call holds the event loop until it fires", () => {
+  let continuation: bigint | undefined;
+  const host = new FakeHost({
+    ...rustBufferSymbols,
+    test_fn_poll: () => (callback: bigint, _data: bigint) => {
+      continuation = callback;
+    },
+  });
+  const nm = open(host).register({
+    symbols,
+    functions: {
+      test_fn_poll: {
+        args: [FfiType.Callback("Continuation"), FfiType.UInt64],
+        ret: FfiType.Void,
+        hasRustCallStatus: false,
+      },
+    },
+    callbacks: {
+      Continuation: {
+        args: [FfiType.UInt64, FfiType.Int8],
+        ret: FfiType.Void,
+        hasRustCallStatus: false,
+      },
+    },
+    structs: {},
+  });
+  const polls: [bigint, number][] = [];
+  nm.test_fn_poll((data: bigint, poll: number) => polls.push([data, poll]), 9n);
+  assert.strictEqual(host.refs.count, 1);
+
+  host.fnPointer(continuation!, { parameters: [], result: "void" })(9n, 0);
+  assert.deepStrictEqual(polls, [[9n, 0]]);
+  assert.strictEqual(host.refs.count, 0);
+});
@@ -0,0 +1,112 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// Test that the player can load and call the arithmetic example crate under
+// the runtime's own FFI. Run under Deno or Bun, after `npm run build` and
+// `cargo build -p uniffi-example-arithmetic`:
+//
+//   deno test --allow-ffi --allow-read --allow-env tests/native/
+//   bun test tests/native/
+//
+// The unit tests under Node drive the player through a fake host; this is
+// the one which crosses into a real library.
+
+import assert from "node:assert";
+import { existsSync } from "node:fs";
+import { dirname, join } from "node:path";
+import process from "node:process";
+import { fileURLToPath } from "node:url";
+
+import { FfiType, UniffiNativeModule } from "../../dist/src/index.js";
+
+// Each runtime's own test runner; a variable keeps Deno from resolving
+// `bun:test` ahead of time.
+const bunTest = "bun:test";
+const test = globalThis.Deno
+  ? (name, fn) => globalThis.Deno.test(name, fn)
+  : (await import(bunTest)).test;
+
+function libPath(libName) {
+  let dir = dirname(fileURLToPath(import.meta.url));
+  while (dir !== dirname(dir)) {
+    const candidate = join(dir, "target", "debug");
+    if (existsSync(candidate)) {
+      const fileName =
+        process.platform === "darwin"
+          ? `lib${libName}.dylib`
+          : `lib${libName}.so`;
+      return join(candidate, fileName);
+    }
+    dir = dirname(dir);
+  }
+  throw new Error("Could not find target/debug/");
+}
+
+const CRATE = "arithmetical";
+
+const SYMBOLS = {
+  rustbuffer_alloc: `ffi_${CRATE}_rustbuffer_alloc`,
+  rustbuffer_free: `ffi_${CRATE}_rustbuffer_free`,
+  rustbuffer_from_bytes: `ffi_${CRATE}_rustbuffer_from_bytes`,
+};
+
+const U64_PAIR = {
+  args: [FfiType.UInt64, FfiType.UInt64],
+  ret: FfiType.UInt64,
+  hasRustCallStatus: true,
+};
+
+function register(options) {
+  return UniffiNativeModule.open(libPath(CRATE)).register(
+    {
+      symbols: SYMBOLS,
+      structs: {},
+      callbacks: {},
+      functions: {
+        [`uniffi_${CRATE}_fn_func_add`]: U64_PAIR,
+        [`uniffi_${CRATE}_fn_func_sub`]: U64_PAIR,
+        [`uniffi_${CRATE}_fn_func_equal`]: {
+          args: [FfiType.UInt64, FfiType.UInt64],
+          ret: FfiType.Int8,
+          hasRustCallStatus: true,
+        },
+        [`uniffi_${CRATE}_fn_func_missing`]: U64_PAIR,
+      },
+    },
+    options,
+  );
+}
+
+test("arithmetic: add(3, 4) = 7", () => {
+  const nm = register();
+  const status = { code: -1 };
+  assert.strictEqual(nm[`uniffi_${CRATE}_fn_func_add`](3n, 4n, status), 7n);
+  assert.strictEqual(status.code, 0);
+});
+
+test("arithmetic: equal(5, 5) is true, equal(5, 6) is false", () => {
+  const nm = register();
+  const status = { code: -1 };
+  assert.strictEqual(nm[`uniffi_${CRATE}_fn_func_equal`](5n, 5n, status), 1);
+  assert.strictEqual(nm[`uniffi_${CRATE}_fn_func_equal`](5n, 6n, status), 0);
+  assert.strictEqual(status.code, 0);
+});
+
+test("arithmetic: sub(2, 3) overflows, with an error status", () => {
+  const nm = register();
+  const status = { code: -1 };
+  nm[`uniffi_${CRATE}_fn_func_sub`](2n, 3n, status);
+  assert.strictEqual(status.code, 1);
+  assert.ok(status.errorBuf instanceof Uint8Array);
+  assert.ok(status.errorBuf.byteLength > 0);
+});
+
+test("arithmetic: a missing symbol throws on its call, not at register()", () => {
+  const nm = register();
+  assert.throws(() =>
+    nm[`uniffi_${CRATE}_fn_func_missing`](1n, 2n, { code: 0 }),
+  );
+  assert.throws(() => register({ verifySymbols: true }));
+});
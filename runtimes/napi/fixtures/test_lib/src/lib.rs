@@ -4,7 +4,7 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 use std::ptr;
-use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, AtomicI8, Ordering};
 use std::sync::Mutex;
 
 #[repr(C)]
@@ -81,6 +81,18 @@ pub extern "C" fn uniffi_test_fn_i64_negate(x: i64, status: &mut RustCallStatus)
     -x
 }
 
+// --- Contract version and API checksum, as uniffi scaffolding exports them ---
+
+#[no_mangle]
+pub extern "C" fn ffi_test_uniffi_contract_version() -> u32 {
+    29
+}
+
+#[no_mangle]
+pub extern "C" fn uniffi_test_checksum_fn_add() -> u16 {
+    4021
+}
+
 // --- RustBuffer helpers ---
 
 /// Census of the `RustBuffer` allocations this fixture currently owns.
@@ -325,6 +337,81 @@ pub extern "C" fn uniffi_test_fn_call_callback_from_thread(
     });
 }
 
+// --- A handle shared between environments ---
+
+/// Create a counter starting at `start`, owned by the returned handle until it is freed.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_counter_new(start: i64, status: &mut RustCallStatus) -> u64 {
+    status.code = 0;
+    Box::into_raw(Box::new(AtomicI64::new(start))) as u64
+}
+
+/// Add `n` to the counter behind `handle`, returning the new total.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_counter_add(
+    handle: u64,
+    n: i64,
+    status: &mut RustCallStatus,
+) -> i64 {
+    status.code = 0;
+    // SAFETY: `handle` came from `uniffi_test_fn_counter_new` and has not been freed.
+    let counter = unsafe { &*(handle as *const AtomicI64) };
+    counter.fetch_add(n, Ordering::SeqCst) + n
+}
+
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_counter_free(handle: u64, status: &mut RustCallStatus) {
+    status.code = 0;
+    // SAFETY: `handle` came from `uniffi_test_fn_counter_new`, and is freed once.
+    drop(unsafe { Box::from_raw(handle as *mut AtomicI64) });
+}
+
+// --- Many callbacks from another thread ---
+
+static BURST_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Call `cb(i, 0)` for each `i` below `count`, in order, from one new thread.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_call_callback_burst_from_thread(
+    cb: SimpleCallback,
+    count: u32,
+    status: &mut RustCallStatus,
+) {
+    status.code = 0;
+    BURST_DONE.store(false, Ordering::SeqCst);
+    std::thread::spawn(move || {
+        for i in 0..count {
+            cb(i.into(), 0);
+        }
+        BURST_DONE.store(true, Ordering::SeqCst);
+    });
+}
+
+/// Call `cb(i, buffer)` for each `i` below `count`, in order, from one new thread, handing
+/// each call a fresh 4-byte buffer. Shares [`BURST_DONE`] with the burst above.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_call_buffer_callback_burst_from_thread(
+    cb: BufferCallback,
+    count: u32,
+    status: &mut RustCallStatus,
+) {
+    status.code = 0;
+    BURST_DONE.store(false, Ordering::SeqCst);
+    std::thread::spawn(move || {
+        for i in 0..count {
+            cb(i.into(), alloc_rustbuffer(&[0xDE, 0xAD, 0xBE, 0xEF]));
+        }
+        BURST_DONE.store(true, Ordering::SeqCst);
+    });
+}
+
+/// Whether the thread started by the last burst has made all its calls.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_is_burst_done(status: &mut RustCallStatus) -> i8 {
+    status.code = 0;
+    BURST_DONE.load(Ordering::SeqCst).into()
+}
+
 // --- VTable test ---
 
 #[repr(C)]
@@ -359,6 +446,7 @@ pub extern "C" fn uniffi_test_fn_use_vtable(handle: u64, status: &mut RustCallSt
 // --- Blocking cross-thread test ---
 
 static THREAD_RESULT: AtomicI32 = AtomicI32::new(0);
+static THREAD_STATUS: AtomicI8 = AtomicI8::new(0);
 static THREAD_DONE: AtomicBool = AtomicBool::new(false);
 
 #[no_mangle]
@@ -375,6 +463,7 @@ pub extern "C" fn uniffi_test_fn_use_vtable_from_thread(handle: u64, status: &mu
             let mut cb_status = new_cb_status();
             let result = (get_value)(handle, &mut cb_status);
             THREAD_RESULT.store(result, Ordering::SeqCst);
+            THREAD_STATUS.store(cb_status.code, Ordering::SeqCst);
             THREAD_DONE.store(true, Ordering::SeqCst);
         });
     }
@@ -396,6 +485,47 @@ pub extern "C" fn uniffi_test_fn_get_thread_result(status: &mut RustCallStatus)
     THREAD_RESULT.load(Ordering::SeqCst)
 }
 
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_get_thread_status(status: &mut RustCallStatus) -> i8 {
+    status.code = 0;
+    THREAD_STATUS.load(Ordering::SeqCst)
+}
+
+// --- Cross-thread callback while the JS thread waits in Rust ---
+
+/// A `RustCallStatus` handed back from the thread that filled it in.
+struct SendStatus(RustCallStatus);
+
+// SAFETY: the error buffer is an allocation of this library's, owned by whoever holds the status.
+unsafe impl Send for SendStatus {}
+
+/// Calls the stored `get_value` from another thread and waits for it, keeping the JS thread in
+/// this call where it cannot answer. The callback's status becomes this call's status.
+#[no_mangle]
+pub extern "C" fn uniffi_test_fn_use_vtable_from_thread_and_wait(
+    handle: u64,
+    status: &mut RustCallStatus,
+) -> i32 {
+    status.code = 0;
+    let get_value = STORED_VTABLE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|vt| vt.get_value);
+    let Some(get_value) = get_value else {
+        return -1;
+    };
+    let (result, cb_status) = std::thread::spawn(move || {
+        let mut cb_status = new_cb_status();
+        let result = (get_value)(handle, &mut cb_status);
+        (result, SendStatus(cb_status))
+    })
+    .join()
+    .unwrap();
+    *status = cb_status.0;
+    result
+}
+
 // --- Non-blocking cross-thread test ---
 
 #[repr(C)]
@@ -3,6 +3,19 @@
 
 /* auto-generated by NAPI-RS */
 
+/** Counters describing a registered module, returned by [`UniffiNativeModule::metrics`]. */
+export interface UniffiNativeModuleMetrics {
+  /**
+   * Callback trampolines built and not yet freed. Only trampolines for callbacks declared
+   * `once` are ever freed, after their JS function has been garbage collected.
+   */
+  liveTrampolines: number
+  /**
+   * Buffers returned by functions declared `externalReturn` that the garbage collector has
+   * yet to release.
+   */
+  liveExternalBuffers: number
+}
 /**
  * The top-level napi class exposed to JavaScript.
  *
@@ -17,7 +30,7 @@ export declare class UniffiNativeModule {
    * Validate the library path and store it for later use by `register()`.
    *
    * The library is not fully opened here — `Module::new()` in `register()`
-   * handles `dlopen`, symbol resolution, and CIF construction in one step.
+   * handles `dlopen` and sets up symbol resolution and CIF construction.
    * However, we do a trial `dlopen` to validate the path eagerly, so that
    * callers get an immediate error for invalid paths rather than a deferred
    * error at `register()` time.
@@ -31,8 +44,56 @@ export declare class UniffiNativeModule {
   /**
    * Parse JS-provided type definitions and produce a JS object whose methods
    * are bound to the foreign functions found in the loaded library.
+   *
+   * `definitions` is either a binary-encoded spec in a `Uint8Array`, as
+   * generated code emits, or the equivalent tagged JS object.
+   *
+   * Each function's symbol is looked up, and its CIF built, on its first
+   * call, so a missing symbol is reported by that call. Passing
+   * `{ verifySymbols: true }` resolves every function here instead, making
+   * `register()` throw on the first one missing from the library.
+   *
+   * A callback invoked from another thread blocks that thread until the JS thread has run it.
+   * Passing `{ dispatchTimeoutMs: n }` bounds that wait: a callback the JS thread has not
+   * answered within `n` milliseconds fails instead, through its `RustCallStatus`. Without it,
+   * the wait only ends early when the JS thread is stuck in a synchronous call into Rust.
+   * It is judged stuck once it has spent `{ deadlockGraceMs: n }` (default 1000) in the same
+   * call since the dispatch; `0` turns the check off.
+   *
+   * An option of the wrong type makes `register()` throw.
+   *
+   * A callback that returns nothing, reports no errors and takes no pointers does not block
+   * its thread: the call is queued for the JS thread, and runs after those queued before it.
+   * Past `{ callbackQueueLimit: n }` queued calls (default 1024) the next blocks as above;
+   * `0` blocks every time.
+   *
+   * Besides a method per function, the object has `batch(calls)`, which takes an array of
+   * `[fnName, args]` pairs and returns the array of their results, making every call in one
+   * crossing into Rust. Each `args` is what calling `fnName` directly would take, status
+   * object included.
+   */
+  register(definitions: object, options?: object | undefined | null): object
+  /**
+   * Swap in a rebuilt copy of the library at `path`, and return a fresh object like the one
+   * `register()` returned, bound to it.
+   *
+   * Every function registered is looked up in the new library before anything changes, so
+   * one it lacks makes `reload()` throw with the current library still in service. Then
+   * in-flight calls drain and the current library is unloaded as by `unload({ force: true })`.
+   * From there, calling the object returned earlier throws, as do async calls still
+   * polling, so do not pass it, or any object handle it returned, to the new one.
+   *
+   * Passing `definitions` as well checks them against those registered, by checksum, and
+   * throws if they differ: a changed API needs the process restarted.
+   *
+   * The system loader hands back a library it already has loaded, so `path` must name a
+   * new file — a copy of each build, say — for the new code to run. A library still
+   * loaded, this one or one kept open for an earlier object's buffers, makes `reload()`
+   * throw with the current library still in service.
    */
-  register(definitions: object): object
+  reload(path: string, definitions?: object | undefined | null): object
+  /** Counters describing the registered module, all zero before `register()`. */
+  metrics(): UniffiNativeModuleMetrics
   /**
    * Unload the native module, draining in-flight calls and optionally force-closing
    * the shared library.
@@ -41,6 +102,8 @@ export declare class UniffiNativeModule {
    * `{ force: true }` is passed, `dlclose` is called on the library after
    * all in-flight calls have drained. Without `force`, the unloading flag is
    * set and callbacks are aborted, but the library mapping is left in place.
+   * While buffers returned by functions declared `externalReturn` are still
+   * reachable, `dlclose` waits for the garbage collector to release the last one.
    *
    * After `unload()` returns, this `UniffiNativeModule` no longer holds a
    * reference to the `Module` — any JS closures that captured the module will
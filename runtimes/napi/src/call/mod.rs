@@ -19,7 +19,14 @@
 //!    JS value via [`marshal::read_return_to_js`], or hand off a Rust-owned
 //!    view for `RustBuffer` returns. The codegen-emitted lift wrapper consumes
 //!    the view inside a `try/finally` and calls back through `rustbuffer_free`
-//!    to release the underlying Rust allocation.
+//!    to release the underlying Rust allocation. A function declared with
+//!    `externalReturn` instead hands over a view that the garbage collector
+//!    releases, so lifted values may alias the Rust allocation.
+//!
+//! [`call_ffi_batch`] runs the same steps for a list of calls, lowering all of them before
+//! invoking them together through
+//! [`Module::call_batch`](uniffi_runtime_core::Module::call_batch), so a hot loop pays for one
+//! crossing into native code rather than one per call.
 
 mod marshal;
 
@@ -36,7 +43,7 @@ use crate::napi_utils::CapacitySymbol;
 use uniffi_runtime_core::ffi_c_types::{RustBufferC, RustCallStatusC};
 use uniffi_runtime_core::slot;
 use uniffi_runtime_core::CallReturn;
-use uniffi_runtime_core::{FfiTypeDesc, Module};
+use uniffi_runtime_core::{FfiTypeDesc, Module, PreparedCall};
 
 /// Execute a single FFI call for `fn_name` registered in `module`.
 ///
@@ -44,6 +51,9 @@ use uniffi_runtime_core::{FfiTypeDesc, Module};
 /// native function via [`Module::call`], and returns the result as a JS value.
 /// If `has_rust_call_status` is set, the final JS argument is treated as a
 /// `{ code, errorBuf }` status object that Rust writes error information into.
+/// If `external_return` is set, a returned `RustBuffer` is released by the
+/// garbage collector rather than by `rustbuffer_free`.
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn call_ffi_function(
     env: &napi::Env,
     ctx: &napi::CallContext<'_>,
@@ -51,8 +61,148 @@ pub(crate) fn call_ffi_function(
     module: &Arc<Module>,
     arg_types: &[FfiTypeDesc],
     has_rust_call_status: bool,
+    external_return: bool,
+    registration: &Arc<crate::register::Registration>,
+) -> Result<JsUnknown> {
+    let lowered = lower_call(
+        env,
+        fn_name,
+        module,
+        arg_types,
+        has_rust_call_status,
+        registration,
+        |i| ctx.get(i),
+    )?;
+
+    // Until this returns, the JS thread cannot answer a callback dispatched from another thread.
+    let call_ret = {
+        let _in_rust = registration.env_state.enter_sync_call();
+        module.call(lowered.call).map_err(core_err)?
+    };
+
+    finish_status(env, module, lowered.status)?;
+    return_to_js(env, module, registration, call_ret, external_return)
+}
+
+/// Execute a list of FFI calls in one crossing into native code.
+///
+/// `calls` is an array of `[fnName, args]` pairs, where `args` holds exactly what a single call
+/// to `fnName` would take — including its trailing status object, if it has a
+/// `RustCallStatus`. Every call is lowered first, then all of them run in order via
+/// [`Module::call_batch`]; each status object is written back as for a single call, and the
+/// results are returned as an array in the same order.
+///
+/// A failing call does not stop the ones after it: as for a single call, a Rust-side error is
+/// reported through that call's status object. An argument that fails to lower throws before
+/// any call runs.
+pub(crate) fn call_ffi_batch(
+    env: &napi::Env,
+    calls: &JsObject,
+    module: &Arc<Module>,
     registration: &Arc<crate::register::Registration>,
 ) -> Result<JsUnknown> {
+    if !calls.is_array()? {
+        return Err(napi::Error::from_reason(
+            "batch expected an array of [fnName, args] pairs",
+        ));
+    }
+    let count = calls.get_array_length()?;
+
+    // NOTE: as in `lower_call`, lowering adopts library-owned `RustBuffer` arguments. If a later
+    // entry fails to lower, the batch throws without invoking anything, orphaning buffers the
+    // earlier entries adopted. Again this is a misuse/error path only.
+    let mut lowered = Vec::with_capacity(count as usize);
+    let mut external_returns = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let entry: JsObject = calls.get_element(index)?;
+        let fn_name: String = entry
+            .get_element::<napi::JsString>(0)?
+            .into_utf8()?
+            .into_owned()?;
+        let args: JsObject = entry.get_element(1)?;
+        let func_def = module.function_def(&fn_name).ok_or_else(|| {
+            napi::Error::from_reason(format!("Function not found in module: {fn_name}"))
+        })?;
+        let arg_count = func_def.args.len() + usize::from(func_def.has_rust_call_status);
+        if (args.get_array_length()? as usize) < arg_count {
+            return Err(napi::Error::from_reason(format!(
+                "batch entry {index} ({fn_name}) expected {arg_count} arguments"
+            )));
+        }
+        lowered.push(lower_call(
+            env,
+            &fn_name,
+            module,
+            &func_def.args,
+            func_def.has_rust_call_status,
+            registration,
+            |i| args.get_element(i as u32),
+        )?);
+        external_returns.push(func_def.external_return);
+    }
+
+    let (calls, statuses): (Vec<_>, Vec<_>) = lowered
+        .into_iter()
+        .map(|lowered| (lowered.call, lowered.status))
+        .unzip();
+
+    // Until this returns, the JS thread cannot answer a callback dispatched from another thread.
+    let results = {
+        let _in_rust = registration.env_state.enter_sync_call();
+        module.call_batch(calls).map_err(core_err)?
+    };
+
+    for status in statuses {
+        finish_status(env, module, status)?;
+    }
+
+    // Every call has run, so a failed one must not strand the `RustBuffer`s the others
+    // returned: release those before reporting it.
+    if let Some(position) = results.iter().position(|result| result.is_err()) {
+        for result in &results {
+            if let Ok(CallReturn::RustBuffer(rb)) = result {
+                // SAFETY: `rb` was just returned by the callee and has no other owner, and
+                // `free_ptr` was resolved by dlsym at registration time.
+                unsafe { napi_utils::free_rustbuffer(*rb, module.rb_ops().free_ptr) };
+            }
+        }
+        let Some(Err(error)) = results.into_iter().nth(position) else {
+            unreachable!("position was found by is_err");
+        };
+        return Err(core_err(error));
+    }
+
+    let mut js_results = env.create_array_with_length(results.len())?;
+    for (index, (result, external_return)) in results.into_iter().zip(external_returns).enumerate()
+    {
+        let call_ret = result.map_err(core_err)?;
+        let js_result = return_to_js(env, module, registration, call_ret, external_return)?;
+        js_results.set_element(index as u32, js_result)?;
+    }
+    Ok(js_results.into_unknown())
+}
+
+/// A call whose arguments have all been lowered, ready to invoke.
+struct LoweredCall<'m> {
+    call: PreparedCall<'m>,
+    /// The `RustCallStatus` the call's status slot points at, boxed so the pointer survives the
+    /// call being moved, and the JS status object to write it back into.
+    status: Option<(Box<RustCallStatusC>, JsObject)>,
+}
+
+/// Marshal the JS arguments of one call to `fn_name` into a [`PreparedCall`].
+///
+/// `arg(i)` reads the `i`th JS argument; if `has_rust_call_status` is set, the one after the
+/// declared arguments is the `{ code, errorBuf }` status object.
+fn lower_call<'m>(
+    env: &napi::Env,
+    fn_name: &str,
+    module: &'m Arc<Module>,
+    arg_types: &[FfiTypeDesc],
+    has_rust_call_status: bool,
+    registration: &Arc<crate::register::Registration>,
+    arg: impl Fn(usize) -> Result<JsUnknown>,
+) -> Result<LoweredCall<'m>> {
     let declared_arg_count = arg_types.len();
 
     let mut call = module.prepare_call(fn_name).map_err(core_err)?;
@@ -63,7 +213,7 @@ pub(crate) fn call_ffi_function(
     // only happens on a misuse/error path — e.g. passing the same alloc'd view twice, which trips
     // the "already consumed" guard — never on the happy path, which always reaches the call.
     for (i, desc) in arg_types.iter().enumerate() {
-        let js_val: JsUnknown = ctx.get(i)?;
+        let js_val = arg(i)?;
         let slot = call.arg_slot(i).map_err(core_err)?;
         match desc {
             FfiTypeDesc::RustBuffer => {
@@ -87,62 +237,10 @@ pub(crate) fn call_ffi_function(
                 slot::write_pointer(slot, struct_ptr);
             }
             FfiTypeDesc::Callback(cb_name) => {
-                // Reuse this function's trampoline if it already has one. Building one is
-                // permanently leaked by design — the library may invoke the pointer from any
-                // thread later — so the intended bound is one per callback type, and building
-                // one per call turns that into unbounded growth.
-                //
-                // Keying on the function object is what makes reuse correct: the Symbols
-                // belong to this `register()` call and so to this env, and a trampoline holds
-                // no per-call state, since callbacks receive their handle as an ordinary
-                // argument.
-                //
-                // A hit costs only the lookup — nothing below runs until a miss.
-                //
-                // SAFETY: `js_val` is a value from the current callback scope, so `raw()`
-                // yields a `napi_value` valid for that scope without transferring ownership.
-                let raw_fn_val = unsafe { js_val.raw() };
-                // SAFETY: `env` is the active env for this call and `raw_fn_val` is the value
-                // read above; a lookup miss is reported as `Ok(None)`, so a JS function
-                // carrying no marker is simply built below rather than misread.
-                let cached = unsafe {
-                    registration
-                        .trampolines
-                        .get(env.raw(), raw_fn_val, cb_name)?
-                };
-                let fn_ptr = match cached {
-                    Some(fn_ptr) => fn_ptr,
-                    None => {
-                        // SAFETY: `raw_fn_val` is a `napi_value` from this callback scope.
-                        // `from_raw` errors rather than aborting if it is not a function, and
-                        // the declared arg type is `Callback`, so a non-function here is a
-                        // caller error surfaced as a JS exception.
-                        let js_fn = unsafe { napi::JsFunction::from_raw(env.raw(), raw_fn_val)? };
-                        let user_data = callback::create_callback_user_data(
-                            env,
-                            js_fn,
-                            cb_name,
-                            module,
-                            registration,
-                        )?;
-                        let fn_ptr = module
-                            .make_callback_trampoline(
-                                cb_name,
-                                callback::on_js_thread,
-                                callback::dispatch_to_js_thread,
-                                callback::is_js_thread,
-                                user_data,
-                            )
-                            .map_err(core_err)?;
-                        // SAFETY: as for the lookup above — same env, same value. The
-                        // pointer stored is the trampoline just built for `cb_name`.
-                        unsafe {
-                            registration
-                                .trampolines
-                                .set(env.raw(), raw_fn_val, cb_name, fn_ptr)?
-                        };
-                        fn_ptr
-                    }
+                // SAFETY: `js_val` is a value from the current callback scope, on the JS
+                // thread that registered `registration`.
+                let fn_ptr = unsafe {
+                    callback::trampoline_for(env, &js_val, cb_name, module, registration)?
                 };
                 slot::write_pointer(slot, fn_ptr);
             }
@@ -152,79 +250,95 @@ pub(crate) fn call_ffi_function(
         }
     }
 
-    let mut rust_call_status = RustCallStatusC::default();
-    let mut status_js_obj: Option<JsObject> = None;
+    let mut status = None;
 
     if has_rust_call_status {
-        let status_idx = declared_arg_count;
-        let js_status: JsObject = ctx.get(status_idx)?;
+        let js_status: JsObject = arg(declared_arg_count)?.coerce_to_object()?;
         let code_val: i32 = js_status.get_named_property("code")?;
-        rust_call_status.code = code_val as i8;
-        status_js_obj = Some(js_status);
+        let mut rust_call_status = Box::new(RustCallStatusC {
+            code: code_val as i8,
+            ..RustCallStatusC::default()
+        });
 
-        let status_ptr = &mut rust_call_status as *mut RustCallStatusC;
+        let status_ptr = &mut *rust_call_status as *mut RustCallStatusC;
         if let Some(rcs_slot) = call.rust_call_status_slot() {
             slot::write_pointer(rcs_slot, status_ptr as *const c_void);
         }
+        status = Some((rust_call_status, js_status));
     }
 
-    let call_ret = module.call(call).map_err(core_err)?;
+    Ok(LoweredCall { call, status })
+}
 
-    if has_rust_call_status {
-        if let Some(mut js_status) = status_js_obj {
-            js_status
-                .set_named_property("code", env.create_int32(rust_call_status.code as i32)?)?;
+/// Write a finished call's `RustCallStatus` back into its JS status object, handing any error
+/// buffer to JS as a copy and releasing the Rust allocation.
+fn finish_status(
+    env: &napi::Env,
+    module: &Module,
+    status: Option<(Box<RustCallStatusC>, JsObject)>,
+) -> Result<()> {
+    if let Some((rust_call_status, mut js_status)) = status {
+        js_status.set_named_property("code", env.create_int32(rust_call_status.code as i32)?)?;
 
-            if rust_call_status.code != 0 && !rust_call_status.error_buf_data.is_null() {
-                let raw_env = env.raw();
+        if rust_call_status.code != 0 && !rust_call_status.error_buf_data.is_null() {
+            let raw_env = env.raw();
 
-                let error_rb = RustBufferC {
-                    capacity: rust_call_status.error_buf_capacity,
-                    len: rust_call_status.error_buf_len,
-                    data: rust_call_status.error_buf_data,
-                };
+            let error_rb = RustBufferC {
+                capacity: rust_call_status.error_buf_capacity,
+                len: rust_call_status.error_buf_len,
+                data: rust_call_status.error_buf_data,
+            };
 
-                match usize::try_from(rust_call_status.error_buf_len) {
-                    Ok(len) => {
-                        if let Ok(typedarray) = unsafe {
-                            napi_utils::create_uint8array(
-                                raw_env,
-                                rust_call_status.error_buf_data,
-                                len,
-                            )
-                        } {
-                            if let Ok(js_uint8array) =
-                                unsafe { JsUnknown::from_raw(raw_env, typedarray) }
-                            {
-                                js_status.set_named_property("errorBuf", js_uint8array)?;
-                            } else {
-                                #[cfg(debug_assertions)]
-                                eprintln!(
-                                    "uniffi-runtime-napi: failed to wrap error buffer as JsUnknown"
-                                );
-                            }
+            match usize::try_from(rust_call_status.error_buf_len) {
+                Ok(len) => {
+                    if let Ok(typedarray) = unsafe {
+                        napi_utils::create_uint8array(raw_env, rust_call_status.error_buf_data, len)
+                    } {
+                        if let Ok(js_uint8array) =
+                            unsafe { JsUnknown::from_raw(raw_env, typedarray) }
+                        {
+                            js_status.set_named_property("errorBuf", js_uint8array)?;
                         } else {
                             #[cfg(debug_assertions)]
                             eprintln!(
-                                "uniffi-runtime-napi: failed to create Uint8Array for error buffer ({len} bytes)"
+                                "uniffi-runtime-napi: failed to wrap error buffer as JsUnknown"
                             );
                         }
-                    }
-                    Err(_) => {
+                    } else {
                         #[cfg(debug_assertions)]
                         eprintln!(
-                            "uniffi-runtime-napi: error buffer len {} exceeds addressable memory",
-                            rust_call_status.error_buf_len
+                            "uniffi-runtime-napi: failed to create Uint8Array for error buffer ({len} bytes)"
                         );
                     }
                 }
-
-                unsafe { napi_utils::free_rustbuffer(error_rb, module.rb_ops().free_ptr) };
+                Err(_) => {
+                    #[cfg(debug_assertions)]
+                    eprintln!(
+                        "uniffi-runtime-napi: error buffer len {} exceeds addressable memory",
+                        rust_call_status.error_buf_len
+                    );
+                }
             }
+
+            unsafe { napi_utils::free_rustbuffer(error_rb, module.rb_ops().free_ptr) };
         }
     }
 
+    Ok(())
+}
+
+/// Convert a call's return into a JS value.
+fn return_to_js(
+    env: &napi::Env,
+    module: &Arc<Module>,
+    registration: &crate::register::Registration,
+    call_ret: CallReturn,
+    external_return: bool,
+) -> Result<JsUnknown> {
     match &call_ret {
+        CallReturn::RustBuffer(rb) if external_return => {
+            rust_buffer_to_js_uint8array_external(env, *rb, module, registration)
+        }
         CallReturn::RustBuffer(rb) => rust_buffer_to_js_uint8array_handoff(
             env,
             *rb,
@@ -235,6 +349,95 @@ pub(crate) fn call_ffi_function(
     }
 }
 
+/// A returned `RustBufferC` owned by the `ArrayBuffer` it backs, for its finalizer to release.
+struct ExternalReturn {
+    module: Arc<Module>,
+    rb: RustBufferC,
+}
+
+/// Hand a returned `RustBufferC` to JS as a `Uint8Array` over an external `ArrayBuffer` that
+/// owns it, for a function declared with `externalReturn`.
+///
+/// Nothing is copied, and nothing need be freed: when the garbage collector finds the buffer
+/// unreachable, its finalizer hands the allocation back to the library. So unlike a handoff
+/// view, this one may be aliased by whatever the lift produces — a byte array, say — and
+/// outlive the call. It carries no capacity marker, so `rustbuffer_free(view)` rejects it.
+///
+/// The buffer holds the library open: see
+/// [`Module::hold_external_rust_buffer`](uniffi_runtime_core::Module::hold_external_rust_buffer).
+fn rust_buffer_to_js_uint8array_external(
+    env: &napi::Env,
+    rb: RustBufferC,
+    module: &Arc<Module>,
+    registration: &crate::register::Registration,
+) -> Result<JsUnknown> {
+    let raw_env = env.raw();
+    let len = match usize::try_from(rb.len) {
+        Ok(len) if len > 0 && rb.capacity > 0 && !rb.data.is_null() => len,
+        // Nothing to alias, or too long to: the handoff path releases such a buffer itself.
+        _ => {
+            return rust_buffer_to_js_uint8array_handoff(
+                env,
+                rb,
+                module.rb_ops().free_ptr,
+                &registration.capacity_symbol,
+            )
+        }
+    };
+
+    module.hold_external_rust_buffer();
+    let hint = Box::into_raw(Box::new(ExternalReturn {
+        module: Arc::clone(module),
+        rb,
+    }));
+    // SAFETY: `rb.data` points to at least `len` bytes the library allocated, which stay
+    // alive until `release_external_return` frees them, after V8 has dropped the buffer.
+    let created = unsafe {
+        napi_utils::create_finalized_uint8array(
+            raw_env,
+            rb.data,
+            len,
+            release_external_return,
+            hint as *mut c_void,
+        )
+    };
+    match created {
+        Ok(typedarray) => {
+            // Tell the GC what the buffer costs, so a large one is not left to linger.
+            let mut adjusted = 0;
+            // SAFETY: `raw_env` is valid for this callback scope.
+            unsafe { napi::sys::napi_adjust_external_memory(raw_env, len as i64, &mut adjusted) };
+            // SAFETY: `typedarray` was just created in this callback scope.
+            Ok(unsafe { JsUnknown::from_raw(raw_env, typedarray)? })
+        }
+        Err((error, finalizer_owns_buffer)) => {
+            if !finalizer_owns_buffer {
+                // SAFETY: `hint` came from `Box::into_raw` above, and with no `ArrayBuffer`
+                // there is no finalizer to reclaim it.
+                let external = unsafe { Box::from_raw(hint) };
+                external.module.release_external_rust_buffer(external.rb);
+            }
+            Err(error)
+        }
+    }
+}
+
+/// The finalizer of an `externalReturn` buffer's `ArrayBuffer`.
+unsafe extern "C" fn release_external_return(
+    raw_env: napi::sys::napi_env,
+    _data: *mut c_void,
+    hint: *mut c_void,
+) {
+    // SAFETY: `hint` is the `ExternalReturn` boxed for this `ArrayBuffer`, whose finalizer
+    // runs once.
+    let external = Box::from_raw(hint as *mut ExternalReturn);
+    let mut adjusted = 0;
+    // SAFETY: `raw_env` is the env the buffer was created in; `len` fit in a `usize` and the
+    // buffer was accounted for, so it is small enough to negate.
+    napi::sys::napi_adjust_external_memory(raw_env, -(external.rb.len as i64), &mut adjusted);
+    external.module.release_external_rust_buffer(external.rb);
+}
+
 /// Hand a returned `RustBufferC` to JS as a `Uint8Array` view aliasing the
 /// Rust-owned bytes — no boundary copy. The codegen-emitted lift wrapper is
 /// expected to call `converter.lift(view)` inside a `try/finally` and invoke
@@ -7,7 +7,9 @@
 //!
 //! Building a trampoline for a callback argument is expensive and **permanently
 //! leaked**, by design: the library may invoke the function pointer from any thread
-//! at any later time, so nothing can safely reclaim it. Each one costs
+//! at any later time, so nothing can safely reclaim it. (The exception is a callback
+//! declared `once`, whose trampoline is freed after its function is collected; see
+//! [Reclaiming trampolines](super#reclaiming-trampolines).) Each one costs
 //!
 //! - a strong `napi_ref` pinning the JS function,
 //! - a leaked `CallbackUserData` (arg layout, cloned type descriptors, `Arc<Module>`),
@@ -37,7 +39,8 @@
 //!   ordinary argument, so reusing one across calls cannot mix up call state.
 //!
 //! A caller that passes a freshly created closure on every call gets no benefit and
-//! behaves exactly as before — there is nothing stable to key on.
+//! behaves exactly as before — there is nothing stable to key on — unless the
+//! callback is `once`, when each closure's trampoline goes with it.
 
 use std::collections::HashMap;
 use std::ffi::c_void;
@@ -209,24 +209,11 @@ fn marshal_field_to_bytes(
             // else: zero-initialized slot is already correct for success status
         }
         FfiTypeDesc::Callback(cb_name) => {
-            // Callback-typed struct field: create a trampoline and write the fn pointer.
-            let js_fn = unsafe { napi::JsFunction::from_raw(env.raw(), js_val.raw())? };
-            let user_data = crate::callback::create_callback_user_data(
-                env,
-                js_fn,
-                cb_name,
-                module,
-                registration,
-            )?;
-            let fn_ptr = module
-                .make_callback_trampoline(
-                    cb_name,
-                    crate::callback::on_js_thread,
-                    crate::callback::dispatch_to_js_thread,
-                    crate::callback::is_js_thread,
-                    user_data,
-                )
-                .map_err(crate::core_err)?;
+            // Callback-typed struct field: write the function's trampoline, as for an argument.
+            // SAFETY: `js_val` is a value from the current scope, on the JS thread.
+            let fn_ptr = unsafe {
+                crate::callback::trampoline_for(env, &js_val, cb_name, module, registration)?
+            };
             slot::write_pointer(slot, fn_ptr);
         }
         other => {
@@ -12,13 +12,20 @@
 //!   converts to JS values, calls the JS function, and writes the return value.
 //! - [`dispatch_to_js_thread`]: runs off the JS thread, copies the arg buffer,
 //!   sends it to the JS thread via a ThreadsafeFunction, and blocks on a
-//!   sync_channel for the return value.
+//!   sync_channel for the return value — or fails the call, if the JS thread
+//!   cannot answer (see [Dispatch timeouts](#dispatch-timeouts)).
 //! - [`is_js_thread`]: returns whether the current thread is the one that registered the
 //!   callback, and so may touch its napi values directly.
 //!
+//! And the one core's fire-and-forget dispatch needs, given by [`post_dispatch`]:
+//!
+//! - [`post_to_js_thread`]: queues core's drain on the ThreadsafeFunction and returns
+//!   without waiting for it.
+//!
 //! Each callback closure is associated with a [`CallbackUserData`] struct that is
-//! leaked to a stable address and passed as `user_data: *const c_void` through the
-//! core trampoline protocol.
+//! boxed at a stable address and passed as `user_data: *const c_void` through the
+//! core trampoline protocol. It lives as long as the trampoline: for good, unless
+//! the callback is `once` (see [Reclaiming trampolines](#reclaiming-trampolines)).
 //!
 //! ## Core trampoline protocol
 //!
@@ -34,11 +41,53 @@
 //!
 //! This module handles BOTH simple callbacks (fire-and-forget) AND VTable callbacks
 //! (blocking with return values and RustCallStatus handling).
+//!
+//! ## Dispatch timeouts
+//!
+//! The JS thread can only answer a dispatched callback from its event loop. If it is
+//! itself blocked in a synchronous call into Rust that is waiting on the dispatching
+//! thread, neither can make progress, and the process hangs without a word. So the
+//! dispatching thread gives up, rather than waiting forever, when either:
+//!
+//! - the JS thread has been inside the same synchronous call into Rust for the module's
+//!   `deadlockGraceMs` since the dispatch ([`DEFAULT_DEADLOCK_GRACE`] unless set, and never
+//!   if `0`), or
+//! - the module's `dispatchTimeoutMs` has passed.
+//!
+//! A callback giving up fails with `RustCallStatus` code 2 (an unexpected error) and
+//! a message saying which, for Rust to report as it would a JS exception. A callback
+//! without a `RustCallStatus` returns zeroes. Either way the JS function is not run
+//! later: the dispatch is withdrawn, unless the JS thread had already started it, in
+//! which case the caller waits for it after all.
+//!
+//! A callback belongs to the environment that registered it, and runs on that one's JS
+//! thread whichever thread calls it — including another environment's JS thread, when
+//! a module is registered in several Node workers. Once that environment is torn down
+//! (its worker has exited, say) the callback fails the same way, as it can never run.
+//!
+//! ## Reclaiming trampolines
+//!
+//! The library may call a function pointer from any thread at any later time, so in
+//! general nothing can tell when a trampoline is safe to free. A callback declared
+//! `once` in the spec is different: the library calls each pointer it is handed
+//! exactly once, so every handoff can be counted. Generated bindings declare the two
+//! UniFFI callbacks of this kind `once`: the continuation a Rust future is polled with,
+//! and the callback Rust calls when it drops a foreign future.
+//!
+//! For these, `fn_ref` is a weak reference, and each handoff to Rust, as an argument
+//! or in a struct, takes a lease on it ([`trampoline_for`], [`lease`]) which the one
+//! call gives back. While any lease is outstanding the JS function stays alive; once
+//! none is, it may be garbage collected, and its finalizer ([`release_when_collected`])
+//! releases the trampoline to core, which frees it and then this module's user data. A lease that is never given back — a
+//! withdrawn dispatch, or a library that breaks its word — keeps the trampoline
+//! alive for good, as if it were not `once`.
 
 use std::ffi::c_void;
-use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
 use std::sync::{Arc, Mutex};
 use std::thread::ThreadId;
+use std::time::{Duration, Instant};
 
 use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
 use napi::{Env, NapiRaw, NapiValue};
@@ -71,9 +120,9 @@ struct RustCallStatusForVTable {
 
 /// Per-callback state passed to core's trampoline as the `user_data` pointer.
 ///
-/// An instance is created when a callback closure is registered and leaked via
-/// `Box::into_raw` so it lives for the lifetime of the closure (effectively
-/// `'static`). The core trampoline receives it as `*const c_void` on every
+/// An instance is created when a callback closure is registered and boxed via
+/// `Box::into_raw` so it lives for the lifetime of the closure: `'static`, unless
+/// the callback is `once` and [`free_callback_user_data`] frees it. The core trampoline receives it as `*const c_void` on every
 /// invocation and passes it through to `on_js_thread`, `dispatch_to_js_thread`,
 /// and `is_js_thread`.
 struct CallbackUserData {
@@ -85,8 +134,11 @@ struct CallbackUserData {
     env_state: Arc<crate::EnvState>,
     /// Raw napi environment handle. Only valid on `owner_thread`.
     raw_env: napi::sys::napi_env,
-    /// GC-preventing reference to the JS callback function. Only valid on `owner_thread`.
+    /// Reference to the JS callback function. Only valid on `owner_thread`. Strong, unless
+    /// `once`, when it is strong only while a [`lease`] is outstanding.
     fn_ref: napi::sys::napi_ref,
+    /// Whether the library calls each pointer it is handed exactly once.
+    once: bool,
     /// Precomputed layout for the arg byte buffer produced by core's trampoline.
     arg_layout: ArgLayout,
     /// FFI type descriptors for each declared positional argument.
@@ -101,7 +153,7 @@ struct CallbackUserData {
     /// Precomputed size of the return value in bytes (0 for void or out_return).
     ret_size: usize,
     /// Thread-safe function for dispatching to `owner_thread`.
-    tsfn: Mutex<Option<ThreadsafeFunction<DispatchPayload, ErrorStrategy::Fatal>>>,
+    tsfn: Mutex<Option<ThreadsafeFunction<JsThreadTask, ErrorStrategy::Fatal>>>,
     /// Reference to the Module, needed for fn_pointer wrapping (Callback-typed args).
     module: Arc<Module>,
     /// Per-registration state, so marshalling a `RustBuffer` on the callback path can
@@ -128,7 +180,21 @@ unsafe impl Sync for CallbackUserData {}
 // DispatchPayload
 // ---------------------------------------------------------------------------
 
-/// Payload sent from the calling thread to the JS thread via ThreadsafeFunction.
+/// Work sent to the JS thread via ThreadsafeFunction.
+enum JsThreadTask {
+    /// A callback the calling thread is waiting on.
+    Dispatch(DispatchPayload),
+    /// Core's queue of fire-and-forget invocations, which nothing waits on.
+    Drain {
+        drain: uniffi_runtime_core::DrainFn,
+        drain_data: *const c_void,
+    },
+}
+
+// SAFETY: `drain_data` is core's trampoline userdata, which stays valid until the drain has
+// run; `DispatchPayload` is `Send` for the reasons given below.
+unsafe impl Send for JsThreadTask {}
+
 /// Payload sent from the calling thread to the JS thread via ThreadsafeFunction.
 struct DispatchPayload {
     /// Copied arg bytes from the core trampoline's flat buffer.
@@ -140,6 +206,82 @@ struct DispatchPayload {
     /// The callback's user_data pointer, forwarded so the TSFN handler can
     /// call `on_js_thread`.
     user_data: *const c_void,
+    /// Which of the JS thread and the calling thread got to the dispatch first.
+    claim: Arc<DispatchClaim>,
+}
+
+/// Decides whether a dispatch runs, or is withdrawn by a caller that has given up on it.
+///
+/// Once the caller has returned, the pointers in the copied args — the `RustCallStatus` and
+/// any out-return — point into a stack frame that no longer exists. So the JS thread must not
+/// run a withdrawn dispatch, and the caller must not withdraw one that is already running.
+struct DispatchClaim(AtomicU8);
+
+impl DispatchClaim {
+    const PENDING: u8 = 0;
+    const RUNNING: u8 = 1;
+    const WITHDRAWN: u8 = 2;
+
+    fn new() -> Self {
+        Self(AtomicU8::new(Self::PENDING))
+    }
+
+    /// Called by the JS thread: `true` if it may run the dispatch.
+    fn start(&self) -> bool {
+        self.claim(Self::RUNNING)
+    }
+
+    /// Called by the calling thread: `true` if the dispatch will now never run.
+    fn withdraw(&self) -> bool {
+        self.claim(Self::WITHDRAWN)
+    }
+
+    fn claim(&self, to: u8) -> bool {
+        self.0
+            .compare_exchange(Self::PENDING, to, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+}
+
+/// How long the JS thread may stay in one synchronous call into Rust, while a dispatch
+/// waits, before the dispatch is failed as a deadlock, unless `deadlockGraceMs` says
+/// otherwise.
+///
+/// Longer than any call that is merely slow ought to block the JS thread, and short
+/// enough that a deadlock is reported rather than mistaken for a hang.
+pub(crate) const DEFAULT_DEADLOCK_GRACE: Duration = Duration::from_millis(1000);
+
+/// How often a waiting dispatch looks to see if it should give up.
+const DISPATCH_POLL: Duration = Duration::from_millis(50);
+
+/// Why a dispatch gave up waiting for the JS thread.
+enum DispatchFailure {
+    Deadlock(Duration),
+    Timeout(Duration),
+    /// The environment that owns the callback has been torn down.
+    EnvGone,
+}
+
+impl DispatchFailure {
+    fn message(&self) -> String {
+        match self {
+            Self::Deadlock(grace) => format!(
+                "Callback dispatched to the JS thread while it was blocked in a synchronous call \
+                 into Rust for over {}ms: the call is probably waiting on this thread, so the \
+                 callback can never run. Make the call async, or invoke the callback from the \
+                 calling thread",
+                grace.as_millis()
+            ),
+            Self::Timeout(timeout) => format!(
+                "Callback dispatched to the JS thread was not answered within the \
+                 dispatchTimeoutMs of {}ms",
+                timeout.as_millis()
+            ),
+            Self::EnvGone => "Callback dispatched to a JS environment that has been torn down, \
+                 such as an exited worker: it can never run"
+                .to_string(),
+        }
+    }
 }
 
 // SAFETY: The `user_data` pointer is a leaked `Box<CallbackUserData>` with a
@@ -163,9 +305,22 @@ unsafe impl Send for DispatchPayload {}
 /// - `user_data` must be a valid `*const CallbackUserData` obtained from
 ///   `Box::into_raw`.
 pub extern "C" fn on_js_thread(args: *const u8, ret: *mut u8, user_data: *const c_void) {
-    // SAFETY: `user_data` was created via `Box::into_raw` and leaked.
+    // SAFETY: `user_data` was created via `Box::into_raw`, and is freed only after the
+    // trampoline has stopped calling this.
     let ud = unsafe { &*(user_data as *const CallbackUserData) };
 
+    call_js(ud, args, ret);
+
+    if ud.once && !ud.env_state.is_shutting_down() {
+        // This is the one call for the lease taken when the pointer was handed over. Once no
+        // lease is left the reference is weak again, and the function free to be collected.
+        // SAFETY: on the owning thread, so `raw_env` and `fn_ref` are valid.
+        unsafe { napi::sys::napi_reference_unref(ud.raw_env, ud.fn_ref, std::ptr::null_mut()) };
+    }
+}
+
+/// The body of [`on_js_thread`]: everything bar giving back a `once` lease.
+fn call_js(ud: &CallbackUserData, args: *const u8, ret: *mut u8) {
     if ud.env_state.is_shutting_down() {
         // Zero out the return buffer so the caller gets a deterministic value.
         if !ret.is_null() {
@@ -371,6 +526,8 @@ pub extern "C" fn dispatch_to_js_thread(
     let ud = unsafe { &*(user_data as *const CallbackUserData) };
 
     if ud.env_state.is_shutting_down() {
+        // SAFETY: `args` and `ret` follow the contracts documented above.
+        unsafe { fail_dispatch(ud, args, ret, &DispatchFailure::EnvGone) };
         return;
     }
 
@@ -388,12 +545,14 @@ pub extern "C" fn dispatch_to_js_thread(
 
     // Create the rendezvous channel.
     let (tx, rx) = sync_channel(1);
+    let claim = Arc::new(DispatchClaim::new());
 
     let payload = DispatchPayload {
         args: args_copy,
         ret_len,
         reply: tx,
         user_data,
+        claim: Arc::clone(&claim),
     };
 
     // Lock the tsfn and call it.
@@ -404,12 +563,26 @@ pub extern "C" fn dispatch_to_js_thread(
             eprintln!("uniffi-runtime-napi: dispatch_to_js_thread has no ThreadsafeFunction");
             return;
         };
-        tsfn.call(payload, ThreadsafeFunctionCallMode::Blocking);
+        tsfn.call(
+            JsThreadTask::Dispatch(payload),
+            ThreadsafeFunctionCallMode::Blocking,
+        );
     }
 
     // Block until the JS thread sends back the return bytes.
-    match rx.recv() {
-        Ok(ret_bytes) => {
+    let reply = match wait_for_reply(&rx, ud) {
+        Ok(reply) => reply,
+        Err(failure) if claim.withdraw() => {
+            // SAFETY: `args` and `ret` follow the contracts documented above.
+            unsafe { fail_dispatch(ud, args, ret, &failure) };
+            return;
+        }
+        // The JS thread started the callback just as we gave up, so it will answer.
+        Err(_) => rx.recv().ok(),
+    };
+
+    match reply {
+        Some(ret_bytes) => {
             if ret_len > 0 && !ret.is_null() && !ret_bytes.is_empty() {
                 let copy_len = ret_len.min(ret_bytes.len());
                 // SAFETY: `ret` points to at least `ret_len` bytes.
@@ -418,15 +591,97 @@ pub extern "C" fn dispatch_to_js_thread(
                 }
             }
         }
-        Err(_) => {
-            // The sender was dropped (e.g., shutdown). Zero the return buffer.
-            if ret_len > 0 && !ret.is_null() {
-                unsafe { std::ptr::write_bytes(ret, 0, ret_len) };
+        None => {
+            // The sender was dropped unsent: the environment was torn down, aborting the
+            // dispatch before it ran.
+            // SAFETY: `args` and `ret` follow the contracts documented above.
+            unsafe { fail_dispatch(ud, args, ret, &DispatchFailure::EnvGone) };
+        }
+    }
+}
+
+/// Wait for the JS thread to answer a dispatch: `Ok(None)` if it never will because the
+/// sender was dropped, or `Err` if the JS thread is stuck or the module's timeout passes.
+fn wait_for_reply(
+    rx: &Receiver<Vec<u8>>,
+    ud: &CallbackUserData,
+) -> Result<Option<Vec<u8>>, DispatchFailure> {
+    let timeout = ud.registration.dispatch_timeout;
+    let grace = ud.registration.deadlock_grace;
+    let started = Instant::now();
+    // The synchronous call the JS thread is in, and when this dispatch first saw it.
+    let mut blocked: Option<(u64, Instant)> = None;
+    loop {
+        let wait = match timeout {
+            Some(timeout) => timeout.saturating_sub(started.elapsed()).min(DISPATCH_POLL),
+            None => DISPATCH_POLL,
+        };
+        match rx.recv_timeout(wait) {
+            Ok(ret_bytes) => return Ok(Some(ret_bytes)),
+            Err(RecvTimeoutError::Disconnected) => return Ok(None),
+            Err(RecvTimeoutError::Timeout) => {}
+        }
+
+        let now = Instant::now();
+        blocked = match (ud.env_state.sync_call(), blocked) {
+            (Some(call), Some((seen, since))) if call == seen => {
+                if let Some(grace) = grace.filter(|grace| now - since >= *grace) {
+                    return Err(DispatchFailure::Deadlock(grace));
+                }
+                Some((seen, since))
+            }
+            (Some(call), _) => Some((call, now)),
+            (None, _) => None,
+        };
+        if let Some(timeout) = timeout {
+            if now - started >= timeout {
+                return Err(DispatchFailure::Timeout(timeout));
             }
         }
     }
 }
 
+/// Fail a withdrawn or abandoned dispatch: an unexpected error in its `RustCallStatus`, if it has one,
+/// and a zeroed return value.
+///
+/// # Safety
+///
+/// `args` and `ret` follow the contracts of [`dispatch_to_js_thread`].
+unsafe fn fail_dispatch(
+    ud: &CallbackUserData,
+    args: *const u8,
+    ret: *mut u8,
+    failure: &DispatchFailure,
+) {
+    let message = failure.message();
+    #[cfg(debug_assertions)]
+    eprintln!("uniffi-runtime-napi: {message}");
+
+    if ud.ret_size > 0 && !ret.is_null() {
+        std::ptr::write_bytes(ret, 0, ud.ret_size);
+    }
+    if !ud.has_rust_call_status || args.is_null() {
+        return;
+    }
+    let Some(ref rcs_slot) = ud.arg_layout.rust_call_status_slot else {
+        return;
+    };
+    let ptr_bytes = std::slice::from_raw_parts(args.add(rcs_slot.offset), rcs_slot.size);
+    let status_ptr = slot::read_pointer(ptr_bytes) as *mut RustCallStatusForVTable;
+    if status_ptr.is_null() {
+        return;
+    }
+    // Code 2 is `CALL_UNEXPECTED_ERROR`, whose error buffer is the message as bare UTF-8.
+    (*status_ptr).code = 2;
+    if let Ok(rb) = napi_utils::rustbuffer_from_raw_bytes(
+        message.as_ptr(),
+        message.len(),
+        ud.module.rb_ops().from_bytes_ptr,
+    ) {
+        (*status_ptr).error_buf = rb;
+    }
+}
+
 /// Returns whether the current thread is the JS thread that registered this callback.
 ///
 /// Per callback, not per process. Each JS thread (a Node worker, say) owns napi values only for
@@ -443,6 +698,44 @@ pub extern "C" fn is_js_thread(user_data: *const c_void) -> bool {
     ud.owner_thread == std::thread::current().id()
 }
 
+/// Queue core's `drain` to run on the JS thread, without waiting for it.
+///
+/// Returns `false` if the environment is shutting down, when it will never run.
+///
+/// # Safety
+///
+/// `user_data` must be a valid `*const CallbackUserData` obtained from `Box::into_raw`.
+pub extern "C" fn post_to_js_thread(
+    drain: uniffi_runtime_core::DrainFn,
+    drain_data: *const c_void,
+    user_data: *const c_void,
+) -> bool {
+    // SAFETY: `user_data` was created via `Box::into_raw`, and outlives its trampoline.
+    let ud = unsafe { &*(user_data as *const CallbackUserData) };
+    if ud.env_state.is_shutting_down() {
+        return false;
+    }
+    let tsfn_guard = ud.tsfn.lock().expect("tsfn mutex poisoned");
+    let Some(tsfn) = tsfn_guard.as_ref() else {
+        return false;
+    };
+    // The queue is unbounded, so this only fails once the function is closing.
+    tsfn.call(
+        JsThreadTask::Drain { drain, drain_data },
+        ThreadsafeFunctionCallMode::NonBlocking,
+    ) == napi::Status::Ok
+}
+
+/// How this registration's fire-and-forget callbacks reach the JS thread without blocking.
+pub(crate) fn post_dispatch(
+    registration: &crate::register::Registration,
+) -> uniffi_runtime_core::PostDispatch {
+    uniffi_runtime_core::PostDispatch {
+        post: post_to_js_thread,
+        queue_limit: registration.callback_queue_limit,
+    }
+}
+
 // ---------------------------------------------------------------------------
 // create_callback_user_data
 // ---------------------------------------------------------------------------
@@ -451,7 +744,7 @@ pub extern "C" fn is_js_thread(user_data: *const c_void) -> bool {
 /// `Module::make_callback_trampoline` as the `user_data` pointer.
 ///
 /// This function:
-/// 1. Creates a persistent `napi_ref` for the JS function.
+/// 1. Creates a `napi_ref` for the JS function: strong, or weak for a `once` callback.
 /// 2. Looks up the callback def from the module spec.
 /// 3. Computes the `ArgLayout` (including extra pointer arg for out_return).
 /// 4. Creates a `ThreadsafeFunction` for cross-thread dispatch.
@@ -490,10 +783,13 @@ pub fn create_callback_user_data(
         }
     };
 
-    // Create a GC-preventing reference to the JS function.
+    // Create a reference to the JS function. A `once` callback's starts weak: each handoff
+    // takes a lease on it instead.
+    let initial_refcount = if def.once { 0 } else { 1 };
     let mut fn_ref: napi::sys::napi_ref = std::ptr::null_mut();
-    let ref_status =
-        unsafe { napi::sys::napi_create_reference(env.raw(), js_fn.raw(), 1, &mut fn_ref) };
+    let ref_status = unsafe {
+        napi::sys::napi_create_reference(env.raw(), js_fn.raw(), initial_refcount, &mut fn_ref)
+    };
     if ref_status != napi::sys::Status::napi_ok {
         return Err(napi::Error::from_reason(format!(
             "Failed to create reference for callback '{callback_name}'"
@@ -506,6 +802,7 @@ pub fn create_callback_user_data(
         env_state: crate::env_state(env.raw()),
         raw_env: env.raw(),
         fn_ref,
+        once: def.once,
         arg_layout,
         arg_types: def.args.clone(),
         ret_type: def.ret.clone(),
@@ -521,11 +818,22 @@ pub fn create_callback_user_data(
     // Create a ThreadsafeFunction for cross-thread dispatch.
     // The TSFN callback will call `on_js_thread` with the payload's args.
     let noop_fn = env.create_function_from_closure("cb_tsfn_dispatch", |_ctx| Ok(()))?;
-    let tsfn: ThreadsafeFunction<DispatchPayload, ErrorStrategy::Fatal> = noop_fn
+    let tsfn: ThreadsafeFunction<JsThreadTask, ErrorStrategy::Fatal> = noop_fn
         .create_threadsafe_function(
             0,
-            move |ctx: napi::threadsafe_function::ThreadSafeCallContext<DispatchPayload>| {
-                let payload = ctx.value;
+            move |ctx: napi::threadsafe_function::ThreadSafeCallContext<JsThreadTask>| {
+                let payload = match ctx.value {
+                    JsThreadTask::Dispatch(payload) => payload,
+                    JsThreadTask::Drain { drain, drain_data } => {
+                        drain(drain_data);
+                        return Ok(Vec::<napi::JsUnknown>::new());
+                    }
+                };
+                if !payload.claim.start() {
+                    // Withdrawn by a caller that has given up and returned; its pointers in
+                    // `payload.args` are no longer valid.
+                    return Ok(Vec::<napi::JsUnknown>::new());
+                }
 
                 let mut ret_buf = vec![0u8; payload.ret_len];
                 // Call on_js_thread to do the actual JS call.
@@ -562,6 +870,191 @@ pub fn create_callback_user_data(
     Ok(userdata_ptr as *const c_void)
 }
 
+// ---------------------------------------------------------------------------
+// Handing trampolines to the library
+// ---------------------------------------------------------------------------
+
+/// The function pointer to hand the library for the JS function `js_val`, as callback
+/// `cb_name`: an argument, or a field of a struct the library is given.
+///
+/// Reuses this function's trampoline if it already has one. A trampoline is kept for good —
+/// the library may invoke the pointer from any thread later — so the intended bound is one
+/// per callback type, and building one per handoff turns that into unbounded growth. Only a
+/// `once` callback's is freed, after its function has been collected; each handoff of one
+/// takes a [`lease`] for the call it makes.
+///
+/// Keying on the function object is what makes reuse correct: the Symbols belong to this
+/// `register()` call and so to this env, and a trampoline holds no per-call state, since
+/// callbacks receive their handle as an ordinary argument.
+///
+/// A hit costs only the lookup — nothing below runs until a miss.
+///
+/// # Safety
+///
+/// `js_val` must be a value from the current scope of `env`, on the JS thread that made
+/// `registration`.
+pub(crate) unsafe fn trampoline_for(
+    env: &Env,
+    js_val: &napi::JsUnknown,
+    cb_name: &str,
+    module: &Arc<Module>,
+    registration: &Arc<crate::register::Registration>,
+) -> napi::Result<uniffi_runtime_core::CallbackFnPtr> {
+    let raw_fn_val = js_val.raw();
+    // A lookup miss is reported as `Ok(None)`, so a JS function carrying no marker is
+    // simply built below rather than misread.
+    let cached = registration
+        .trampolines
+        .get(env.raw(), raw_fn_val, cb_name)?;
+    let once = module
+        .spec_callbacks()
+        .get(cb_name)
+        .is_some_and(|def| def.once);
+    let fn_ptr = match cached {
+        Some(fn_ptr) => fn_ptr,
+        None => {
+            // `from_raw` errors rather than aborting if it is not a function, so a
+            // non-function here is a caller error surfaced as a JS exception.
+            let js_fn = napi::JsFunction::from_raw(env.raw(), raw_fn_val)?;
+            let user_data = create_callback_user_data(env, js_fn, cb_name, module, registration)?;
+            let fn_ptr = module
+                .make_callback_trampoline(
+                    cb_name,
+                    on_js_thread,
+                    dispatch_to_js_thread,
+                    is_js_thread,
+                    Some(post_dispatch(registration)),
+                    user_data,
+                )
+                .map_err(crate::core_err)?;
+            // The pointer stored is the trampoline just built for `cb_name`.
+            registration
+                .trampolines
+                .set(env.raw(), raw_fn_val, cb_name, fn_ptr)?;
+            if once {
+                // `raw_fn_val` is the function `fn_ptr` was built over, and this is the
+                // only place a trampoline is built for it.
+                release_when_collected(env, raw_fn_val, module, fn_ptr)?;
+            }
+            fn_ptr
+        }
+    };
+    // A `once` trampoline is held only for the one call each handoff makes.
+    if once {
+        if let Some(user_data) = module.callback_trampoline_user_data(fn_ptr) {
+            // User data of a live trampoline; it stays live, since its function is
+            // reachable from this scope.
+            lease(user_data)?;
+        }
+    }
+    Ok(fn_ptr)
+}
+
+// ---------------------------------------------------------------------------
+// Reclaiming `once` trampolines
+// ---------------------------------------------------------------------------
+
+/// Keep a `once` callback's JS function alive until the library's call for this handoff.
+///
+/// Taken each time its trampoline is handed to the library, and given back by
+/// [`on_js_thread`] after the call.
+///
+/// # Safety
+///
+/// Must be called on the JS thread that registered the callback, with `user_data` from
+/// [`create_callback_user_data`] for a live `once` trampoline.
+pub unsafe fn lease(user_data: *const c_void) -> napi::Result<()> {
+    let ud = &*(user_data as *const CallbackUserData);
+    let status = napi::sys::napi_reference_ref(ud.raw_env, ud.fn_ref, std::ptr::null_mut());
+    if status != napi::sys::Status::napi_ok {
+        return Err(napi::Error::from_reason(
+            "Failed to keep a callback alive for the library",
+        ));
+    }
+    Ok(())
+}
+
+/// What a collected JS function's finalizer needs to release its trampoline.
+struct Reclaim {
+    module: Arc<Module>,
+    fn_ptr: uniffi_runtime_core::CallbackFnPtr,
+}
+
+/// Release the `once` trampoline `fn_ptr` when `raw_fn`, the function it calls, is collected.
+///
+/// # Safety
+///
+/// `raw_fn` must be a function value from the current scope of `env`, and `fn_ptr` a `once`
+/// trampoline built over it by `module`, which is released nowhere else.
+pub unsafe fn release_when_collected(
+    env: &Env,
+    raw_fn: napi::sys::napi_value,
+    module: &Arc<Module>,
+    fn_ptr: uniffi_runtime_core::CallbackFnPtr,
+) -> napi::Result<()> {
+    let reclaim = Box::into_raw(Box::new(Reclaim {
+        module: Arc::clone(module),
+        fn_ptr,
+    }));
+    let status = napi::sys::napi_add_finalizer(
+        env.raw(),
+        raw_fn,
+        reclaim as *mut c_void,
+        Some(reclaim_trampoline),
+        std::ptr::null_mut(),
+        std::ptr::null_mut(),
+    );
+    if status != napi::sys::Status::napi_ok {
+        // Never released, then: the trampoline lives for good, as any other would.
+        drop(Box::from_raw(reclaim));
+        return Err(napi::Error::from_reason(
+            "Failed to watch a callback for collection",
+        ));
+    }
+    Ok(())
+}
+
+/// Finalizer for a `once` callback's JS function; see [`release_when_collected`].
+unsafe extern "C" fn reclaim_trampoline(
+    _env: napi::sys::napi_env,
+    data: *mut c_void,
+    _hint: *mut c_void,
+) {
+    let reclaim = Box::from_raw(data as *mut Reclaim);
+    // SAFETY: the function has been collected, so every lease on it has been given back, and
+    // each was given back by the one call the library makes for it. So the library has made
+    // every call it ever will through this pointer, though the last may still be returning;
+    // core waits for that before freeing.
+    reclaim
+        .module
+        .release_callback_trampoline(reclaim.fn_ptr, free_callback_user_data);
+    // Dropped only now: `release_callback_trampoline` borrows the module this may keep alive.
+    drop(reclaim);
+}
+
+/// Free a [`CallbackUserData`], once core has freed the trampoline made with it.
+///
+/// Runs on the JS thread, in [`reclaim_trampoline`].
+extern "C" fn free_callback_user_data(user_data: *const c_void) {
+    // SAFETY: created by `Box::into_raw` in `create_callback_user_data`, and core calls this
+    // once, after the trampoline that used it is gone.
+    let ud = unsafe { Box::from_raw(user_data as *mut CallbackUserData) };
+    if ud.env_state.is_shutting_down() {
+        // The env is going, and its handles with it; this leaks nothing that outlives it.
+        std::mem::forget(ud);
+        return;
+    }
+    // SAFETY: on the owning thread, with the env still live.
+    unsafe { napi::sys::napi_delete_reference(ud.raw_env, ud.fn_ref) };
+    let tsfn = ud.tsfn.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(tsfn) = tsfn {
+        if !ud.env_state.unregister_tsfn(tsfn.raw()) {
+            // Aborted at teardown already; releasing it again is not allowed.
+            std::mem::forget(tsfn);
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Byte <-> JS conversion helpers (private)
 // ---------------------------------------------------------------------------
@@ -17,7 +17,7 @@ use napi::{Env, JsObject, Result};
 use uniffi_runtime_core::{FfiTypeDesc, Module, VTableField};
 
 use crate::callback::{
-    create_callback_user_data, dispatch_to_js_thread, is_js_thread, on_js_thread,
+    create_callback_user_data, dispatch_to_js_thread, is_js_thread, on_js_thread, post_dispatch,
 };
 
 /// Build a C-compatible VTable struct from a JS object implementing a UniFFI trait.
@@ -64,6 +64,7 @@ pub fn build_vtable_struct(
                 on_js_thread,
                 dispatch_to_js_thread,
                 is_js_thread,
+                Some(post_dispatch(registration)),
                 user_data,
             )
             .map_err(crate::core_err)?;
@@ -18,7 +18,9 @@
 //! The bridge is **driven by type definitions supplied from JavaScript**. No Rust code
 //! generation is needed per target library. The flow is:
 //!
-//! 1. JS describes function signatures as tagged objects (e.g., `{ tag: 'Int32' }`).
+//! 1. JS describes function signatures, either as tagged objects (e.g., `{ tag: 'Int32' }`)
+//!    or, as generated code does, as the compact binary encoding from
+//!    [`uniffi_runtime_core::spec::binary`] in a `Uint8Array`.
 //! 2. Rust parses these into [`FfiTypeDesc`](uniffi_runtime_core::FfiTypeDesc) values
 //!    via [`spec_from_js`].
 //! 3. `uniffi-runtime-core` maps each `FfiTypeDesc` to native types and builds the
@@ -43,10 +45,21 @@
 //! between these two strategies, and it answers per callback: each callback records the thread
 //! that registered it, alongside the `napi_env` and `ThreadsafeFunction` it already held for that
 //! same thread.
+//!
+//! ## Workers
+//!
+//! Each Node worker is an environment of its own, with its own JS thread. A worker opens and
+//! registers the library itself: a `UniffiNativeModule` belongs to the environment that made it,
+//! but the library under it is loaded once per process, so handles are plain numbers any
+//! environment may pass to it. A callback always runs in the environment that registered it,
+//! whichever thread invokes it, and fails once that environment is gone; see
+//! [`EnvState`] for what each environment keeps, and `callback` for the dispatch.
 
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::Duration;
 
 use napi::bindgen_prelude::*;
 use napi::JsObject;
@@ -58,7 +71,7 @@ mod napi_utils;
 mod register;
 
 use std::sync::Arc;
-use uniffi_runtime_core::Module;
+use uniffi_runtime_core::{Module, Resolution, DEFAULT_CALLBACK_QUEUE_LIMIT};
 
 /// Convert a core error into a napi error.
 pub(crate) fn core_err(e: uniffi_runtime_core::Error) -> napi::Error {
@@ -87,14 +100,25 @@ pub struct EnvState {
     /// be the same decision under the same lock. As two pieces of state, a handle could be pushed
     /// after the sweep had already passed it, leaving it bound to an event loop that no longer
     /// exists with nothing left to abort it.
-    tsfns: Mutex<Option<Vec<TsfnHandle>>>,
+    ///
+    /// A set, since a `once` callback's handle is taken back out when its trampoline is freed.
+    tsfns: Mutex<Option<HashSet<TsfnHandle>>>,
+    /// The outermost synchronous call into Rust this environment's JS thread is inside, or 0.
+    ///
+    /// Written only by the JS thread, and read by threads waiting on a callback dispatch: while
+    /// this is set, the JS thread cannot drain its event loop, so a dispatch cannot be answered
+    /// until the call returns. See [`EnvState::enter_sync_call`].
+    sync_call: AtomicU64,
+    /// Numbers the outermost synchronous calls, so a waiting thread can tell one long call from a
+    /// run of short ones.
+    sync_calls_started: AtomicU64,
 }
 
 /// A `napi_threadsafe_function`, as a value that may cross threads.
 ///
 /// The newtype exists for the two impls below. They are the only `unsafe` this needs, and putting
 /// them here rather than on [`EnvState`] keeps them off the fields that never wanted them.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 struct TsfnHandle(napi::sys::napi_threadsafe_function);
 
 // SAFETY: napi's threadsafe function APIs are explicitly callable from any thread, which is the
@@ -107,7 +131,30 @@ impl EnvState {
     fn new() -> Self {
         Self {
             shutdown: AtomicBool::new(false),
-            tsfns: Mutex::new(Some(Vec::new())),
+            tsfns: Mutex::new(Some(HashSet::new())),
+            sync_call: AtomicU64::new(0),
+            sync_calls_started: AtomicU64::new(0),
+        }
+    }
+
+    /// Mark the JS thread as inside a synchronous call into Rust until the guard is dropped.
+    ///
+    /// A call made from a callback already running inside one is part of the outer call, so only
+    /// the outermost call takes a new number.
+    pub(crate) fn enter_sync_call(&self) -> SyncCallGuard<'_> {
+        let outer = self.sync_call.load(Ordering::Relaxed);
+        if outer == 0 {
+            let id = self.sync_calls_started.fetch_add(1, Ordering::Relaxed) + 1;
+            self.sync_call.store(id, Ordering::Release);
+        }
+        SyncCallGuard { state: self, outer }
+    }
+
+    /// The synchronous call into Rust the JS thread is currently inside, if any.
+    pub(crate) fn sync_call(&self) -> Option<u64> {
+        match self.sync_call.load(Ordering::Acquire) {
+            0 => None,
+            id => Some(id),
         }
     }
 
@@ -129,11 +176,11 @@ impl EnvState {
     ///
     /// `raw` must be a live threadsafe function this environment owns, and ownership of it passes
     /// here: the caller must not release it, because this either stores it for the teardown sweep
-    /// or aborts it on the spot.
+    /// or aborts it on the spot. [`unregister_tsfn`](Self::unregister_tsfn) hands it back.
     pub unsafe fn register_tsfn(&self, raw: napi::sys::napi_threadsafe_function) {
         let orphaned = match &mut *self.tsfns() {
             Some(live) => {
-                live.push(TsfnHandle(raw));
+                live.insert(TsfnHandle(raw));
                 false
             }
             None => true,
@@ -152,12 +199,27 @@ impl EnvState {
         }
     }
 
+    /// Take back a TSFN handle passed to [`register_tsfn`](Self::register_tsfn), to release it
+    /// before the environment is torn down.
+    ///
+    /// Returns `false` if the environment has been torn down, when the handle has been aborted
+    /// already and must not be touched again.
+    pub fn unregister_tsfn(&self, raw: napi::sys::napi_threadsafe_function) -> bool {
+        match &mut *self.tsfns() {
+            Some(live) => {
+                live.remove(&TsfnHandle(raw));
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Close this environment to new handles and take the ones it holds.
     ///
     /// The guard is released with the returned value, so the caller aborts without holding the
     /// lock. That matters: aborting unblocks Rust worker threads, and one of those may re-enter
     /// through a callback before the sweep has finished.
-    fn close(&self) -> Vec<TsfnHandle> {
+    fn close(&self) -> HashSet<TsfnHandle> {
         self.shutdown.store(true, Ordering::Release);
         self.tsfns().take().unwrap_or_default()
     }
@@ -165,11 +227,23 @@ impl EnvState {
     /// A poisoned lock means a panic while the list was held. It guards a list of opaque handles
     /// with no cross-field invariant a panic could break, and a panic raised from here would cross
     /// the napi boundary as an abort, so recovering beats taking the host process down.
-    fn tsfns(&self) -> MutexGuard<'_, Option<Vec<TsfnHandle>>> {
+    fn tsfns(&self) -> MutexGuard<'_, Option<HashSet<TsfnHandle>>> {
         self.tsfns.lock().unwrap_or_else(PoisonError::into_inner)
     }
 }
 
+/// Returned by [`EnvState::enter_sync_call`]; restores the enclosing call, if any, on drop.
+pub(crate) struct SyncCallGuard<'a> {
+    state: &'a EnvState,
+    outer: u64,
+}
+
+impl Drop for SyncCallGuard<'_> {
+    fn drop(&mut self) {
+        self.state.sync_call.store(self.outer, Ordering::Release);
+    }
+}
+
 /// Every environment that has registered a module, keyed by its raw `napi_env`.
 ///
 /// Touched when a callback is created and when an environment is torn down, both cold paths. A
@@ -240,6 +314,18 @@ fn install_env_cleanup_hook(env: &Env) {
     }
 }
 
+/// The `key` property of `register()`'s options, if it is set: `undefined` and `null` are
+/// unset, and a value of another type is an error, rather than taken as unset.
+fn option<T: FromNapiValue + ValidateNapiValue>(
+    options: &Option<JsObject>,
+    key: &str,
+) -> napi::Result<Option<T>> {
+    match options {
+        Some(o) => o.get_named_property::<Option<T>>(key),
+        None => Ok(None),
+    }
+}
+
 /// The top-level napi class exposed to JavaScript.
 ///
 /// `UniffiNativeModule` is the entry point for loading a UniFFI shared library
@@ -254,6 +340,19 @@ pub struct UniffiNativeModule {
     library_path: String,
     /// The `Module` created by `register()`. `None` until `register()` is called.
     module: Option<Arc<Module>>,
+    /// The options `register()` was given, for `reload()` to register again with.
+    options: register::RegisterOptions,
+}
+
+/// Counters describing a registered module, returned by [`UniffiNativeModule::metrics`].
+#[napi(object)]
+pub struct UniffiNativeModuleMetrics {
+    /// Callback trampolines built and not yet freed. Only trampolines for callbacks declared
+    /// `once` are ever freed, after their JS function has been garbage collected.
+    pub live_trampolines: u32,
+    /// Buffers returned by functions declared `externalReturn` that the garbage collector has
+    /// yet to release.
+    pub live_external_buffers: u32,
 }
 
 #[napi]
@@ -261,7 +360,7 @@ impl UniffiNativeModule {
     /// Validate the library path and store it for later use by `register()`.
     ///
     /// The library is not fully opened here — `Module::new()` in `register()`
-    /// handles `dlopen`, symbol resolution, and CIF construction in one step.
+    /// handles `dlopen` and sets up symbol resolution and CIF construction.
     /// However, we do a trial `dlopen` to validate the path eagerly, so that
     /// callers get an immediate error for invalid paths rather than a deferred
     /// error at `register()` time.
@@ -277,22 +376,141 @@ impl UniffiNativeModule {
         Ok(Self {
             library_path: path,
             module: None,
+            options: register::RegisterOptions {
+                resolution: Resolution::Lazy,
+                dispatch_timeout: None,
+                deadlock_grace: Some(callback::DEFAULT_DEADLOCK_GRACE),
+                callback_queue_limit: DEFAULT_CALLBACK_QUEUE_LIMIT,
+            },
         })
     }
 
     /// Parse JS-provided type definitions and produce a JS object whose methods
     /// are bound to the foreign functions found in the loaded library.
+    ///
+    /// `definitions` is either a binary-encoded spec in a `Uint8Array`, as
+    /// generated code emits, or the equivalent tagged JS object.
+    ///
+    /// Each function's symbol is looked up, and its CIF built, on its first
+    /// call, so a missing symbol is reported by that call. Passing
+    /// `{ verifySymbols: true }` resolves every function here instead, making
+    /// `register()` throw on the first one missing from the library.
+    ///
+    /// A callback invoked from another thread blocks that thread until the JS thread has run it.
+    /// Passing `{ dispatchTimeoutMs: n }` bounds that wait: a callback the JS thread has not
+    /// answered within `n` milliseconds fails instead, through its `RustCallStatus`. Without it,
+    /// the wait only ends early when the JS thread is stuck in a synchronous call into Rust.
+    /// It is judged stuck once it has spent `{ deadlockGraceMs: n }` (default 1000) in the same
+    /// call since the dispatch; `0` turns the check off.
+    ///
+    /// An option of the wrong type makes `register()` throw.
+    ///
+    /// A callback that returns nothing, reports no errors and takes no pointers does not block
+    /// its thread: the call is queued for the JS thread, and runs after those queued before it.
+    /// Past `{ callbackQueueLimit: n }` queued calls (default 1024) the next blocks as above;
+    /// `0` blocks every time.
+    ///
+    /// Besides a method per function, the object has `batch(calls)`, which takes an array of
+    /// `[fnName, args]` pairs and returns the array of their results, making every call in one
+    /// crossing into Rust. Each `args` is what calling `fnName` directly would take, status
+    /// object included.
     #[napi]
-    pub fn register(&mut self, env: Env, definitions: JsObject) -> napi::Result<JsObject> {
+    pub fn register(
+        &mut self,
+        env: Env,
+        definitions: JsObject,
+        options: Option<JsObject>,
+    ) -> napi::Result<JsObject> {
+        let verify_symbols = option::<bool>(&options, "verifySymbols")?.unwrap_or(false);
+        let dispatch_timeout = option::<u32>(&options, "dispatchTimeoutMs")?
+            .map(|ms| Duration::from_millis(ms.into()));
+        let deadlock_grace = match option::<u32>(&options, "deadlockGraceMs")? {
+            None => Some(callback::DEFAULT_DEADLOCK_GRACE),
+            Some(0) => None,
+            Some(ms) => Some(Duration::from_millis(ms.into())),
+        };
+        let callback_queue_limit = option::<u32>(&options, "callbackQueueLimit")?
+            .map_or(DEFAULT_CALLBACK_QUEUE_LIMIT, |limit| limit as usize);
+        let resolution = if verify_symbols {
+            Resolution::Eager
+        } else {
+            Resolution::Lazy
+        };
         // Install the env cleanup hook on the first register call. This is the
         // earliest point where we have a valid `Env` (the `#[module_init]` ctor
         // runs before the napi env exists).
         install_env_cleanup_hook(&env);
-        let (result, module) = register::register(env, &self.library_path, definitions)?;
+        let options = register::RegisterOptions {
+            resolution,
+            dispatch_timeout,
+            deadlock_grace,
+            callback_queue_limit,
+        };
+        let (result, module) = register::register(env, &self.library_path, definitions, options)?;
         self.module = Some(module);
+        self.options = options;
         Ok(result)
     }
 
+    /// Swap in a rebuilt copy of the library at `path`, and return a fresh object like the one
+    /// `register()` returned, bound to it.
+    ///
+    /// Every function registered is looked up in the new library before anything changes, so
+    /// one it lacks makes `reload()` throw with the current library still in service. Then
+    /// in-flight calls drain and the current library is unloaded as by `unload({ force: true })`.
+    /// From there, calling the object returned earlier throws, as do async calls still
+    /// polling, so do not pass it, or any object handle it returned, to the new one.
+    ///
+    /// Passing `definitions` as well checks them against those registered, by checksum, and
+    /// throws if they differ: a changed API needs the process restarted.
+    ///
+    /// The system loader hands back a library it already has loaded, so `path` must name a
+    /// new file — a copy of each build, say — for the new code to run. A library still
+    /// loaded, this one or one kept open for an earlier object's buffers, makes `reload()`
+    /// throw with the current library still in service.
+    #[napi]
+    pub fn reload(
+        &mut self,
+        env: Env,
+        path: String,
+        definitions: Option<JsObject>,
+    ) -> napi::Result<JsObject> {
+        let module = self
+            .module
+            .as_ref()
+            .ok_or_else(|| napi::Error::from_reason("reload() called before register()"))?;
+        if let Some(definitions) = definitions {
+            let given = register::parse_definitions(definitions)?.checksum();
+            let registered = module.spec_checksum();
+            if given != registered {
+                return Err(napi::Error::from_reason(format!(
+                    "reload() was given definitions with checksum {given:016x}, but those \
+                     registered have checksum {registered:016x}: restart to pick up a changed API"
+                )));
+            }
+        }
+        let next = module.reload(Path::new(&path)).map_err(core_err)?;
+        self.module = Some(Arc::clone(&next));
+        self.library_path = path;
+        register::facade(env, &next, self.options)
+    }
+
+    /// Counters describing the registered module, all zero before `register()`.
+    #[napi]
+    pub fn metrics(&self) -> UniffiNativeModuleMetrics {
+        let (live_trampolines, live_external_buffers) =
+            self.module.as_ref().map_or((0, 0), |module| {
+                (
+                    module.live_callback_trampolines(),
+                    module.live_external_rust_buffers(),
+                )
+            });
+        UniffiNativeModuleMetrics {
+            live_trampolines: u32::try_from(live_trampolines).unwrap_or(u32::MAX),
+            live_external_buffers: u32::try_from(live_external_buffers).unwrap_or(u32::MAX),
+        }
+    }
+
     /// Unload the native module, draining in-flight calls and optionally force-closing
     /// the shared library.
     ///
@@ -300,6 +518,8 @@ impl UniffiNativeModule {
     /// `{ force: true }` is passed, `dlclose` is called on the library after
     /// all in-flight calls have drained. Without `force`, the unloading flag is
     /// set and callbacks are aborted, but the library mapping is left in place.
+    /// While buffers returned by functions declared `externalReturn` are still
+    /// reachable, `dlclose` waits for the garbage collector to release the last one.
     ///
     /// After `unload()` returns, this `UniffiNativeModule` no longer holds a
     /// reference to the `Module` — any JS closures that captured the module will
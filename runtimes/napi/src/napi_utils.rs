@@ -447,6 +447,28 @@ pub unsafe fn create_external_uint8array(
     extern "C" fn noop_finalize(_env: napi::sys::napi_env, _data: *mut c_void, _hint: *mut c_void) {
     }
 
+    // SAFETY: forwarded preconditions; the no-op finalizer ignores its hint.
+    create_finalized_uint8array(raw_env, data, len, noop_finalize, std::ptr::null_mut())
+        .map_err(|(error, _)| error)
+}
+
+/// Like [`create_external_uint8array`], but `finalize` runs, with `hint`, once the view's
+/// `ArrayBuffer` has been garbage collected.
+///
+/// On failure, the `bool` is whether the `ArrayBuffer` was created, in which case
+/// `finalize` will still run and owns the cleanup; otherwise it never runs.
+///
+/// # Safety
+///
+/// As for [`create_external_uint8array`], except that `data` must stay alive until
+/// `finalize` runs.
+pub unsafe fn create_finalized_uint8array(
+    raw_env: napi::sys::napi_env,
+    data: *mut u8,
+    len: usize,
+    finalize: unsafe extern "C" fn(napi::sys::napi_env, *mut c_void, *mut c_void),
+    hint: *mut c_void,
+) -> std::result::Result<napi::sys::napi_value, (napi::Error, bool)> {
     let mut arraybuffer = std::ptr::null_mut();
     // SAFETY: raw_env is valid (precondition); `data`+`len` describe a valid byte
     // range we intend to expose; the finalizer is a static extern "C" function.
@@ -454,13 +476,14 @@ pub unsafe fn create_external_uint8array(
         raw_env,
         data as *mut c_void,
         len,
-        Some(noop_finalize),
-        std::ptr::null_mut(),
+        Some(finalize),
+        hint,
         &mut arraybuffer,
     );
     if status != napi::sys::Status::napi_ok {
-        return Err(napi::Error::from_reason(
-            "Failed to create external ArrayBuffer".to_string(),
+        return Err((
+            napi::Error::from_reason("Failed to create external ArrayBuffer".to_string()),
+            false,
         ));
     }
 
@@ -477,8 +500,9 @@ pub unsafe fn create_external_uint8array(
         &mut typedarray,
     );
     if status != napi::sys::Status::napi_ok {
-        return Err(napi::Error::from_reason(
-            "Failed to create Uint8Array view".to_string(),
+        return Err((
+            napi::Error::from_reason("Failed to create Uint8Array view".to_string()),
+            true,
         ));
     }
     Ok(typedarray)
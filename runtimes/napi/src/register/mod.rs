@@ -12,16 +12,17 @@ use std::ffi::c_void;
 use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use napi::bindgen_prelude::*;
 use napi::{JsObject, JsUnknown, NapiRaw, NapiValue, Result};
 
-use crate::call::call_ffi_function;
+use crate::call::{call_ffi_batch, call_ffi_function};
 use crate::core_err;
 use crate::napi_utils;
 use crate::napi_utils::CapacitySymbol;
 use uniffi_runtime_core::ffi_c_types::RustBufferC;
-use uniffi_runtime_core::{FfiTypeDesc, Module};
+use uniffi_runtime_core::{FfiTypeDesc, Module, ModuleSpec, Resolution};
 
 /// State created once per `register()` call and shared by every function closure the
 /// resulting facade exposes.
@@ -39,6 +40,36 @@ pub(crate) struct Registration {
     /// for. Without this, dispatch rebuilds one per call — a permanent ~3 KB leak, and
     /// the async poll loop passes its continuation on every poll. See `callback::cache`.
     pub(crate) trampolines: crate::callback::cache::TrampolineCache,
+    /// The environment `register()` ran on, so each call can mark its JS thread as blocked in
+    /// Rust for callbacks dispatched from other threads to see.
+    pub(crate) env_state: Arc<crate::EnvState>,
+    /// How long a callback dispatched from another thread waits for the JS thread, from the
+    /// `dispatchTimeoutMs` option. `None` waits until the JS thread answers, or is found stuck.
+    pub(crate) dispatch_timeout: Option<Duration>,
+    /// How long the JS thread may stay in one synchronous call into Rust, while a dispatch
+    /// waits, before the dispatch is failed as a deadlock, from the `deadlockGraceMs` option.
+    /// `None` never judges it stuck.
+    pub(crate) deadlock_grace: Option<Duration>,
+    /// How many invocations of a fire-and-forget callback may wait for the JS thread before
+    /// the next one waits with them, from the `callbackQueueLimit` option.
+    pub(crate) callback_queue_limit: usize,
+}
+
+/// The options passed to `register()`, kept for `reload()` to register again with.
+#[derive(Clone, Copy)]
+pub(crate) struct RegisterOptions {
+    pub(crate) resolution: Resolution,
+    /// From `dispatchTimeoutMs`; see [`Registration::dispatch_timeout`].
+    pub(crate) dispatch_timeout: Option<Duration>,
+    /// From `deadlockGraceMs`; see [`Registration::deadlock_grace`].
+    pub(crate) deadlock_grace: Option<Duration>,
+    /// From `callbackQueueLimit`; see [`Registration::callback_queue_limit`].
+    pub(crate) callback_queue_limit: usize,
+}
+
+/// Parse `definitions`, in either of the shapes `register()` accepts.
+pub fn parse_definitions(definitions: JsObject) -> Result<ModuleSpec> {
+    spec_from_js::parse_module_spec(definitions)
 }
 
 /// Build a JS object whose methods call into the native library described by `definitions`.
@@ -46,14 +77,30 @@ pub fn register(
     env: Env,
     library_path: &str,
     definitions: JsObject,
+    options: RegisterOptions,
 ) -> Result<(JsObject, Arc<Module>)> {
-    let spec = spec_from_js::parse_module_spec(&definitions)?;
+    let spec = parse_definitions(definitions)?;
 
     extern "C" fn noop_abort(_: *const c_void) {}
-    let module = Module::new(Path::new(library_path), spec, noop_abort, std::ptr::null())
-        .map_err(core_err)?;
+    let module = Module::new(
+        Path::new(library_path),
+        spec,
+        options.resolution,
+        noop_abort,
+        std::ptr::null(),
+    )
+    .map_err(core_err)?;
+
+    let result = facade(env, &module, options)?;
+    Ok((result, module))
+}
+
+/// Build the JS object `register()` returns for `module`: a method per function in its
+/// spec, plus `batch`, `rustbuffer_alloc` and `rustbuffer_free`.
+pub fn facade(env: Env, module: &Arc<Module>, options: RegisterOptions) -> Result<JsObject> {
+    let mut names: Vec<String> = module.function_names().map(str::to_string).collect();
+    names.sort();
 
-    let functions: JsObject = definitions.get_named_property("functions")?;
     let mut result = env.create_object()?;
 
     // SAFETY: env is the active napi env supplied by node for this register call. The
@@ -64,26 +111,22 @@ pub fn register(
         trampolines: unsafe {
             crate::callback::cache::TrampolineCache::new(env.raw(), module.spec_callbacks().keys())?
         },
+        env_state: crate::env_state(env.raw()),
+        dispatch_timeout: options.dispatch_timeout,
+        deadlock_grace: options.deadlock_grace,
+        callback_queue_limit: options.callback_queue_limit,
     });
 
-    let names = functions.get_property_names()?;
-    let len = names.get_array_length()?;
-
-    for i in 0..len {
-        let name: String = names
-            .get_element::<napi::JsString>(i)?
-            .into_utf8()?
-            .as_str()?
-            .to_owned();
-
+    for name in names {
         let fn_name = name.clone();
-        let module_ref = Arc::clone(&module);
+        let module_ref = Arc::clone(module);
 
         let func_def = module.function_def(&name).ok_or_else(|| {
             napi::Error::from_reason(format!("Function not found in module: {name}"))
         })?;
         let arg_types: Rc<Vec<FfiTypeDesc>> = Rc::new(func_def.args.clone());
         let has_rust_call_status = func_def.has_rust_call_status;
+        let external_return = func_def.external_return;
         let reg_for_call = Arc::clone(&registration);
 
         let js_func = env.create_function_from_closure(&name, move |ctx| {
@@ -94,6 +137,7 @@ pub fn register(
                 &module_ref,
                 &arg_types,
                 has_rust_call_status,
+                external_return,
                 &reg_for_call,
             )
         })?;
@@ -101,11 +145,21 @@ pub fn register(
         result.set_named_property(&name, js_func)?;
     }
 
+    // `batch([[fnName, args], ...])` -> the results of each call, in order, from one crossing
+    // into native code. Each `args` is what the single call would take, status object included.
+    let batch_module = Arc::clone(module);
+    let reg_for_batch = Arc::clone(&registration);
+    let batch_fn = env.create_function_from_closure("batch", move |ctx| {
+        let calls: JsObject = ctx.get(0)?;
+        call_ffi_batch(ctx.env, &calls, &batch_module, &reg_for_batch)
+    })?;
+    result.set_named_property("batch", batch_fn)?;
+
     // `rustbuffer_alloc(n)` -> Uint8Array view over Rust-owned memory of capacity `n`.
     // `rustbuffer_free(view)` -> hands the underlying (ptr, capacity) back to the
     // library's `rustbuffer_free`. Together they let JS allocate buffers that the
     // codegen-emitted lowering path can fill in place and ship to Rust without copying.
-    let alloc_module = Arc::clone(&module);
+    let alloc_module = Arc::clone(module);
     let reg_for_alloc = Arc::clone(&registration);
     let alloc_fn = env.create_function_from_closure("rustbuffer_alloc", move |ctx| {
         let size_arg: i32 = ctx.get(0)?;
@@ -154,7 +208,7 @@ pub fn register(
     })?;
     result.set_named_property("rustbuffer_alloc", alloc_fn)?;
 
-    let free_module = Arc::clone(&module);
+    let free_module = Arc::clone(module);
     let reg_for_free = Arc::clone(&registration);
     let free_fn = env.create_function_from_closure("rustbuffer_free", move |ctx| {
         let js_val: JsUnknown = ctx.get(0)?;
@@ -210,5 +264,5 @@ pub fn register(
     })?;
     result.set_named_property("rustbuffer_free", free_fn)?;
 
-    Ok((result, module))
+    Ok(result)
 }
@@ -4,12 +4,18 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/
  */
 //! Parse a `definitions` JsObject from JS into a plain-Rust `ModuleSpec`.
+//!
+//! `definitions` comes in one of two shapes: the binary encoding from
+//! [`uniffi_runtime_core::spec::binary`], which generated code passes as a
+//! `Uint8Array`, or a tagged JS object that hand-written callers (and the
+//! tests) find easier to write. Both produce the same `ModuleSpec`.
 
 use std::collections::HashMap;
 
-use napi::{JsObject, Result};
+use napi::{JsObject, JsTypedArray, Result, TypedArrayType};
 use uniffi_runtime_core::{
-    CallbackDef, FfiTypeDesc, FunctionDef, ModuleSpec, RustBufferSymbols, StructDef, StructField,
+    ApiChecks, CallbackDef, FfiTypeDesc, FunctionDef, ModuleSpec, RustBufferSymbols, StructDef,
+    StructField,
 };
 
 /// Parse an `FfiTypeDesc` from a JS object with shape `{ tag: string, ...params }`.
@@ -58,7 +64,11 @@ fn ffi_type_desc_from_js(obj: &JsObject) -> Result<FfiTypeDesc> {
     }
 }
 
-pub fn parse_module_spec(definitions: &JsObject) -> Result<ModuleSpec> {
+pub fn parse_module_spec(definitions: JsObject) -> Result<ModuleSpec> {
+    if definitions.is_typedarray()? {
+        return decode_module_spec(definitions);
+    }
+
     let symbols: JsObject = definitions.get_named_property("symbols")?;
     let rustbuffer_symbols = RustBufferSymbols {
         alloc: symbols.get_named_property::<String>("rustbuffer_alloc")?,
@@ -66,18 +76,36 @@ pub fn parse_module_spec(definitions: &JsObject) -> Result<ModuleSpec> {
         from_bytes: symbols.get_named_property::<String>("rustbuffer_from_bytes")?,
     };
 
-    let functions = parse_functions(definitions)?;
-    let callbacks = parse_callbacks(definitions)?;
-    let structs = parse_structs(definitions)?;
+    let functions = parse_functions(&definitions)?;
+    let callbacks = parse_callbacks(&definitions)?;
+    let structs = parse_structs(&definitions)?;
+    let api_checks = parse_api_checks(&definitions)?;
 
     Ok(ModuleSpec {
         rustbuffer_symbols,
         functions,
         callbacks,
         structs,
+        api_checks,
     })
 }
 
+/// Decode the binary spec held by a `Uint8Array`.
+///
+/// The bytes are borrowed from the array's backing store only for the duration of
+/// the decode, which copies everything it keeps.
+fn decode_module_spec(definitions: JsObject) -> Result<ModuleSpec> {
+    // SAFETY: `is_typedarray` has just confirmed the value is a typed array.
+    let array = unsafe { definitions.into_unknown().cast::<JsTypedArray>() }.into_value()?;
+    if array.typedarray_type != TypedArrayType::Uint8 {
+        return Err(napi::Error::from_reason(format!(
+            "Binary module definitions must be a Uint8Array, got {:?}",
+            array.typedarray_type
+        )));
+    }
+    uniffi_runtime_core::spec::binary::decode(array.as_ref()).map_err(crate::core_err)
+}
+
 fn parse_functions(defs: &JsObject) -> Result<HashMap<String, FunctionDef>> {
     let mut out = HashMap::new();
     let functions: JsObject = defs.get_named_property("functions")?;
@@ -100,12 +128,16 @@ fn parse_functions(defs: &JsObject) -> Result<HashMap<String, FunctionDef>> {
         let ret_obj: JsObject = f.get_named_property("ret")?;
         let ret = ffi_type_desc_from_js(&ret_obj)?;
         let has_rust_call_status: bool = f.get_named_property("hasRustCallStatus")?;
+        let external_return: bool = f
+            .get_named_property::<bool>("externalReturn")
+            .unwrap_or(false);
         out.insert(
             name,
             FunctionDef {
                 args,
                 ret,
                 has_rust_call_status,
+                external_return,
             },
         );
     }
@@ -138,6 +170,7 @@ fn parse_callbacks(defs: &JsObject) -> Result<HashMap<String, CallbackDef>> {
         let ret = ffi_type_desc_from_js(&ret_obj)?;
         let has_rust_call_status: bool = c.get_named_property("hasRustCallStatus")?;
         let out_return: bool = c.get_named_property::<bool>("outReturn").unwrap_or(false);
+        let once: bool = c.get_named_property::<bool>("once").unwrap_or(false);
         out.insert(
             name,
             CallbackDef {
@@ -145,6 +178,7 @@ fn parse_callbacks(defs: &JsObject) -> Result<HashMap<String, CallbackDef>> {
                 ret,
                 has_rust_call_status,
                 out_return,
+                once,
             },
         );
     }
@@ -184,3 +218,43 @@ fn parse_structs(defs: &JsObject) -> Result<HashMap<String, StructDef>> {
     }
     Ok(out)
 }
+
+/// The optional `apiChecks` entry:
+///
+/// ```js
+/// apiChecks: {
+///   contractVersionSymbol: "ffi_foo_uniffi_contract_version",
+///   contractVersion: 29,
+///   checksums: { uniffi_foo_checksum_func_add: 4021 },
+/// }
+/// ```
+fn parse_api_checks(defs: &JsObject) -> Result<Option<ApiChecks>> {
+    if !defs.has_named_property("apiChecks")? {
+        return Ok(None);
+    }
+    let checks: JsObject = defs.get_named_property("apiChecks")?;
+    let contract_version_symbol: String = checks.get_named_property("contractVersionSymbol")?;
+    let contract_version: u32 = checks.get_named_property("contractVersion")?;
+    let mut checksums = HashMap::new();
+    if checks.has_named_property("checksums")? {
+        let entries: JsObject = checks.get_named_property("checksums")?;
+        let names = entries.get_property_names()?;
+        for i in 0..names.get_array_length()? {
+            let name: String = names
+                .get_element::<napi::JsString>(i)?
+                .into_utf8()?
+                .as_str()?
+                .to_owned();
+            let checksum: u32 = entries.get_named_property(&name)?;
+            let checksum = u16::try_from(checksum).map_err(|_| {
+                napi::Error::from_reason(format!("Checksum for {name} does not fit in a u16"))
+            })?;
+            checksums.insert(name, checksum);
+        }
+    }
+    Ok(Some(ApiChecks {
+        contract_version_symbol,
+        contract_version,
+        checksums,
+    }))
+}
@@ -0,0 +1,90 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import assert from "node:assert";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+
+const LIB_PATH = libPath("uniffi_napi_test_lib");
+
+// The test library reports contract version 29, and checksum 4021 for
+// `uniffi_test_fn_add`.
+function definitions(apiChecks) {
+  return {
+    symbols: {
+      rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+      rustbuffer_free: "uniffi_test_rustbuffer_free",
+      rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+    },
+    structs: {},
+    callbacks: {},
+    functions: {
+      uniffi_test_fn_add: {
+        args: [FfiType.Int32, FfiType.Int32],
+        ret: FfiType.Int32,
+        hasRustCallStatus: true,
+      },
+    },
+    apiChecks,
+  };
+}
+
+test("api checks: a matching library registers", () => {
+  const nm = UniffiNativeModule.open(LIB_PATH).register(
+    definitions({
+      contractVersionSymbol: "ffi_test_uniffi_contract_version",
+      contractVersion: 29,
+      checksums: { uniffi_test_checksum_fn_add: 4021 },
+    }),
+  );
+  assert.strictEqual(nm.uniffi_test_fn_add(1, 2, { code: 0 }), 3);
+});
+
+test("api checks: a different contract version throws", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(
+    () =>
+      native.register(
+        definitions({
+          contractVersionSymbol: "ffi_test_uniffi_contract_version",
+          contractVersion: 30,
+          checksums: {},
+        }),
+      ),
+    /contract version mismatch: the bindings expect 30, but the library has 29/,
+  );
+});
+
+test("api checks: a different checksum names the function", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(
+    () =>
+      native.register(
+        definitions({
+          contractVersionSymbol: "ffi_test_uniffi_contract_version",
+          contractVersion: 29,
+          checksums: { uniffi_test_checksum_fn_add: 1234 },
+        }),
+      ),
+    /checksum mismatch for uniffi_test_checksum_fn_add: the bindings expect 1234, but the library has 4021/,
+  );
+});
+
+test("api checks: a missing checksum function throws", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(
+    () =>
+      native.register(
+        definitions({
+          contractVersionSymbol: "ffi_test_uniffi_contract_version",
+          contractVersion: 29,
+          checksums: { uniffi_test_checksum_fn_missing: 1 },
+        }),
+      ),
+    /symbol not found: uniffi_test_checksum_fn_missing/,
+  );
+});
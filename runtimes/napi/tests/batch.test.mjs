@@ -0,0 +1,126 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import assert from "node:assert";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+
+const LIB_PATH = libPath("uniffi_napi_test_lib");
+
+const DEFINITIONS = {
+  symbols: {
+    rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+    rustbuffer_free: "uniffi_test_rustbuffer_free",
+    rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+  },
+  structs: {},
+  callbacks: {},
+  functions: {
+    uniffi_test_fn_add: {
+      args: [FfiType.Int32, FfiType.Int32],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_error: {
+      args: [],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_echo_buffer: {
+      args: [FfiType.RustBuffer],
+      ret: FfiType.RustBuffer,
+      hasRustCallStatus: true,
+    },
+  },
+};
+
+function register() {
+  return UniffiNativeModule.open(LIB_PATH).register(DEFINITIONS);
+}
+
+test("batch: returns each call's result in order", () => {
+  const nm = register();
+  const statuses = [];
+  const calls = [];
+  for (let i = 0; i < 1000; i++) {
+    const status = { code: 0 };
+    statuses.push(status);
+    calls.push(["uniffi_test_fn_add", [i, 1, status]]);
+  }
+
+  const results = nm.batch(calls);
+
+  assert.strictEqual(results.length, 1000);
+  results.forEach((result, i) => assert.strictEqual(result, i + 1));
+  statuses.forEach((status) => assert.strictEqual(status.code, 0));
+});
+
+test("batch: a failing call reports through its own status", () => {
+  const nm = register();
+  const before = { code: 0 };
+  const failing = { code: 0 };
+  const after = { code: 0 };
+
+  const results = nm.batch([
+    ["uniffi_test_fn_add", [2, 3, before]],
+    ["uniffi_test_fn_error", [failing]],
+    ["uniffi_test_fn_add", [4, 5, after]],
+  ]);
+
+  assert.deepStrictEqual(results, [5, 0, 9]);
+  assert.strictEqual(before.code, 0);
+  assert.strictEqual(failing.code, 2);
+  assert.strictEqual(
+    new TextDecoder().decode(failing.errorBuf),
+    "something went wrong",
+  );
+  assert.strictEqual(after.code, 0);
+});
+
+test("batch: RustBuffer arguments and returns", () => {
+  const nm = register();
+  const inputs = [new Uint8Array([1, 2, 3]), new Uint8Array([4, 5])];
+
+  const results = nm.batch(
+    inputs.map((input) => [
+      "uniffi_test_fn_echo_buffer",
+      [input, { code: 0 }],
+    ]),
+  );
+
+  results.forEach((view, i) => {
+    assert.deepStrictEqual(view, inputs[i]);
+    nm.rustbuffer_free(view);
+  });
+});
+
+test("batch: an empty batch returns an empty array", () => {
+  const nm = register();
+  assert.deepStrictEqual(nm.batch([]), []);
+});
+
+test("batch: an unknown function throws before any call runs", () => {
+  const nm = register();
+  const status = { code: -1 };
+  assert.throws(
+    () =>
+      nm.batch([
+        ["uniffi_test_fn_add", [1, 2, status]],
+        ["uniffi_test_fn_missing", []],
+      ]),
+    /Function not found in module: uniffi_test_fn_missing/,
+  );
+  assert.strictEqual(status.code, -1);
+});
+
+test("batch: too few arguments throws", () => {
+  const nm = register();
+  assert.throws(
+    () => nm.batch([["uniffi_test_fn_add", [1, 2]]]),
+    /expected 3 arguments/,
+  );
+});
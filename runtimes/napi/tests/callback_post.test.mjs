@@ -0,0 +1,191 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// A callback that returns nothing and reports nothing back does not block the
+// thread that invokes it: the call is queued for the JS thread, and the thread
+// carries on. Calls still run in the order they were made, and a thread that
+// gets `callbackQueueLimit` calls ahead of the JS thread waits for it.
+//
+// Each test keeps the JS thread busy while the Rust thread makes its calls, so
+// none can run until the test yields to the event loop.
+import { test } from "node:test";
+import assert from "node:assert";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+import { pollUntil } from "./helpers/poll.mjs";
+
+const BURST = 100;
+
+const LIB_PATH = libPath("uniffi_napi_test_lib");
+
+// Register the test library's functions on `native`, by default a module of
+// its own.
+function openModule(options, native = UniffiNativeModule.open(LIB_PATH)) {
+  return native.register(
+    {
+      symbols: {
+        rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+        rustbuffer_free: "uniffi_test_rustbuffer_free",
+        rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+      },
+      structs: {},
+      callbacks: {
+        simple_callback: {
+          args: [FfiType.UInt64, FfiType.Int8],
+          ret: FfiType.Void,
+          hasRustCallStatus: false,
+        },
+        buffer_callback: {
+          args: [FfiType.UInt64, FfiType.RustBuffer],
+          ret: FfiType.Void,
+          hasRustCallStatus: false,
+        },
+      },
+      functions: {
+        uniffi_test_fn_call_callback: {
+          args: [
+            FfiType.Callback("simple_callback"),
+            FfiType.UInt64,
+            FfiType.Int8,
+          ],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_call_callback_burst_from_thread: {
+          args: [FfiType.Callback("simple_callback"), FfiType.UInt32],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_call_buffer_callback_burst_from_thread: {
+          args: [FfiType.Callback("buffer_callback"), FfiType.UInt32],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_live_buffer_count: {
+          args: [],
+          ret: FfiType.Int32,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_is_burst_done: {
+          args: [],
+          ret: FfiType.Int8,
+          hasRustCallStatus: true,
+        },
+      },
+    },
+    options,
+  );
+}
+
+// Start a burst, then hold the JS thread for up to `ms`, or until the burst's
+// thread has made all its calls. Returns whether it had.
+function burstWhileBusy(
+  nm,
+  callback,
+  ms,
+  burst = "uniffi_test_fn_call_callback_burst_from_thread",
+) {
+  const status = { code: 0 };
+  nm[burst](callback, BURST, status);
+  assert.strictEqual(status.code, 0);
+  const deadline = Date.now() + ms;
+  while (Date.now() < deadline) {
+    if (nm.uniffi_test_fn_is_burst_done(status) === 1) {
+      return true;
+    }
+  }
+  return false;
+}
+
+async function allCalls(nm, seen) {
+  const status = { code: 0 };
+  await pollUntil(
+    () =>
+      seen.length === BURST && nm.uniffi_test_fn_is_burst_done(status) === 1,
+    `expected ${BURST} calls, saw ${seen.length}`,
+    10_000,
+  );
+  return seen;
+}
+
+const inOrder = Array.from({ length: BURST }, (_, i) => BigInt(i));
+
+test("the calling thread does not wait for a void callback", async () => {
+  const nm = openModule();
+  const seen = [];
+  const done = burstWhileBusy(nm, (handle) => seen.push(handle), 5000);
+
+  assert.ok(done, "the Rust thread must finish while the JS thread is busy");
+  assert.strictEqual(seen.length, 0, "nothing runs until the JS thread is free");
+  assert.deepStrictEqual(await allCalls(nm, seen), inOrder);
+});
+
+test("a thread that gets too far ahead waits for the JS thread", async () => {
+  const nm = openModule({ callbackQueueLimit: 10 });
+  const seen = [];
+  const done = burstWhileBusy(nm, (handle) => seen.push(handle), 300);
+
+  assert.ok(!done, "the Rust thread must wait once 10 calls are queued");
+  assert.deepStrictEqual(await allCalls(nm, seen), inOrder);
+});
+
+test("a callbackQueueLimit of 0 makes every call wait", async () => {
+  const nm = openModule({ callbackQueueLimit: 0 });
+  const seen = [];
+  const done = burstWhileBusy(nm, (handle) => seen.push(handle), 300);
+
+  assert.ok(!done, "the Rust thread must wait for its first call");
+  assert.deepStrictEqual(await allCalls(nm, seen), inOrder);
+});
+
+test("a call on the JS thread runs after the calls queued before it", async () => {
+  const nm = openModule();
+  const seen = [];
+  const callback = (handle) => seen.push(handle);
+  assert.ok(burstWhileBusy(nm, callback, 5000));
+
+  const status = { code: 0 };
+  nm.uniffi_test_fn_call_callback(callback, BigInt(BURST), 0, status);
+  assert.strictEqual(status.code, 0);
+  assert.strictEqual(seen.length, 0, "the call must not overtake those queued");
+
+  await pollUntil(
+    () => seen.length === BURST + 1,
+    `expected ${BURST + 1} calls, saw ${seen.length}`,
+    10_000,
+  );
+  assert.deepStrictEqual(seen, [...inOrder, BigInt(BURST)]);
+});
+
+test("unloading frees the buffers of calls still queued", () => {
+  // Counted through a second registration, which the unload leaves working.
+  const counter = openModule();
+  const status = { code: 0 };
+  const before = counter.uniffi_test_live_buffer_count(status);
+
+  // Kept to unload: the registered functions cannot.
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const nm = openModule(undefined, native);
+  const done = burstWhileBusy(
+    nm,
+    () => assert.fail("a queued call must not run after unloading"),
+    5000,
+    "uniffi_test_fn_call_buffer_callback_burst_from_thread",
+  );
+  assert.ok(done, "the Rust thread must finish while the JS thread is busy");
+  assert.strictEqual(
+    counter.uniffi_test_live_buffer_count(status),
+    before + BURST,
+    "each queued call holds its buffer",
+  );
+
+  native.unload();
+  assert.strictEqual(
+    counter.uniffi_test_live_buffer_count(status),
+    before,
+    "unloading frees the buffer of each call still queued",
+  );
+});
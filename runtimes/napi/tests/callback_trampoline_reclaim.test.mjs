@@ -0,0 +1,126 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// A callback declared `once` has its trampoline freed after its JS function is
+// collected; any other callback's lives as long as the process.
+//
+// The trampoline cache bounds callers that pass the same function every time.
+// A fresh closure on every call is something nothing can cache, so without
+// reclaiming each call leaks a trampoline for good. The 100k-closure test of
+// that is in the `futures` fixture, under the spec the generator writes, in
+// `fixtures/futures/tests/bindings/test_trampoline_reclaim.ts`.
+import { test } from "node:test";
+import assert from "node:assert";
+import { setImmediate as tick } from "node:timers/promises";
+import v8 from "node:v8";
+import vm from "node:vm";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+
+v8.setFlagsFromString("--expose-gc");
+const gc = vm.runInNewContext("gc");
+
+function openModule({ once }) {
+  const native = UniffiNativeModule.open(libPath("uniffi_napi_test_lib"));
+  const nm = native.register({
+    symbols: {
+      rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+      rustbuffer_free: "uniffi_test_rustbuffer_free",
+      rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+    },
+    structs: {},
+    callbacks: {
+      simple_cb: {
+        args: [FfiType.UInt64, FfiType.Int8],
+        ret: FfiType.Void,
+        hasRustCallStatus: false,
+        once,
+      },
+    },
+    functions: {
+      uniffi_test_fn_call_callback: {
+        args: [FfiType.Callback("simple_cb"), FfiType.UInt64, FfiType.Int8],
+        ret: FfiType.Void,
+        hasRustCallStatus: true,
+      },
+    },
+  });
+  return { native, nm };
+}
+
+// Finalizers run on the event loop after a collection, and core frees a
+// released trampoline only at a later release, so this takes a few rounds.
+async function collect(native, below) {
+  for (let round = 0; round < 50; round++) {
+    gc();
+    await tick();
+    if (native.metrics().liveTrampolines < below) {
+      break;
+    }
+  }
+  return native.metrics().liveTrampolines;
+}
+
+test("metrics are zero before anything is called", () => {
+  const { native } = openModule({ once: true });
+  assert.deepStrictEqual(native.metrics(), {
+    liveTrampolines: 0,
+    liveExternalBuffers: 0,
+  });
+});
+
+test("fresh once closures do not leak their trampolines", async () => {
+  const { native, nm } = openModule({ once: true });
+  const status = { code: 0 };
+  let calls = 0;
+  for (let i = 0; i < 1000; i++) {
+    nm.uniffi_test_fn_call_callback(
+      (_handle, value) => {
+        calls += value;
+      },
+      1n,
+      1,
+      status,
+    );
+    assert.strictEqual(status.code, 0);
+  }
+  assert.strictEqual(calls, 1000, "every closure must still be called");
+
+  const live = await collect(native, 100);
+  assert.ok(
+    live < 100,
+    `expected collected closures to free their trampolines, ${live} live`,
+  );
+});
+
+test("a once callback passed again reuses its trampoline", async () => {
+  const { native, nm } = openModule({ once: true });
+  const status = { code: 0 };
+  let calls = 0;
+  const cb = (_handle, value) => {
+    calls += value;
+  };
+  for (let i = 0; i < 10; i++) {
+    nm.uniffi_test_fn_call_callback(cb, 1n, 1, status);
+  }
+  await collect(native, 0);
+  assert.strictEqual(calls, 10);
+  assert.strictEqual(
+    native.metrics().liveTrampolines,
+    1,
+    "a reachable function must keep its trampoline",
+  );
+});
+
+test("trampolines of callbacks not declared once are kept", async () => {
+  const { native, nm } = openModule({ once: false });
+  const status = { code: 0 };
+  for (let i = 0; i < 10; i++) {
+    nm.uniffi_test_fn_call_callback(() => {}, 1n, 1, status);
+  }
+  await collect(native, 10);
+  assert.strictEqual(native.metrics().liveTrampolines, 10);
+});
@@ -0,0 +1,191 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import { spawn } from "node:child_process";
+import { join } from "node:path";
+import assert from "node:assert";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+import { pollUntil } from "./helpers/poll.mjs";
+
+const LIB_PATH = libPath("uniffi_napi_test_lib");
+
+const SYMBOLS = {
+  rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+  rustbuffer_free: "uniffi_test_rustbuffer_free",
+  rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+};
+
+function busyWait(ms) {
+  const end = Date.now() + ms;
+  while (Date.now() < end) {
+    // Keep the JS thread from its event loop, without calling into Rust.
+  }
+}
+
+test("dispatch: a callback the JS thread does not answer in time fails", async () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  const nm = lib.register(
+    {
+      symbols: SYMBOLS,
+      structs: {
+        TestVTable: [
+          { name: "get_value", type: FfiType.Callback("vtable_get_value") },
+          { name: "free", type: FfiType.Callback("vtable_free") },
+        ],
+      },
+      callbacks: {
+        vtable_get_value: {
+          args: [FfiType.UInt64],
+          ret: FfiType.Int32,
+          hasRustCallStatus: true,
+        },
+        vtable_free: {
+          args: [FfiType.UInt64],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+      },
+      functions: {
+        uniffi_test_fn_init_vtable: {
+          args: [FfiType.Reference(FfiType.Struct("TestVTable"))],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_use_vtable_from_thread: {
+          args: [FfiType.UInt64],
+          ret: FfiType.Void,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_is_thread_done: {
+          args: [],
+          ret: FfiType.Int8,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_get_thread_result: {
+          args: [],
+          ret: FfiType.Int32,
+          hasRustCallStatus: true,
+        },
+        uniffi_test_fn_get_thread_status: {
+          args: [],
+          ret: FfiType.Int8,
+          hasRustCallStatus: true,
+        },
+      },
+    },
+    { dispatchTimeoutMs: 100 },
+  );
+
+  let called = false;
+  nm.uniffi_test_fn_init_vtable(
+    {
+      get_value: (handle, callStatus) => {
+        called = true;
+        callStatus.code = 0;
+        return Number(handle) * 10;
+      },
+      free: (handle, callStatus) => {
+        callStatus.code = 0;
+      },
+    },
+    { code: 0 },
+  );
+
+  const status = { code: 0 };
+  nm.uniffi_test_fn_use_vtable_from_thread(7n, status);
+  assert.strictEqual(status.code, 0);
+
+  // Busy in Javascript rather than in Rust, so this is the timeout, not the deadlock check.
+  busyWait(500);
+
+  await pollUntil(
+    () => nm.uniffi_test_fn_is_thread_done({ code: 0 }) === 1,
+    "Timed out waiting for the dispatch to fail",
+  );
+  assert.strictEqual(nm.uniffi_test_fn_get_thread_status({ code: 0 }), 2);
+  assert.strictEqual(nm.uniffi_test_fn_get_thread_result({ code: 0 }), 0);
+  // The withdrawn dispatch never reaches Javascript, even once it is free.
+  await new Promise((resolve) => setTimeout(resolve, 50));
+  assert.strictEqual(called, false);
+});
+
+// A synchronous call into Rust which waits on a thread dispatching a callback fails the callback.
+//
+// Run in a child process on purpose: without the deadlock check this hangs inside native code,
+// where nothing in-process can interrupt it.
+/** Run the deadlocking script with these register() options, and return what it printed. */
+async function runDeadlock(options = {}) {
+  const script = join(import.meta.dirname, "helpers", "sync-call-deadlock.mjs");
+  const child = spawn(process.execPath, [script, JSON.stringify(options)], {
+    stdio: ["ignore", "pipe", "inherit"],
+  });
+
+  let out = "";
+  child.stdout.on("data", (chunk) => {
+    out += String(chunk);
+  });
+
+  const exitCode = await new Promise((resolve) => {
+    const timer = setTimeout(() => {
+      child.kill("SIGKILL");
+      resolve(null);
+    }, 30_000);
+    child.on("close", (code) => {
+      clearTimeout(timer);
+      resolve(code);
+    });
+  });
+  return { out, exitCode };
+}
+
+test("dispatch: a callback while the JS thread waits in Rust fails rather than hangs", async () => {
+  const { out, exitCode } = await runDeadlock();
+
+  assert.match(
+    out,
+    /^STATUS 2 RESULT 0 .*blocked in a synchronous call into Rust for over 1000ms/m,
+    "the call did not fail with an unexpected error",
+  );
+  assert.doesNotMatch(out, /^CALLED$/m, "the withdrawn callback still ran");
+  assert.strictEqual(exitCode, 0, "the child did not exit on its own");
+});
+
+test("dispatch: deadlockGraceMs sets how long the JS thread may wait in Rust", async () => {
+  const { out, exitCode } = await runDeadlock({ deadlockGraceMs: 200 });
+
+  assert.match(
+    out,
+    /^STATUS 2 RESULT 0 .*blocked in a synchronous call into Rust for over 200ms/m,
+  );
+  assert.strictEqual(exitCode, 0, "the child did not exit on its own");
+});
+
+test("dispatch: with deadlockGraceMs 0, only dispatchTimeoutMs ends the wait", async () => {
+  const { out, exitCode } = await runDeadlock({
+    deadlockGraceMs: 0,
+    dispatchTimeoutMs: 1500,
+  });
+
+  assert.match(out, /^STATUS 2 RESULT 0 .*within the dispatchTimeoutMs of 1500ms/m);
+  assert.strictEqual(exitCode, 0, "the child did not exit on its own");
+});
+
+test("register: an option of the wrong type throws", () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  const definitions = { symbols: SYMBOLS, structs: {}, callbacks: {}, functions: {} };
+  for (const options of [
+    { dispatchTimeoutMs: "100" },
+    { deadlockGraceMs: true },
+    { callbackQueueLimit: "10" },
+    { verifySymbols: "yes" },
+  ]) {
+    assert.throws(() => lib.register(definitions, options), Error, JSON.stringify(options));
+  }
+  // Unset, undefined and null are all the default.
+  lib.register(definitions, { dispatchTimeoutMs: undefined, callbackQueueLimit: null });
+});
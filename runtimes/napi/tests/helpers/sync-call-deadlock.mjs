@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// Makes a synchronous call into Rust which waits on another thread, while that thread invokes a
+// callback, printing the status the call comes back with.
+// Run as a script by dispatch_timeout.test.mjs; see there for why it is a separate process.
+// The register() options, if any, are the first argument, as JSON.
+import lib from "../../lib.js";
+import { libPath } from "./lib-path.mjs";
+
+const { UniffiNativeModule, FfiType } = lib;
+
+const DEFINITIONS = {
+  symbols: {
+    rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+    rustbuffer_free: "uniffi_test_rustbuffer_free",
+    rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+  },
+  structs: {
+    TestVTable: [
+      { name: "get_value", type: FfiType.Callback("vtable_get_value") },
+      { name: "free", type: FfiType.Callback("vtable_free") },
+    ],
+  },
+  callbacks: {
+    vtable_get_value: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    vtable_free: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Void,
+      hasRustCallStatus: true,
+    },
+  },
+  functions: {
+    uniffi_test_fn_init_vtable: {
+      args: [FfiType.Reference(FfiType.Struct("TestVTable"))],
+      ret: FfiType.Void,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_use_vtable_from_thread_and_wait: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+  },
+};
+
+const nm = UniffiNativeModule.open(libPath("uniffi_napi_test_lib")).register(
+  DEFINITIONS,
+  JSON.parse(process.argv[2] ?? "{}"),
+);
+
+nm.uniffi_test_fn_init_vtable(
+  {
+    get_value: (handle, callStatus) => {
+      console.log("CALLED");
+      callStatus.code = 0;
+      return Number(handle) * 10;
+    },
+    free: (handle, callStatus) => {
+      callStatus.code = 0;
+    },
+  },
+  { code: 0 },
+);
+
+const status = { code: 0 };
+const result = nm.uniffi_test_fn_use_vtable_from_thread_and_wait(7n, status);
+const message = status.errorBuf
+  ? new TextDecoder().decode(status.errorBuf)
+  : "";
+console.log(`STATUS ${status.code} RESULT ${result} ${message}`);
@@ -0,0 +1,127 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// The test library as workers.test.mjs registers it, in the main thread and in
+// each worker; and the worker side, when run as a Worker's script.
+//
+// A worker answers each `{ id, op, ...args }` message with `{ id, result }`, or
+// `{ id, error }`.
+import { isMainThread, parentPort } from "node:worker_threads";
+import lib from "../../lib.js";
+import { libPath } from "./lib-path.mjs";
+
+const { UniffiNativeModule, FfiType } = lib;
+
+const DEFINITIONS = {
+  symbols: {
+    rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+    rustbuffer_free: "uniffi_test_rustbuffer_free",
+    rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+  },
+  structs: {
+    TestVTable: [
+      { name: "get_value", type: FfiType.Callback("vtable_get_value") },
+      { name: "free", type: FfiType.Callback("vtable_free") },
+    ],
+  },
+  callbacks: {
+    vtable_get_value: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    vtable_free: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Void,
+      hasRustCallStatus: true,
+    },
+  },
+  functions: {
+    uniffi_test_fn_add: {
+      args: [FfiType.Int32, FfiType.Int32],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_counter_new: {
+      args: [FfiType.Int64],
+      ret: FfiType.UInt64,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_counter_add: {
+      args: [FfiType.UInt64, FfiType.Int64],
+      ret: FfiType.Int64,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_counter_free: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Void,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_init_vtable: {
+      args: [FfiType.Reference(FfiType.Struct("TestVTable"))],
+      ret: FfiType.Void,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_fn_use_vtable_from_thread_and_wait: {
+      args: [FfiType.UInt64],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+  },
+};
+
+export function openModule() {
+  const native = UniffiNativeModule.open(libPath("uniffi_napi_test_lib"));
+  return { native, nm: native.register(DEFINITIONS) };
+}
+
+// Call `get_value` on the stored VTable from a Rust thread, as `{ result,
+// code, message }`.
+export function useVTable(nm, handle) {
+  const status = { code: 0 };
+  const result = nm.uniffi_test_fn_use_vtable_from_thread_and_wait(
+    handle,
+    status,
+  );
+  const message = status.errorBuf
+    ? new TextDecoder().decode(status.errorBuf)
+    : "";
+  return { result, code: status.code, message };
+}
+
+function serve() {
+  const { native, nm } = openModule();
+  const ops = {
+    add: ({ a, b }) => nm.uniffi_test_fn_add(a, b, { code: 0 }),
+    counterAdd: ({ handle, n }) =>
+      nm.uniffi_test_fn_counter_add(handle, n, { code: 0 }),
+    initVTable: ({ factor }) =>
+      nm.uniffi_test_fn_init_vtable(
+        {
+          get_value: (handle, callStatus) => {
+            callStatus.code = 0;
+            return Number(handle) * factor;
+          },
+          free: (_handle, callStatus) => {
+            callStatus.code = 0;
+          },
+        },
+        { code: 0 },
+      ),
+    useVTable: ({ handle }) => useVTable(nm, handle),
+    unload: () => native.unload({ force: true }),
+  };
+  parentPort.on("message", ({ id, op, ...args }) => {
+    try {
+      parentPort.postMessage({ id, result: ops[op](args) });
+    } catch (e) {
+      parentPort.postMessage({ id, error: String(e) });
+    }
+  });
+}
+
+if (!isMainThread) {
+  serve();
+}
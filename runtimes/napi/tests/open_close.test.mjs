@@ -6,7 +6,7 @@
 import { test } from "node:test";
 import assert from "node:assert";
 import lib from "../lib.js";
-const { UniffiNativeModule } = lib;
+const { UniffiNativeModule, FfiType } = lib;
 import { libPath } from "./helpers/lib-path.mjs";
 
 const LIB_PATH = libPath("uniffi_napi_test_lib");
@@ -43,3 +43,80 @@ test("register() throws for missing symbol", () => {
     });
   }, /Error/);
 });
+
+// The binary spec that generated code passes in place of the object above:
+// magic "UBSP", format version 3, the three RustBuffer symbols, then empty
+// function, callback and struct tables and no API checks. See
+// `uniffi_runtime_core::spec::binary`.
+function binarySpec(symbols) {
+  const encoder = new TextEncoder();
+  const bytes = [..."UBSP"].map((c) => c.charCodeAt(0));
+  const u32 = (n) => bytes.push(n & 0xff, (n >> 8) & 0xff, (n >> 16) & 0xff, n >>> 24);
+  bytes.push(3, 0);
+  for (const name of [
+    symbols.rustbuffer_alloc,
+    symbols.rustbuffer_free,
+    symbols.rustbuffer_from_bytes,
+  ]) {
+    const utf8 = encoder.encode(name);
+    u32(utf8.length);
+    bytes.push(...utf8);
+  }
+  u32(0);
+  u32(0);
+  u32(0);
+  bytes.push(0);
+  return new Uint8Array(bytes);
+}
+
+test("register() accepts a binary spec", () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  const nm = lib.register(binarySpec(SYMBOLS));
+  assert.strictEqual(typeof nm.rustbuffer_alloc, "function");
+});
+
+test("register() rejects a malformed binary spec", () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(() => {
+    lib.register(new Uint8Array([0x55, 0x42, 0x53, 0x50, 0x63, 0x00]));
+  }, /invalid module spec/);
+});
+
+// A spec naming one function the test library exports and one it does not.
+function withMissingFunction() {
+  return {
+    symbols: SYMBOLS,
+    structs: {},
+    callbacks: {},
+    functions: {
+      uniffi_test_fn_add: {
+        args: [FfiType.Int32, FfiType.Int32],
+        ret: FfiType.Int32,
+        hasRustCallStatus: true,
+      },
+      uniffi_test_fn_nonexistent: {
+        args: [],
+        ret: FfiType.Void,
+        hasRustCallStatus: false,
+      },
+    },
+  };
+}
+
+test("register() defers a missing function symbol to its first call", () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  const nm = lib.register(withMissingFunction());
+
+  const status = { code: 0 };
+  assert.strictEqual(nm.uniffi_test_fn_add(3, 4, status), 7);
+  assert.throws(() => nm.uniffi_test_fn_nonexistent(), /symbol not found/);
+  // A failed resolution is not cached: the next call reports it again.
+  assert.throws(() => nm.uniffi_test_fn_nonexistent(), /symbol not found/);
+});
+
+test("register() with verifySymbols throws for a missing function symbol", () => {
+  const lib = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(() => {
+    lib.register(withMissingFunction(), { verifySymbols: true });
+  }, /symbol not found/);
+});
@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "node:test";
+import assert from "node:assert";
+import { copyFileSync, mkdtempSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { basename, join } from "node:path";
+import lib from "../lib.js";
+const { UniffiNativeModule, FfiType } = lib;
+import { libPath } from "./helpers/lib-path.mjs";
+
+const LIB_PATH = libPath("uniffi_napi_test_lib");
+
+const DEFINITIONS = {
+  symbols: {
+    rustbuffer_alloc: "uniffi_test_rustbuffer_alloc",
+    rustbuffer_free: "uniffi_test_rustbuffer_free",
+    rustbuffer_from_bytes: "uniffi_test_rustbuffer_from_bytes",
+  },
+  structs: {},
+  callbacks: {},
+  functions: {
+    uniffi_test_fn_add: {
+      args: [FfiType.Int32, FfiType.Int32],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+    uniffi_test_live_buffer_count: {
+      args: [],
+      ret: FfiType.Int32,
+      hasRustCallStatus: true,
+    },
+  },
+};
+
+// The loader hands back a library it already has loaded, so each reload
+// needs a copy of the build under a new name; reload refuses one it has.
+function copyOfLib() {
+  const dir = mkdtempSync(join(tmpdir(), "uniffi-reload-"));
+  const path = join(dir, basename(LIB_PATH));
+  copyFileSync(LIB_PATH, path);
+  return path;
+}
+
+test("reload: the new object calls into the new library", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const before = native.register(DEFINITIONS);
+  // Counted by the library's own statics, which a fresh copy starts afresh.
+  // The buffer is never freed: it belongs to the library being replaced.
+  before.rustbuffer_alloc(8);
+  assert.strictEqual(before.uniffi_test_live_buffer_count({ code: 0 }), 1);
+
+  const after = native.reload(copyOfLib());
+
+  assert.strictEqual(after.uniffi_test_fn_add(2, 3, { code: 0 }), 5);
+  assert.strictEqual(after.uniffi_test_live_buffer_count({ code: 0 }), 0);
+});
+
+test("reload: the old object throws once replaced", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const before = native.register(DEFINITIONS);
+  native.reload(copyOfLib());
+
+  assert.throws(
+    () => before.uniffi_test_fn_add(1, 2, { code: 0 }),
+    /module was reloaded/,
+  );
+});
+
+test("reload: matching definitions are accepted", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  native.register(DEFINITIONS);
+  const after = native.reload(copyOfLib(), DEFINITIONS);
+  assert.strictEqual(after.uniffi_test_fn_add(4, 5, { code: 0 }), 9);
+});
+
+test("reload: changed definitions throw and keep the library", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const before = native.register(DEFINITIONS);
+  const changed = {
+    ...DEFINITIONS,
+    functions: {
+      ...DEFINITIONS.functions,
+      uniffi_test_fn_add: {
+        ...DEFINITIONS.functions.uniffi_test_fn_add,
+        ret: FfiType.Int64,
+      },
+    },
+  };
+
+  assert.throws(
+    () => native.reload(copyOfLib(), changed),
+    /checksum [0-9a-f]{16}, but those registered have checksum [0-9a-f]{16}/,
+  );
+  assert.strictEqual(before.uniffi_test_fn_add(1, 1, { code: 0 }), 2);
+});
+
+test("reload: a library missing a function throws and keeps the library", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const before = native.register(DEFINITIONS);
+
+  assert.throws(
+    () => native.reload(libPath("uniffi_fixture_simple")),
+    /symbol not found/,
+  );
+  assert.strictEqual(before.uniffi_test_fn_add(1, 1, { code: 0 }), 2);
+});
+
+test("reload: the loaded library's own path throws and keeps the library", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const before = native.register(DEFINITIONS);
+
+  assert.throws(() => native.reload(LIB_PATH), /library is already loaded/);
+  assert.strictEqual(before.uniffi_test_fn_add(1, 1, { code: 0 }), 2);
+});
+
+test("reload: a library a replaced module holds open throws", () => {
+  const first = copyOfLib();
+  const native = UniffiNativeModule.open(first);
+  const before = native.register({
+    ...DEFINITIONS,
+    functions: {
+      ...DEFINITIONS.functions,
+      uniffi_test_fn_make_buffer: {
+        args: [FfiType.UInt8, FfiType.UInt32],
+        ret: FfiType.RustBuffer,
+        hasRustCallStatus: true,
+        externalReturn: true,
+      },
+    },
+  });
+  // Held, so the replaced module leaves its library open for it.
+  const view = before.uniffi_test_fn_make_buffer(0xab, 16, { code: 0 });
+  const after = native.reload(copyOfLib());
+
+  assert.throws(() => native.reload(first), /library is already loaded/);
+  assert.strictEqual(after.uniffi_test_fn_add(2, 2, { code: 0 }), 4);
+  assert.ok(view.every((byte) => byte === 0xab));
+});
+
+test("reload: before register throws", () => {
+  const native = UniffiNativeModule.open(LIB_PATH);
+  assert.throws(
+    () => native.reload(copyOfLib()),
+    /reload\(\) called before register\(\)/,
+  );
+});
@@ -15,12 +15,22 @@
 // and passing that view as the argument. If the runtime converts that view by copying it into
 // a second buffer, the first one is orphaned: codegen never frees a lowered argument, and the
 // view carries a no-op finalizer. One whole payload then leaks per call.
+//
+// A function declared `externalReturn` hands its returned buffer to the garbage collector
+// instead, and the last tests here check that it is released exactly once, and not before:
+// not even by a forced unload.
 import { test } from "node:test";
 import assert from "node:assert";
+import { setImmediate as tick } from "node:timers/promises";
+import v8 from "node:v8";
+import vm from "node:vm";
 import lib from "../lib.js";
 const { UniffiNativeModule, FfiType } = lib;
 import { libPath } from "./helpers/lib-path.mjs";
 
+v8.setFlagsFromString("--expose-gc");
+const gc = vm.runInNewContext("gc");
+
 const LIB_PATH = libPath("uniffi_napi_test_lib");
 
 const SYMBOLS = {
@@ -45,6 +55,12 @@ const FUNCTIONS = {
     ret: FfiType.RustBuffer,
     hasRustCallStatus: true,
   },
+  uniffi_test_fn_make_buffer: {
+    args: [FfiType.UInt8, FfiType.UInt32],
+    ret: FfiType.RustBuffer,
+    hasRustCallStatus: true,
+    externalReturn: true,
+  },
   uniffi_test_live_buffer_count: {
     args: [],
     ret: FfiType.Int32,
@@ -58,7 +74,8 @@ const FUNCTIONS = {
 };
 
 function openModule() {
-  const nm = UniffiNativeModule.open(LIB_PATH).register({
+  const native = UniffiNativeModule.open(LIB_PATH);
+  const nm = native.register({
     symbols: SYMBOLS,
     structs: {},
     callbacks: {},
@@ -68,7 +85,16 @@ function openModule() {
     count: nm.uniffi_test_live_buffer_count({ code: 0 }),
     bytes: Number(nm.uniffi_test_live_buffer_bytes({ code: 0 })),
   });
-  return { nm, live };
+  return { native, nm, live };
+}
+
+// Finalizers run on the event loop after a collection, so this takes a few
+// rounds.
+async function collectUntil(done) {
+  for (let round = 0; round < 50 && !done(); round++) {
+    gc();
+    await tick();
+  }
 }
 
 // Sized so a leak is unmistakable: 64 iterations x 1024 bytes is 64 KB, and the assertion is
@@ -212,3 +238,58 @@ test("empty rustbuffer_alloc view is accounted for", () => {
   assert.strictEqual(after.count, before.count);
   assert.strictEqual(after.bytes, before.bytes);
 });
+
+test("externalReturn buffer is released once collected", async () => {
+  const { native, nm, live } = openModule();
+  const before = live();
+
+  let checksum = 0;
+  for (let i = 0; i < ITERATIONS; i++) {
+    const status = { code: 0 };
+    const view = nm.uniffi_test_fn_make_buffer(i & 0xff, PAYLOAD_SIZE, status);
+    assert.strictEqual(status.code, 0);
+    assert.strictEqual(view.byteLength, PAYLOAD_SIZE);
+    checksum += view[PAYLOAD_SIZE - 1];
+    // Not the caller's to free: the garbage collector owns it.
+    assert.throws(() => nm.rustbuffer_free(view), /unowned/);
+  }
+  assert.strictEqual(checksum, (ITERATIONS * (ITERATIONS - 1)) / 2);
+  assert.strictEqual(native.metrics().liveExternalBuffers, ITERATIONS);
+
+  await collectUntil(() => native.metrics().liveExternalBuffers === 0);
+
+  assert.strictEqual(native.metrics().liveExternalBuffers, 0);
+  const after = live();
+  assert.strictEqual(after.count, before.count);
+  assert.strictEqual(after.bytes, before.bytes);
+});
+
+test("externalReturn buffer outlives a forced unload", async () => {
+  // A second registration of the same library reads its counters after the
+  // first is unloaded.
+  const { live } = openModule();
+  const { native, nm } = openModule();
+  const before = live();
+
+  let view = nm.uniffi_test_fn_make_buffer(0xab, PAYLOAD_SIZE, { code: 0 });
+  // An alias, as a zero-copy lift would make, keeps the buffer alive too.
+  let alias = view.subarray(8, 16);
+  assert.strictEqual(live().count, before.count + 1);
+
+  native.unload({ force: true });
+  gc();
+  await tick();
+
+  // Still the library's allocation, still intact: nothing freed it early.
+  assert.strictEqual(live().count, before.count + 1);
+  assert.ok(view.every((byte) => byte === 0xab));
+  view = undefined;
+  await collectUntil(() => false);
+  assert.ok(alias.every((byte) => byte === 0xab));
+  assert.strictEqual(live().count, before.count + 1);
+
+  alias = undefined;
+  await collectUntil(() => live().count === before.count);
+  assert.strictEqual(live().count, before.count);
+  assert.strictEqual(live().bytes, before.bytes);
+});
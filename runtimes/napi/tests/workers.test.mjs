@@ -0,0 +1,132 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+// One library registered in several Node workers: each worker has its own
+// environment and its own module, over the one loaded library.
+import { test } from "node:test";
+import assert from "node:assert";
+import { join } from "node:path";
+import { Worker } from "node:worker_threads";
+import { openModule, useVTable } from "./helpers/worker-module.mjs";
+
+const SCRIPT = join(import.meta.dirname, "helpers", "worker-module.mjs");
+
+// A worker serving the test library, with `call(op, args)` returning its answer.
+async function startWorker() {
+  const worker = new Worker(SCRIPT);
+  let nextId = 0;
+  const pending = new Map();
+  worker.on("message", ({ id, result, error }) => {
+    const { resolve, reject } = pending.get(id);
+    pending.delete(id);
+    if (error === undefined) {
+      resolve(result);
+    } else {
+      reject(new Error(error));
+    }
+  });
+  await new Promise((resolve) => worker.once("online", resolve));
+  return {
+    worker,
+    call(op, args = {}) {
+      const id = nextId++;
+      return new Promise((resolve, reject) => {
+        pending.set(id, { resolve, reject });
+        worker.postMessage({ id, op, ...args });
+      });
+    },
+  };
+}
+
+test("workers: the same library registers and runs in several workers", async () => {
+  const { nm } = openModule();
+  const a = await startWorker();
+  const b = await startWorker();
+  try {
+    assert.strictEqual(await a.call("add", { a: 2, b: 3 }), 5);
+    assert.strictEqual(await b.call("add", { a: 4, b: 5 }), 9);
+    assert.strictEqual(nm.uniffi_test_fn_add(6, 7, { code: 0 }), 13);
+  } finally {
+    await Promise.all([a.worker.terminate(), b.worker.terminate()]);
+  }
+});
+
+test("workers: a handle made in one environment works in another", async () => {
+  const { nm } = openModule();
+  const a = await startWorker();
+  const b = await startWorker();
+  try {
+    const handle = nm.uniffi_test_fn_counter_new(1n, { code: 0 });
+    assert.strictEqual(await a.call("counterAdd", { handle, n: 10n }), 11n);
+    assert.strictEqual(await b.call("counterAdd", { handle, n: 100n }), 111n);
+    assert.strictEqual(
+      nm.uniffi_test_fn_counter_add(handle, 1000n, { code: 0 }),
+      1111n,
+    );
+    nm.uniffi_test_fn_counter_free(handle, { code: 0 });
+  } finally {
+    await Promise.all([a.worker.terminate(), b.worker.terminate()]);
+  }
+});
+
+test("workers: a callback runs in the worker that registered it", async () => {
+  const { nm } = openModule();
+  const a = await startWorker();
+  try {
+    await a.call("initVTable", { factor: 100 });
+    // From the main thread, via a Rust thread, to the worker.
+    assert.deepStrictEqual(useVTable(nm, 7n), {
+      result: 700,
+      code: 0,
+      message: "",
+    });
+
+    nm.uniffi_test_fn_init_vtable(
+      {
+        get_value: (handle, callStatus) => {
+          callStatus.code = 0;
+          return Number(handle) * 3;
+        },
+        free: (_handle, callStatus) => {
+          callStatus.code = 0;
+        },
+      },
+      { code: 0 },
+    );
+    // And back: from the worker to the main thread, which is free meanwhile.
+    assert.deepStrictEqual(await a.call("useVTable", { handle: 7n }), {
+      result: 21,
+      code: 0,
+      message: "",
+    });
+  } finally {
+    await a.worker.terminate();
+  }
+});
+
+test("workers: a callback of an exited worker fails rather than answering zero", async () => {
+  const { nm } = openModule();
+  const a = await startWorker();
+  await a.call("initVTable", { factor: 100 });
+  await a.worker.terminate();
+
+  const { result, code, message } = useVTable(nm, 7n);
+  assert.strictEqual(result, 0);
+  assert.strictEqual(code, 2, "the call must fail with an unexpected error");
+  assert.match(message, /torn down/);
+});
+
+test("workers: force-unloading in one worker leaves the others working", async () => {
+  const { nm } = openModule();
+  const a = await startWorker();
+  const b = await startWorker();
+  try {
+    await a.call("unload");
+    assert.strictEqual(await b.call("add", { a: 1, b: 2 }), 3);
+    assert.strictEqual(nm.uniffi_test_fn_add(3, 4, { code: 0 }), 7);
+  } finally {
+    await Promise.all([a.worker.terminate(), b.worker.terminate()]);
+  }
+});
@@ -6,6 +6,8 @@
 export { FfiType, type FfiTypeDesc } from "./ffi-type.js";
 export {
   UniffiNativeModule,
+  verifyApiChecks,
+  type ApiChecks,
   type WasmSource,
   type ModuleDefinitions,
   type NativeModuleInterface,
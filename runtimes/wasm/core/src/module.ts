@@ -28,6 +28,16 @@ export type WasmSource =
   | URL
   | string;
 
+/**
+ * The uniffi contract version and API checksums the bindings were generated
+ * against. Each checksum is keyed by the export that returns it.
+ */
+export interface ApiChecks {
+  contractVersionSymbol: string;
+  contractVersion: number;
+  checksums: Record<string, number>;
+}
+
 export interface ModuleDefinitions {
   symbols: {
     rustbuffer_alloc: string;
@@ -37,6 +47,7 @@ export interface ModuleDefinitions {
   functions: Record<string, FunctionDef>;
   callbacks: Record<string, CallbackDef>;
   structs: Record<string, FieldDesc[]>;
+  apiChecks?: ApiChecks;
 }
 
 export type NativeModuleInterface = Record<string, (...args: any[]) => any>;
@@ -146,6 +157,44 @@ function installPanicLog(
   setSlot(slot);
 }
 
+/**
+ * Call the module's contract version and checksum exports, and throw on the
+ * first that disagrees with `checks`, naming it and both values. A `.wasm`
+ * built from different Rust than the bindings would otherwise fail, or
+ * misbehave, only once the changed function is called.
+ */
+export function verifyApiChecks(
+  exports: WebAssembly.Exports,
+  checks: ApiChecks,
+): void {
+  const call = (symbol: string): number => {
+    const fn = exports[symbol];
+    if (typeof fn !== "function") {
+      throw new Error(`register: wasm export "${symbol}" not found`);
+    }
+    return (fn as () => number)();
+  };
+
+  const version = call(checks.contractVersionSymbol);
+  if (version !== checks.contractVersion) {
+    throw new Error(
+      `register: uniffi contract version mismatch: the bindings expect ` +
+        `${checks.contractVersion}, but the library has ${version}`,
+    );
+  }
+  for (const symbol of Object.keys(checks.checksums).sort()) {
+    const expected = checks.checksums[symbol];
+    const found = call(symbol);
+    if (found !== expected) {
+      throw new Error(
+        `register: API checksum mismatch for ${symbol}: the bindings ` +
+          `expect ${expected}, but the library has ${found}; regenerate ` +
+          `the bindings against this build`,
+      );
+    }
+  }
+}
+
 export class UniffiNativeModule {
   readonly memory: Memory;
   readonly scratch: Scratch;
@@ -293,6 +342,9 @@ export class UniffiNativeModule {
         "register: definitions.symbols.rustbuffer_free must be a non-empty string",
       );
     }
+    if (definitions.apiChecks) {
+      verifyApiChecks(this.exports, definitions.apiChecks);
+    }
     const structs = new Map<string, StructLayout>();
     for (const [name, fields] of Object.entries(definitions.structs)) {
       structs.set(name, compileStructLayout(fields));
@@ -5,7 +5,7 @@
  */
 import { test } from "node:test";
 import assert from "node:assert";
-import { UniffiNativeModule } from "../src/module.js";
+import { UniffiNativeModule, verifyApiChecks } from "../src/module.js";
 
 /**
  * Pre-assembled bytes for a minimal host wasm module that exports the
@@ -117,3 +117,53 @@ test("rustbuffer_alloc(0) returns an empty view without allocating", async () =>
   // with a 0-length range, which would be a no-op anyway but the ptr may be 0).
   nm.rustbuffer_free(empty);
 });
+
+// Stand-ins for a cdylib's contract version and checksum exports.
+const CHECKED_EXPORTS = {
+  ffi_test_uniffi_contract_version: () => 29,
+  uniffi_test_checksum_func_add: () => 4021,
+} as unknown as WebAssembly.Exports;
+
+test("verifyApiChecks accepts matching exports", () => {
+  verifyApiChecks(CHECKED_EXPORTS, {
+    contractVersionSymbol: "ffi_test_uniffi_contract_version",
+    contractVersion: 29,
+    checksums: { uniffi_test_checksum_func_add: 4021 },
+  });
+});
+
+test("verifyApiChecks names a mismatched contract version", () => {
+  assert.throws(
+    () =>
+      verifyApiChecks(CHECKED_EXPORTS, {
+        contractVersionSymbol: "ffi_test_uniffi_contract_version",
+        contractVersion: 30,
+        checksums: {},
+      }),
+    /contract version mismatch: the bindings expect 30, but the library has 29/,
+  );
+});
+
+test("verifyApiChecks names a mismatched checksum and both values", () => {
+  assert.throws(
+    () =>
+      verifyApiChecks(CHECKED_EXPORTS, {
+        contractVersionSymbol: "ffi_test_uniffi_contract_version",
+        contractVersion: 29,
+        checksums: { uniffi_test_checksum_func_add: 1234 },
+      }),
+    /mismatch for uniffi_test_checksum_func_add: the bindings expect 1234, but the library has 4021/,
+  );
+});
+
+test("verifyApiChecks reports a missing checksum export", () => {
+  assert.throws(
+    () =>
+      verifyApiChecks(CHECKED_EXPORTS, {
+        contractVersionSymbol: "ffi_test_uniffi_contract_version",
+        contractVersion: 29,
+        checksums: { uniffi_test_checksum_func_gone: 1 },
+      }),
+    /wasm export "uniffi_test_checksum_func_gone" not found/,
+  );
+});
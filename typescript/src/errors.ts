@@ -134,6 +134,13 @@ export const UniffiInternalError = (() => {
       );
     }
   }
+  class ReloadedObject extends Error {
+    constructor() {
+      super(
+        "The object was made before the native library was reloaded, and can no longer be used",
+      );
+    }
+  }
   class ContractVersionMismatch extends Error {
     constructor(rustVersion: any, bindingsVersion: any) {
       super(
@@ -171,6 +178,7 @@ export const UniffiInternalError = (() => {
     UnexpectedRustCallStatusCode,
     UnexpectedRustCallError,
     UnexpectedStaleHandle,
+    ReloadedObject,
     RustPanic,
     Unimplemented,
   };
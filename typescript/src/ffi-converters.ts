@@ -329,6 +329,20 @@ export const FfiConverterUint8Array = (() => {
     allocationSize(value: Uint8Array): number {
       return 4 + value.byteLength;
     }
+    // Lift without copying: the result aliases `view`, whose memory the
+    // runtime keeps alive until both are garbage collected. For returns
+    // declared `externalReturn`, which must not be passed to
+    // `rustbuffer_free`.
+    liftView(view: Uint8Array): Uint8Array {
+      if (view.byteLength < 4) {
+        throw new UniffiInternalError.BufferOverflow();
+      }
+      const length = new DataView(view.buffer, view.byteOffset, 4).getInt32(0);
+      if (length < 0 || 4 + length > view.byteLength) {
+        throw new UniffiInternalError.BufferOverflow();
+      }
+      return view.subarray(4, 4 + length);
+    }
   }
   return new FFIConverter();
 })();
@@ -15,9 +15,12 @@ export * from "./errors.ts";
 export * from "./ffi-converters.ts";
 export * from "./ffi-types.ts";
 export * from "./handle-map.ts";
+export * from "./json.ts";
 export * from "./objects.ts";
 export * from "./records.ts";
 export * from "./result.ts";
 export * from "./rust-call.ts";
 export * from "./symbols.ts";
+export * from "./tracing.ts";
 export * from "./type-utils.ts";
+export * from "./validators.ts";
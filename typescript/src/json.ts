@@ -0,0 +1,514 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+// JSON encodings for generated types, so that values can be persisted or sent
+// over the network. `JSON.stringify` throws on a `bigint`, turns a `Date`
+// into a string it won't turn back, and an `ArrayBuffer` or `Map` into `{}`.
+//
+// Generated code builds one codec per record and enum when `generateJson` is
+// set, out of the combinators in `uniffiJson`. The canonical encoding is:
+//
+// - `i64` and `u64`: a decimal string, e.g. `"18446744073709551615"`.
+// - `f32` and `f64`: a number, or one of `"NaN"`, `"Infinity"`, `"-Infinity"`.
+// - timestamps: an ISO 8601 string, e.g. `"2024-01-31T12:00:00.000Z"`.
+// - durations: a number of milliseconds.
+// - byte arrays: a base64 string, with padding.
+// - optionals: `null` for `undefined`. An optional optional has no
+//   encoding: `Some(None)` and `None` are both `undefined` by then.
+// - maps with string keys: an object; other maps: an array of `[key, value]`.
+// - records: an object, keyed by the Typescript field names.
+// - flat enums: the variant name, e.g. `"Red"`.
+// - enums with fields: `{ "tag": "Variant", "inner": { ... } }`, or with an
+//   array for `inner` for tuple variants. `inner` is left out for variants
+//   without fields.
+// - flat errors: `{ "tag": "Variant", "message": "..." }`.
+//
+// Objects and callback interfaces have no encoding. Decoding checks values as
+// `uniffiValidators` does, so an integer out of its type's range is an error.
+
+import { uniffiValidators, type UniffiValidator } from "./validators.ts";
+
+/** A value that `JSON.stringify` can encode, and `JSON.parse` return. */
+export type UniffiJsonValue =
+  | null
+  | boolean
+  | number
+  | string
+  | UniffiJsonValue[]
+  | { [key: string]: UniffiJsonValue };
+
+/** Thrown by `fromJSON` when the JSON is not an encoding of the type. */
+export class UniffiJsonError extends Error {
+  constructor(
+    readonly path: string,
+    readonly expected: string,
+  ) {
+    super(`${path}: expected ${expected}`);
+  }
+
+  static instanceOf(err: any): err is UniffiJsonError {
+    return err instanceof UniffiJsonError;
+  }
+}
+
+export interface UniffiJsonCodec<T> {
+  toJSON(value: T): UniffiJsonValue;
+  fromJSON(json: unknown): T;
+}
+
+/**
+ * A codec, with the path of the value being decoded threaded through for
+ * error messages.
+ */
+type Codec<T> = UniffiJsonCodec<T> & {
+  decode(json: unknown, path: string): T;
+};
+
+function codec<T>(
+  description: string,
+  toJSON: (value: T) => UniffiJsonValue,
+  decode: (json: unknown, path: string) => T,
+): Codec<T> {
+  return {
+    toJSON,
+    decode,
+    fromJSON: (json: unknown) => decode(json, description),
+  };
+}
+
+function decodeWith<T>(c: UniffiJsonCodec<T>, json: unknown, path: string): T {
+  const inner = c as Codec<T>;
+  return inner.decode ? inner.decode(json, path) : c.fromJSON(json);
+}
+
+function fail(path: string, expected: string): never {
+  throw new UniffiJsonError(path, expected);
+}
+
+function hasOwn(obj: object, key: string): boolean {
+  return Object.prototype.hasOwnProperty.call(obj, key);
+}
+
+function isObject(json: unknown): json is Record<string, unknown> {
+  return typeof json === "object" && json !== null && !Array.isArray(json);
+}
+
+function bigInteger(validator: UniffiValidator<bigint>) {
+  const { description } = validator;
+  return codec<bigint>(
+    description,
+    (value) => value.toString(),
+    (json, path) => {
+      let value: bigint | undefined;
+      if (typeof json === "string" && /^-?\d+$/.test(json)) {
+        value = BigInt(json);
+      } else if (Number.isSafeInteger(json)) {
+        value = BigInt(json as number);
+      }
+      if (value === undefined || !validator.is(value)) {
+        fail(path, `${description} as a decimal string`);
+      }
+      return value;
+    },
+  );
+}
+
+/** A value JSON encodes as itself, decoded if `validator` accepts it. */
+function checked<T>(
+  validator: UniffiValidator<T>,
+  description: string = validator.description,
+) {
+  return codec<T>(
+    description,
+    (value) => value as UniffiJsonValue,
+    (json, path) => {
+      if (!validator.is(json)) {
+        fail(path, description);
+      }
+      return json;
+    },
+  );
+}
+
+function float(description: string) {
+  return codec<number>(
+    description,
+    (value) => (Number.isFinite(value) ? value : String(value)),
+    (json, path) => {
+      if (typeof json === "number") {
+        return json;
+      }
+      switch (json) {
+        case "NaN":
+          return NaN;
+        case "Infinity":
+          return Infinity;
+        case "-Infinity":
+          return -Infinity;
+      }
+      fail(path, description);
+    },
+  );
+}
+
+const base64Chars =
+  "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+function toBase64(bytes: Uint8Array): string {
+  let s = "";
+  for (let i = 0; i < bytes.length; i += 3) {
+    const n =
+      (bytes[i] << 16) | ((bytes[i + 1] ?? 0) << 8) | (bytes[i + 2] ?? 0);
+    s += base64Chars[(n >> 18) & 63] + base64Chars[(n >> 12) & 63];
+    s += i + 1 < bytes.length ? base64Chars[(n >> 6) & 63] : "=";
+    s += i + 2 < bytes.length ? base64Chars[n & 63] : "=";
+  }
+  return s;
+}
+
+function fromBase64(s: string, path: string): Uint8Array {
+  const body = s.replace(/=+$/, "");
+  if (s.length % 4 !== 0 || s.length - body.length > 2) {
+    fail(path, "a base64 string");
+  }
+  const bytes = new Uint8Array((body.length * 3) >> 2);
+  let n = 0;
+  let bits = 0;
+  let j = 0;
+  for (let i = 0; i < body.length; i++) {
+    const c = base64Chars.indexOf(body[i]);
+    if (c < 0) {
+      fail(path, "a base64 string");
+    }
+    n = (n << 6) | c;
+    bits += 6;
+    if (bits >= 8) {
+      bits -= 8;
+      bytes[j++] = (n >> bits) & 0xff;
+    }
+  }
+  return bytes;
+}
+
+// `UniffiError` prefixes its message with the type and variant name; strip
+// that, so the message doesn't grow each time it goes through JSON.
+function errorMessage(name: string, error: Error & { tag: string }): string {
+  const prefix = `${name}.${error.tag}`;
+  if (error.message === prefix) {
+    return "";
+  }
+  if (error.message.startsWith(`${prefix}: `)) {
+    return error.message.slice(prefix.length + 2);
+  }
+  return error.message;
+}
+
+function unsupported<T>(name: string): UniffiJsonCodec<T> {
+  return codec<T>(
+    name,
+    () => {
+      throw new Error(`${name} has no JSON encoding`);
+    },
+    (_json, path) => fail(path, `nothing: ${name} has no JSON encoding`),
+  );
+}
+
+/** The codecs `uniffiJson.optional` made, to turn down optional optionals. */
+const optionals = new WeakSet<UniffiJsonCodec<any>>();
+
+/** The fields of a record, or of an enum variant, by name. */
+export type UniffiJsonFields = Record<string, UniffiJsonCodec<any>>;
+
+function fieldsToJSON(value: any, fields: UniffiJsonFields) {
+  const json: { [key: string]: UniffiJsonValue } = {};
+  for (const [name, field] of Object.entries(fields)) {
+    json[name] = field.toJSON(value[name]);
+  }
+  return json;
+}
+
+function fieldsFromJSON(json: unknown, path: string, fields: UniffiJsonFields) {
+  if (!isObject(json)) {
+    fail(path, "an object");
+  }
+  const value: Record<string, any> = {};
+  for (const [name, field] of Object.entries(fields)) {
+    const v = decodeWith(field, json[name], `${path}.${name}`);
+    if (v !== undefined) {
+      value[name] = v;
+    }
+  }
+  return value;
+}
+
+/** How to encode, and recreate, one variant of an enum with fields. */
+export type UniffiJsonVariant<T> = {
+  toJSON(value: any): UniffiJsonValue | undefined;
+  decode(inner: unknown, path: string): T;
+};
+
+export const uniffiJson = Object.freeze({
+  bool: checked(uniffiValidators.bool),
+  string: checked(uniffiValidators.string),
+  int8: checked(uniffiValidators.int8),
+  int16: checked(uniffiValidators.int16),
+  int32: checked(uniffiValidators.int32),
+  uint8: checked(uniffiValidators.uint8),
+  uint16: checked(uniffiValidators.uint16),
+  uint32: checked(uniffiValidators.uint32),
+  int64: bigInteger(uniffiValidators.int64),
+  uint64: bigInteger(uniffiValidators.uint64),
+  float32: float("f32"),
+  float64: float("f64"),
+  timestamp: codec<Date>(
+    "timestamp",
+    (value) => value.toISOString(),
+    (json, path) => {
+      const date = typeof json === "string" ? new Date(json) : undefined;
+      if (date === undefined || isNaN(date.getTime())) {
+        fail(path, "an ISO 8601 timestamp");
+      }
+      return date;
+    },
+  ),
+  duration: checked(uniffiValidators.duration, "duration in milliseconds"),
+  arrayBuffer: codec<ArrayBuffer>(
+    "ArrayBuffer",
+    (value) => toBase64(new Uint8Array(value)),
+    (json, path) => {
+      if (typeof json !== "string") {
+        fail(path, "a base64 string");
+      }
+      return fromBase64(json, path).buffer as ArrayBuffer;
+    },
+  ),
+  uint8Array: codec<Uint8Array>(
+    "Uint8Array",
+    (value) => toBase64(value),
+    (json, path) => {
+      if (typeof json !== "string") {
+        fail(path, "a base64 string");
+      }
+      return fromBase64(json, path);
+    },
+  ),
+  /** Objects and callback interfaces, which cannot be encoded. */
+  unsupported,
+  optional<T>(inner: UniffiJsonCodec<T>): UniffiJsonCodec<T | undefined> {
+    if (optionals.has(inner)) {
+      return unsupported("Option<Option<T>>");
+    }
+    const optional = codec<T | undefined>(
+      "optional",
+      (value) => (value === undefined ? null : inner.toJSON(value)),
+      (json, path) =>
+        json === null || json === undefined
+          ? undefined
+          : decodeWith(inner, json, path),
+    );
+    optionals.add(optional);
+    return optional;
+  },
+  sequence<T>(inner: UniffiJsonCodec<T>): UniffiJsonCodec<Array<T>> {
+    return codec<Array<T>>(
+      "Array",
+      (value) => value.map((item) => inner.toJSON(item)),
+      (json, path) => {
+        if (!Array.isArray(json)) {
+          fail(path, "an array");
+        }
+        return json.map((item, i) => decodeWith(inner, item, `${path}[${i}]`));
+      },
+    );
+  },
+  /** A map with string keys, as an object. */
+  stringMap<V>(inner: UniffiJsonCodec<V>): UniffiJsonCodec<Map<string, V>> {
+    return codec<Map<string, V>>(
+      "Map",
+      (value) => {
+        const json: { [key: string]: UniffiJsonValue } = {};
+        for (const [k, v] of value) {
+          json[k] = inner.toJSON(v);
+        }
+        return json;
+      },
+      (json, path) => {
+        if (!isObject(json)) {
+          fail(path, "an object");
+        }
+        const value = new Map<string, V>();
+        for (const [k, v] of Object.entries(json)) {
+          value.set(k, decodeWith(inner, v, `${path}.${k}`));
+        }
+        return value;
+      },
+    );
+  },
+  /** Any other map, as an array of `[key, value]` pairs. */
+  map<K, V>(
+    key: UniffiJsonCodec<K>,
+    inner: UniffiJsonCodec<V>,
+  ): UniffiJsonCodec<Map<K, V>> {
+    return codec<Map<K, V>>(
+      "Map",
+      (value) =>
+        Array.from(value, ([k, v]) => [key.toJSON(k), inner.toJSON(v)]),
+      (json, path) => {
+        if (!Array.isArray(json)) {
+          fail(path, "an array of [key, value] pairs");
+        }
+        const value = new Map<K, V>();
+        json.forEach((entry, i) => {
+          if (!Array.isArray(entry) || entry.length !== 2) {
+            fail(`${path}[${i}]`, "a [key, value] pair");
+          }
+          value.set(
+            decodeWith(key, entry[0], `${path}[${i}][0]`),
+            decodeWith(inner, entry[1], `${path}[${i}][1]`),
+          );
+        });
+        return value;
+      },
+    );
+  },
+  /** Refer to a codec declared later in the module. */
+  lazy<T>(get: () => UniffiJsonCodec<T>): UniffiJsonCodec<T> {
+    return codec<T>(
+      "lazy",
+      (value) => get().toJSON(value),
+      (json, path) => decodeWith(get(), json, path),
+    );
+  },
+  /**
+   * A custom type: `toJSON` and `fromJSON` convert to and from the JSON
+   * directly.
+   */
+  custom<T>(
+    toJSON: (value: T) => UniffiJsonValue,
+    fromJSON: (json: any) => T,
+  ): UniffiJsonCodec<T> {
+    return { toJSON, fromJSON };
+  },
+  /** A custom type, encoded as the builtin type it converts to. */
+  convert<T, U>(
+    builtin: UniffiJsonCodec<U>,
+    lower: (value: T) => U,
+    lift: (intermediate: U) => T,
+  ): UniffiJsonCodec<T> {
+    return codec<T>(
+      "custom",
+      (value) => builtin.toJSON(lower(value)),
+      (json, path) => lift(decodeWith(builtin, json, path)),
+    );
+  },
+  record<T>(name: string, fields: UniffiJsonFields): UniffiJsonCodec<T> {
+    return codec<T>(
+      name,
+      (value) => fieldsToJSON(value, fields),
+      (json, path) => fieldsFromJSON(json, path, fields) as T,
+    );
+  },
+  /** A flat enum: the variant names, and their values. */
+  flatEnum<T>(name: string, variants: Record<string, T>): UniffiJsonCodec<T> {
+    const names = new Map<T, string>();
+    for (const [variant, value] of Object.entries(variants)) {
+      names.set(value, variant);
+    }
+    return codec<T>(
+      name,
+      (value) => names.get(value) ?? fail(name, "a variant"),
+      (json, path) => {
+        if (typeof json !== "string" || !hasOwn(variants, json)) {
+          fail(path, `a variant of ${name}`);
+        }
+        return variants[json];
+      },
+    );
+  },
+  /** A flat error: the variant names, and how to make each from a message. */
+  flatError<T extends Error & { tag: string }>(
+    name: string,
+    variants: Record<string, (message: string) => T>,
+  ): UniffiJsonCodec<T> {
+    return codec<T>(
+      name,
+      (value) => ({ tag: value.tag, message: errorMessage(name, value) }),
+      (json, path) => {
+        if (!isObject(json)) {
+          fail(path, "an object");
+        }
+        const tag = json.tag;
+        if (typeof tag !== "string" || !hasOwn(variants, tag)) {
+          fail(`${path}.tag`, `a variant of ${name}`);
+        }
+        if (typeof json.message !== "string") {
+          fail(`${path}.message`, "string");
+        }
+        return variants[tag](json.message);
+      },
+    );
+  },
+  /** A variant with named fields. */
+  variant<T>(
+    fields: UniffiJsonFields,
+    create: (inner: any) => T,
+  ): UniffiJsonVariant<T> {
+    if (Object.keys(fields).length === 0) {
+      return {
+        toJSON: () => undefined,
+        decode: () => create({}),
+      };
+    }
+    return {
+      toJSON: (value) => fieldsToJSON(value.inner, fields),
+      decode: (inner, path) => create(fieldsFromJSON(inner, path, fields)),
+    };
+  },
+  /** A variant with unnamed fields. */
+  tupleVariant<T>(
+    fields: ReadonlyArray<UniffiJsonCodec<any>>,
+    create: (inner: any[]) => T,
+  ): UniffiJsonVariant<T> {
+    return {
+      toJSON: (value) => fields.map((field, i) => field.toJSON(value.inner[i])),
+      decode: (inner, path) => {
+        if (!Array.isArray(inner) || inner.length !== fields.length) {
+          fail(path, `an array of ${fields.length}`);
+        }
+        return create(
+          fields.map((field, i) =>
+            decodeWith(field, inner[i], `${path}[${i}]`),
+          ),
+        );
+      },
+    };
+  },
+  /** An enum with fields, or an error with fields. */
+  taggedEnum<T extends { tag: string }>(
+    name: string,
+    variants: Record<string, UniffiJsonVariant<T>>,
+  ): UniffiJsonCodec<T> {
+    return codec<T>(
+      name,
+      (value) => {
+        const inner = variants[value.tag].toJSON(value);
+        return inner === undefined
+          ? { tag: value.tag }
+          : { tag: value.tag, inner };
+      },
+      (json, path) => {
+        if (!isObject(json)) {
+          fail(path, "an object");
+        }
+        const tag = json.tag;
+        if (typeof tag !== "string" || !hasOwn(variants, tag)) {
+          fail(`${path}.tag`, `a variant of ${name}`);
+        }
+        return variants[tag].decode(json.inner, `${path}.inner`);
+      },
+    );
+  },
+});
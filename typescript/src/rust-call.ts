@@ -37,6 +37,18 @@ export class UniffiRustCaller<Status extends UniffiRustCallStatus> {
     return this.makeRustCall(caller, liftString, errorHandler);
   }
 
+  /**
+   * Throws what `callStatus` reports, as `rustCall` does for the status it
+   * makes. For statuses made ahead of the call, as a batch of calls does.
+   */
+  checkCallStatus(
+    callStatus: Status,
+    liftString: StringLifter = emptyStringLifter,
+    errorHandler?: UniffiErrorHandler,
+  ): void {
+    uniffiCheckCallStatus(callStatus, liftString, errorHandler);
+  }
+
   createCallStatus(): Status {
     return this.statusConstructor();
   }
@@ -0,0 +1,162 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+// Tracing of calls into Rust. When `tracerImport` is set, the generated code
+// wraps each function, method and constructor in `uniffiTraceCall` (or
+// `uniffiTraceCallAsync`), which reports to the imported tracer: e.g. to make
+// OpenTelemetry spans, or React Native performance marks.
+//
+// Without `tracerImport`, none of this is referenced by the generated code.
+
+/** A call into Rust, as reported to a `UniffiTracer`. */
+export type UniffiTracedCall = {
+  /** The namespace of the crate, e.g. `arithmetic`. */
+  readonly module: string;
+  /** The function, or `Type.method`, as named in Typescript. */
+  readonly name: string;
+  /** A short summary of the arguments, e.g. `(42, "abc", Uint8Array(16))`. */
+  readonly args: string;
+  /** If the call returns a `Promise`. */
+  readonly isAsync: boolean;
+};
+
+export type UniffiTracedOutcome = {
+  /** Milliseconds from the call to its return, or its promise settling. */
+  readonly durationMs: number;
+  /** `error` is what was thrown, if `ok` is `false`. */
+  readonly ok: boolean;
+  readonly error?: unknown;
+};
+
+/**
+ * The default export of the `tracerImport` module. `callStarted` may return a
+ * value, e.g. a span, which is handed back to `callEnded`.
+ */
+export interface UniffiTracer<Span = unknown> {
+  callStarted?(call: UniffiTracedCall): Span;
+  callEnded(
+    call: UniffiTracedCall,
+    outcome: UniffiTracedOutcome,
+    span: Span | undefined,
+  ): void;
+}
+
+// @ts-ignore -- performance is not in every Javascript runtime.
+const perf: { now(): number } | undefined = globalThis.performance;
+const now: () => number =
+  typeof perf?.now === "function" ? () => perf!.now() : () => Date.now();
+
+const maxStringLength = 32;
+const maxArgsLength = 120;
+
+function summarize(value: unknown): string {
+  switch (typeof value) {
+    case "string":
+      return JSON.stringify(
+        value.length > maxStringLength
+          ? `${value.slice(0, maxStringLength)}…`
+          : value,
+      );
+    case "bigint":
+      return `${value}n`;
+    case "function":
+      return "function";
+    case "object":
+      if (value === null) {
+        return "null";
+      }
+      if (Array.isArray(value)) {
+        return `Array(${value.length})`;
+      }
+      if (value instanceof ArrayBuffer) {
+        return `ArrayBuffer(${value.byteLength})`;
+      }
+      if (ArrayBuffer.isView(value)) {
+        return `${value.constructor.name}(${value.byteLength})`;
+      }
+      if (value instanceof Map) {
+        return `Map(${value.size})`;
+      }
+      if (value instanceof Date) {
+        return value.toISOString();
+      }
+      return value.constructor?.name ?? "object";
+    default:
+      return String(value);
+  }
+}
+
+/** A short, bounded, summary of the arguments of a call. */
+export function uniffiSummarizeArgs(args: ReadonlyArray<unknown>): string {
+  const s = `(${args.map(summarize).join(", ")})`;
+  return s.length > maxArgsLength ? `${s.slice(0, maxArgsLength - 2)}…)` : s;
+}
+
+function traced(
+  module: string,
+  name: string,
+  args: ReadonlyArray<unknown>,
+  isAsync: boolean,
+): UniffiTracedCall {
+  return { module, name, args: uniffiSummarizeArgs(args), isAsync };
+}
+
+// A tracer that throws must not change the outcome of the call.
+function safely(fn: () => void) {
+  try {
+    fn();
+  } catch (_e) {}
+}
+
+export function uniffiTraceCall<T>(
+  tracer: UniffiTracer,
+  module: string,
+  name: string,
+  args: ReadonlyArray<unknown>,
+  call: () => T,
+): T {
+  const info = traced(module, name, args, false);
+  let span: unknown;
+  safely(() => (span = tracer.callStarted?.(info)));
+  const start = now();
+  try {
+    const result = call();
+    const durationMs = now() - start;
+    safely(() => tracer.callEnded(info, { durationMs, ok: true }, span));
+    return result;
+  } catch (error: unknown) {
+    const durationMs = now() - start;
+    safely(() =>
+      tracer.callEnded(info, { durationMs, ok: false, error }, span),
+    );
+    throw error;
+  }
+}
+
+export async function uniffiTraceCallAsync<T>(
+  tracer: UniffiTracer,
+  module: string,
+  name: string,
+  args: ReadonlyArray<unknown>,
+  call: () => Promise<T>,
+): Promise<T> {
+  const info = traced(module, name, args, true);
+  let span: unknown;
+  safely(() => (span = tracer.callStarted?.(info)));
+  const start = now();
+  try {
+    const result = await call();
+    const durationMs = now() - start;
+    safely(() => tracer.callEnded(info, { durationMs, ok: true }, span));
+    return result;
+  } catch (error: unknown) {
+    const durationMs = now() - start;
+    safely(() =>
+      tracer.callEnded(info, { durationMs, ok: false, error }, span),
+    );
+    throw error;
+  }
+}
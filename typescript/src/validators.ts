@@ -0,0 +1,282 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+
+// Runtime validators for values that did not come from Rust: JSON off the
+// network, or out of storage. Passing a malformed value to Rust otherwise
+// fails deep inside an FfiConverter, with an error that says nothing about
+// which field was wrong.
+//
+// Generated code builds one validator per record and enum when
+// `generateValidators` is set, out of the combinators in `uniffiValidators`.
+// Each has the `parse`/`safeParse` shape of a zod schema, so it can stand in
+// for one.
+
+/**
+ * Thrown by a validator: `path` is where in the value the problem is, e.g.
+ * `Person.address.postcode`.
+ */
+export class UniffiValidationError extends Error {
+  constructor(
+    readonly path: string,
+    readonly expected: string,
+    readonly received: string,
+  ) {
+    super(`${path}: expected ${expected}, received ${received}`);
+  }
+
+  static instanceOf(err: any): err is UniffiValidationError {
+    return err instanceof UniffiValidationError;
+  }
+}
+
+export type UniffiSafeParseResult<T> =
+  | { success: true; data: T }
+  | { success: false; error: UniffiValidationError };
+
+export interface UniffiValidator<T> {
+  /** What the validator expects, for error messages. */
+  readonly description: string;
+  /** Throw a `UniffiValidationError` if `value` is not a `T`. */
+  check(value: unknown, path: string): void;
+  /** Return `value` as a `T`, or throw a `UniffiValidationError`. */
+  parse(value: unknown): T;
+  safeParse(value: unknown): UniffiSafeParseResult<T>;
+  is(value: unknown): value is T;
+}
+
+function describe(value: unknown): string {
+  if (value === null) {
+    return "null";
+  }
+  if (Array.isArray(value)) {
+    return "Array";
+  }
+  if (typeof value === "object") {
+    return (value as object).constructor?.name ?? "object";
+  }
+  if (typeof value === "string") {
+    return JSON.stringify(value);
+  }
+  if (typeof value === "bigint") {
+    return `${value}n`;
+  }
+  return String(value);
+}
+
+function validator<T>(
+  description: string,
+  check: (value: unknown, path: string) => void,
+): UniffiValidator<T> {
+  const v: UniffiValidator<T> = {
+    description,
+    check,
+    parse(value: unknown): T {
+      check(value, description);
+      return value as T;
+    },
+    safeParse(value: unknown): UniffiSafeParseResult<T> {
+      try {
+        return { success: true, data: v.parse(value) };
+      } catch (e: unknown) {
+        if (e instanceof UniffiValidationError) {
+          return { success: false, error: e };
+        }
+        throw e;
+      }
+    },
+    is(value: unknown): value is T {
+      return v.safeParse(value).success;
+    },
+  };
+  return v;
+}
+
+function fail(path: string, expected: string, value: unknown): never {
+  throw new UniffiValidationError(path, expected, describe(value));
+}
+
+function integer(description: string, min: number, max: number) {
+  return validator<number>(description, (value, path) => {
+    if (
+      !Number.isInteger(value) ||
+      (value as number) < min ||
+      (value as number) > max
+    ) {
+      fail(path, description, value);
+    }
+  });
+}
+
+function bigInteger(description: string, min: bigint, max: bigint) {
+  return validator<bigint>(description, (value, path) => {
+    if (typeof value !== "bigint" || value < min || value > max) {
+      fail(path, description, value);
+    }
+  });
+}
+
+function number(description: string) {
+  return validator<number>(description, (value, path) => {
+    if (typeof value !== "number") {
+      fail(path, description, value);
+    }
+  });
+}
+
+/** The fields of a record, or of an enum variant, by name. */
+export type UniffiFieldValidators = Record<string, UniffiValidator<any>>;
+
+function checkFields(value: any, path: string, fields: UniffiFieldValidators) {
+  for (const [name, field] of Object.entries(fields)) {
+    field.check(value[name], `${path}.${name}`);
+  }
+}
+
+export const uniffiValidators = Object.freeze({
+  any: validator<any>("any", () => {}),
+  bool: validator<boolean>("boolean", (value, path) => {
+    if (typeof value !== "boolean") {
+      fail(path, "boolean", value);
+    }
+  }),
+  string: validator<string>("string", (value, path) => {
+    if (typeof value !== "string") {
+      fail(path, "string", value);
+    }
+  }),
+  int8: integer("i8", -0x80, 0x7f),
+  int16: integer("i16", -0x8000, 0x7fff),
+  int32: integer("i32", -0x80000000, 0x7fffffff),
+  uint8: integer("u8", 0, 0xff),
+  uint16: integer("u16", 0, 0xffff),
+  uint32: integer("u32", 0, 0xffffffff),
+  int64: bigInteger(
+    "i64",
+    -(BigInt(1) << BigInt(63)),
+    (BigInt(1) << BigInt(63)) - BigInt(1),
+  ),
+  uint64: bigInteger("u64", BigInt(0), (BigInt(1) << BigInt(64)) - BigInt(1)),
+  float32: number("f32"),
+  float64: number("f64"),
+  timestamp: validator<Date>("Date", (value, path) => {
+    if (!(value instanceof Date) || isNaN(value.getTime())) {
+      fail(path, "Date", value);
+    }
+  }),
+  /** Durations are milliseconds, and cannot be negative. */
+  duration: validator<number>("duration", (value, path) => {
+    if (typeof value !== "number" || !(value >= 0)) {
+      fail(path, "duration", value);
+    }
+  }),
+  arrayBuffer: validator<ArrayBuffer>("ArrayBuffer", (value, path) => {
+    if (!(value instanceof ArrayBuffer)) {
+      fail(path, "ArrayBuffer", value);
+    }
+  }),
+  uint8Array: validator<Uint8Array>("Uint8Array", (value, path) => {
+    if (!(value instanceof Uint8Array)) {
+      fail(path, "Uint8Array", value);
+    }
+  }),
+  /** An object or callback interface: anything but a primitive. */
+  object<T>(name: string): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => {
+      if (typeof value !== "object" || value === null) {
+        fail(path, name, value);
+      }
+    });
+  },
+  /** Errors, and other types with a generated `instanceOf`. */
+  instance<T>(
+    name: string,
+    instanceOf: (value: any) => boolean,
+  ): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => {
+      if (typeof value !== "object" || value === null || !instanceOf(value)) {
+        fail(path, name, value);
+      }
+    });
+  },
+  optional<T>(inner: UniffiValidator<T>): UniffiValidator<T | undefined> {
+    const description = `${inner.description} | undefined`;
+    return validator<T | undefined>(description, (value, path) => {
+      if (value !== undefined) {
+        inner.check(value, path);
+      }
+    });
+  },
+  sequence<T>(inner: UniffiValidator<T>): UniffiValidator<Array<T>> {
+    const description = `Array<${inner.description}>`;
+    return validator<Array<T>>(description, (value, path) => {
+      if (!Array.isArray(value)) {
+        fail(path, description, value);
+      }
+      value.forEach((item, i) => inner.check(item, `${path}[${i}]`));
+    });
+  },
+  map<K, V>(
+    key: UniffiValidator<K>,
+    inner: UniffiValidator<V>,
+  ): UniffiValidator<Map<K, V>> {
+    const description = `Map<${key.description}, ${inner.description}>`;
+    return validator<Map<K, V>>(description, (value, path) => {
+      if (!(value instanceof Map)) {
+        fail(path, description, value);
+      }
+      for (const [k, v] of value) {
+        key.check(k, `${path}.keys()`);
+        inner.check(v, `${path}.get(${describe(k)})`);
+      }
+    });
+  },
+  /** Refer to a validator declared later in the module. */
+  lazy<T>(name: string, get: () => UniffiValidator<T>): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => get().check(value, path));
+  },
+  record<T>(name: string, fields: UniffiFieldValidators): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => {
+      if (typeof value !== "object" || value === null || Array.isArray(value)) {
+        fail(path, name, value);
+      }
+      checkFields(value, path, fields);
+    });
+  },
+  /** A flat enum: one of the listed values. */
+  oneOf<T>(name: string, values: ReadonlyArray<T>): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => {
+      if (!values.includes(value as T)) {
+        fail(path, name, value);
+      }
+    });
+  },
+  /**
+   * An enum with fields: an object with one of the listed `tag`s, whose
+   * `inner` has that variant's fields. Tuple variants are keyed by index.
+   */
+  taggedEnum<T>(
+    name: string,
+    variants: Record<string, UniffiFieldValidators>,
+  ): UniffiValidator<T> {
+    return validator<T>(name, (value, path) => {
+      if (typeof value !== "object" || value === null) {
+        fail(path, name, value);
+      }
+      const tag = (value as any).tag;
+      const fields = typeof tag === "string" ? variants[tag] : undefined;
+      if (fields === undefined) {
+        fail(`${path}.tag`, `a variant of ${name}`, tag);
+      }
+      if (Object.keys(fields).length > 0) {
+        const inner = (value as any).inner;
+        if (typeof inner !== "object" || inner === null) {
+          fail(`${path}.inner`, `${name}.${tag}`, inner);
+        }
+        checkFields(inner, `${path}.inner`, fields);
+      }
+    });
+  },
+});
@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import {
+  type UniffiTracedCall,
+  type UniffiTracedOutcome,
+  type UniffiTracer,
+} from "@ubjs/core";
+
+/**
+ * This is used in both the generated code and the test.
+ * To get it into the generated typescript, it should be the `tracerImport`
+ * in the {@link ../uniffi.toml | uniffi.toml file}.
+ */
+
+export type TracedCall = UniffiTracedCall & UniffiTracedOutcome;
+
+export const tracedCalls: Array<TracedCall> = [];
+
+const tracer: UniffiTracer = {
+  callEnded(call: UniffiTracedCall, outcome: UniffiTracedOutcome): void {
+    tracedCalls.push({ ...call, ...outcome });
+  },
+};
+
+export default tracer;
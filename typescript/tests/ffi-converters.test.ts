@@ -17,6 +17,7 @@ import {
   FfiConverterUInt8,
   FfiConverterUint8Array,
 } from "../src/ffi-converters";
+import { UniffiInternalError } from "../src/errors";
 
 const testAlloc = (n: number) => new Uint8Array(n);
 import { Asserts, test } from "../testing/asserts";
@@ -123,6 +124,21 @@ test("AbstractFfiConverterByteArray.lower uses the supplied allocator", (t) => {
   t.assertEqual(Array.from(lifted), [1, 2, 3, 4]);
 });
 
+test("FfiConverterUint8Array.liftView aliases the buffer", (t) => {
+  const view = FfiConverterUint8Array.lower(
+    new Uint8Array([5, 6, 7]),
+    testAlloc,
+  );
+  const lifted = FfiConverterUint8Array.liftView(view);
+  t.assertEqual(Array.from(lifted), [5, 6, 7]);
+  t.assertEqual(lifted.buffer, view.buffer);
+  t.assertEqual(lifted.byteOffset, view.byteOffset + 4);
+  t.assertThrows(
+    (e) => e instanceof UniffiInternalError.BufferOverflow,
+    () => FfiConverterUint8Array.liftView(view.subarray(0, 5)),
+  );
+});
+
 test("Array of optional shorts", (t) => {
   const converter = new FfiConverterArray(
     new FfiConverterOptional(FfiConverterUInt16),
@@ -0,0 +1,150 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "../testing/asserts";
+import "../testing/polyfills";
+import { UniffiJsonError, uniffiJson as j } from "../src/json";
+
+test("Primitives have a canonical encoding", (t) => {
+  const max = BigInt("18446744073709551615");
+  t.assertEqual(j.uint64.toJSON(max), "18446744073709551615");
+  t.assertEqual(j.uint64.fromJSON("18446744073709551615"), max);
+  t.assertEqual(j.int64.fromJSON(-5), BigInt(-5));
+  t.assertEqual(j.float64.toJSON(Infinity), "Infinity");
+  t.assertTrue(isNaN(j.float64.fromJSON("NaN")));
+
+  const date = new Date(Date.UTC(2024, 0, 31, 12));
+  t.assertEqual(j.timestamp.toJSON(date), "2024-01-31T12:00:00.000Z");
+  const decoded = j.timestamp.fromJSON("2024-01-31T12:00:00.000Z");
+  t.assertEqual(decoded.getTime(), date.getTime());
+  t.assertEqual(j.duration.toJSON(1500), 1500);
+});
+
+test("Integers out of their type's range are rejected", (t) => {
+  const rejects = (decode: () => unknown) =>
+    t.assertThrows(UniffiJsonError.instanceOf, decode);
+  rejects(() => j.uint64.fromJSON("-1"));
+  rejects(() => j.uint64.fromJSON(-1));
+  rejects(() => j.uint64.fromJSON("18446744073709551616"));
+  rejects(() => j.int64.fromJSON("9223372036854775808"));
+  rejects(() => j.int8.fromJSON(128));
+  rejects(() => j.int8.fromJSON(1.5));
+  rejects(() => j.uint32.fromJSON(4294967296));
+  rejects(() => j.uint32.fromJSON(-1));
+  rejects(() => j.duration.fromJSON(-1));
+  t.assertEqual(j.int8.fromJSON(-128), -128);
+  t.assertEqual(j.uint32.fromJSON(4294967295), 4294967295);
+  t.assertEqual(
+    j.int64.fromJSON("-9223372036854775808"),
+    -(BigInt(1) << BigInt(63)),
+  );
+});
+
+test("Optional optionals have no encoding", (t) => {
+  const nested = j.optional(j.optional(j.string));
+  t.assertThrows(
+    (e) => e.message === "Option<Option<T>> has no JSON encoding",
+    () => nested.toJSON("a"),
+  );
+  t.assertThrows(UniffiJsonError.instanceOf, () => nested.fromJSON(null));
+  // Optionals further in are fine.
+  const wrapped = j.optional(j.sequence(j.optional(j.string)));
+  t.assertEqual(wrapped.toJSON(["a", undefined]), ["a", null]);
+});
+
+test("Byte arrays are base64", (t) => {
+  const cases: Array<[number[], string]> = [
+    [[], ""],
+    [[0x66], "Zg=="],
+    [[0x66, 0x6f], "Zm8="],
+    [[0x66, 0x6f, 0x6f], "Zm9v"],
+    [[0xff, 0xfe, 0xfd, 0xfc], "//79/A=="],
+  ];
+  for (const [bytes, encoded] of cases) {
+    t.assertEqual(j.uint8Array.toJSON(new Uint8Array(bytes)), encoded);
+    t.assertEqual(Array.from(j.uint8Array.fromJSON(encoded)), bytes);
+    const buffer = j.arrayBuffer.fromJSON(encoded);
+    t.assertEqual(buffer.byteLength, bytes.length);
+    t.assertEqual(j.arrayBuffer.toJSON(buffer), encoded);
+  }
+  t.assertThrows(UniffiJsonError.instanceOf, () =>
+    j.uint8Array.fromJSON("Zg"),
+  );
+});
+
+test("Records, optionals and maps round-trip through JSON.stringify", (t) => {
+  type Point = { x: bigint; label?: string; tags: Map<string, number[]> };
+  const point = j.record<Point>("Point", {
+    x: j.int64,
+    label: j.optional(j.string),
+    tags: j.stringMap(j.sequence(j.uint8)),
+  });
+  const value: Point = { x: BigInt(1), tags: new Map([["a", [1, 2]]]) };
+  const text = JSON.stringify(point.toJSON(value));
+  t.assertEqual(text, '{"x":"1","label":null,"tags":{"a":[1,2]}}');
+  const decoded = point.fromJSON(JSON.parse(text));
+  t.assertEqual(decoded.x, BigInt(1));
+  t.assertEqual(decoded.label, undefined);
+  t.assertEqual(decoded.tags.get("a"), [1, 2]);
+
+  const byNumber = j.map(j.uint32, j.bool);
+  t.assertEqual(byNumber.toJSON(new Map([[3, true]])), [[3, true]]);
+  t.assertEqual(byNumber.fromJSON([[3, true]]).get(3), true);
+});
+
+test("Errors say where", (t) => {
+  const inner = j.record("Inner", { n: j.uint32 });
+  const outer = j.record("Outer", { items: j.sequence(inner) });
+  t.assertThrows(
+    (e) => UniffiJsonError.instanceOf(e) && e.path === "Outer.items[1].n",
+    () => outer.fromJSON({ items: [{ n: 1 }, { n: "2" }] }),
+  );
+});
+
+test("Enums are encoded by variant name", (t) => {
+  enum Color {
+    Red,
+    Green,
+  }
+  const color = j.flatEnum("Color", { Red: Color.Red, Green: Color.Green });
+  t.assertEqual(color.toJSON(Color.Green), "Green");
+  t.assertEqual(color.fromJSON("Red"), Color.Red);
+  t.assertThrows(
+    (e) => e.path === "Color",
+    () => color.fromJSON("Blue"),
+  );
+
+  type Shape =
+    | { tag: "Circle"; inner: { radius: number } }
+    | { tag: "Pair"; inner: [number, number] }
+    | { tag: "Empty" };
+  const shape = j.taggedEnum<Shape>("Shape", {
+    Circle: j.variant({ radius: j.float64 }, (inner) => ({
+      tag: "Circle",
+      inner,
+    })),
+    Pair: j.tupleVariant([j.int32, j.int32], (inner) => ({
+      tag: "Pair",
+      inner: [inner[0], inner[1]],
+    })),
+    Empty: j.variant({}, () => ({ tag: "Empty" })),
+  });
+  const circle: Shape = { tag: "Circle", inner: { radius: 2 } };
+  t.assertEqual(shape.toJSON(circle), { tag: "Circle", inner: { radius: 2 } });
+  t.assertEqual(
+    shape.fromJSON({ tag: "Circle", inner: { radius: 2 } }),
+    circle,
+  );
+  t.assertEqual(shape.toJSON({ tag: "Pair", inner: [1, 2] }), {
+    tag: "Pair",
+    inner: [1, 2],
+  });
+  t.assertEqual(shape.toJSON({ tag: "Empty" }), { tag: "Empty" });
+  t.assertEqual(shape.fromJSON({ tag: "Empty" }), { tag: "Empty" });
+  t.assertThrows(
+    (e) => e.path === "Shape.inner[1]",
+    () => shape.fromJSON({ tag: "Pair", inner: [1, "2"] }),
+  );
+});
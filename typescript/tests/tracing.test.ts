@@ -0,0 +1,94 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { asyncTest, test } from "../testing/asserts";
+import "../testing/polyfills";
+import {
+  type UniffiTracedCall,
+  type UniffiTracedOutcome,
+  type UniffiTracer,
+  uniffiSummarizeArgs,
+  uniffiTraceCall,
+  uniffiTraceCallAsync,
+} from "../src/tracing";
+
+type Traced = {
+  call: UniffiTracedCall;
+  outcome: UniffiTracedOutcome;
+  span: string | undefined;
+};
+
+function recorder(): { tracer: UniffiTracer<string>; traced: Traced[] } {
+  const traced: Traced[] = [];
+  const tracer: UniffiTracer<string> = {
+    callStarted: (call) => `span:${call.name}`,
+    callEnded: (call, outcome, span) => traced.push({ call, outcome, span }),
+  };
+  return { tracer, traced };
+}
+
+test("Arguments are summarized", (t) => {
+  t.assertEqual(
+    uniffiSummarizeArgs([1, BigInt(2), "a", undefined, null, true]),
+    '(1, 2n, "a", undefined, null, true)',
+  );
+  t.assertEqual(
+    uniffiSummarizeArgs([new Uint8Array(16), [1, 2], new Map()]),
+    "(Uint8Array(16), Array(2), Map(0))",
+  );
+  t.assertEqual(
+    uniffiSummarizeArgs(["x".repeat(40)]),
+    `("${"x".repeat(32)}…")`,
+  );
+  t.assertTrue(uniffiSummarizeArgs(new Array(100).fill(1)).length <= 120);
+});
+
+test("Sync calls are traced", (t) => {
+  const { tracer, traced } = recorder();
+  t.assertEqual(uniffiTraceCall(tracer, "m", "add", [1, 2], () => 3), 3);
+  t.assertThrows(
+    (e) => e === "boom",
+    () =>
+      uniffiTraceCall(tracer, "m", "fail", [], () => {
+        throw "boom";
+      }),
+  );
+  t.assertEqual(traced.length, 2);
+  const [ok, failed] = traced;
+  t.assertEqual(ok.call.name, "add");
+  t.assertEqual(ok.call.args, "(1, 2)");
+  t.assertFalse(ok.call.isAsync);
+  t.assertTrue(ok.outcome.ok);
+  t.assertEqual(ok.span, "span:add");
+  t.assertFalse(failed.outcome.ok);
+  t.assertEqual(failed.outcome.error, "boom");
+});
+
+test("A throwing tracer does not change the outcome", (t) => {
+  const tracer: UniffiTracer = {
+    callStarted: () => {
+      throw new Error("tracer");
+    },
+    callEnded: () => {
+      throw new Error("tracer");
+    },
+  };
+  t.assertEqual(uniffiTraceCall(tracer, "m", "add", [], () => 3), 3);
+});
+
+(async () => {
+  await asyncTest("Async calls are traced when settled", async (t) => {
+    const { tracer, traced } = recorder();
+    const promise = uniffiTraceCallAsync(tracer, "m", "later", [], () =>
+      Promise.resolve("done"),
+    );
+    t.assertEqual(traced.length, 0);
+    t.assertEqual(await promise, "done");
+    t.assertEqual(traced.length, 1);
+    t.assertTrue(traced[0].call.isAsync);
+    t.assertTrue(traced[0].outcome.ok);
+    t.end();
+  });
+})();
@@ -0,0 +1,78 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/
+ */
+import { test } from "../testing/asserts";
+import "../testing/polyfills";
+import {
+  UniffiValidationError,
+  type UniffiValidator,
+  uniffiValidators as v,
+} from "../src/validators";
+
+test("Integers are range checked", (t) => {
+  t.assertTrue(v.uint8.is(255));
+  t.assertFalse(v.uint8.is(256));
+  t.assertFalse(v.uint8.is(-1));
+  t.assertFalse(v.int32.is(1.5));
+  t.assertTrue(v.int32.is(-0x80000000));
+  t.assertFalse(v.int32.is(-0x80000001));
+  t.assertTrue(v.uint64.is(BigInt("0xffffffffffffffff")));
+  t.assertFalse(v.uint64.is(BigInt("0x10000000000000000")));
+  t.assertFalse(v.int64.is(1));
+});
+
+test("Other primitives", (t) => {
+  t.assertTrue(v.float64.is(NaN));
+  t.assertFalse(v.float32.is("1.0"));
+  t.assertTrue(v.timestamp.is(new Date()));
+  t.assertFalse(v.timestamp.is(new Date("not a date")));
+  t.assertFalse(v.duration.is(-1));
+  t.assertTrue(v.arrayBuffer.is(new ArrayBuffer(2)));
+  t.assertFalse(v.arrayBuffer.is(new Uint8Array(2)));
+});
+
+test("Errors say where and what", (t) => {
+  const point = v.record("Point", { x: v.int32, y: v.int32 });
+  const line = v.record("Line", { points: v.sequence(point) });
+  const result = line.safeParse({ points: [{ x: 1, y: 2 }, { x: 3 }] });
+  t.assertFalse(result.success);
+  if (!result.success) {
+    t.assertTrue(UniffiValidationError.instanceOf(result.error));
+    t.assertEqual(result.error.path, "Line.points[1].y");
+    t.assertEqual(result.error.expected, "i32");
+    t.assertEqual(result.error.received, "undefined");
+  }
+});
+
+test("Lazy validators allow recursive types", (t) => {
+  type Tree = { children: Array<Tree> };
+  const tree: UniffiValidator<Tree> = v.record("Tree", {
+    children: v.sequence(v.lazy("Tree", () => tree)),
+  });
+  t.assertTrue(tree.is({ children: [{ children: [] }] }));
+  t.assertThrows(
+    (e) => e.path === "Tree.children[0].children",
+    () => tree.parse({ children: [{ children: null }] }),
+  );
+});
+
+test("Tagged enums check the tag, then the fields", (t) => {
+  const shape = v.taggedEnum("Shape", {
+    Circle: { radius: v.float64 },
+    Pair: { 0: v.int32, 1: v.int32 },
+    Empty: {},
+  });
+  t.assertTrue(shape.is({ tag: "Circle", inner: { radius: 1.5 } }));
+  t.assertTrue(shape.is({ tag: "Pair", inner: [1, 2] }));
+  t.assertTrue(shape.is({ tag: "Empty" }));
+  t.assertThrows(
+    (e) => e.path === "Shape.tag",
+    () => shape.parse({ tag: "Square" }),
+  );
+  t.assertThrows(
+    (e) => e.path === "Shape.inner.1",
+    () => shape.parse({ tag: "Pair", inner: [1, "2"] }),
+  );
+});